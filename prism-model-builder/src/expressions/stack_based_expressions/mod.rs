@@ -9,14 +9,8 @@ mod optimisations;
 
 use crate::expressions::ValuationSource;
 use crate::expressions::stack_based_expressions::sub_expression_manager::EmptySubexpressionProvider;
-use prism_model::{Expression, VariableManager, VariableRange, VariableReference};
-
-#[derive(PartialEq, Debug, Copy, Clone)]
-pub enum ExpressionType {
-    Int,
-    Bool,
-    Float,
-}
+pub use prism_model::ExpressionType;
+use prism_model::{Expression, VariableManager, VariableReference};
 
 pub struct StackBasedExpression<V> {
     operations: Vec<Operation<V>>,
@@ -48,8 +42,8 @@ impl StackBasedExpression<VariableReference> {
         variable_manager: &VariableManager<E, S>,
     ) -> Self {
         let mut operations = Vec::new();
-        let expression_type =
-            Self::process_expression(expression, &mut operations, variable_manager);
+        Self::process_expression(expression, &mut operations, variable_manager);
+        let expression_type = expression.checked_type(variable_manager);
 
         Self {
             operations,
@@ -57,452 +51,252 @@ impl StackBasedExpression<VariableReference> {
         }
     }
 
+    // The types of all sub-expressions are determined by the type checker, which runs before the
+    // model is built, so the operations only have to pick the variant for the operand types.
     fn process_expression<S: Clone, E>(
         expression: &Expression<VariableReference, S>,
         operations: &mut Vec<Operation<VariableReference>>,
         variable_manager: &VariableManager<E, S>,
-    ) -> ExpressionType {
+    ) {
         match expression {
-            Expression::Int(i, _) => {
-                operations.push(Operation::PushInt(*i));
-                ExpressionType::Int
-            }
-            Expression::Float(f, _) => {
-                operations.push(Operation::PushFloat(*f));
-                ExpressionType::Float
-            }
-            Expression::Bool(b, _) => {
-                operations.push(Operation::PushBool(*b));
-                ExpressionType::Bool
+            Expression::Int(i, _) => operations.push(Operation::PushInt(*i)),
+            Expression::Float(f, _) => operations.push(Operation::PushFloat(*f)),
+            Expression::Bool(b, _) => operations.push(Operation::PushBool(*b)),
+            Expression::VarOrConst(id, _) => {
+                operations.push(match expression.checked_type(variable_manager) {
+                    ExpressionType::Int => Operation::PushVarOrConstInt(*id),
+                    ExpressionType::Float => Operation::PushVarOrConstFloat(*id),
+                    ExpressionType::Bool => Operation::PushVarOrConstBool(*id),
+                })
             }
-            Expression::VarOrConst(id, _) => match variable_manager.variables[id.index].range {
-                VariableRange::BoundedInt { .. } | VariableRange::UnboundedInt { .. } => {
-                    operations.push(Operation::PushVarOrConstInt(*id));
-                    ExpressionType::Int
-                }
-                VariableRange::Float { .. } => {
-                    operations.push(Operation::PushVarOrConstFloat(*id));
-                    ExpressionType::Float
-                }
-                VariableRange::Boolean { .. } => {
-                    operations.push(Operation::PushVarOrConstBool(*id));
-                    ExpressionType::Bool
-                }
-            },
             Expression::Label(_, _) => {
                 panic!(
                     "Labels must be expanded before transforming an expression into a stack-based expression"
                 )
             }
             Expression::Function(name, args, _) => {
-                if name.name == "min" || name.name == "max" {
-                    let mut all_int = true;
-                    let mut args_ops = Vec::new();
-                    for arg in args {
-                        let mut arg_ops = Vec::new();
-                        let arg_type =
-                            Self::process_expression(arg, &mut arg_ops, variable_manager);
-                        args_ops.push((arg_type, arg_ops));
-                        match arg_type {
-                            ExpressionType::Int => {}
-                            ExpressionType::Bool => {
-                                panic!("Cannot apply {} to boolean operands", name.name)
-                            }
-                            ExpressionType::Float => {
-                                all_int = false;
-                            }
+                let all_int = args
+                    .iter()
+                    .all(|arg| arg.checked_type(variable_manager) == ExpressionType::Int);
+                match &name.name[..] {
+                    "min" | "max" => {
+                        for arg in args {
+                            Self::process_number(arg, !all_int, operations, variable_manager);
                         }
+                        operations.push(match (&name.name[..], all_int) {
+                            ("max", true) => Operation::MaxInt(args.len()),
+                            ("max", false) => Operation::MaxFloat(args.len()),
+                            (_, true) => Operation::MinInt(args.len()),
+                            (_, false) => Operation::MinFloat(args.len()),
+                        });
                     }
-                    for (arg_type, mut arg_ops) in args_ops {
-                        operations.append(&mut arg_ops);
-                        if !all_int && arg_type == ExpressionType::Int {
-                            operations.push(Operation::IntToFloat);
+                    // Rounding an integer is a no-op. However, this is done in some models (see
+                    // e.g. csma from QComp)
+                    "floor" | "ceil" | "round" => {
+                        Self::process_expression(&args[0], operations, variable_manager);
+                        if !all_int {
+                            operations.push(match &name.name[..] {
+                                "floor" => Operation::Floor,
+                                "ceil" => Operation::Ceil,
+                                _ => Operation::Round,
+                            });
                         }
                     }
-
-                    if name.name == "max" {
-                        if all_int {
-                            operations.push(Operation::MaxInt(args.len()));
-                            ExpressionType::Int
-                        } else {
-                            operations.push(Operation::MaxFloat(args.len()));
-                            ExpressionType::Float
+                    "pow" => {
+                        for arg in args {
+                            Self::process_number(arg, !all_int, operations, variable_manager);
                         }
-                    } else {
-                        if all_int {
-                            operations.push(Operation::MinInt(args.len()));
-                            ExpressionType::Int
+                        operations.push(if all_int {
+                            Operation::PowInt
                         } else {
-                            operations.push(Operation::MinFloat(args.len()));
-                            ExpressionType::Float
-                        }
-                    }
-                } else if name.name == "floor" {
-                    assert_eq!(args.len(), 1, "Function floor takes exactly one operand");
-                    let arg_type = Self::process_expression(&args[0], operations, variable_manager);
-
-                    if arg_type == ExpressionType::Float {
-                        operations.push(Operation::Floor);
-                        ExpressionType::Int
-                    } else if arg_type == ExpressionType::Int {
-                        // Flooring an integer is a no-op. However, this is done in some models
-                        // (see e.g. csma from QComp)
-                        ExpressionType::Int
-                    } else {
-                        panic!("Function floor can only operate on floats and integers");
-                    }
-                } else if name.name == "ceil" {
-                    assert_eq!(args.len(), 1, "Function ceil takes exactly one operand");
-                    let arg_type = Self::process_expression(&args[0], operations, variable_manager);
-
-                    if arg_type == ExpressionType::Float {
-                        operations.push(Operation::Ceil);
-                        ExpressionType::Int
-                    } else if arg_type == ExpressionType::Int {
-                        // "Ceil"ing an integer is a no-op.
-                        ExpressionType::Int
-                    } else {
-                        panic!("Function ceil can only operate on floats");
-                    }
-                } else if name.name == "round" {
-                    assert_eq!(args.len(), 1, "Function round takes exactly one operand");
-                    let arg_type = Self::process_expression(&args[0], operations, variable_manager);
-
-                    if arg_type == ExpressionType::Float {
-                        operations.push(Operation::Round);
-                        ExpressionType::Int
-                    } else if arg_type == ExpressionType::Int {
-                        // Rounding an integer is a no-op.
-                        ExpressionType::Int
-                    } else {
-                        panic!("Function round can only operate on floats");
+                            Operation::PowFloat
+                        });
                     }
-                } else if name.name == "pow" {
-                    assert_eq!(args.len(), 2, "Function pow takes exactly two operands");
-                    let arg1_type =
-                        Self::process_expression(&args[0], operations, variable_manager);
-
-                    let mut arg2ops = Vec::new();
-                    let arg2_type =
-                        Self::process_expression(&args[1], &mut arg2ops, variable_manager);
-
-                    if arg1_type == ExpressionType::Float || arg2_type == ExpressionType::Float {
-                        if arg1_type == ExpressionType::Int {
-                            operations.push(Operation::IntToFloat);
+                    "mod" => {
+                        for arg in args {
+                            Self::process_expression(arg, operations, variable_manager);
                         }
-                        operations.append(&mut arg2ops);
-                        if arg2_type == ExpressionType::Int {
-                            operations.push(Operation::IntToFloat);
-                        }
-                        operations.push(Operation::PowFloat);
-                        ExpressionType::Float
-                    } else {
-                        operations.append(&mut arg2ops);
-                        operations.push(Operation::PowInt);
-                        ExpressionType::Int
-                    }
-                } else if name.name == "mod" {
-                    assert_eq!(args.len(), 2, "Function mod takes exactly two operands");
-                    let arg1_type =
-                        Self::process_expression(&args[0], operations, variable_manager);
-                    let arg2_type =
-                        Self::process_expression(&args[1], operations, variable_manager);
-
-                    if arg1_type == ExpressionType::Int && arg2_type == ExpressionType::Int {
                         operations.push(Operation::Mod);
-                        ExpressionType::Int
-                    } else {
-                        panic!("Function mod operates on two integer operands");
-                    }
-                } else if name.name == "log" {
-                    assert_eq!(args.len(), 2, "Function log takes exactly two operands");
-                    let arg1_type =
-                        Self::process_expression(&args[0], operations, variable_manager);
-                    if arg1_type == ExpressionType::Int {
-                        operations.push(Operation::IntToFloat);
                     }
-                    let arg2_type =
-                        Self::process_expression(&args[1], operations, variable_manager);
-                    if arg2_type == ExpressionType::Int {
-                        operations.push(Operation::IntToFloat);
+                    "log" => {
+                        for arg in args {
+                            Self::process_number(arg, true, operations, variable_manager);
+                        }
+                        operations.push(Operation::LogFloat);
                     }
-
-                    operations.push(Operation::LogFloat);
-                    ExpressionType::Float
-                } else {
-                    panic!("Unknown function name {}", name.name);
+                    _ => unreachable!("Unknown function {}", name.name),
                 }
             }
             Expression::Minus(inner, _) => {
-                let inner_type = Self::process_expression(inner, operations, variable_manager);
-                match inner_type {
-                    ExpressionType::Int => {
-                        operations.push(Operation::NegateInt);
-                    }
-                    ExpressionType::Float => {
-                        operations.push(Operation::NegateFloat);
-                    }
-                    ExpressionType::Bool => {
-                        panic!("Cannot apply the unary minus to a boolean")
-                    }
-                }
-                inner_type
+                Self::process_expression(inner, operations, variable_manager);
+                operations.push(match inner.checked_type(variable_manager) {
+                    ExpressionType::Int => Operation::NegateInt,
+                    _ => Operation::NegateFloat,
+                });
             }
-            Expression::Multiplication(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::Multiplication(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::MultiplyInt,
-                Operation::MultiplyFloat,
-                ExpressionType::Int,
-                ExpressionType::Float,
+                [Operation::MultiplyInt, Operation::MultiplyFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::Division(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::Division(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::DivideInt,
-                Operation::DivideFloat,
-                ExpressionType::Float,
-                ExpressionType::Float,
+                [Operation::DivideInt, Operation::DivideFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::Addition(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::Addition(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::AddInt,
-                Operation::AddFloat,
-                ExpressionType::Int,
-                ExpressionType::Float,
+                [Operation::AddInt, Operation::AddFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::Subtraction(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::Subtraction(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::SubtractInt,
-                Operation::SubtractFloat,
-                ExpressionType::Int,
-                ExpressionType::Float,
+                [Operation::SubtractInt, Operation::SubtractFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::LessThan(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::LessThan(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::LessThanInt,
-                Operation::LessThanFloat,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
+                [Operation::LessThanInt, Operation::LessThanFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::LessOrEqual(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::LessOrEqual(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::LessOrEqualInt,
-                Operation::LessOrEqualFloat,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
+                [Operation::LessOrEqualInt, Operation::LessOrEqualFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::GreaterThan(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::GreaterThan(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::GreaterThanInt,
-                Operation::GreaterThanFloat,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
+                [Operation::GreaterThanInt, Operation::GreaterThanFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::GreaterOrEqual(arg1, arg2, _) => Self::int_or_float_operation(
+            Expression::GreaterOrEqual(arg1, arg2, _) => Self::binary_operation(
                 arg1,
                 arg2,
-                Operation::GreaterOrEqualInt,
-                Operation::GreaterOrEqualFloat,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
+                [Operation::GreaterOrEqualInt, Operation::GreaterOrEqualFloat],
                 operations,
                 variable_manager,
             ),
-            Expression::Equals(arg1, arg2, _) => Self::int_float_or_bool_operation(
+            Expression::Equals(arg1, arg2, _) => Self::comparison_operation(
                 arg1,
                 arg2,
-                Operation::EqualsInt,
-                Operation::EqualsFloat,
-                Operation::EqualsBool,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
+                [
+                    Operation::EqualsInt,
+                    Operation::EqualsFloat,
+                    Operation::EqualsBool,
+                ],
                 operations,
                 variable_manager,
             ),
-            Expression::NotEquals(arg1, arg2, _) => Self::int_float_or_bool_operation(
+            Expression::NotEquals(arg1, arg2, _) => Self::comparison_operation(
                 arg1,
                 arg2,
-                Operation::NotEqualsInt,
-                Operation::NotEqualsFloat,
-                Operation::NotEqualsBool,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
-                ExpressionType::Bool,
+                [
+                    Operation::NotEqualsInt,
+                    Operation::NotEqualsFloat,
+                    Operation::NotEqualsBool,
+                ],
                 operations,
                 variable_manager,
             ),
             Expression::Negation(arg, _) => {
-                let inner_type = Self::process_expression(arg, operations, variable_manager);
-                if inner_type != ExpressionType::Bool {
-                    panic!("Invalid type for the negation operator");
-                }
+                Self::process_expression(arg, operations, variable_manager);
                 operations.push(Operation::NegateBool);
-                ExpressionType::Bool
             }
-            Expression::Conjunction(arg1, arg2, _) => {
-                let type1 = Self::process_expression(arg1, operations, variable_manager);
-                let type2 = Self::process_expression(arg2, operations, variable_manager);
-                if type1 == ExpressionType::Bool && type2 == ExpressionType::Bool {
-                    operations.push(Operation::Conjunction);
-                    ExpressionType::Bool
-                } else {
-                    panic!("Conjunction can only operate on two booleans");
-                }
-            }
-            Expression::Disjunction(arg1, arg2, _) => {
-                let type1 = Self::process_expression(arg1, operations, variable_manager);
-                let type2 = Self::process_expression(arg2, operations, variable_manager);
-                if type1 == ExpressionType::Bool && type2 == ExpressionType::Bool {
-                    operations.push(Operation::Disjunction);
-                    ExpressionType::Bool
-                } else {
-                    panic!("Conjunction can only operate on two booleans");
-                }
-            }
-            Expression::IfAndOnlyIf(arg1, arg2, _) => {
-                let type1 = Self::process_expression(arg1, operations, variable_manager);
-                let type2 = Self::process_expression(arg2, operations, variable_manager);
-                if type1 == ExpressionType::Bool && type2 == ExpressionType::Bool {
-                    operations.push(Operation::IfAndOnlyIf);
-                    ExpressionType::Bool
-                } else {
-                    panic!("Conjunction can only operate on two booleans");
-                }
-            }
-            Expression::Implies(arg1, arg2, _) => {
-                let type1 = Self::process_expression(arg1, operations, variable_manager);
-                let type2 = Self::process_expression(arg2, operations, variable_manager);
-                if type1 == ExpressionType::Bool && type2 == ExpressionType::Bool {
-                    operations.push(Operation::Implies);
-                    ExpressionType::Bool
-                } else {
-                    panic!("Conjunction can only operate on two booleans");
-                }
+            Expression::Conjunction(arg1, arg2, _)
+            | Expression::Disjunction(arg1, arg2, _)
+            | Expression::IfAndOnlyIf(arg1, arg2, _)
+            | Expression::Implies(arg1, arg2, _) => {
+                Self::process_expression(arg1, operations, variable_manager);
+                Self::process_expression(arg2, operations, variable_manager);
+                operations.push(match expression {
+                    Expression::Conjunction(..) => Operation::Conjunction,
+                    Expression::Disjunction(..) => Operation::Disjunction,
+                    Expression::IfAndOnlyIf(..) => Operation::IfAndOnlyIf,
+                    _ => Operation::Implies,
+                });
             }
             Expression::Ternary(guard, arg1, arg2, _) => {
-                let guard_type = Self::process_expression(guard, operations, variable_manager);
-                let type1 = Self::process_expression(arg1, operations, variable_manager);
-                let mut ops2 = Vec::new();
-                let type2 = Self::process_expression(arg2, &mut ops2, variable_manager);
-                if guard_type == ExpressionType::Bool {
-                    if type1 == ExpressionType::Int && type2 == ExpressionType::Float {
-                        operations.push(Operation::IntToFloat);
-                    }
-                    operations.append(&mut ops2);
-
-                    if type1 == ExpressionType::Float && type2 == ExpressionType::Int {
-                        operations.push(Operation::IntToFloat);
-                    }
-
-                    if type1 == ExpressionType::Int && type2 == ExpressionType::Int {
-                        operations.push(Operation::TernaryInt);
-                        ExpressionType::Int
-                    } else if (type1 == ExpressionType::Int || type1 == ExpressionType::Float)
-                        || (type2 == ExpressionType::Int && type2 == ExpressionType::Float)
-                    {
-                        operations.push(Operation::TernaryFloat);
-                        ExpressionType::Float
-                    } else if type1 == ExpressionType::Bool && type2 == ExpressionType::Bool {
-                        operations.push(Operation::TernaryBool);
-                        ExpressionType::Bool
-                    } else {
-                        panic!("Incompatible operands for ternary operation")
-                    }
-                } else {
-                    panic!("The guard of a ternary expression must be a boolean");
-                }
+                Self::process_expression(guard, operations, variable_manager);
+                Self::comparison_operation(
+                    arg1,
+                    arg2,
+                    [
+                        Operation::TernaryInt,
+                        Operation::TernaryFloat,
+                        Operation::TernaryBool,
+                    ],
+                    operations,
+                    variable_manager,
+                );
             }
         }
     }
 
-    fn int_or_float_operation<S: Clone, E>(
-        arg1: &Expression<VariableReference, S>,
-        arg2: &Expression<VariableReference, S>,
-        int_operation: Operation<VariableReference>,
-        float_operation: Operation<VariableReference>,
-        int_type: ExpressionType,
-        float_type: ExpressionType,
+    /// Processes a numeric operand, converting it to a float if required
+    fn process_number<S: Clone, E>(
+        expression: &Expression<VariableReference, S>,
+        as_float: bool,
         operations: &mut Vec<Operation<VariableReference>>,
         variable_manager: &VariableManager<E, S>,
-    ) -> ExpressionType {
-        let mut ops2 = Vec::new();
-        let type1 = Self::process_expression(arg1, operations, variable_manager);
-        let type2 = Self::process_expression(arg2, &mut ops2, variable_manager);
-
-        if type1 == ExpressionType::Int && type2 == ExpressionType::Float {
-            operations.push(Operation::IntToFloat);
-        }
-        operations.append(&mut ops2);
-        if type1 == ExpressionType::Float && type2 == ExpressionType::Int {
+    ) {
+        Self::process_expression(expression, operations, variable_manager);
+        if as_float && expression.checked_type(variable_manager) == ExpressionType::Int {
             operations.push(Operation::IntToFloat);
         }
-        if type1 == ExpressionType::Float || type2 == ExpressionType::Float {
-            operations.push(float_operation);
-            float_type
-        } else if type1 == ExpressionType::Int && type2 == ExpressionType::Int {
-            operations.push(int_operation);
-            int_type
-        } else {
-            panic!("The operation can only operate on ints and floats");
-        }
     }
 
-    fn int_float_or_bool_operation<S: Clone, E>(
+    /// Processes an operation on two numbers, which operates on floats unless both are ints
+    fn binary_operation<S: Clone, E>(
         arg1: &Expression<VariableReference, S>,
         arg2: &Expression<VariableReference, S>,
-        int_operation: Operation<VariableReference>,
-        float_operation: Operation<VariableReference>,
-        bool_operation: Operation<VariableReference>,
-        int_type: ExpressionType,
-        float_type: ExpressionType,
-        bool_type: ExpressionType,
+        [int_operation, float_operation]: [Operation<VariableReference>; 2],
         operations: &mut Vec<Operation<VariableReference>>,
         variable_manager: &VariableManager<E, S>,
-    ) -> ExpressionType {
-        let mut ops2 = Vec::new();
-        let type1 = Self::process_expression(arg1, operations, variable_manager);
-        let type2 = Self::process_expression(arg2, &mut ops2, variable_manager);
+    ) {
+        let as_float = arg1.checked_type(variable_manager) == ExpressionType::Float
+            || arg2.checked_type(variable_manager) == ExpressionType::Float;
+        Self::process_number(arg1, as_float, operations, variable_manager);
+        Self::process_number(arg2, as_float, operations, variable_manager);
+        operations.push(if as_float {
+            float_operation
+        } else {
+            int_operation
+        });
+    }
 
-        if type1 == ExpressionType::Int && type2 == ExpressionType::Float {
-            operations.push(Operation::IntToFloat);
-        }
-        operations.append(&mut ops2);
-        if type1 == ExpressionType::Float && type2 == ExpressionType::Int {
-            operations.push(Operation::IntToFloat);
-        }
-        if type1 == ExpressionType::Float || type2 == ExpressionType::Float {
-            operations.push(float_operation);
-            float_type
-        } else if type1 == ExpressionType::Int && type2 == ExpressionType::Int {
-            operations.push(int_operation);
-            int_type
-        } else if type1 == ExpressionType::Bool && type2 == ExpressionType::Bool {
+    /// Like `binary_operation`, but the operands may also be two booleans
+    fn comparison_operation<S: Clone, E>(
+        arg1: &Expression<VariableReference, S>,
+        arg2: &Expression<VariableReference, S>,
+        [int_operation, float_operation, bool_operation]: [Operation<VariableReference>; 3],
+        operations: &mut Vec<Operation<VariableReference>>,
+        variable_manager: &VariableManager<E, S>,
+    ) {
+        if arg1.checked_type(variable_manager) == ExpressionType::Bool {
+            Self::process_expression(arg1, operations, variable_manager);
+            Self::process_expression(arg2, operations, variable_manager);
             operations.push(bool_operation);
-            bool_type
         } else {
-            panic!(
-                "The operation can only operate on two booleans or on a combination of integers and floats"
+            Self::binary_operation(
+                arg1,
+                arg2,
+                [int_operation, float_operation],
+                operations,
+                variable_manager,
             );
         }
     }
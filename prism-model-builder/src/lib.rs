@@ -2,6 +2,7 @@ pub use probabilistic_models;
 
 pub mod expressions;
 mod model_in_progress;
+mod players;
 mod synchronised_actions;
 mod variables;
 
@@ -11,17 +12,19 @@ use crate::expressions::stack_based_expressions::{
 };
 use crate::expressions::{TreeWalkingEvaluator, ValuationSource, VariableType};
 use crate::model_in_progress::ModelInProgress;
+use crate::players::PlayerAssignment;
 use crate::synchronised_actions::{SynchronisedAction, SynchronisedActions};
 use crate::variables::{ConstAndVarValuationSource, ModelVariableInfo};
 use log::info;
 use prism_model::{
-    Command, Expression, Identifier, Model, Update, VariableManager, VariableRange,
-    VariableReference,
+    Command, Expression, Identifier, Model, ModelType, RewardsTarget, Update, VariableManager,
+    VariableRange, VariableReference,
 };
 use probabilistic_models::probabilistic_properties::Query;
 use probabilistic_models::{
-    Action, AtomicProposition, AtomicPropositions, Builder, Distribution, ModelTypes,
-    PredecessorsBuilder, ProbabilisticModel, Successor, Valuation, ValuationBuilder,
+    Action, AtomicProposition, AtomicPropositions, Builder, Distribution, ModelTypes, Owners,
+    PredecessorsBuilder, ProbabilisticModel, RewardStructure, Successor, Valuation,
+    ValuationBuilder,
 };
 use probabilistic_models::{DistributionBuilder, Predecessor};
 use std::collections::HashMap;
@@ -58,7 +61,7 @@ pub enum UserProvidedConstValue {
 
 pub struct ModelBuildingOutput<M: ModelTypes> {
     pub model: ProbabilisticModel<M>,
-    pub properties: Vec<Query<i64, f64, AtomicProposition>>,
+    pub properties: Vec<Query<f64, f64, AtomicProposition>>,
 }
 
 pub trait ExpressionContext<E> {
@@ -146,6 +149,10 @@ pub struct ExplicitModelBuilder<M: ModelTypes> {
     model_in_progress: ModelInProgress<M>,
     open_states: Vec<usize>,
     variable_info: variables::ModelVariableInfo<M::Valuation>,
+    /// Whether command weights are rates rather than probabilities, as in CTMCs.
+    continuous_time: bool,
+    /// The players of games. Players are ignored if the built model only has a single player.
+    players: Option<PlayerAssignment>,
 }
 
 impl<M: ModelTypes> ExplicitModelBuilder<M> {
@@ -161,15 +168,17 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
     >(
         properties: I,
         variable_info: &variables::ModelVariableInfo<M::Valuation>,
-    ) -> Result<Vec<Query<i64, f64, AtomicProposition>>, ModelBuildingError> {
+    ) -> Result<Vec<Query<f64, f64, AtomicProposition>>, ModelBuildingError> {
         let const_valuation_source = variable_info.get_const_only_valuation_source();
 
         let mut result = Vec::new();
         for property in properties {
             result.push(
                 property
+                    // Path bounds are real-valued in continuous-time models, so they are
+                    // evaluated as floats. Discrete-time checkers reject non-integer bounds.
                     .map_i(&mut |ex| {
-                        TreeWalkingEvaluator::new().evaluate_as_int(&ex, &const_valuation_source)
+                        TreeWalkingEvaluator::new().evaluate_as_float(&ex, &const_valuation_source)
                     })
                     .map_f(&mut |ex| {
                         TreeWalkingEvaluator::new().evaluate_as_float(&ex, &const_valuation_source)
@@ -250,10 +259,30 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
 
         let synchronised_actions = SynchronisedActions::from_prism(&model);
 
+        let reward_structures = model
+            .rewards
+            .rewards
+            .iter()
+            .map(|rewards| RewardStructure::new(rewards.name.as_ref().map(|n| n.name.clone())))
+            .collect();
+
+        let players =
+            PlayerAssignment::from_prism(&model).filter(|_| M::Owners::max_player_count() > 1);
+        if let Some(players) = &players
+            && players.player_count() > M::Owners::max_player_count()
+        {
+            return Err(ModelBuildingError::TooManyPlayers {
+                players: players.player_count(),
+                supported: M::Owners::max_player_count(),
+            });
+        }
+
         let mut builder = Self {
-            model_in_progress: ModelInProgress::new(atomic_propositions.len()),
+            model_in_progress: ModelInProgress::new(atomic_propositions.len(), reward_structures),
             open_states: Vec::new(),
             variable_info,
+            continuous_time: matches!(model.model_type, ModelType::Ctmc(_)),
+            players,
         };
 
         builder.create_initial_states(&model, &mut expression_context)?;
@@ -268,9 +297,15 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
             )?;
         }
 
-        let model = builder
+        let continuous_time = builder.continuous_time;
+        let mut model = builder
             .model_in_progress
             .into_model(builder.variable_info.valuation_context);
+        if continuous_time {
+            // Command weights of CTMCs are rates rather than probabilities.
+            model.convert_rates_to_probabilities();
+            model.rebuild_predecessors();
+        }
 
         info!(
             "Model built in {:?} ({} states)",
@@ -303,8 +338,10 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
         expression_context.reset_context();
 
         self.evaluate_atomic_propositions(state, atomic_propositions, expression_context);
+        self.evaluate_state_rewards(state, model, expression_context);
 
         let mut action_index = 0;
+        let mut owner = None;
         for module_index in 0..model.modules.modules.len() {
             let module = &model.modules.modules[module_index];
             for command_index in 0..module.commands.len() {
@@ -312,29 +349,84 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
                 if command.action.is_some() {
                     continue; // Synchronising actions are handled separately
                 }
-                self.process_nonsynchronised_command(
+                let enabled = self.process_nonsynchronised_command(
                     state,
                     model,
                     &mut action_index,
                     &command,
                     expression_context,
                 );
+                if enabled && let Some(players) = &self.players {
+                    self.add_controlling_player(
+                        state,
+                        players.player_of_module(module_index),
+                        &mut owner,
+                    )?;
+                }
             }
         }
 
         for synchronised_action in synchronised_actions {
-            self.process_synchronising_action(
+            let enabled = self.process_synchronising_action(
                 state,
                 model,
                 &mut action_index,
                 &synchronised_action,
                 expression_context,
             );
+            if enabled && let Some(players) = &self.players {
+                self.add_controlling_player(
+                    state,
+                    players.player_of_action(&synchronised_action.name),
+                    &mut owner,
+                )?;
+            }
+        }
+
+        if let Some(owner) = owner {
+            self.model_in_progress.get_state_mut(state).owner =
+                M::Owners::from_player_index(owner).expect("Player count was checked before");
         }
 
         Ok(())
     }
 
+    /// Games are turn-based, so all commands that are enabled in a state must be controlled by
+    /// the same player, who then owns the state.
+    fn add_controlling_player(
+        &self,
+        state: usize,
+        player: Option<usize>,
+        owner: &mut Option<usize>,
+    ) -> Result<(), ModelBuildingError> {
+        let players = self.players.as_ref().unwrap();
+        let describe_state = || {
+            self.model_in_progress
+                .get_state(state)
+                .valuation
+                .displayable(&self.variable_info.valuation_context)
+                .to_string()
+        };
+        match (player, *owner) {
+            (None, _) => Err(ModelBuildingError::UncontrolledCommand {
+                state: describe_state(),
+            }),
+            (Some(player), Some(previous)) if player != previous => {
+                Err(ModelBuildingError::ConcurrentPlayers {
+                    state: describe_state(),
+                    players: (
+                        players.get_name(previous).to_string(),
+                        players.get_name(player).to_string(),
+                    ),
+                })
+            }
+            (Some(player), _) => {
+                *owner = Some(player);
+                Ok(())
+            }
+        }
+    }
+
     fn process_nonsynchronised_command<S: Clone, E, EC: ExpressionContext<E>>(
         &mut self,
         state: usize,
@@ -342,7 +434,7 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
         action_index: &mut usize,
         command: &Command<Identifier<S>, E, VariableReference, S>,
         expression_context: &mut EC,
-    ) {
+    ) -> bool {
         let valuation = &self.model_in_progress.get_state(state).valuation;
         let val_source = self.variable_info.get_valuation_source(valuation);
         let guard = expression_context.evaluate_bool(&command.guard, &val_source);
@@ -378,7 +470,7 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
             }
 
             let action_name_index = self.model_in_progress.get_unnamed_action_name_index();
-            let successors = distribution.finish();
+            let successors = self.finish_distribution(distribution);
             self.model_in_progress
                 .get_state_mut(state)
                 .actions
@@ -386,8 +478,10 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
                     successors,
                     action_name_index,
                 });
+            self.evaluate_action_rewards(state, *action_index, None, model, expression_context);
             *action_index += 1;
         }
+        guard
     }
 
     fn process_synchronising_action<S: Clone, E, EC: ExpressionContext<E>>(
@@ -397,7 +491,7 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
         action_index: &mut usize,
         synchronised_action: &SynchronisedAction,
         expression_context: &mut EC,
-    ) {
+    ) -> bool {
         let action_name_index = self
             .model_in_progress
             .get_action_name_index(&synchronised_action.name);
@@ -513,13 +607,21 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
                     }
                 }
 
+                let successors = self.finish_distribution(distribution);
                 self.model_in_progress
                     .get_state_mut(state)
                     .actions
                     .add_action(Action {
-                        successors: distribution.finish(),
+                        successors,
                         action_name_index,
                     });
+                self.evaluate_action_rewards(
+                    state,
+                    *action_index,
+                    Some(&synchronised_action.name),
+                    model,
+                    expression_context,
+                );
                 *action_index += 1;
 
                 for i in (0..n).rev() {
@@ -537,6 +639,18 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
                 }
             }
         }
+        all_satisfied
+    }
+
+    fn finish_distribution(
+        &self,
+        distribution: <M::Distribution as Distribution>::Builder,
+    ) -> M::Distribution {
+        if self.continuous_time {
+            distribution.finish_rates()
+        } else {
+            distribution.finish()
+        }
     }
 
     fn evaluate_atomic_propositions<E, EC: ExpressionContext<E>>(
@@ -553,6 +667,63 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
         }
     }
 
+    fn evaluate_state_rewards<S: Clone, E, EC: ExpressionContext<E>>(
+        &mut self,
+        state_index: usize,
+        model: &Model<(), Identifier<S>, E, VariableReference, S>,
+        expression_context: &mut EC,
+    ) {
+        for (reward_index, rewards) in model.rewards.rewards.iter().enumerate() {
+            let state = self.model_in_progress.get_state(state_index);
+            let val_source = self.variable_info.get_valuation_source(&state.valuation);
+            let mut reward = 0.0;
+            for element in &rewards.entries {
+                if let RewardsTarget::State = element.target
+                    && expression_context.evaluate_bool(&element.condition, &val_source)
+                {
+                    reward += expression_context.evaluate_float(&element.value, &val_source);
+                }
+            }
+            if reward != 0.0 {
+                self.model_in_progress
+                    .get_reward_structure_mut(reward_index)
+                    .set_state_reward(state_index, reward);
+            }
+        }
+    }
+
+    /// Evaluates the transition rewards of an action. `action_name` is `None` for actions that
+    /// stem from unlabelled commands, which are rewarded by `[]` reward items.
+    fn evaluate_action_rewards<S: Clone, E, EC: ExpressionContext<E>>(
+        &mut self,
+        state_index: usize,
+        action_index: usize,
+        action_name: Option<&str>,
+        model: &Model<(), Identifier<S>, E, VariableReference, S>,
+        expression_context: &mut EC,
+    ) {
+        for (reward_index, rewards) in model.rewards.rewards.iter().enumerate() {
+            let state = self.model_in_progress.get_state(state_index);
+            let val_source = self.variable_info.get_valuation_source(&state.valuation);
+            let mut reward = 0.0;
+            for element in &rewards.entries {
+                let matches = match &element.target {
+                    RewardsTarget::State => false,
+                    RewardsTarget::Action(None) => action_name.is_none(),
+                    RewardsTarget::Action(Some(name)) => action_name == Some(name.name.as_str()),
+                };
+                if matches && expression_context.evaluate_bool(&element.condition, &val_source) {
+                    reward += expression_context.evaluate_float(&element.value, &val_source);
+                }
+            }
+            if reward != 0.0 {
+                self.model_in_progress
+                    .get_reward_structure_mut(reward_index)
+                    .set_action_reward(state_index, action_index, reward);
+            }
+        }
+    }
+
     fn apply_assignments<S: Clone, E, EC: ExpressionContext<E>>(
         &self,
         variable_manager: &VariableManager<E, S>,
@@ -611,8 +782,12 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
         model: &Model<(), Identifier<S>, E, VariableReference, S>,
         expression_context: &mut EC,
     ) -> Result<(), ModelBuildingError> {
-        if model.init_constraint.is_some() {
-            panic!("Init constraints are not yet supported by the model builder");
+        if let Some(init_constraint) = &model.init_constraint {
+            return self.create_initial_states_from_constraint(
+                model,
+                init_constraint,
+                expression_context,
+            );
         }
         let const_value_source = self.variable_info.get_const_only_valuation_source();
 
@@ -679,6 +854,84 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
         Ok(())
     }
 
+    /// Adds every valuation that satisfies the init constraint as an initial state. This requires
+    /// all variables to be bounded integers or booleans, so that their valuations can be
+    /// enumerated. Initial values of variables are ignored, as PRISM forbids them in models with
+    /// an init constraint.
+    fn create_initial_states_from_constraint<S: Clone, E, EC: ExpressionContext<E>>(
+        &mut self,
+        model: &Model<(), Identifier<S>, E, VariableReference, S>,
+        init_constraint: &E,
+        expression_context: &mut EC,
+    ) -> Result<(), ModelBuildingError> {
+        // The range of every variable, with booleans represented by 0 and 1
+        let mut ranges = Vec::new();
+        let mut is_boolean = Vec::new();
+        for (i, variable) in model.variable_manager.variables.iter().enumerate() {
+            if let Some(index) = self.variable_info.valuation_map.map_to_variable(i) {
+                match variable.range {
+                    VariableRange::BoundedInt { .. } => {
+                        match self.variable_info.details[index].bounds {
+                            Some(bounds) => ranges.push(bounds),
+                            None => panic!("Variable bounds list is inconsistent"),
+                        }
+                        is_boolean.push(false);
+                    }
+                    VariableRange::Boolean { .. } => {
+                        ranges.push((0, 1));
+                        is_boolean.push(true);
+                    }
+                    VariableRange::UnboundedInt { .. } | VariableRange::Float { .. } => {
+                        return Err(ModelBuildingError::UnboundedVariableInInitConstraint(
+                            variable.name.name.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut values: Vec<i64> = ranges.iter().map(|&(min, _)| min).collect();
+        let mut found_initial_state = false;
+        loop {
+            let mut valuation_builder =
+                M::Valuation::get_builder(&self.variable_info.valuation_context);
+            for (&value, &is_boolean) in values.iter().zip(&is_boolean) {
+                if is_boolean {
+                    valuation_builder.add_bool(value == 1);
+                } else {
+                    valuation_builder.add_bounded_int(value);
+                }
+            }
+            let valuation = valuation_builder.finish();
+
+            expression_context.reset_context();
+            let val_source = self.variable_info.get_valuation_source(&valuation);
+            if expression_context.evaluate_bool(init_constraint, &val_source) {
+                let index = self.get_or_add_state(valuation);
+                self.model_in_progress.add_initial_state(index);
+                found_initial_state = true;
+            }
+
+            // Advance to the next valuation. The last variable changes fastest.
+            let mut position = values.len();
+            loop {
+                if position == 0 {
+                    return if found_initial_state {
+                        Ok(())
+                    } else {
+                        Err(ModelBuildingError::NoInitialState)
+                    };
+                }
+                position -= 1;
+                if values[position] < ranges[position].1 {
+                    values[position] += 1;
+                    break;
+                }
+                values[position] = ranges[position].0;
+            }
+        }
+    }
+
     #[allow(unused)]
     fn print_valuation<S: Clone>(
         valuation: &M::Valuation,
@@ -715,4 +968,19 @@ impl<M: ModelTypes> ExplicitModelBuilder<M> {
 }
 
 #[derive(Debug)]
-pub enum ModelBuildingError {}
+pub enum ModelBuildingError {
+    /// Init constraints can only be used if all variables are bounded integers or booleans.
+    UnboundedVariableInInitConstraint(String),
+    /// No valuation satisfies the init constraint.
+    NoInitialState,
+    /// The game has more players than the type of the built model supports.
+    TooManyPlayers { players: usize, supported: usize },
+    /// A command that is not controlled by any player is enabled in a state of a game.
+    UncontrolledCommand { state: String },
+    /// Commands of different players are enabled in the same state of a game, but games must be
+    /// turn-based.
+    ConcurrentPlayers {
+        state: String,
+        players: (String, String),
+    },
+}
@@ -1,6 +1,6 @@
 use probabilistic_models::{
     ActionCollection, AtomicPropositions, Builder, InitialStates, InitialStatesBuilder, ModelTypes,
-    Predecessors, PredecessorsBuilder, ProbabilisticModel, State,
+    Predecessors, PredecessorsBuilder, ProbabilisticModel, RewardStructure, State,
 };
 use rustc_hash::FxHashMap;
 
@@ -9,6 +9,7 @@ pub struct StateInProgress<M: ModelTypes> {
     pub actions: <M::ActionCollection as ActionCollection<M::Distribution>>::Builder,
     pub atomic_propositions: M::AtomicPropositions,
     pub predecessors: <M::Predecessors as Predecessors>::Builder,
+    pub owner: M::Owners,
 }
 
 pub struct ModelInProgress<M: ModelTypes> {
@@ -21,10 +22,12 @@ pub struct ModelInProgress<M: ModelTypes> {
     action_name_indices: FxHashMap<String, usize>,
 
     atomic_proposition_count: usize,
+
+    reward_structures: Vec<RewardStructure>,
 }
 
 impl<M: ModelTypes> ModelInProgress<M> {
-    pub fn new(atomic_proposition_count: usize) -> Self {
+    pub fn new(atomic_proposition_count: usize, reward_structures: Vec<RewardStructure>) -> Self {
         Self {
             states: Vec::new(),
             valuation_to_state: FxHashMap::default(),
@@ -35,6 +38,8 @@ impl<M: ModelTypes> ModelInProgress<M> {
             action_name_indices: FxHashMap::default(),
 
             atomic_proposition_count,
+
+            reward_structures,
         }
     }
     pub fn get_unnamed_action_name_index(&mut self) -> usize {
@@ -52,6 +57,10 @@ impl<M: ModelTypes> ModelInProgress<M> {
         }
     }
 
+    pub fn get_reward_structure_mut(&mut self, index: usize) -> &mut RewardStructure {
+        &mut self.reward_structures[index]
+    }
+
     pub fn get_state(&self, index: usize) -> &StateInProgress<M> {
         &self.states[index]
     }
@@ -76,6 +85,7 @@ impl<M: ModelTypes> ModelInProgress<M> {
             actions: action_builder,
             atomic_propositions,
             predecessors,
+            owner: <M::Owners as probabilistic_models::Owners>::default_owner(),
         });
         index
     }
@@ -100,12 +110,13 @@ impl<M: ModelTypes> ModelInProgress<M> {
             self.atomic_proposition_count,
         );
         result.action_names = self.action_names;
+        result.reward_structures = self.reward_structures;
         for state_in_progress in self.states.into_iter() {
             let state = State {
                 valuation: state_in_progress.valuation,
                 actions: state_in_progress.actions.finish(),
                 atomic_propositions: state_in_progress.atomic_propositions,
-                owner: <M::Owners as probabilistic_models::Owners>::default_owner(),
+                owner: state_in_progress.owner,
                 predecessors: state_in_progress.predecessors.finish(),
             };
             result.states.push(state);
@@ -0,0 +1,61 @@
+use prism_model::{Identifier, Model, ModelType, VariableReference};
+use std::collections::HashMap;
+
+/// The players that control the commands of a stochastic multi-player game
+pub struct PlayerAssignment {
+    module_players: Vec<Option<usize>>,
+    action_players: HashMap<String, usize>,
+    names: Vec<String>,
+}
+
+impl PlayerAssignment {
+    /// Returns `None` if the model is not a game.
+    pub fn from_prism<S: Clone, E>(
+        model: &Model<(), Identifier<S>, E, VariableReference, S>,
+    ) -> Option<Self> {
+        if !matches!(model.model_type, ModelType::Smg(_)) {
+            return None;
+        }
+
+        let module_players = model
+            .modules
+            .modules
+            .iter()
+            .map(|module| model.players.player_of_module(&module.name.name))
+            .collect();
+        let mut action_players = HashMap::new();
+        for (index, player) in model.players.players.iter().enumerate() {
+            for action in &player.actions {
+                action_players.insert(action.name.clone(), index);
+            }
+        }
+        let names = model
+            .players
+            .players
+            .iter()
+            .map(|player| player.name.name.clone())
+            .collect();
+
+        Some(Self {
+            module_players,
+            action_players,
+            names,
+        })
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn player_of_module(&self, module_index: usize) -> Option<usize> {
+        self.module_players[module_index]
+    }
+
+    pub fn player_of_action(&self, action_name: &str) -> Option<usize> {
+        self.action_players.get(action_name).copied()
+    }
+
+    pub fn get_name(&self, player: usize) -> &str {
+        &self.names[player]
+    }
+}
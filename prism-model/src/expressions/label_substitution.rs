@@ -3,6 +3,8 @@ use crate::{Expression, Identifier, IdentityMapExpression};
 pub struct LabelSubstitutionVisitor<'a, S: Clone> {
     pub label_name: &'a Identifier<S>,
     pub expression: &'a Expression<Identifier<S>, S>,
+    /// Whether the inserted expression takes the span of the replaced label
+    pub at_occurrence: bool,
 }
 
 impl<'a, S: Clone> crate::expressions::identity_map::Private for LabelSubstitutionVisitor<'a, S> {}
@@ -10,7 +12,9 @@ impl<'a, S: Clone> IdentityMapExpression<crate::Identifier<S>, S>
     for LabelSubstitutionVisitor<'a, S>
 {
     fn visit_label(&mut self, name: Identifier<S>, span: S) -> Expression<Identifier<S>, S> {
-        if &name == self.label_name {
+        if &name == self.label_name && self.at_occurrence {
+            self.expression.clone().map_span(&|_| span.clone())
+        } else if &name == self.label_name {
             self.expression.clone()
         } else {
             Expression::Label(name, span)
@@ -226,15 +226,45 @@ impl<S: Clone> Expression<Identifier<S>, S> {
         labels: &LabelManager<Expression<Identifier<S>, S>, S>,
     ) {
         for label in &labels.labels {
-            let mut visitor = LabelSubstitutionVisitor {
-                label_name: &label.name,
-                expression: &label.condition,
-            };
-
-            let condition = std::mem::replace(self, Expression::Bool(false, default_span.clone()));
-            *self = condition.visit(&mut visitor);
+            self.substitute_label(default_span.clone(), &label.name, &label.condition);
         }
     }
+
+    /// Replaces every occurrence of the label `name` by `expression`.
+    pub fn substitute_label(
+        &mut self,
+        default_span: S,
+        name: &Identifier<S>,
+        expression: &Expression<Identifier<S>, S>,
+    ) {
+        let mut visitor = LabelSubstitutionVisitor {
+            label_name: name,
+            expression,
+            at_occurrence: false,
+        };
+
+        let condition = std::mem::replace(self, Expression::Bool(false, default_span));
+        *self = condition.visit(&mut visitor);
+    }
+
+    /// Like `substitute_label`, but the inserted expressions take the span of the occurrence of
+    /// the label. This is meant for expressions from another source, such as the initial states
+    /// of the model in a property.
+    pub fn substitute_label_at_occurrence(
+        &mut self,
+        default_span: S,
+        name: &Identifier<S>,
+        expression: &Expression<Identifier<S>, S>,
+    ) {
+        let mut visitor = LabelSubstitutionVisitor {
+            label_name: name,
+            expression,
+            at_occurrence: true,
+        };
+
+        let condition = std::mem::replace(self, Expression::Bool(false, default_span));
+        *self = condition.visit(&mut visitor);
+    }
     pub fn substitute_formulas(
         &mut self,
         default_span: S,
@@ -43,6 +43,7 @@ impl<S: Clone> Identifier<S> {
         "endinvariant",
         "endmodule",
         "endobservables",
+        "endplayer",
         "endrewards",
         "endsystem",
         "false",
@@ -69,6 +70,7 @@ impl<S: Clone> Identifier<S> {
         "Pmax",
         "Pmin",
         "P",
+        "player",
         "pomdp",
         "popta",
         "probabilistic",
@@ -80,6 +82,7 @@ impl<S: Clone> Identifier<S> {
         "Rmin",
         "R",
         "S",
+        "smg",
         "stochastic",
         "system",
         "true",
@@ -24,6 +24,9 @@ pub use labels::{AddLabelError, Label, LabelManager};
 mod model;
 pub use model::{Model, ModelType, ModuleExpansionError};
 
+mod players;
+pub use players::{AddPlayerError, Player, PlayerError, PlayerManager};
+
 mod rewards;
 pub use rewards::{AddRewardsError, Rewards, RewardsElement, RewardsManager, RewardsTarget};
 
@@ -37,7 +40,10 @@ mod identifier;
 pub use identifier::{Identifier, InvalidName};
 
 mod properties;
-pub use properties::SubstitutableQuery;
+pub use properties::{NamedProperty, SubstitutableQuery};
+
+mod typing;
+pub use typing::{ArgumentCount, ExpectedType, ExpressionType, TypeError, check_query_types};
 
 use std::fmt::{Display, Formatter};
 
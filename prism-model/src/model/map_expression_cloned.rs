@@ -1,6 +1,7 @@
 use crate::{
     Assignment, Command, Formula, FormulaManager, Label, LabelManager, Module, ModuleManager,
-    Rewards, RewardsElement, RewardsManager, Update, VariableInfo, VariableManager, VariableRange,
+    PlayerManager, Rewards, RewardsElement, RewardsManager, Update, VariableInfo, VariableManager,
+    VariableRange,
 };
 
 impl<AM: Default + Clone, A: Clone, E, V: Clone, S: Clone> super::Model<AM, A, E, V, S> {
@@ -131,6 +132,9 @@ impl<AM: Default + Clone, A: Clone, E, V: Clone, S: Clone> super::Model<AM, A, E
             init_constraint,
             labels,
             rewards,
+            players: PlayerManager {
+                players: self.players.players.clone(),
+            },
             span: self.span.clone(),
         }
     }
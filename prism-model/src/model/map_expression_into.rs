@@ -126,6 +126,7 @@ impl<AM: Default, A, E, V, S: Clone> super::Model<AM, A, E, V, S> {
             init_constraint,
             labels,
             rewards,
+            players: self.players,
             span: self.span,
         }
     }
@@ -11,6 +11,7 @@ pub use renamed_module_expansion::ModuleExpansionError;
 
 use crate::formulas::FormulaManager;
 use crate::module::RenamedModule;
+use crate::players::PlayerManager;
 use crate::rewards::RewardsManager;
 use crate::{
     Displayable, Expression, Identifier, LabelManager, ModuleManager, VariableInfo,
@@ -34,6 +35,8 @@ pub struct Model<AM, A, E, V, S: Clone> {
     pub labels: LabelManager<E, S>,
     pub rewards: RewardsManager<A, E, S>,
 
+    pub players: PlayerManager<S>,
+
     pub span: S,
 }
 
@@ -49,6 +52,7 @@ impl<AM: Default, A, E, V, S: Clone> Model<AM, A, E, V, S> {
             init_constraint: None,
             labels: LabelManager::new(),
             rewards: RewardsManager::new(),
+            players: PlayerManager::new(),
             span,
         }
     }
@@ -63,6 +67,7 @@ impl<AM: Default, A, E, V, S: Clone> Model<AM, A, E, V, S> {
         init_constraint: Option<E>,
         labels: LabelManager<E, S>,
         rewards: RewardsManager<A, E, S>,
+        players: PlayerManager<S>,
         span: S,
     ) -> Self {
         Self {
@@ -75,6 +80,7 @@ impl<AM: Default, A, E, V, S: Clone> Model<AM, A, E, V, S> {
             init_constraint,
             labels,
             rewards,
+            players,
             span,
         }
     }
@@ -151,6 +157,7 @@ impl<AM, A, V, S: Clone> Model<AM, A, Expression<V, S>, V, S> {
             init_constraint: self.init_constraint.map(|i| i.map_span(map)),
             labels: self.labels.map_span(map),
             rewards: self.rewards.map_span(map),
+            players: self.players.map_span(map),
             span: map(self.span),
         }
     }
@@ -269,6 +276,74 @@ impl<AM, A, V: VariableIdentifierProvider<Expression<V, S>, S>, S: Clone>
 
         self.init_constraint = init_constraint;
     }
+
+    /// The variables that neither have an initial value nor a default one, i.e. unbounded integers
+    /// and floats without `init`. An init constraint determines the initial values on its own.
+    pub fn variables_without_initial_value(&self) -> Vec<Identifier<S>> {
+        if self.init_constraint.is_some() {
+            return Vec::new();
+        }
+        self.variable_manager
+            .variables
+            .iter()
+            .filter(|variable| !variable.is_constant && variable.initial_value.is_none())
+            .filter(|variable| {
+                matches!(
+                    variable.range,
+                    VariableRange::UnboundedInt { .. } | VariableRange::Float { .. }
+                )
+            })
+            .map(|variable| variable.name.clone())
+            .collect()
+    }
+
+    /// An expression that holds exactly in the initial states, i.e. the init constraint or the
+    /// conjunction of the initial values of all variables. Variables without an initial value start
+    /// at their default value. Panics if a variable has no default value, see
+    /// `variables_without_initial_value`.
+    pub fn initial_states_expression(&self) -> Expression<V, S>
+    where
+        V: Clone,
+    {
+        if let Some(init_constraint) = &self.init_constraint {
+            return init_constraint.clone();
+        }
+
+        self.variable_manager
+            .variables
+            .iter()
+            .enumerate()
+            .filter(|(_, variable)| !variable.is_constant)
+            .map(|(variable_index, variable)| {
+                let value =
+                    variable
+                        .initial_value
+                        .clone()
+                        .unwrap_or_else(|| match &variable.range {
+                            VariableRange::BoundedInt { min, .. } => min.clone(),
+                            VariableRange::Boolean { .. } => {
+                                Expression::Bool(false, variable.range.span().clone())
+                            }
+                            VariableRange::UnboundedInt { .. } | VariableRange::Float { .. } => {
+                                panic!("Variable {} does not have an initial value", variable.name)
+                            }
+                        });
+                let identifier = V::get_variable_identifier(variable, variable_index);
+                Expression::Equals(
+                    Box::new(Expression::VarOrConst(identifier, self.span.clone())),
+                    Box::new(value),
+                    self.span.clone(),
+                )
+            })
+            .reduce(|constraints, constraint| {
+                Expression::Conjunction(
+                    Box::new(constraints),
+                    Box::new(constraint),
+                    self.span.clone(),
+                )
+            })
+            .unwrap_or_else(|| Expression::Bool(true, self.span.clone()))
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -276,6 +351,8 @@ pub enum ModelType<S> {
     Dtmc(S),
     Ctmc(S),
     Mdp(S),
+    /// A turn-based stochastic multi-player game, as supported by PRISM-games
+    Smg(S),
 }
 impl<S> ModelType<S> {
     pub fn get_span(&self) -> &S {
@@ -283,6 +360,7 @@ impl<S> ModelType<S> {
             ModelType::Dtmc(s) => s,
             ModelType::Ctmc(s) => s,
             ModelType::Mdp(s) => s,
+            ModelType::Smg(s) => s,
         }
     }
 
@@ -291,6 +369,7 @@ impl<S> ModelType<S> {
             ModelType::Dtmc(span) => ModelType::Dtmc(map(span)),
             ModelType::Ctmc(span) => ModelType::Ctmc(map(span)),
             ModelType::Mdp(span) => ModelType::Mdp(map(span)),
+            ModelType::Smg(span) => ModelType::Smg(map(span)),
         }
     }
 }
@@ -307,6 +386,9 @@ impl<S> Display for ModelType<S> {
             ModelType::Mdp(_) => {
                 write!(f, "mdp")
             }
+            ModelType::Smg(_) => {
+                write!(f, "smg")
+            }
         }
     }
 }
@@ -350,6 +432,7 @@ impl<Ctx, AM, A: Display, E: Displayable<Ctx>, V: Displayable<Ctx>, S: Clone> Di
         for rewards in &self.rewards.rewards {
             writeln!(f, "{}", rewards.displayable(context))?;
         }
+        write!(f, "{}", self.players)?;
 
         Ok(())
     }
@@ -207,6 +207,7 @@ impl<S: Clone> super::Model<(), Identifier<S>, Expression<Identifier<S>, S>, Ide
                 init_constraint,
                 labels,
                 rewards,
+                self.players,
                 self.span,
             ))
         } else {
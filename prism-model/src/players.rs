@@ -0,0 +1,211 @@
+use crate::{Identifier, Model, ModelType};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+pub struct PlayerManager<S: Clone> {
+    pub players: Vec<Player<S>>,
+}
+
+impl<S: Clone> PlayerManager<S> {
+    pub fn new() -> Self {
+        Self {
+            players: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Player<S>> {
+        self.players.get(index)
+    }
+
+    pub fn add(&mut self, player: Player<S>) -> Result<usize, AddPlayerError> {
+        for (index, other_player) in self.players.iter().enumerate() {
+            if other_player.name == player.name {
+                return Err(AddPlayerError::PlayerExists { index });
+            }
+        }
+        let index = self.players.len();
+        self.players.push(player);
+        Ok(index)
+    }
+
+    /// Returns the index of the player that controls the module with the given name
+    pub fn player_of_module(&self, module_name: &str) -> Option<usize> {
+        self.players
+            .iter()
+            .position(|player| player.modules.iter().any(|m| m.name == module_name))
+    }
+
+    /// Returns the index of the player that controls the action with the given name
+    pub fn player_of_action(&self, action_name: &str) -> Option<usize> {
+        self.players
+            .iter()
+            .position(|player| player.actions.iter().any(|a| a.name == action_name))
+    }
+
+    pub fn map_span<S2: Clone, F: Fn(S) -> S2>(self, map: &F) -> PlayerManager<S2> {
+        PlayerManager {
+            players: self.players.into_iter().map(|p| p.map_span(map)).collect(),
+        }
+    }
+}
+
+impl<S: Clone> Default for PlayerManager<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Clone> Display for PlayerManager<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for player in &self.players {
+            writeln!(f, "{}", player)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum AddPlayerError {
+    PlayerExists { index: usize },
+}
+
+/// A player of a stochastic multi-player game. Unsynchronised commands are controlled by the
+/// player of their module, while synchronised commands are controlled by the player of their
+/// action.
+#[derive(Clone)]
+pub struct Player<S: Clone> {
+    pub name: Identifier<S>,
+    pub modules: Vec<Identifier<S>>,
+    pub actions: Vec<Identifier<S>>,
+    pub span: S,
+}
+
+impl<S: Clone> Player<S> {
+    pub fn new(name: Identifier<S>, span: S) -> Self {
+        Self {
+            name,
+            modules: Vec::new(),
+            actions: Vec::new(),
+            span,
+        }
+    }
+
+    pub fn map_span<S2: Clone, F: Fn(S) -> S2>(self, map: &F) -> Player<S2> {
+        Player {
+            name: self.name.map_span(map),
+            modules: self.modules.into_iter().map(|m| m.map_span(map)).collect(),
+            actions: self.actions.into_iter().map(|a| a.map_span(map)).collect(),
+            span: map(self.span),
+        }
+    }
+}
+
+impl<S: Clone> Display for Player<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "player {}", self.name)?;
+        let elements = self
+            .modules
+            .iter()
+            .map(|m| m.name.clone())
+            .chain(self.actions.iter().map(|a| format!("[{}]", a)))
+            .collect::<Vec<_>>();
+        writeln!(f, "    {}", elements.join(", "))?;
+        writeln!(f, "endplayer")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PlayerError<S: Clone> {
+    /// Players can only be declared in `smg` models
+    PlayerOutsideGame {
+        player: S,
+        model_type: S,
+    },
+    /// `smg` models must declare at least one player
+    MissingPlayers {
+        model_type: S,
+    },
+    UnknownModule {
+        name: Identifier<S>,
+    },
+    UnknownAction {
+        name: Identifier<S>,
+    },
+    /// Every module and action can be controlled by at most one player
+    DuplicateControl {
+        name: Identifier<S>,
+        previous_occurrence: S,
+    },
+}
+
+impl<AM, E, V, S: Clone> Model<AM, Identifier<S>, E, V, S> {
+    /// Checks that the players of the model only control modules and actions that exist and that
+    /// no module or action is controlled by several players. This must be called after renamed
+    /// modules were expanded.
+    pub fn check_players(&self) -> Result<(), Vec<PlayerError<S>>> {
+        let mut errors = Vec::new();
+
+        match &self.model_type {
+            ModelType::Smg(span) => {
+                if self.players.players.is_empty() {
+                    errors.push(PlayerError::MissingPlayers {
+                        model_type: span.clone(),
+                    });
+                }
+            }
+            model_type => {
+                for player in &self.players.players {
+                    errors.push(PlayerError::PlayerOutsideGame {
+                        player: player.span.clone(),
+                        model_type: model_type.get_span().clone(),
+                    });
+                }
+            }
+        }
+
+        let mut controlled_modules = HashMap::new();
+        let mut controlled_actions = HashMap::new();
+        for player in &self.players.players {
+            for module in &player.modules {
+                if self.modules.get_index_by_name(module).is_none() {
+                    errors.push(PlayerError::UnknownModule {
+                        name: module.clone(),
+                    });
+                } else if let Some(previous) =
+                    controlled_modules.insert(module.name.clone(), module.span.clone())
+                {
+                    errors.push(PlayerError::DuplicateControl {
+                        name: module.clone(),
+                        previous_occurrence: previous,
+                    });
+                }
+            }
+            for action in &player.actions {
+                let exists = self.modules.modules.iter().any(|module| {
+                    module
+                        .commands
+                        .iter()
+                        .any(|command| command.action.as_ref() == Some(action))
+                });
+                if !exists {
+                    errors.push(PlayerError::UnknownAction {
+                        name: action.clone(),
+                    });
+                } else if let Some(previous) =
+                    controlled_actions.insert(action.name.clone(), action.span.clone())
+                {
+                    errors.push(PlayerError::DuplicateControl {
+                        name: action.clone(),
+                        previous_occurrence: previous,
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -3,7 +3,15 @@ use crate::{
     CyclicDependency, Expression, FormulaManager, Identifier, LabelManager, VariableManager,
     VariableReference,
 };
-use probabilistic_properties::Query;
+use probabilistic_properties::{Query, StateFormula};
+
+/// A property of a properties file. Named properties can be referenced by later properties
+/// using the syntax for labels, i.e. `"name"`.
+#[derive(Clone)]
+pub struct NamedProperty<Q, S: Clone> {
+    pub name: Option<Identifier<S>>,
+    pub query: Q,
+}
 
 pub trait SubstitutableQuery<S: Clone> {
     fn substitute_labels(
@@ -12,12 +20,32 @@ pub trait SubstitutableQuery<S: Clone> {
         labels: &LabelManager<Expression<Identifier<S>, S>, S>,
     );
 
+    /// Replaces the label `name` by `expression`, which takes the span of each occurrence, see
+    /// `Expression::substitute_label_at_occurrence`.
+    fn substitute_label_at_occurrence(
+        &mut self,
+        default_span: S,
+        name: &Identifier<S>,
+        expression: &Expression<Identifier<S>, S>,
+    );
+
     fn substitute_formulas(
         &mut self,
         default_span: S,
         formulas: &FormulaManager<Expression<Identifier<S>, S>, S>,
     ) -> Result<(), CyclicDependency<S>>;
 
+    /// Replaces references to the given properties. If the whole query is a reference, it is
+    /// replaced by the referenced query. Otherwise, references to state formulas are replaced by
+    /// these formulas. References that cannot be resolved are left untouched, so they are
+    /// reported as unknown identifiers later on.
+    fn substitute_property_references(
+        &mut self,
+        default_span: S,
+        properties: &[NamedProperty<Self, S>],
+    ) where
+        Self: Sized;
+
     fn replace_identifiers_by_variable_indices<R>(
         self,
         variable_manager: &VariableManager<R, S>,
@@ -52,6 +80,22 @@ impl<S: Clone> SubstitutableQuery<S>
             ex.substitute_labels(default_span.clone(), labels);
         });
     }
+    fn substitute_label_at_occurrence(
+        &mut self,
+        default_span: S,
+        name: &Identifier<S>,
+        expression: &Expression<Identifier<S>, S>,
+    ) {
+        self.as_mut().map_e(&mut |ex| {
+            ex.substitute_label_at_occurrence(default_span.clone(), name, expression);
+        });
+        self.as_mut().map_f(&mut |ex| {
+            ex.substitute_label_at_occurrence(default_span.clone(), name, expression);
+        });
+        self.as_mut().map_i(&mut |ex| {
+            ex.substitute_label_at_occurrence(default_span.clone(), name, expression);
+        });
+    }
     fn substitute_formulas(
         &mut self,
         default_span: S,
@@ -73,6 +117,44 @@ impl<S: Clone> SubstitutableQuery<S>
         Ok(())
     }
 
+    fn substitute_property_references(
+        &mut self,
+        default_span: S,
+        properties: &[NamedProperty<Self, S>],
+    ) {
+        let find = |name: &Identifier<S>| {
+            properties
+                .iter()
+                .find(|p| p.name.as_ref() == Some(name))
+                .map(|p| &p.query)
+        };
+
+        if let Query::StateFormula(StateFormula::Expression(Expression::Label(name, _))) = self
+            && let Some(query) = find(name)
+        {
+            *self = query.clone();
+            return;
+        }
+
+        self.visit_state_formulas_mut(&mut |formula| {
+            if let StateFormula::Expression(Expression::Label(name, _)) = formula
+                && let Some(Query::StateFormula(referenced)) = find(name)
+            {
+                *formula = referenced.clone();
+            }
+        });
+
+        for property in properties {
+            if let (Some(name), Query::StateFormula(StateFormula::Expression(referenced))) =
+                (&property.name, &property.query)
+            {
+                self.as_mut().map_e(&mut |ex| {
+                    ex.substitute_label(default_span.clone(), name, referenced);
+                });
+            }
+        }
+    }
+
     fn replace_identifiers_by_variable_indices<R>(
         self,
         variable_manager: &VariableManager<R, S>,
@@ -0,0 +1,512 @@
+use crate::{Expression, Identifier, Model, VariableManager, VariableRange, VariableReference};
+use probabilistic_properties::Query;
+
+/// The type of the value of an expression
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExpressionType {
+    Int,
+    Float,
+    Bool,
+}
+
+impl ExpressionType {
+    pub fn of_range<E, S: Clone>(range: &VariableRange<E, S>) -> Self {
+        match range {
+            VariableRange::BoundedInt { .. } | VariableRange::UnboundedInt { .. } => Self::Int,
+            VariableRange::Float { .. } => Self::Float,
+            VariableRange::Boolean { .. } => Self::Bool,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ExpressionType::Int => "int",
+            ExpressionType::Float => "double",
+            ExpressionType::Bool => "bool",
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        *self != ExpressionType::Bool
+    }
+
+    /// Whether a value of type `value` may be stored in a variable or constant of this type. Ints
+    /// are converted to doubles, but not vice versa.
+    pub fn accepts(&self, value: ExpressionType) -> bool {
+        *self == value || (*self == ExpressionType::Float && value == ExpressionType::Int)
+    }
+
+    fn combine_numbers(self, other: ExpressionType) -> ExpressionType {
+        if self == ExpressionType::Int && other == ExpressionType::Int {
+            ExpressionType::Int
+        } else {
+            ExpressionType::Float
+        }
+    }
+}
+
+/// The types that are accepted at some position of an expression
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExpectedType {
+    Int,
+    Number,
+    Bool,
+}
+
+impl ExpectedType {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ExpectedType::Int => "int",
+            ExpectedType::Number => "int or double",
+            ExpectedType::Bool => "bool",
+        }
+    }
+
+    fn accepts(&self, found: ExpressionType) -> bool {
+        match self {
+            ExpectedType::Int => found == ExpressionType::Int,
+            ExpectedType::Number => found.is_number(),
+            ExpectedType::Bool => found == ExpressionType::Bool,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TypeError<S: Clone> {
+    UnexpectedType {
+        span: S,
+        expected: ExpectedType,
+        found: ExpressionType,
+    },
+    /// The operands of `=`, `!=` or the branches of `?:` must either both be numbers or both be
+    /// booleans
+    IncompatibleOperands {
+        span: S,
+        lhs: (ExpressionType, S),
+        rhs: (ExpressionType, S),
+    },
+    UnknownFunction {
+        name: Identifier<S>,
+    },
+    WrongArgumentCount {
+        name: Identifier<S>,
+        span: S,
+        expected: ArgumentCount,
+        found: usize,
+    },
+    AssignmentToConstant {
+        target_span: S,
+        definition: S,
+    },
+    IncompatibleValue {
+        variable_name: Identifier<S>,
+        variable_type: ExpressionType,
+        value_span: S,
+        value_type: ExpressionType,
+    },
+    /// A constant value lies outside the bounds of the variable it is assigned to. Only values and
+    /// bounds that are integer literals are checked here; everything that depends on constants is
+    /// checked when the model is built.
+    ValueOutOfRange {
+        variable_name: Identifier<S>,
+        range_span: S,
+        value_span: S,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArgumentCount {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl ArgumentCount {
+    fn accepts(&self, count: usize) -> bool {
+        match self {
+            ArgumentCount::Exactly(expected) => count == *expected,
+            ArgumentCount::AtLeast(expected) => count >= *expected,
+        }
+    }
+}
+
+impl<S: Clone> Expression<VariableReference, S> {
+    /// Determines the type of the expression and reports every type error in it. Returns `None` if
+    /// the type cannot be determined because of an error.
+    pub fn check_type<E>(
+        &self,
+        variable_manager: &VariableManager<E, S>,
+        errors: &mut Vec<TypeError<S>>,
+    ) -> Option<ExpressionType> {
+        let mut checker = TypeChecker {
+            variable_manager,
+            errors,
+        };
+        checker.infer(self)
+    }
+
+    /// The type of an expression that passed the type checker, e.g. when building the model.
+    /// Panics if the type cannot be determined.
+    pub fn checked_type<E>(&self, variable_manager: &VariableManager<E, S>) -> ExpressionType {
+        self.check_type(variable_manager, &mut Vec::new())
+            .expect("The expression must pass the type checker")
+    }
+
+    /// Like `check_type`, but additionally reports an error if the expression does not have the
+    /// expected type.
+    pub fn check_expected_type<E>(
+        &self,
+        expected: ExpectedType,
+        variable_manager: &VariableManager<E, S>,
+        errors: &mut Vec<TypeError<S>>,
+    ) {
+        let mut checker = TypeChecker {
+            variable_manager,
+            errors,
+        };
+        checker.expect(self, expected);
+    }
+}
+
+struct TypeChecker<'a, E, S: Clone> {
+    variable_manager: &'a VariableManager<E, S>,
+    errors: &'a mut Vec<TypeError<S>>,
+}
+
+impl<'a, E, S: Clone> TypeChecker<'a, E, S> {
+    fn expect(
+        &mut self,
+        expression: &Expression<VariableReference, S>,
+        expected: ExpectedType,
+    ) -> Option<ExpressionType> {
+        let found = self.infer(expression)?;
+        if expected.accepts(found) {
+            Some(found)
+        } else {
+            self.errors.push(TypeError::UnexpectedType {
+                span: expression.span().clone(),
+                expected,
+                found,
+            });
+            None
+        }
+    }
+
+    fn numbers(
+        &mut self,
+        lhs: &Expression<VariableReference, S>,
+        rhs: &Expression<VariableReference, S>,
+    ) -> Option<ExpressionType> {
+        let lhs = self.expect(lhs, ExpectedType::Number);
+        let rhs = self.expect(rhs, ExpectedType::Number);
+        Some(lhs?.combine_numbers(rhs?))
+    }
+
+    fn booleans(
+        &mut self,
+        lhs: &Expression<VariableReference, S>,
+        rhs: &Expression<VariableReference, S>,
+    ) -> Option<ExpressionType> {
+        let lhs = self.expect(lhs, ExpectedType::Bool);
+        let rhs = self.expect(rhs, ExpectedType::Bool);
+        lhs?;
+        rhs?;
+        Some(ExpressionType::Bool)
+    }
+
+    // Both numbers or both booleans, as for `=` and the branches of `?:`
+    fn compatible(
+        &mut self,
+        lhs: &Expression<VariableReference, S>,
+        rhs: &Expression<VariableReference, S>,
+        span: &S,
+    ) -> Option<ExpressionType> {
+        let lhs_type = self.infer(lhs);
+        let rhs_type = self.infer(rhs);
+        let (lhs_type, rhs_type) = (lhs_type?, rhs_type?);
+        if lhs_type.is_number() && rhs_type.is_number() {
+            Some(lhs_type.combine_numbers(rhs_type))
+        } else if lhs_type == ExpressionType::Bool && rhs_type == ExpressionType::Bool {
+            Some(ExpressionType::Bool)
+        } else {
+            self.errors.push(TypeError::IncompatibleOperands {
+                span: span.clone(),
+                lhs: (lhs_type, lhs.span().clone()),
+                rhs: (rhs_type, rhs.span().clone()),
+            });
+            None
+        }
+    }
+
+    fn infer(&mut self, expression: &Expression<VariableReference, S>) -> Option<ExpressionType> {
+        match expression {
+            Expression::Int(_, _) => Some(ExpressionType::Int),
+            Expression::Float(_, _) => Some(ExpressionType::Float),
+            Expression::Bool(_, _) => Some(ExpressionType::Bool),
+            Expression::VarOrConst(reference, _) => self
+                .variable_manager
+                .get(reference)
+                .map(|variable| ExpressionType::of_range(&variable.range)),
+            Expression::Label(_, _) => Some(ExpressionType::Bool),
+            Expression::Function(name, arguments, span) => {
+                self.infer_function(name, arguments, span)
+            }
+            Expression::Minus(inner, _) => self.expect(inner, ExpectedType::Number),
+            Expression::Multiplication(lhs, rhs, _)
+            | Expression::Addition(lhs, rhs, _)
+            | Expression::Subtraction(lhs, rhs, _) => self.numbers(lhs, rhs),
+            Expression::Division(lhs, rhs, _) => {
+                self.numbers(lhs, rhs).map(|_| ExpressionType::Float)
+            }
+            Expression::LessThan(lhs, rhs, _)
+            | Expression::LessOrEqual(lhs, rhs, _)
+            | Expression::GreaterThan(lhs, rhs, _)
+            | Expression::GreaterOrEqual(lhs, rhs, _) => {
+                self.numbers(lhs, rhs).map(|_| ExpressionType::Bool)
+            }
+            Expression::Equals(lhs, rhs, span) | Expression::NotEquals(lhs, rhs, span) => self
+                .compatible(lhs, rhs, span)
+                .map(|_| ExpressionType::Bool),
+            Expression::Negation(inner, _) => self.expect(inner, ExpectedType::Bool),
+            Expression::Conjunction(lhs, rhs, _)
+            | Expression::Disjunction(lhs, rhs, _)
+            | Expression::IfAndOnlyIf(lhs, rhs, _)
+            | Expression::Implies(lhs, rhs, _) => self.booleans(lhs, rhs),
+            Expression::Ternary(condition, lhs, rhs, span) => {
+                let condition = self.expect(condition, ExpectedType::Bool);
+                let result = self.compatible(lhs, rhs, span);
+                condition?;
+                result
+            }
+        }
+    }
+
+    fn infer_function(
+        &mut self,
+        name: &Identifier<S>,
+        arguments: &[Expression<VariableReference, S>],
+        span: &S,
+    ) -> Option<ExpressionType> {
+        use ArgumentCount::{AtLeast, Exactly};
+        let (argument_count, argument_type) = match &name.name[..] {
+            "min" | "max" => (AtLeast(2), ExpectedType::Number),
+            "floor" | "ceil" | "round" => (Exactly(1), ExpectedType::Number),
+            "pow" | "log" => (Exactly(2), ExpectedType::Number),
+            "mod" => (Exactly(2), ExpectedType::Int),
+            _ => {
+                self.errors
+                    .push(TypeError::UnknownFunction { name: name.clone() });
+                return None;
+            }
+        };
+
+        let argument_types = arguments
+            .iter()
+            .map(|argument| self.expect(argument, argument_type))
+            .collect::<Vec<_>>();
+        if !argument_count.accepts(arguments.len()) {
+            self.errors.push(TypeError::WrongArgumentCount {
+                name: name.clone(),
+                span: span.clone(),
+                expected: argument_count,
+                found: arguments.len(),
+            });
+            return None;
+        }
+        let argument_types = argument_types.into_iter().collect::<Option<Vec<_>>>()?;
+
+        Some(match &name.name[..] {
+            "floor" | "ceil" | "round" | "mod" => ExpressionType::Int,
+            "log" => ExpressionType::Float,
+            _ => argument_types
+                .into_iter()
+                .reduce(ExpressionType::combine_numbers)
+                .unwrap(),
+        })
+    }
+}
+
+impl<V, S: Clone> Expression<V, S> {
+    /// The value of the expression if it is a (possibly negated) integer literal
+    fn as_int_literal(&self) -> Option<i64> {
+        match self {
+            Expression::Int(value, _) => Some(*value),
+            Expression::Minus(inner, _) => inner.as_int_literal().map(|value| -value),
+            _ => None,
+        }
+    }
+}
+
+impl<S: Clone> VariableRange<Expression<VariableReference, S>, S> {
+    /// Reports an error if both the value and the bounds are integer literals and the value lies
+    /// outside the bounds
+    fn check_literal_value(
+        &self,
+        variable_name: Identifier<S>,
+        value: &Expression<VariableReference, S>,
+        errors: &mut Vec<TypeError<S>>,
+    ) {
+        let VariableRange::BoundedInt { min, max, span } = self else {
+            return;
+        };
+        if let (Some(value_literal), Some(min), Some(max)) = (
+            value.as_int_literal(),
+            min.as_int_literal(),
+            max.as_int_literal(),
+        ) && !(min..=max).contains(&value_literal)
+        {
+            errors.push(TypeError::ValueOutOfRange {
+                variable_name,
+                range_span: span.clone(),
+                value_span: value.span().clone(),
+                value: value_literal,
+                min,
+                max,
+            });
+        }
+    }
+}
+
+impl<AM, A, S: Clone> Model<AM, A, Expression<VariableReference, S>, VariableReference, S> {
+    /// Checks the types of all expressions in the model, so that type errors are found before the
+    /// model is built instead of during the exploration of the state space. Formulas are checked
+    /// where they are used. Initial values and updates are only checked against the bounds of
+    /// their variable if both are integer literals, see `TypeError::ValueOutOfRange`.
+    pub fn check_types(&self) -> Result<(), Vec<TypeError<S>>> {
+        let mut errors = Vec::new();
+        let variables = &self.variable_manager;
+
+        for variable in &variables.variables {
+            if let VariableRange::BoundedInt { min, max, .. } = &variable.range {
+                min.check_expected_type(ExpectedType::Int, variables, &mut errors);
+                max.check_expected_type(ExpectedType::Int, variables, &mut errors);
+            }
+            if let Some(initial_value) = &variable.initial_value {
+                let variable_type = ExpressionType::of_range(&variable.range);
+                if let Some(value_type) = initial_value.check_type(variables, &mut errors)
+                    && !variable_type.accepts(value_type)
+                {
+                    errors.push(TypeError::IncompatibleValue {
+                        variable_name: variable.name.clone(),
+                        variable_type,
+                        value_span: initial_value.span().clone(),
+                        value_type,
+                    });
+                }
+                variable.range.check_literal_value(
+                    variable.name.clone(),
+                    initial_value,
+                    &mut errors,
+                );
+            }
+        }
+
+        if let Some(init_constraint) = &self.init_constraint {
+            init_constraint.check_expected_type(ExpectedType::Bool, variables, &mut errors);
+        }
+
+        for module in &self.modules.modules {
+            for command in &module.commands {
+                command
+                    .guard
+                    .check_expected_type(ExpectedType::Bool, variables, &mut errors);
+                for update in &command.updates {
+                    update.probability.check_expected_type(
+                        ExpectedType::Number,
+                        variables,
+                        &mut errors,
+                    );
+                    for assignment in &update.assignments {
+                        let Some(target) = variables.get(&assignment.target) else {
+                            continue;
+                        };
+                        let value_type = assignment.value.check_type(variables, &mut errors);
+                        if target.is_constant {
+                            errors.push(TypeError::AssignmentToConstant {
+                                target_span: assignment.target_span.clone(),
+                                definition: target.span.clone(),
+                            });
+                            continue;
+                        }
+                        let variable_type = ExpressionType::of_range(&target.range);
+                        if let Some(value_type) = value_type
+                            && !variable_type.accepts(value_type)
+                        {
+                            errors.push(TypeError::IncompatibleValue {
+                                variable_name: Identifier {
+                                    name: target.name.name.clone(),
+                                    span: assignment.target_span.clone(),
+                                },
+                                variable_type,
+                                value_span: assignment.value.span().clone(),
+                                value_type,
+                            });
+                        }
+                        target.range.check_literal_value(
+                            Identifier {
+                                name: target.name.name.clone(),
+                                span: assignment.target_span.clone(),
+                            },
+                            &assignment.value,
+                            &mut errors,
+                        );
+                    }
+                }
+            }
+        }
+
+        for label in &self.labels.labels {
+            label
+                .condition
+                .check_expected_type(ExpectedType::Bool, variables, &mut errors);
+        }
+
+        for rewards in &self.rewards.rewards {
+            for entry in &rewards.entries {
+                entry
+                    .condition
+                    .check_expected_type(ExpectedType::Bool, variables, &mut errors);
+                entry
+                    .value
+                    .check_expected_type(ExpectedType::Number, variables, &mut errors);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+type ResolvedQuery<S> = Query<
+    Expression<VariableReference, S>,
+    Expression<VariableReference, S>,
+    Expression<VariableReference, S>,
+>;
+
+/// Checks the types of all expressions in a query. State formulas must be booleans, while bounds
+/// on probabilities, rewards and steps must be numbers.
+pub fn check_query_types<E, S: Clone>(
+    query: &ResolvedQuery<S>,
+    variable_manager: &VariableManager<E, S>,
+) -> Result<(), Vec<TypeError<S>>> {
+    let mut errors = Vec::new();
+    let mut check = |expression: Expression<VariableReference, S>, expected| {
+        expression.check_expected_type(expected, variable_manager, &mut errors);
+    };
+    query
+        .clone()
+        .map_i(&mut |e| check(e, ExpectedType::Number))
+        .map_f(&mut |e| check(e, ExpectedType::Number))
+        .map_e(&mut |e| check(e, ExpectedType::Bool));
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
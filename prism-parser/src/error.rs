@@ -55,6 +55,16 @@ pub enum PrismParserValidationError<S: Clone> {
     UnknownVariable {
         identifier: Identifier<S>,
     },
+    TypeError {
+        error: prism_model::TypeError<S>,
+    },
+    PlayerError {
+        error: prism_model::PlayerError<S>,
+    },
+    /// An unbounded integer or float variable without `init`, which has no default initial value
+    MissingInitialValue {
+        variable: Identifier<S>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -66,6 +76,8 @@ pub enum ElementKind {
     Formula,
     Reward,
     Module,
+    Property,
+    Player,
 }
 
 impl<'a, S: Clone, T> Into<PrismParserError<'a, S, T>> for PrismParserValidationError<S> {
@@ -14,6 +14,7 @@ pub enum Token {
     Pta,
     Pomdp,
     Popta,
+    Smg,
 
     Module,
     EndModule,
@@ -25,6 +26,8 @@ pub enum Token {
     EndInit,
     Rewards,
     EndRewards,
+    Player,
+    EndPlayer,
 
     Int,
     Double,
@@ -55,6 +58,10 @@ pub enum Token {
     Finally,
     Generally,
     Until,
+    Next,
+    WeakUntil,
+
+    Filter,
 
     Identifier(String),
 
@@ -123,6 +130,7 @@ impl Display for Token {
             Token::Pta => write!(f, "pta"),
             Token::Pomdp => write!(f, "pomdp"),
             Token::Popta => write!(f, "popta"),
+            Token::Smg => write!(f, "smg"),
             Token::Module => write!(f, "module"),
             Token::EndModule => write!(f, "endmodule"),
             Token::Const => write!(f, "const"),
@@ -133,6 +141,8 @@ impl Display for Token {
             Token::EndInit => write!(f, "endinit"),
             Token::Rewards => write!(f, "rewards"),
             Token::EndRewards => write!(f, "endrewards"),
+            Token::Player => write!(f, "player"),
+            Token::EndPlayer => write!(f, "endplayer"),
             Token::Int => write!(f, "int"),
             Token::Double => write!(f, "double"),
             Token::Bool => write!(f, "bool"),
@@ -155,6 +165,9 @@ impl Display for Token {
             Token::Finally => write!(f, "F"),
             Token::Generally => write!(f, "G"),
             Token::Until => write!(f, "U"),
+            Token::Next => write!(f, "X"),
+            Token::WeakUntil => write!(f, "W"),
+            Token::Filter => write!(f, "filter"),
             Token::Identifier(_) => write!(f, "[Identifier]"),
             Token::LeftSqBracket => write!(f, "["),
             Token::RightSqBracket => write!(f, "]"),
@@ -260,6 +273,7 @@ fn lexer<'a>()
         "pta" => Token::Pta,
         "pomdp" => Token::Pomdp,
         "popta" => Token::Popta,
+        "smg" => Token::Smg,
 
         "module" => Token::Module,
         "endmodule" => Token::EndModule,
@@ -271,6 +285,8 @@ fn lexer<'a>()
         "endinit" => Token::EndInit,
         "rewards" => Token::Rewards,
         "endrewards" => Token::EndRewards,
+        "player" => Token::Player,
+        "endplayer" => Token::EndPlayer,
 
         "int" => Token::Int,
         "double" => Token::Double,
@@ -288,9 +304,9 @@ fn lexer<'a>()
         "TMin" | "Tmin" => Token::TMin,
         "T" => Token::T,
 
-        "LRA" => Token::LRA,
-        "LRAMax" | "LRAmax" => Token::LRAMax,
-        "LRAMin" | "LRAmin" => Token::LRAMin,
+        "LRA" | "S" => Token::LRA,
+        "LRAMax" | "LRAmax" | "Smax" => Token::LRAMax,
+        "LRAMin" | "LRAmin" | "Smin" => Token::LRAMin,
 
         "I" => Token::Instantaneous,
         "C" => Token::Cumulative,
@@ -300,6 +316,11 @@ fn lexer<'a>()
 
         "F" => Token::Finally,
         "G" => Token::Generally,
+        "U" => Token::Until,
+        "X" => Token::Next,
+        "W" => Token::WeakUntil,
+
+        "filter" => Token::Filter,
 
         "true" => Token::True,
         "false" => Token::False,
@@ -16,28 +16,40 @@ pub struct ParseResult<'a, O> {
 }
 
 pub struct ParseResults<'a, 'b> {
-    pub model: ParseResult<
-        'a,
-        prism_model::Model<
-            (),
-            prism_model::Identifier<Span>,
-            prism_model::Expression<prism_model::VariableReference, Span>,
-            prism_model::VariableReference,
-            Span,
-        >,
-    >,
-    pub properties: Vec<
-        ParseResult<
-            'b,
-            probabilistic_properties::Query<
-                prism_model::Expression<prism_model::VariableReference, Span>,
-                prism_model::Expression<prism_model::VariableReference, Span>,
-                prism_model::Expression<prism_model::VariableReference, Span>,
-            >,
-        >,
-    >,
+    pub model: ParseResult<'a, ResolvedModel>,
+    pub properties: Vec<ParseResult<'b, ResolvedQuery>>,
 }
 
+pub struct PropertiesFileParseResults<'a, 'b> {
+    pub model: ParseResult<'a, ResolvedModel>,
+    pub properties: ParseResult<'b, Vec<prism_model::NamedProperty<ResolvedQuery, Span>>>,
+}
+
+type UnresolvedModel = prism_model::Model<
+    (),
+    prism_model::Identifier<Span>,
+    prism_model::Expression<prism_model::Identifier<Span>, Span>,
+    prism_model::Identifier<Span>,
+    Span,
+>;
+pub(crate) type UnresolvedQuery = probabilistic_properties::Query<
+    prism_model::Expression<prism_model::Identifier<Span>, Span>,
+    prism_model::Expression<prism_model::Identifier<Span>, Span>,
+    prism_model::Expression<prism_model::Identifier<Span>, Span>,
+>;
+type ResolvedModel = prism_model::Model<
+    (),
+    prism_model::Identifier<Span>,
+    prism_model::Expression<prism_model::VariableReference, Span>,
+    prism_model::VariableReference,
+    Span,
+>;
+type ResolvedQuery = probabilistic_properties::Query<
+    prism_model::Expression<prism_model::VariableReference, Span>,
+    prism_model::Expression<prism_model::VariableReference, Span>,
+    prism_model::Expression<prism_model::VariableReference, Span>,
+>;
+
 pub fn parse_prism<'a, 'b, P: AsRef<str>>(
     source: &'a str,
     properties: &[P],
@@ -50,29 +62,20 @@ pub fn parse_prism<'a, 'b, P: AsRef<str>>(
         .map(|_| Vec::new())
         .collect::<Vec<_>>();
     if let Some(lexer_output) = lex(source, &mut model_errors) {
-        let (output, parse_errors) = parser::program_parser()
-            .map_with(|ast, e| (ast, e.span()))
-            .parse(
-                lexer_output
-                    .as_slice()
-                    .map((source.len()..source.len()).into(), |(t, s)| (t, s)),
-            )
-            .into_output_errors();
-        process_parser_errors(&mut model_errors, parse_errors);
-        let output = output.map(|(o, _)| o);
+        let output = parse_program(source, lexer_output, &mut model_errors);
 
         let lexed_properties = properties
             .iter()
             .zip(property_errors.iter_mut())
             .map(|(p, errs)| lex(p.as_ref(), errs))
             .collect::<Vec<_>>();
-        let mut parsed_properties = lexed_properties
+        let parsed_properties = lexed_properties
             .into_iter()
             .zip(properties)
             .zip(property_errors.iter_mut())
             .map(|((lexer_output, source), errs)| {
                 let source = source.as_ref();
-                lexer_output.map_or(None, |lexer_output| {
+                lexer_output.and_then(|lexer_output| {
                     let (output, parse_errors) = parser::query_parser()
                         .map_with(|ast, e| (ast, e.span()))
                         .parse(
@@ -88,97 +91,12 @@ pub fn parse_prism<'a, 'b, P: AsRef<str>>(
             })
             .collect::<Vec<_>>();
 
-        let (output, properties) = match output {
-            Some(mut output) => {
-                parsed_properties
-                    .iter_mut()
-                    .zip(property_errors.iter_mut())
-                    .for_each(|(p_option, errs)| {
-                        if let Some(p) = p_option {
-                            use prism_model::SubstitutableQuery;
-                            p.substitute_labels(SimpleSpan::new(0, 1), &output.labels);
-                            let substitution =
-                                p.substitute_formulas(SimpleSpan::new(0, 1), &output.formulas);
-                            if let Err(err) = substitution {
-                                errs.push(
-                                    PrismParserValidationError::CyclicFormulaDependency {
-                                        cycle: err,
-                                    }
-                                    .into(),
-                                );
-                                *p_option = None
-                            }
-                        }
-                    });
-
-                if let Err(err) = output.substitute_formulas(SimpleSpan::new(0, 1)) {
-                    model_errors.push(
-                        PrismParserValidationError::CyclicFormulaDependency { cycle: err }.into(),
-                    )
-                }
-
-                if let Err(error) = output.expand_renamed_models() {
-                    model_errors
-                        .push(PrismParserValidationError::ModuleExpansionError { error }.into());
-                    let mut empty_vec = Vec::with_capacity(properties.len());
-                    for _ in 0..properties.len() {
-                        empty_vec.push(None);
-                    }
-                    (None, empty_vec)
-                } else {
-                    let properties = parsed_properties
-                        .into_iter()
-                        .zip(property_errors.iter_mut())
-                        .map(|(p, errs)| {
-                            p.map_or(None, |p| {
-                                use prism_model::SubstitutableQuery;
-                                match p.replace_identifiers_by_variable_indices(
-                                    &output.variable_manager,
-                                ) {
-                                    Ok(p) => Some(p),
-                                    Err(e) => {
-                                        for err in e {
-                                            errs.push(
-                                                PrismParserValidationError::UnknownVariable {
-                                                    identifier: err.identifier,
-                                                }
-                                                .into(),
-                                            );
-                                        }
-                                        None
-                                    }
-                                }
-                            })
-                        })
-                        .collect::<Vec<_>>();
-
-                    (
-                        match output.replace_identifiers_by_variable_indices() {
-                            Ok(output) => Some(output),
-                            Err(errs) => {
-                                for err in errs {
-                                    model_errors.push(
-                                        PrismParserValidationError::UnknownVariable {
-                                            identifier: err.identifier,
-                                        }
-                                        .into(),
-                                    )
-                                }
-                                None
-                            }
-                        },
-                        properties,
-                    )
-                }
-            }
-            None => {
-                let mut empty_vec = Vec::with_capacity(properties.len());
-                for _ in 0..properties.len() {
-                    empty_vec.push(None);
-                }
-                (None, empty_vec)
-            }
-        };
+        let (output, properties) = process_model_and_properties(
+            output,
+            parsed_properties,
+            &mut model_errors,
+            &mut property_errors,
+        );
 
         let properties = properties
             .into_iter()
@@ -220,6 +138,281 @@ pub fn parse_prism<'a, 'b, P: AsRef<str>>(
     }
 }
 
+/// Parses a model together with a PRISM properties file. Constants declared in the properties
+/// file are added to the model. Properties may reference named properties that precede them in
+/// the file.
+pub fn parse_prism_with_properties_file<'a, 'b>(
+    source: &'a str,
+    properties_source: &'a str,
+) -> PropertiesFileParseResults<'b, 'b> {
+    let source_character_to_line = CharacterToLineMap::from_str(source);
+    let properties_character_to_line = CharacterToLineMap::from_str(properties_source);
+
+    let mut model_errors = Vec::new();
+    let mut properties_errors = Vec::new();
+    let Some(lexer_output) = lex(source, &mut model_errors) else {
+        return PropertiesFileParseResults {
+            model: ParseResult {
+                output: None,
+                character_to_lines: None,
+                errors: model_errors,
+            },
+            properties: ParseResult {
+                output: None,
+                character_to_lines: None,
+                errors: properties_errors,
+            },
+        };
+    };
+    let mut output = parse_program(source, lexer_output, &mut model_errors);
+
+    let properties_file = lex(properties_source, &mut properties_errors).and_then(|lexer_output| {
+        let (output, parse_errors) = parser::properties_file_parser()
+            .parse(lexer_output.as_slice().map(
+                (properties_source.len()..properties_source.len()).into(),
+                |(t, s)| (t, s),
+            ))
+            .into_output_errors();
+        process_parser_errors(&mut properties_errors, parse_errors);
+        output
+    });
+
+    let (output, properties) = match properties_file {
+        Some(properties_file) => {
+            if let Some(output) = &mut output {
+                for c in properties_file.consts {
+                    let span = c.span;
+                    if let Err(prism_model::VariableAddError::VariableExists { reference }) =
+                        output.variable_manager.add_variable(c)
+                    {
+                        properties_errors.push(
+                            PrismParserValidationError::DuplicateElement {
+                                previous_occurrence: output
+                                    .variable_manager
+                                    .get(&reference)
+                                    .unwrap()
+                                    .span,
+                                new_definition: span,
+                                kind: error::ElementKind::Const,
+                            }
+                            .into(),
+                        );
+                    }
+                }
+            }
+
+            let mut properties = properties_file.properties;
+            for i in 0..properties.len() {
+                use prism_model::SubstitutableQuery;
+                let (previous, remaining) = properties.split_at_mut(i);
+                remaining[0]
+                    .query
+                    .substitute_property_references(SimpleSpan::new(0, 1), previous);
+            }
+            let names = properties
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>();
+
+            let mut property_errors = (0..properties.len())
+                .map(|_| Vec::new())
+                .collect::<Vec<_>>();
+            let (output, queries) = process_model_and_properties(
+                output,
+                properties.into_iter().map(|p| Some(p.query)).collect(),
+                &mut model_errors,
+                &mut property_errors,
+            );
+            properties_errors.extend(property_errors.into_iter().flatten());
+
+            let properties = names
+                .into_iter()
+                .zip(queries)
+                .map(|(name, query)| query.map(|query| prism_model::NamedProperty { name, query }))
+                .collect::<Option<Vec<_>>>();
+            (output, properties)
+        }
+        None => (
+            process_model_and_properties(output, Vec::new(), &mut model_errors, &mut []).0,
+            None,
+        ),
+    };
+
+    PropertiesFileParseResults {
+        model: ParseResult {
+            output,
+            character_to_lines: Some(source_character_to_line),
+            errors: model_errors,
+        },
+        properties: ParseResult {
+            output: properties,
+            character_to_lines: Some(properties_character_to_line),
+            errors: properties_errors,
+        },
+    }
+}
+
+fn parse_program(
+    source: &str,
+    lexer_output: Vec<(Token, Span)>,
+    model_errors: &mut Vec<PrismParserError<Span, String>>,
+) -> Option<UnresolvedModel> {
+    let (output, parse_errors) = parser::program_parser()
+        .map_with(|ast, e| (ast, e.span()))
+        .parse(
+            lexer_output
+                .as_slice()
+                .map((source.len()..source.len()).into(), |(t, s)| (t, s)),
+        )
+        .into_output_errors();
+    process_parser_errors(model_errors, parse_errors);
+    output.map(|(o, _)| o)
+}
+
+/// Substitutes labels and formulas in the model and properties, expands renamed modules,
+/// resolves the `"init"` label of the properties to the initial states, replaces identifiers by
+/// references to variables and checks the types of all expressions and the players of games.
+fn process_model_and_properties(
+    output: Option<UnresolvedModel>,
+    mut parsed_properties: Vec<Option<UnresolvedQuery>>,
+    model_errors: &mut Vec<PrismParserError<Span, String>>,
+    property_errors: &mut [Vec<PrismParserError<Span, String>>],
+) -> (Option<ResolvedModel>, Vec<Option<ResolvedQuery>>) {
+    match output {
+        Some(mut output) => {
+            parsed_properties
+                .iter_mut()
+                .zip(property_errors.iter_mut())
+                .for_each(|(p_option, errs)| {
+                    if let Some(p) = p_option {
+                        use prism_model::SubstitutableQuery;
+                        p.substitute_labels(SimpleSpan::new(0, 1), &output.labels);
+                        let substitution =
+                            p.substitute_formulas(SimpleSpan::new(0, 1), &output.formulas);
+                        if let Err(err) = substitution {
+                            errs.push(
+                                PrismParserValidationError::CyclicFormulaDependency { cycle: err }
+                                    .into(),
+                            );
+                            *p_option = None
+                        }
+                    }
+                });
+
+            if let Err(err) = output.substitute_formulas(SimpleSpan::new(0, 1)) {
+                model_errors
+                    .push(PrismParserValidationError::CyclicFormulaDependency { cycle: err }.into())
+            }
+
+            if let Err(error) = output.expand_renamed_models() {
+                model_errors
+                    .push(PrismParserValidationError::ModuleExpansionError { error }.into());
+                let mut empty_vec = Vec::with_capacity(parsed_properties.len());
+                for _ in 0..parsed_properties.len() {
+                    empty_vec.push(None);
+                }
+                (None, empty_vec)
+            } else {
+                let missing_initial_values = output.variables_without_initial_value();
+                if !missing_initial_values.is_empty() {
+                    model_errors.extend(missing_initial_values.into_iter().map(|variable| {
+                        PrismParserValidationError::MissingInitialValue { variable }.into()
+                    }));
+                    return (None, parsed_properties.iter().map(|_| None).collect());
+                }
+
+                // The label only needs a name, each occurrence keeps its own span
+                let init_label = prism_model::Identifier::new_potentially_reserved(
+                    "init",
+                    SimpleSpan::new(0, 1),
+                )
+                .unwrap();
+                let initial_states = output.initial_states_expression();
+                let properties = parsed_properties
+                    .into_iter()
+                    .zip(property_errors.iter_mut())
+                    .map(|(p, errs)| {
+                        p.and_then(|mut p| {
+                            use prism_model::SubstitutableQuery;
+                            p.substitute_label_at_occurrence(
+                                SimpleSpan::new(0, 1),
+                                &init_label,
+                                &initial_states,
+                            );
+                            match p
+                                .replace_identifiers_by_variable_indices(&output.variable_manager)
+                            {
+                                Ok(p) => match prism_model::check_query_types(
+                                    &p,
+                                    &output.variable_manager,
+                                ) {
+                                    Ok(()) => Some(p),
+                                    Err(e) => {
+                                        errs.extend(e.into_iter().map(|error| {
+                                            PrismParserValidationError::TypeError { error }.into()
+                                        }));
+                                        None
+                                    }
+                                },
+                                Err(e) => {
+                                    for err in e {
+                                        errs.push(
+                                            PrismParserValidationError::UnknownVariable {
+                                                identifier: err.identifier,
+                                            }
+                                            .into(),
+                                        );
+                                    }
+                                    None
+                                }
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                (
+                    match output.replace_identifiers_by_variable_indices() {
+                        Ok(output) => {
+                            let type_errors = output.check_types().err().unwrap_or_default();
+                            let player_errors = output.check_players().err().unwrap_or_default();
+                            if type_errors.is_empty() && player_errors.is_empty() {
+                                Some(output)
+                            } else {
+                                model_errors.extend(type_errors.into_iter().map(|error| {
+                                    PrismParserValidationError::TypeError { error }.into()
+                                }));
+                                model_errors.extend(player_errors.into_iter().map(|error| {
+                                    PrismParserValidationError::PlayerError { error }.into()
+                                }));
+                                None
+                            }
+                        }
+                        Err(errs) => {
+                            for err in errs {
+                                model_errors.push(
+                                    PrismParserValidationError::UnknownVariable {
+                                        identifier: err.identifier,
+                                    }
+                                    .into(),
+                                )
+                            }
+                            None
+                        }
+                    },
+                    properties,
+                )
+            }
+        }
+        None => {
+            let mut empty_vec = Vec::with_capacity(parsed_properties.len());
+            for _ in 0..parsed_properties.len() {
+                empty_vec.push(None);
+            }
+            (None, empty_vec)
+        }
+    }
+}
+
 fn process_parser_errors(
     errors: &mut Vec<PrismParserError<Span, String>>,
     parse_errors: Vec<PrismParserError<Span, Token>>,
@@ -31,7 +31,11 @@ where
             .then_ignore(just(Token::RightBracket))
             .map_with(|(name, args), e| Expression::Function(name, args, e.span()));
 
-        let label = just(Token::Quote).ignore_then(identifier_parser()).then_ignore(just(Token::Quote))
+        // `"init"` refers to the initial states, even though `init` is a keyword
+        let init_label = select! {
+            Token::Init = e => Identifier::new_potentially_reserved("init", e.span()).unwrap()
+        };
+        let label = just(Token::Quote).ignore_then(identifier_parser().or(init_label)).then_ignore(just(Token::Quote))
             .map_with(|name, e| Expression::Label(name, e.span()));
 
         let parenthesised = expr
@@ -7,7 +7,9 @@ mod init_constraint;
 mod label;
 mod model_type;
 mod module;
+mod player;
 mod program;
+mod properties_file;
 mod property;
 mod rewards;
 
@@ -20,7 +22,9 @@ pub use init_constraint::*;
 pub use label::*;
 pub use model_type::*;
 pub use module::*;
+pub use player::*;
 pub use program::*;
+pub use properties_file::*;
 pub use property::*;
 pub use rewards::*;
 
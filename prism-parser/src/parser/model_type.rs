@@ -12,6 +12,7 @@ where
         .map_with(|_, e| prism_model::ModelType::Dtmc(e.span()))
         .or(just(Token::Ctmc).map_with(|_, e| prism_model::ModelType::Ctmc(e.span())))
         .or(just(Token::Mdp).map_with(|_, e| prism_model::ModelType::Mdp(e.span())))
+        .or(just(Token::Smg).map_with(|_, e| prism_model::ModelType::Smg(e.span())))
         .or(just(Token::Pta).try_map(|_, span: Span| {
             Err(PrismParserValidationError::UnsupportedModelType {
                 model_type: "pta",
@@ -0,0 +1,49 @@
+use super::{E, identifier_parser};
+use crate::{Span, Token};
+use chumsky::IterParser;
+use chumsky::Parser;
+use chumsky::input::ValueInput;
+use chumsky::prelude::just;
+use prism_model::Identifier;
+
+pub fn player_parser<'a, 'b, I>() -> impl Parser<'a, I, prism_model::Player<Span>, E<'a>>
+where
+    I: ValueInput<'a, Token = Token, Span = Span>,
+{
+    just(Token::Player)
+        .ignore_then(identifier_parser())
+        .then(
+            player_element_parser()
+                .separated_by(just(Token::Comma))
+                .collect::<Vec<_>>(),
+        )
+        .then_ignore(just(Token::EndPlayer))
+        .map_with(|(name, elements), e| {
+            let mut player = prism_model::Player::new(name, e.span());
+            for element in elements {
+                match element {
+                    PlayerElement::Module(module) => player.modules.push(module),
+                    PlayerElement::Action(action) => player.actions.push(action),
+                }
+            }
+            player
+        })
+        .labelled("player")
+        .as_context()
+}
+
+enum PlayerElement {
+    Module(Identifier<Span>),
+    Action(Identifier<Span>),
+}
+
+fn player_element_parser<'a, 'b, I>() -> impl Parser<'a, I, PlayerElement, E<'a>>
+where
+    I: ValueInput<'a, Token = Token, Span = Span>,
+{
+    identifier_parser()
+        .delimited_by(just(Token::LeftSqBracket), just(Token::RightSqBracket))
+        .map(PlayerElement::Action)
+        .or(identifier_parser().map(PlayerElement::Module))
+        .labelled("module or action")
+}
@@ -1,6 +1,7 @@
 use super::{
     E, const_parser, formula_parser, global_variable_declaration_parser, init_constraint_parser,
-    label_parser, model_type_parser, module_parser, renamed_module_parser, rewards_parser,
+    label_parser, model_type_parser, module_parser, player_parser, renamed_module_parser,
+    rewards_parser,
 };
 use crate::error::ElementKind;
 use crate::{PrismParserError, PrismParserValidationError, Span, Token};
@@ -75,6 +76,7 @@ fn build_program_from_type_and_elements<'a>(
     let mut formulas = prism_model::FormulaManager::new();
     let mut init_constraint = None;
     let mut rewards = prism_model::RewardsManager::new();
+    let mut players = prism_model::PlayerManager::new();
 
     for element in elements {
         match element {
@@ -187,6 +189,23 @@ fn build_program_from_type_and_elements<'a>(
                     }
                 }
             }
+            ProgramElement::Player(p) => {
+                let span = p.span;
+                match players.add(p) {
+                    Ok(_) => {}
+                    Err(prism_model::AddPlayerError::PlayerExists { index }) => {
+                        let previous = players.get(index).unwrap();
+                        emitter.emit(
+                            PrismParserValidationError::DuplicateElement {
+                                previous_occurrence: previous.span,
+                                new_definition: span,
+                                kind: ElementKind::Player,
+                            }
+                            .into(),
+                        )
+                    }
+                }
+            }
         }
     }
 
@@ -208,6 +227,7 @@ fn build_program_from_type_and_elements<'a>(
         init_constraint.map(|(i, _)| i),
         labels,
         rewards,
+        players,
         span,
     )
 }
@@ -230,6 +250,7 @@ enum ProgramElement {
     Formula(prism_model::Formula<Expression<Identifier<Span>, Span>, Span>),
     InitConstraint(prism_model::Expression<Identifier<Span>, Span>, Span),
     Rewards(prism_model::Rewards<Identifier<Span>, Expression<Identifier<Span>, Span>, Span>),
+    Player(prism_model::Player<Span>),
 }
 
 fn program_element_parser<'a, 'b, I>() -> impl Parser<'a, I, ProgramElement, E<'a>>
@@ -245,5 +266,6 @@ where
         .or(formula_parser().map(ProgramElement::Formula))
         .or(global_variable_declaration_parser().map(ProgramElement::GlobalVariable))
         .or(rewards_parser().map(ProgramElement::Rewards))
+        .or(player_parser().map(ProgramElement::Player))
         .or(init_constraint_parser().map(|(i, e)| ProgramElement::InitConstraint(i, e)))
 }
@@ -0,0 +1,70 @@
+use super::{E, const_parser, identifier_parser, query_parser};
+use crate::error::ElementKind;
+use crate::{PrismParserValidationError, Span, Token, UnresolvedQuery};
+use chumsky::IterParser;
+use chumsky::Parser;
+use chumsky::input::ValueInput;
+use chumsky::prelude::just;
+use prism_model::{Expression, Identifier, NamedProperty, VariableInfo};
+
+pub struct PropertiesFile {
+    pub consts: Vec<VariableInfo<Expression<Identifier<Span>, Span>, Span>>,
+    pub properties: Vec<NamedProperty<UnresolvedQuery, Span>>,
+}
+
+enum PropertiesFileElement {
+    Const(VariableInfo<Expression<Identifier<Span>, Span>, Span>),
+    Property(NamedProperty<UnresolvedQuery, Span>),
+}
+
+/// Parses a PRISM properties file, i.e. a list of constant declarations and properties. A
+/// property may be preceded by a name (`"name": P=? [...]`) and followed by a semicolon.
+pub fn properties_file_parser<'a, 'b, I>() -> impl Parser<'a, I, PropertiesFile, E<'a>>
+where
+    I: ValueInput<'a, Token = Token, Span = Span>,
+{
+    let property = identifier_parser()
+        .delimited_by(just(Token::Quote), just(Token::Quote))
+        .then_ignore(just(Token::Colon))
+        .or_not()
+        .then(query_parser())
+        .then_ignore(just(Token::Semicolon).or_not())
+        .map(|(name, query)| PropertiesFileElement::Property(NamedProperty { name, query }))
+        .labelled("property")
+        .as_context();
+
+    const_parser()
+        .map(PropertiesFileElement::Const)
+        .or(property)
+        .repeated()
+        .collect::<Vec<_>>()
+        .validate(|elements, _, emitter| {
+            let mut consts = Vec::new();
+            let mut properties: Vec<NamedProperty<_, Span>> = Vec::new();
+            for element in elements {
+                match element {
+                    PropertiesFileElement::Const(c) => consts.push(c),
+                    PropertiesFileElement::Property(p) => {
+                        if let Some(name) = &p.name {
+                            let previous = properties
+                                .iter()
+                                .filter_map(|previous| previous.name.as_ref())
+                                .find(|previous| previous.name == name.name);
+                            if let Some(previous) = previous {
+                                emitter.emit(
+                                    PrismParserValidationError::DuplicateElement {
+                                        previous_occurrence: previous.span,
+                                        new_definition: name.span,
+                                        kind: ElementKind::Property,
+                                    }
+                                    .into(),
+                                );
+                            }
+                        }
+                        properties.push(p);
+                    }
+                }
+            }
+            PropertiesFile { consts, properties }
+        })
+}
@@ -1,11 +1,12 @@
 use super::{E, expression_parser, identifier_parser};
 use crate::{Span, Token};
-use chumsky::Parser;
 use chumsky::input::ValueInput;
-use chumsky::prelude::{Recursive, just};
+use chumsky::prelude::{Recursive, just, recursive};
+use chumsky::{Parser, select};
 use prism_model::{Expression, Identifier};
 use probabilistic_properties::{
-    Bound, BoundOperator, NonDeterminismKind, PathFormula, Query, RewardFormula, StateFormula,
+    Bound, BoundOperator, FilterOperator, LtlFormula, NonDeterminismKind, PathFormula, Query,
+    RewardFormula, StateFormula,
 };
 
 pub fn query_parser<'a, 'b, I>() -> impl Parser<
@@ -21,10 +22,14 @@ pub fn query_parser<'a, 'b, I>() -> impl Parser<
 where
     I: ValueInput<'a, Token = Token, Span = Span>,
 {
+    let mut query_parser = Recursive::declare();
     let mut state_formula_parser = Recursive::declare();
     let mut path_formula_parser = Recursive::declare();
 
-    state_formula_parser.define(define_state_formula_parser(path_formula_parser.clone()));
+    state_formula_parser.define(define_state_formula_parser(
+        path_formula_parser.clone(),
+        state_formula_parser.clone(),
+    ));
     path_formula_parser.define(define_path_formula_parser(state_formula_parser.clone()));
 
     let probability_value = probability_min_max()
@@ -88,12 +93,65 @@ where
             reward,
         });
 
-    probability_value
-        .or(state_formula)
-        .or(reward_bound)
-        .or(reward_value)
-        .or(time_bound)
-        .or(time_value)
+    let long_run_average_value = lra_min_max()
+        .then_ignore(just(Token::Equal))
+        .then_ignore(just(Token::Questionmark))
+        .then_ignore(just(Token::LeftSqBracket))
+        .then(state_formula_parser.clone())
+        .then_ignore(just(Token::RightSqBracket))
+        .map(|(non_determinism, condition)| Query::LongRunAverageValue {
+            non_determinism,
+            condition,
+        });
+
+    // `filter(op, query)` or `filter(op, query, states)`
+    let filter = just(Token::Filter)
+        .ignore_then(just(Token::LeftBracket))
+        .ignore_then(filter_operator())
+        .then_ignore(just(Token::Comma))
+        .then(query_parser.clone())
+        .then(
+            just(Token::Comma)
+                .ignore_then(state_formula_parser.clone())
+                .or_not(),
+        )
+        .then_ignore(just(Token::RightBracket))
+        .map(|((operator, query), states)| Query::Filter {
+            operator,
+            query: Box::new(query),
+            states,
+        });
+
+    query_parser.define(
+        filter
+            .or(probability_value)
+            .or(long_run_average_value)
+            .or(state_formula)
+            .or(reward_bound)
+            .or(reward_value)
+            .or(time_bound)
+            .or(time_value),
+    );
+    query_parser
+}
+
+fn filter_operator<'a, I>() -> impl Parser<'a, I, FilterOperator, E<'a>> + Clone
+where
+    I: ValueInput<'a, Token = Token, Span = Span>,
+{
+    // Apart from `min` and `max`, the operators are not keywords and are lexed as identifiers.
+    select! {
+        Token::Min => FilterOperator::Min,
+        Token::Max => FilterOperator::Max,
+        Token::Identifier(name) if name == "avg" => FilterOperator::Avg,
+        Token::Identifier(name) if name == "sum" => FilterOperator::Sum,
+        Token::Identifier(name) if name == "count" => FilterOperator::Count,
+        Token::Identifier(name) if name == "forall" => FilterOperator::ForAll,
+        Token::Identifier(name) if name == "exists" => FilterOperator::Exists,
+        Token::Identifier(name) if name == "first" => FilterOperator::First,
+        Token::Identifier(name) if name == "print" => FilterOperator::Print,
+        Token::Identifier(name) if name == "state" => FilterOperator::State,
+    }
 }
 
 pub fn probability_min_max<'a, 'b, I>()
@@ -176,7 +234,8 @@ pub fn define_path_formula_parser<
                 Expression<Identifier<Span>, Span>,
             >,
             E<'a>,
-        > + Clone,
+        > + Clone
+        + 'a,
 >(
     state_formula_parser: SF,
 ) -> impl Parser<
@@ -227,16 +286,155 @@ where
         });
 
     let generally = just(Token::Generally)
-        .ignore_then(state_formula_parser)
+        .ignore_then(state_formula_parser.clone())
         .map(|condition| PathFormula::Generally {
             condition: Box::new(condition),
         });
 
-    until
+    let next = just(Token::Next)
+        .ignore_then(state_formula_parser.clone())
+        .map(|condition| PathFormula::Next {
+            condition: Box::new(condition),
+        });
+
+    let weak_until = state_formula_parser
+        .clone()
+        .then_ignore(just(Token::WeakUntil))
+        .then(state_formula_parser.clone())
+        .map(|(before, after)| PathFormula::WeakUntil {
+            before: Box::new(before),
+            after: Box::new(after),
+        });
+
+    // `R` is also the reward operator, which cannot occur between two state formulas
+    let release = state_formula_parser
+        .clone()
+        .then_ignore(just(Token::R))
+        .then(state_formula_parser.clone())
+        .map(|(release, condition)| PathFormula::Release {
+            release: Box::new(release),
+            condition: Box::new(condition),
+        });
+
+    let pctl = until
         .or(eventually)
         .or(bounded_until)
         .or(bounded_eventually)
         .or(generally)
+        .or(next)
+        .or(weak_until)
+        .or(release);
+
+    // Formulas that do not fit a single PCTL operator, e.g. `G F a`, are parsed as LTL formulas
+    pctl.then_ignore(just(Token::RightSqBracket).rewind())
+        .or(ltl_formula_parser(state_formula_parser).map(|formula| PathFormula::Ltl { formula }))
+}
+
+type ParsedLtlFormula = LtlFormula<
+    Expression<Identifier<Span>, Span>,
+    Expression<Identifier<Span>, Span>,
+    Expression<Identifier<Span>, Span>,
+>;
+
+/// Temporal operators bind weaker than the operators of expressions, so `F a & b` is `F (a & b)`.
+/// Unary operators bind stronger than `U`, `W` and `R`, which bind stronger than `&`, `|` and `=>`.
+fn ltl_formula_parser<
+    'a,
+    I,
+    SF: Parser<
+            'a,
+            I,
+            StateFormula<
+                Expression<Identifier<Span>, Span>,
+                Expression<Identifier<Span>, Span>,
+                Expression<Identifier<Span>, Span>,
+            >,
+            E<'a>,
+        > + Clone
+        + 'a,
+>(
+    state_formula_parser: SF,
+) -> impl Parser<'a, I, ParsedLtlFormula, E<'a>> + Clone
+where
+    I: ValueInput<'a, Token = Token, Span = Span>,
+{
+    recursive(|ltl| {
+        let atom = state_formula_parser
+            .map(LtlFormula::StateFormula)
+            .or(ltl.delimited_by(just(Token::LeftBracket), just(Token::RightBracket)));
+
+        let unary = recursive(|unary| {
+            just(Token::Negation)
+                .ignore_then(unary.clone())
+                .map(|formula| LtlFormula::Not(Box::new(formula)))
+                .or(just(Token::Next)
+                    .ignore_then(unary.clone())
+                    .map(|formula| LtlFormula::Next(Box::new(formula))))
+                .or(just(Token::Finally)
+                    .ignore_then(unary.clone())
+                    .map(|formula| LtlFormula::Eventually(Box::new(formula))))
+                .or(just(Token::Generally)
+                    .ignore_then(unary)
+                    .map(|formula| LtlFormula::Generally(Box::new(formula))))
+                .or(atom)
+        })
+        .boxed();
+
+        // `U`, `W` and `R` are right-associative
+        let temporal = recursive(|temporal| {
+            unary
+                .clone()
+                .then(
+                    just(Token::Until)
+                        .or(just(Token::WeakUntil))
+                        .or(just(Token::R))
+                        .then(temporal)
+                        .or_not(),
+                )
+                .map(|(left, right)| match right {
+                    None => left,
+                    Some((Token::Until, right)) => LtlFormula::Until {
+                        before: Box::new(left),
+                        after: Box::new(right),
+                    },
+                    // `a W b` is `a U b | G a`
+                    Some((Token::WeakUntil, right)) => LtlFormula::Or(
+                        Box::new(LtlFormula::Until {
+                            before: Box::new(left.clone()),
+                            after: Box::new(right),
+                        }),
+                        Box::new(LtlFormula::Generally(Box::new(left))),
+                    ),
+                    // `a R b` is `!(!a U !b)`
+                    Some((_, right)) => LtlFormula::Not(Box::new(LtlFormula::Until {
+                        before: Box::new(LtlFormula::Not(Box::new(left))),
+                        after: Box::new(LtlFormula::Not(Box::new(right))),
+                    })),
+                })
+        })
+        .boxed();
+
+        let conjunction = temporal.clone().foldl(
+            just(Token::And).ignore_then(temporal).repeated(),
+            |left, right| LtlFormula::And(Box::new(left), Box::new(right)),
+        );
+
+        let disjunction = conjunction.clone().foldl(
+            just(Token::Or).ignore_then(conjunction).repeated(),
+            |left, right| LtlFormula::Or(Box::new(left), Box::new(right)),
+        );
+
+        // `a => b` is `!a | b`
+        disjunction
+            .clone()
+            .foldl(
+                just(Token::Implies).ignore_then(disjunction).repeated(),
+                |left, right| {
+                    LtlFormula::Or(Box::new(LtlFormula::Not(Box::new(left))), Box::new(right))
+                },
+            )
+            .boxed()
+    })
 }
 
 pub fn define_state_formula_parser<
@@ -253,8 +451,19 @@ pub fn define_state_formula_parser<
             >,
             E<'a>,
         > + Clone,
+    SF: Parser<
+            'a,
+            I,
+            StateFormula<
+                Expression<Identifier<Span>, Span>,
+                Expression<Identifier<Span>, Span>,
+                Expression<Identifier<Span>, Span>,
+            >,
+            E<'a>,
+        > + Clone,
 >(
     path_formula_parser: PF,
+    state_formula_parser: SF,
 ) -> impl Parser<
     'a,
     I,
@@ -285,12 +494,14 @@ where
 
     let long_run_average = lra_min_max()
         .then(bound_parser())
-        .then(path_formula_parser)
+        .then_ignore(just(Token::LeftSqBracket))
+        .then(state_formula_parser)
+        .then_ignore(just(Token::RightSqBracket))
         .map(
-            |((non_determinism, bound), path)| StateFormula::LongRunAverage {
+            |((non_determinism, bound), condition)| StateFormula::LongRunAverage {
                 non_determinism,
                 bound,
-                path: Box::new(path),
+                condition: Box::new(condition),
             },
         );
 
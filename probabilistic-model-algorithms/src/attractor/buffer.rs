@@ -1,5 +1,6 @@
 use probabilistic_models::{ActionCollection, ProbabilisticModel, TwoPlayer, VectorPredecessors};
 
+#[derive(Clone)]
 pub struct AttractorBuffer {
     counts: Vec<Count>,
     pub open_list: Vec<usize>,
@@ -76,6 +77,12 @@ impl AttractorBuffer {
         self.counts[index].reset_sink_state()
     }
 
+    /// The count of a state of the avoiding player before any of its successors was attracted,
+    /// i.e. its number of actions
+    pub fn get_default(&self, index: usize) -> u32 {
+        self.counts[index].default
+    }
+
     pub fn get_value(&self, index: usize) -> u32 {
         self.counts[index].current
     }
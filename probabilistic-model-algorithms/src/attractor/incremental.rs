@@ -0,0 +1,188 @@
+use super::AttractorBuffer;
+use crate::regions::MutableStateRegion;
+use probabilistic_models::{Predecessors, ProbabilisticModel, TwoPlayer, VectorPredecessors};
+
+/// An attractor of a fixed set of target states that is kept up to date while the owners of the
+/// states change, so that consecutive computations can build on each other.
+///
+/// If the reaching player only gains states, every state that was attracted before is still
+/// attracted, so the attractor is only extended from the newly gained states. Whenever the
+/// reaching player loses a state of the attractor, the next computation starts from scratch.
+#[derive(Clone)]
+pub struct IncrementalAttractor {
+    buffer: AttractorBuffer,
+    target_states: Vec<usize>,
+    reaching_player: TwoPlayer,
+    owners: Vec<TwoPlayer>,
+    // States that are never attracted, regardless of their owner
+    sink_states: Vec<bool>,
+    attracted: Vec<bool>,
+    // Whether the counts in the buffer and the attracted states belong to the current owners. The
+    // open list of the buffer contains the attracted states whose predecessors were not updated yet.
+    up_to_date: bool,
+}
+
+impl IncrementalAttractor {
+    pub fn create<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        model: &ProbabilisticModel<M>,
+        target_states: Vec<usize>,
+        reaching_player: TwoPlayer,
+    ) -> Self {
+        Self {
+            buffer: AttractorBuffer::create(model),
+            target_states,
+            reaching_player,
+            owners: model.states.iter().map(|state| state.owner).collect(),
+            sink_states: vec![false; model.states.len()],
+            attracted: vec![false; model.states.len()],
+            up_to_date: false,
+        }
+    }
+
+    /// Like `create`, but the given sink states are never attracted, e.g. because the
+    /// reaching player must not pass through them.
+    pub fn create_with_sink_states<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        model: &ProbabilisticModel<M>,
+        target_states: Vec<usize>,
+        sink_states: Vec<bool>,
+        reaching_player: TwoPlayer,
+    ) -> Self {
+        let mut attractor = Self::create(model, target_states, reaching_player);
+        attractor.sink_states = sink_states;
+        attractor
+    }
+
+    pub fn set_owner(&mut self, index: usize, owner: TwoPlayer) {
+        if self.owners[index] == owner {
+            return;
+        }
+        self.owners[index] = owner;
+        if !self.up_to_date || self.sink_states[index] {
+            return;
+        }
+
+        if owner == self.reaching_player {
+            if !self.attracted[index] {
+                // Successors that were attracted already decremented the count of the state
+                if self.buffer.get_value(index) < self.buffer.get_default(index) {
+                    self.attract(index);
+                } else {
+                    self.buffer.reset_reaching_player(index);
+                }
+            }
+        } else if self.attracted[index] {
+            self.up_to_date = false;
+        } else {
+            // None of the successors was attracted, as the state would be attracted otherwise
+            self.buffer.reset_avoiding_player(index);
+        }
+    }
+
+    pub fn adapt_to_owners<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) {
+        for (index, state) in model.states.iter().enumerate() {
+            self.set_owner(index, state.owner);
+        }
+    }
+
+    /// Discards the previous computations, so that the next one starts from the target states.
+    pub fn reset(&mut self) {
+        self.up_to_date = false;
+    }
+
+    pub fn contains_state<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        state: usize,
+    ) -> bool {
+        self.propagate(model, Some(state))
+    }
+
+    pub fn region<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+        R: MutableStateRegion,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) -> R {
+        self.propagate(model, None);
+        let mut result = R::create(model.states.len());
+        for (index, &attracted) in self.attracted.iter().enumerate() {
+            if attracted {
+                result.add_state(index);
+            }
+        }
+        result
+    }
+
+    fn restart(&mut self) {
+        for (index, owner) in self.owners.iter().enumerate() {
+            if self.sink_states[index] {
+                self.buffer.reset_sink_state(index);
+            } else if *owner == self.reaching_player {
+                self.buffer.reset_reaching_player(index);
+            } else {
+                self.buffer.reset_avoiding_player(index);
+            }
+        }
+        self.attracted.fill(false);
+        self.buffer.open_list.clear();
+        for i in 0..self.target_states.len() {
+            self.attract(self.target_states[i]);
+        }
+        self.up_to_date = true;
+    }
+
+    fn attract(&mut self, index: usize) {
+        self.attracted[index] = true;
+        self.buffer.set_value(index, 0);
+        self.buffer.open_list.push(index);
+    }
+
+    // Extends the attractor until `state` is attracted or no further states can be attracted.
+    // Attracted states stay on the open list until all of their predecessors are updated, so that a
+    // later call can continue where this one stopped.
+    fn propagate<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        state: Option<usize>,
+    ) -> bool {
+        if !self.up_to_date {
+            self.restart();
+        }
+        if state.is_some_and(|state| self.attracted[state]) {
+            return true;
+        }
+
+        while let Some(next) = self.buffer.open_list.pop() {
+            let mut found = false;
+            for predecessor in model.states[next].predecessors.iter() {
+                let count = self.buffer.get_value(predecessor.from);
+                if count > 0 {
+                    self.buffer.set_value(predecessor.from, count - 1);
+                    if count == 1 {
+                        self.attracted[predecessor.from] = true;
+                        self.buffer.open_list.push(predecessor.from);
+                        found |= state == Some(predecessor.from);
+                    }
+                }
+            }
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+}
@@ -4,9 +4,15 @@ pub use buffer::AttractorBuffer;
 mod full_region;
 pub use full_region::{attractor, attractor_with_buffer};
 
+mod incremental;
+pub use incremental::IncrementalAttractor;
+
 mod state_included;
 pub use state_included::{attractor_contains_state, attractor_contains_state_with_buffer};
 
+mod strategy;
+pub use strategy::attractor_strategy_with_buffer;
+
 use probabilistic_models::{Predecessors, ProbabilisticModel, TwoPlayer, VectorPredecessors};
 
 trait AttractorCondition {
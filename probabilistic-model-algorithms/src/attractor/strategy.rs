@@ -0,0 +1,47 @@
+use super::AttractorBuffer;
+use crate::deterministic_games::MemorylessStrategy;
+use crate::regions::MutableStateRegion;
+use probabilistic_models::{Predecessors, ProbabilisticModel, TwoPlayer, VectorPredecessors};
+
+// Works like `attractor_with_buffer`, but additionally records in `strategy` the action through
+// which each state of `reaching_player` outside `region` was attracted. These choices form a
+// strategy that reaches `region`. States of the other player do not get a choice.
+pub fn attractor_strategy_with_buffer<
+    M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    R1: Iterator<Item = usize>,
+    R2: MutableStateRegion,
+>(
+    model: &ProbabilisticModel<M>,
+    region: R1,
+    buffer: &mut AttractorBuffer,
+    strategy: &mut MemorylessStrategy,
+    reaching_player: TwoPlayer,
+) -> R2 {
+    let mut result = R2::create(model.states.len());
+    buffer.open_list.clear();
+
+    for state in region {
+        result.add_state(state);
+        buffer.open_list.push(state);
+        buffer.set_value(state, 0);
+    }
+
+    while let Some(next) = buffer.open_list.pop() {
+        let state = &model.states[next];
+        for predecessor in state.predecessors.iter() {
+            let count = buffer.get_value(predecessor.from);
+            if count > 0 {
+                buffer.set_value(predecessor.from, count - 1);
+                if count == 1 {
+                    result.add_state(predecessor.from);
+                    if model.states[predecessor.from].owner == reaching_player {
+                        strategy.set_choice(predecessor.from, predecessor.action_index);
+                    }
+                    buffer.open_list.push(predecessor.from);
+                }
+            }
+        }
+    }
+
+    result
+}
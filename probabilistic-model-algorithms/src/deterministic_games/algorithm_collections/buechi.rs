@@ -1,12 +1,14 @@
 use super::super::AdaptableOwners;
-use super::NonstochasticGameAlgorithm;
+use super::{NonstochasticGameAlgorithm, winner_from_initial_states};
 use crate::attractor;
+use crate::deterministic_games::MemorylessStrategy;
 use crate::regions::{FlagStateRegion, InvertedStateRegion, MutableStateRegion, StateRegion};
 use probabilistic_models::probabilistic_properties::{Bound, BoundOperator, Query, StateFormula};
 use probabilistic_models::{
     AtomicProposition, InitialStates, ModelTypes, ProbabilisticModel, TwoPlayer, VectorPredecessors,
 };
 
+#[derive(Clone)]
 pub struct BuechiAlgorithmCollection {
     buechi_states: AtomicProposition,
 }
@@ -21,8 +23,7 @@ impl NonstochasticGameAlgorithm for BuechiAlgorithmCollection {
         &self,
         model: &ProbabilisticModel<M>,
     ) -> Self::ModelContext {
-        assert_eq!(model.initial_states.count(), 1);
-        let initial_state = model.initial_states.get(0);
+        let initial_states = model.initial_states.iter().copied().collect();
         let buechi_states = model.get_states_with_ap(self.buechi_states);
         let mut buffer = attractor::AttractorBuffer::create(model);
         buffer.reset_owner_counts(model, TwoPlayer::PlayerOne);
@@ -31,13 +32,13 @@ impl NonstochasticGameAlgorithm for BuechiAlgorithmCollection {
             buffer,
             owners: vec![TwoPlayer::PlayerOne; model.states.len()],
             unreachable: FlagStateRegion::create(model.states.len()),
-            initial_state,
+            initial_states,
         };
         context.adapt_to_owners(&model);
         context
     }
 
-    fn create_if_compatible(property: &Query<i64, f64, AtomicProposition>) -> Option<Self> {
+    fn create_if_compatible(property: &Query<f64, f64, AtomicProposition>) -> Option<Self> {
         if let Query::StateFormula(StateFormula::ProbabilityBound {
             non_determinism: Option::None,
             bound:
@@ -71,7 +72,11 @@ impl NonstochasticGameAlgorithm for BuechiAlgorithmCollection {
         model: &ProbabilisticModel<M>,
         context: &mut Self::ModelContext,
     ) -> TwoPlayer {
-        self.winning_from_state_with_context(model, context.initial_state, context)
+        if let [initial_state] = context.initial_states[..] {
+            return self.winning_from_state_with_context(model, initial_state, context);
+        }
+        let winning_region = self.winning_region_with_context(model, context);
+        winner_from_initial_states(&winning_region, &context.initial_states)
     }
 
     fn winning_from_state_with_context<
@@ -127,13 +132,49 @@ impl NonstochasticGameAlgorithm for BuechiAlgorithmCollection {
 
         InvertedStateRegion::new(context.unreachable.clone())
     }
+
+    fn winning_region_and_strategy_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> (Self::WinningRegionType, MemorylessStrategy) {
+        let winning_region = self.winning_region_with_context(model, context);
+
+        // Within the winning region, player one can always reach another Büchi state, so we
+        // combine an attractor strategy towards those states with staying in the region once a
+        // Büchi state has been reached. All states outside the winning region are sinks here.
+        context.reset_buffer(TwoPlayer::PlayerOne);
+        let winning_buechi_states: Vec<usize> = context
+            .buechi_states
+            .iter()
+            .filter(|&&s| winning_region.contains(s))
+            .cloned()
+            .collect();
+        let mut strategy = MemorylessStrategy::create(model.states.len());
+        attractor::attractor_strategy_with_buffer::<_, _, FlagStateRegion>(
+            model,
+            winning_buechi_states.iter().cloned(),
+            &mut context.buffer,
+            &mut strategy,
+            TwoPlayer::PlayerOne,
+        );
+        strategy.choose_actions_staying_in(
+            model,
+            winning_buechi_states.into_iter(),
+            &winning_region,
+        );
+        (winning_region, strategy)
+    }
 }
+#[derive(Clone)]
 pub struct BuechiAlgorithmContext {
     buechi_states: Vec<usize>,
     buffer: attractor::AttractorBuffer,
     owners: Vec<TwoPlayer>,
     unreachable: FlagStateRegion,
-    initial_state: usize,
+    initial_states: Vec<usize>,
 }
 
 impl BuechiAlgorithmContext {
@@ -7,6 +7,10 @@ pub use safety::{SafetyAlgorithmCollection, SafetyAlgorithmContext};
 mod buechi;
 pub use buechi::{BuechiAlgorithmCollection, BuechiAlgorithmContext};
 
+mod next;
+pub use next::{NextAlgorithmCollection, NextAlgorithmContext};
+
+use super::MemorylessStrategy;
 use crate::regions::{BoxedStateRegion, StateRegion};
 use probabilistic_models::probabilistic_properties::Query;
 use probabilistic_models::{AtomicProposition, ProbabilisticModel, TwoPlayer, VectorPredecessors};
@@ -24,7 +28,7 @@ pub trait NonstochasticGameAlgorithm: Sized {
 
     fn create_if_compatible(
         property: &probabilistic_models::probabilistic_properties::Query<
-            i64,
+            f64,
             f64,
             AtomicProposition,
         >,
@@ -84,6 +88,90 @@ pub trait NonstochasticGameAlgorithm: Sized {
         model: &ProbabilisticModel<M>,
         context: &mut Self::ModelContext,
     ) -> Self::WinningRegionType;
+
+    /// Like `winning_with_context`, but may build on the previous computation with the same
+    /// context instead of solving the game from scratch. This pays off when solving a sequence of
+    /// games that only differ in the owners of a few states, e.g. when player one gains states
+    /// from one game to the next. By default, the game is solved from scratch. Only reachability
+    /// objectives are solved incrementally, all other objectives are always solved from scratch.
+    fn winning_incremental_with_context<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> TwoPlayer {
+        self.winning_with_context(model, context)
+    }
+
+    /// Like `winning_region_with_context`, but may build on the previous computation with the same
+    /// context, see `winning_incremental_with_context`.
+    fn winning_region_incremental_with_context<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Self::WinningRegionType {
+        self.winning_region_with_context(model, context)
+    }
+
+    /// Computes a memoryless strategy with which player one wins from every state of their
+    /// winning region.
+    fn winning_strategy<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) -> MemorylessStrategy {
+        let mut context = self.create_model_context(model);
+        self.winning_strategy_with_context(model, &mut context)
+    }
+
+    fn winning_strategy_with_context<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> MemorylessStrategy {
+        self.winning_region_and_strategy_with_context(model, context)
+            .1
+    }
+
+    /// Computes the winning region of player one together with a memoryless strategy with which
+    /// they win from every state of it, solving the game only once.
+    fn winning_region_and_strategy<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) -> (Self::WinningRegionType, MemorylessStrategy) {
+        let mut context = self.create_model_context(model);
+        self.winning_region_and_strategy_with_context(model, &mut context)
+    }
+
+    fn winning_region_and_strategy_with_context<
+        M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> (Self::WinningRegionType, MemorylessStrategy);
+}
+
+/// Player one wins the game if they win from every initial state.
+fn winner_from_initial_states<R: StateRegion>(
+    winning_region: &R,
+    initial_states: &[usize],
+) -> TwoPlayer {
+    match initial_states
+        .iter()
+        .all(|&state| winning_region.contains(state))
+    {
+        true => TwoPlayer::PlayerOne,
+        false => TwoPlayer::PlayerTwo,
+    }
 }
 
 pub trait ChangeableOwners {
@@ -103,7 +191,7 @@ pub fn is_winning<
     M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
 >(
     model: &probabilistic_models::ProbabilisticModel<M>,
-    property: &Query<i64, f64, AtomicProposition>,
+    property: &Query<f64, f64, AtomicProposition>,
 ) -> TwoPlayer {
     if let Some(mut safety) = SafetyAlgorithmCollection::create_if_compatible(property) {
         safety.winning(model)
@@ -113,6 +201,8 @@ pub fn is_winning<
         reachability.winning(model)
     } else if let Some(mut buechi) = BuechiAlgorithmCollection::create_if_compatible(property) {
         buechi.winning(model)
+    } else if let Some(mut next) = NextAlgorithmCollection::create_if_compatible(property) {
+        next.winning(model)
     } else {
         panic!("Unsupported property type")
     }
@@ -122,7 +212,7 @@ pub fn winning_from_state<
     M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
 >(
     model: &probabilistic_models::ProbabilisticModel<M>,
-    property: &Query<i64, f64, AtomicProposition>,
+    property: &Query<f64, f64, AtomicProposition>,
     state: usize,
 ) -> TwoPlayer {
     if let Some(mut safety) = SafetyAlgorithmCollection::create_if_compatible(property) {
@@ -133,6 +223,8 @@ pub fn winning_from_state<
         reachability.winning_from_state(model, state)
     } else if let Some(mut buechi) = BuechiAlgorithmCollection::create_if_compatible(property) {
         buechi.winning_from_state(model, state)
+    } else if let Some(mut next) = NextAlgorithmCollection::create_if_compatible(property) {
+        next.winning_from_state(model, state)
     } else {
         panic!("Unsupported property type")
     }
@@ -142,7 +234,7 @@ pub fn winning_region<
     M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
 >(
     model: &probabilistic_models::ProbabilisticModel<M>,
-    property: &Query<i64, f64, AtomicProposition>,
+    property: &Query<f64, f64, AtomicProposition>,
 ) -> BoxedStateRegion {
     if let Some(mut safety) = SafetyAlgorithmCollection::create_if_compatible(property) {
         safety.winning_region(model).into()
@@ -152,6 +244,8 @@ pub fn winning_region<
         reachability.winning_region(model).into()
     } else if let Some(mut buechi) = BuechiAlgorithmCollection::create_if_compatible(property) {
         buechi.winning_region(model).into()
+    } else if let Some(mut next) = NextAlgorithmCollection::create_if_compatible(property) {
+        next.winning_region(model).into()
     } else {
         panic!("Unsupported property type")
     }
@@ -0,0 +1,176 @@
+use super::super::AdaptableOwners;
+use super::{NonstochasticGameAlgorithm, winner_from_initial_states};
+use crate::deterministic_games::MemorylessStrategy;
+use crate::regions::{FlagStateRegion, MutableStateRegion, StateRegion};
+use probabilistic_models::probabilistic_properties::{
+    Bound, BoundOperator, PathFormula, Query, StateFormula,
+};
+use probabilistic_models::{
+    Action, ActionCollection, AtomicProposition, Distribution, InitialStates, ModelTypes,
+    ProbabilisticModel, TwoPlayer, VectorPredecessors,
+};
+
+/// Solves `X target` by computing the controllable predecessors of the target states, i.e. the
+/// states from which player one can force the game into a target state in a single step. Player
+/// one needs one action that only leads to target states in their own states, and all actions
+/// must only lead to target states in the states of player two. Deadlocks have no successor, so
+/// player one loses there.
+#[derive(Clone)]
+pub struct NextAlgorithmCollection {
+    target_states: AtomicProposition,
+}
+
+impl NextAlgorithmCollection {
+    fn is_controllable_predecessor<M: ModelTypes>(
+        model: &ProbabilisticModel<M>,
+        state: usize,
+        context: &NextAlgorithmContext,
+    ) -> bool {
+        let actions = &model.states[state].actions;
+        if actions.get_number_of_actions() == 0 {
+            return false;
+        }
+        let leads_to_target = |action: &Action<M::Distribution>| {
+            action
+                .successors
+                .iter()
+                .all(|successor| context.target_states.contains(successor.index))
+        };
+        match context.owners[state] {
+            TwoPlayer::PlayerOne => actions.iter().any(leads_to_target),
+            TwoPlayer::PlayerTwo => actions.iter().all(leads_to_target),
+        }
+    }
+}
+
+impl NonstochasticGameAlgorithm for NextAlgorithmCollection {
+    type WinningRegionType = FlagStateRegion;
+    type ModelContext = NextAlgorithmContext;
+
+    fn create_model_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &self,
+        model: &ProbabilisticModel<M>,
+    ) -> Self::ModelContext {
+        let initial_states = model.initial_states.iter().copied().collect();
+        let mut target_states = FlagStateRegion::create(model.states.len());
+        for state in model.get_states_with_ap(self.target_states) {
+            target_states.add_state(state);
+        }
+        let mut context = NextAlgorithmContext {
+            target_states,
+            owners: vec![TwoPlayer::PlayerOne; model.states.len()],
+            initial_states,
+        };
+        context.adapt_to_owners(model);
+        context
+    }
+
+    fn create_if_compatible(property: &Query<f64, f64, AtomicProposition>) -> Option<Self> {
+        if let Query::StateFormula(StateFormula::ProbabilityBound {
+            non_determinism: Option::None,
+            bound:
+                Bound {
+                    operator: BoundOperator::GreaterOrEqual,
+                    value: 1.0,
+                },
+            path,
+        }) = property
+            && let PathFormula::Next { condition } = path.as_ref()
+        {
+            Some(Self {
+                target_states: *condition.as_expression()?,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn winning_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> TwoPlayer {
+        if let [initial_state] = context.initial_states[..] {
+            return self.winning_from_state_with_context(model, initial_state, context);
+        }
+        let winning_region = self.winning_region_with_context(model, context);
+        winner_from_initial_states(&winning_region, &context.initial_states)
+    }
+
+    fn winning_from_state_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        state: usize,
+        context: &mut Self::ModelContext,
+    ) -> TwoPlayer {
+        match Self::is_controllable_predecessor(model, state, context) {
+            true => TwoPlayer::PlayerOne,
+            false => TwoPlayer::PlayerTwo,
+        }
+    }
+
+    fn winning_region_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Self::WinningRegionType {
+        let mut winning_region = FlagStateRegion::create(model.states.len());
+        for state in 0..model.states.len() {
+            if Self::is_controllable_predecessor(model, state, context) {
+                winning_region.add_state(state);
+            }
+        }
+        winning_region
+    }
+
+    fn winning_region_and_strategy_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> (Self::WinningRegionType, MemorylessStrategy) {
+        let winning_region = self.winning_region_with_context(model, context);
+        let mut strategy = MemorylessStrategy::create(model.states.len());
+        strategy.choose_actions_staying_in(
+            model,
+            (0..model.states.len()).filter(|&state| {
+                winning_region.contains(state) && context.owners[state] == TwoPlayer::PlayerOne
+            }),
+            &context.target_states,
+        );
+        (winning_region, strategy)
+    }
+}
+
+#[derive(Clone)]
+pub struct NextAlgorithmContext {
+    target_states: FlagStateRegion,
+    owners: Vec<TwoPlayer>,
+    initial_states: Vec<usize>,
+}
+
+impl super::ChangeableOwners for NextAlgorithmContext {
+    fn set_owner(&mut self, index: usize, owner: TwoPlayer) {
+        self.owners[index] = owner;
+    }
+}
+
+impl super::AdaptableOwners for NextAlgorithmContext {
+    fn adapt_to_owners<M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>>(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) {
+        for (index, state) in model.states.iter().enumerate() {
+            self.owners[index] = state.owner;
+        }
+    }
+}
@@ -1,13 +1,21 @@
-use super::NonstochasticGameAlgorithm;
+use super::{NonstochasticGameAlgorithm, winner_from_initial_states};
 use crate::attractor;
+use crate::deterministic_games::MemorylessStrategy;
 use crate::regions::FlagStateRegion;
-use probabilistic_models::probabilistic_properties::{Bound, BoundOperator, Query, StateFormula};
+use probabilistic_models::probabilistic_properties::{
+    Bound, BoundOperator, PathFormula, Query, StateFormula,
+};
 use probabilistic_models::{
-    AtomicProposition, InitialStates, ModelTypes, ProbabilisticModel, TwoPlayer, VectorPredecessors,
+    AtomicProposition, AtomicPropositions, InitialStates, ModelTypes, ProbabilisticModel,
+    TwoPlayer, VectorPredecessors,
 };
 
+/// Solves `F target` and `before U target`. For the latter, states that satisfy neither `before`
+/// nor `target` are sinks that player one can never attract.
+#[derive(Clone)]
 pub struct ReachabilityAlgorithmCollection {
     target_states: AtomicProposition,
+    before_states: Option<AtomicProposition>,
 }
 
 impl NonstochasticGameAlgorithm for ReachabilityAlgorithmCollection {
@@ -20,19 +28,36 @@ impl NonstochasticGameAlgorithm for ReachabilityAlgorithmCollection {
         &self,
         model: &ProbabilisticModel<M>,
     ) -> Self::ModelContext {
-        assert_eq!(model.initial_states.count(), 1);
-        let initial_state = model.initial_states.get(0);
+        let initial_states = model.initial_states.iter().copied().collect();
         let target_states = model.get_states_with_ap(self.target_states);
-        let mut buffer = attractor::AttractorBuffer::create(model);
-        buffer.reset_owner_counts(model, TwoPlayer::PlayerOne);
-        ReachabilityAlgorithmContext {
+        let sink_states: Vec<bool> = model
+            .states
+            .iter()
+            .map(|state| {
+                let aps = &state.atomic_propositions;
+                self.before_states
+                    .is_some_and(|before| !aps.get_value(before.index))
+                    && !aps.get_value(self.target_states.index)
+            })
+            .collect();
+        let attractor = attractor::IncrementalAttractor::create_with_sink_states(
+            model,
+            target_states.clone(),
+            sink_states.clone(),
+            TwoPlayer::PlayerOne,
+        );
+        let mut context = ReachabilityAlgorithmContext {
             target_states,
-            buffer,
-            initial_state,
-        }
+            sink_states,
+            buffer: attractor::AttractorBuffer::create(model),
+            attractor,
+            initial_states,
+        };
+        context.reset_buffer(model);
+        context
     }
 
-    fn create_if_compatible(property: &Query<i64, f64, AtomicProposition>) -> Option<Self> {
+    fn create_if_compatible(property: &Query<f64, f64, AtomicProposition>) -> Option<Self> {
         if let Query::StateFormula(StateFormula::ProbabilityBound {
             non_determinism: Option::None,
             bound:
@@ -42,9 +67,18 @@ impl NonstochasticGameAlgorithm for ReachabilityAlgorithmCollection {
                 },
             path,
         }) = property
-            && let Some(StateFormula::Expression(ap)) = path.eventually_condition()
         {
-            Some(Self { target_states: *ap })
+            match path.as_ref() {
+                PathFormula::Eventually { condition } => Some(Self {
+                    target_states: *condition.as_expression()?,
+                    before_states: None,
+                }),
+                PathFormula::Until { before, after } => Some(Self {
+                    target_states: *after.as_expression()?,
+                    before_states: Some(*before.as_expression()?),
+                }),
+                _ => None,
+            }
         } else {
             None
         }
@@ -57,7 +91,11 @@ impl NonstochasticGameAlgorithm for ReachabilityAlgorithmCollection {
         model: &ProbabilisticModel<M>,
         context: &mut Self::ModelContext,
     ) -> TwoPlayer {
-        self.winning_from_state_with_context(model, context.initial_state, context)
+        if let [initial_state] = context.initial_states[..] {
+            return self.winning_from_state_with_context(model, initial_state, context);
+        }
+        let winning_region = self.winning_region_with_context(model, context);
+        winner_from_initial_states(&winning_region, &context.initial_states)
     }
 
     fn winning_from_state_with_context<
@@ -91,16 +129,83 @@ impl NonstochasticGameAlgorithm for ReachabilityAlgorithmCollection {
             &mut context.buffer,
         )
     }
+
+    fn winning_incremental_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> TwoPlayer {
+        let initial_states = &context.initial_states;
+        match initial_states
+            .iter()
+            .all(|&state| context.attractor.contains_state(model, state))
+        {
+            true => TwoPlayer::PlayerOne,
+            false => TwoPlayer::PlayerTwo,
+        }
+    }
+
+    fn winning_region_incremental_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Self::WinningRegionType {
+        context.attractor.region(model)
+    }
+
+    fn winning_region_and_strategy_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> (Self::WinningRegionType, MemorylessStrategy) {
+        let mut strategy = MemorylessStrategy::create(model.states.len());
+        let winning_region = attractor::attractor_strategy_with_buffer(
+            model,
+            context.target_states.iter().cloned(),
+            &mut context.buffer,
+            &mut strategy,
+            TwoPlayer::PlayerOne,
+        );
+        (winning_region, strategy)
+    }
 }
 
+#[derive(Clone)]
 pub struct ReachabilityAlgorithmContext {
     target_states: Vec<usize>,
+    sink_states: Vec<bool>,
     buffer: attractor::AttractorBuffer,
-    initial_state: usize,
+    // Kept separately from the buffer, which is reset for every computation from scratch
+    attractor: attractor::IncrementalAttractor,
+    initial_states: Vec<usize>,
+}
+
+impl ReachabilityAlgorithmContext {
+    fn reset_buffer<M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>>(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) {
+        self.buffer.reset_owner_counts(model, TwoPlayer::PlayerOne);
+        for (index, &sink) in self.sink_states.iter().enumerate() {
+            if sink {
+                self.buffer.reset_sink_state(index);
+            }
+        }
+    }
 }
 
 impl super::ChangeableOwners for ReachabilityAlgorithmContext {
     fn set_owner(&mut self, index: usize, owner: TwoPlayer) {
+        self.attractor.set_owner(index, owner);
+        if self.sink_states[index] {
+            return;
+        }
         match owner {
             TwoPlayer::PlayerOne => self.buffer.reset_reaching_player(index),
             TwoPlayer::PlayerTwo => self.buffer.reset_avoiding_player(index),
@@ -113,6 +218,7 @@ impl super::AdaptableOwners for ReachabilityAlgorithmContext {
         &mut self,
         model: &ProbabilisticModel<M>,
     ) {
-        self.buffer.reset_owner_counts(model, TwoPlayer::PlayerOne);
+        self.reset_buffer(model);
+        self.attractor.adapt_to_owners(model);
     }
 }
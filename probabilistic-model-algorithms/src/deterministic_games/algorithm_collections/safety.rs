@@ -1,13 +1,22 @@
-use super::NonstochasticGameAlgorithm;
+use super::{NonstochasticGameAlgorithm, winner_from_initial_states};
 use crate::attractor;
+use crate::deterministic_games::MemorylessStrategy;
 use crate::regions::{FlagStateRegion, InvertedStateRegion, StateRegion};
-use probabilistic_models::probabilistic_properties::{Bound, BoundOperator, Query, StateFormula};
+use probabilistic_models::probabilistic_properties::{
+    Bound, BoundOperator, Query, SafetyComplement, StateFormula,
+};
 use probabilistic_models::{
-    AtomicProposition, InitialStates, ModelTypes, ProbabilisticModel, TwoPlayer, VectorPredecessors,
+    AtomicProposition, AtomicPropositions, InitialStates, ModelTypes, ProbabilisticModel,
+    TwoPlayer, VectorPredecessors,
 };
 
+/// Solves `G good`, `good W after` and `release R good` via their complementary reachability
+/// objective, which player two tries to satisfy by attracting the game to a bad state. Once a path
+/// visits a released state, i.e. an `after` state or a good `release` state, it no longer needs to
+/// stay in the good states, so released states are sinks that player two can never attract.
+#[derive(Clone)]
 pub struct SafetyAlgorithmCollection {
-    good_states: AtomicProposition,
+    complement: SafetyComplement<AtomicProposition>,
 }
 
 impl NonstochasticGameAlgorithm for SafetyAlgorithmCollection {
@@ -20,19 +29,29 @@ impl NonstochasticGameAlgorithm for SafetyAlgorithmCollection {
         &self,
         model: &ProbabilisticModel<M>,
     ) -> Self::ModelContext {
-        assert_eq!(model.initial_states.count(), 1);
-        let initial_state = model.initial_states.get(0);
-        let bad_states = model.get_states_without_ap(self.good_states);
-        let mut buffer = attractor::AttractorBuffer::create(model);
-        buffer.reset_owner_counts(model, TwoPlayer::PlayerTwo);
-        SafetyAlgorithmContext {
+        let initial_states = model.initial_states.iter().copied().collect();
+        let (bad_states, released_states): (Vec<bool>, Vec<bool>) = model
+            .states
+            .iter()
+            .map(|state| {
+                self.complement
+                    .classify(|ap| state.atomic_propositions.get_value(ap.index))
+            })
+            .unzip();
+        let bad_states = (0..model.states.len())
+            .filter(|&state| bad_states[state])
+            .collect();
+        let mut context = SafetyAlgorithmContext {
             bad_states,
-            buffer,
-            initial_state,
-        }
+            released_states,
+            buffer: attractor::AttractorBuffer::create(model),
+            initial_states,
+        };
+        context.reset_buffer(model);
+        context
     }
 
-    fn create_if_compatible(property: &Query<i64, f64, AtomicProposition>) -> Option<Self> {
+    fn create_if_compatible(property: &Query<f64, f64, AtomicProposition>) -> Option<Self> {
         if let Query::StateFormula(StateFormula::ProbabilityBound {
             non_determinism: Option::None,
             bound:
@@ -42,9 +61,10 @@ impl NonstochasticGameAlgorithm for SafetyAlgorithmCollection {
                 },
             path,
         }) = property
-            && let Some(StateFormula::Expression(ap)) = path.generally_condition()
         {
-            Some(Self { good_states: *ap })
+            Some(Self {
+                complement: path.as_safety_complement()?,
+            })
         } else {
             None
         }
@@ -57,7 +77,11 @@ impl NonstochasticGameAlgorithm for SafetyAlgorithmCollection {
         model: &ProbabilisticModel<M>,
         context: &mut Self::ModelContext,
     ) -> TwoPlayer {
-        self.winning_from_state_with_context(model, context.initial_state, context)
+        if let [initial_state] = context.initial_states[..] {
+            return self.winning_from_state_with_context(model, initial_state, context);
+        }
+        let winning_region = self.winning_region_with_context(model, context);
+        winner_from_initial_states(&winning_region, &context.initial_states)
     }
 
     fn winning_from_state_with_context<
@@ -92,16 +116,52 @@ impl NonstochasticGameAlgorithm for SafetyAlgorithmCollection {
         )
         .inverted()
     }
+
+    fn winning_region_and_strategy_with_context<
+        M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> (Self::WinningRegionType, MemorylessStrategy) {
+        let winning_region = self.winning_region_with_context(model, context);
+        let mut strategy = MemorylessStrategy::create(model.states.len());
+        strategy.choose_actions_staying_in(
+            model,
+            (0..model.states.len()).filter(|&s| winning_region.contains(s)),
+            &winning_region,
+        );
+        (winning_region, strategy)
+    }
 }
 
+#[derive(Clone)]
 pub struct SafetyAlgorithmContext {
     bad_states: Vec<usize>,
+    released_states: Vec<bool>,
     buffer: attractor::AttractorBuffer,
-    initial_state: usize,
+    initial_states: Vec<usize>,
+}
+
+impl SafetyAlgorithmContext {
+    fn reset_buffer<M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>>(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) {
+        self.buffer.reset_owner_counts(model, TwoPlayer::PlayerTwo);
+        for (index, &released) in self.released_states.iter().enumerate() {
+            if released {
+                self.buffer.reset_sink_state(index);
+            }
+        }
+    }
 }
 
 impl super::ChangeableOwners for SafetyAlgorithmContext {
     fn set_owner(&mut self, index: usize, owner: TwoPlayer) {
+        if self.released_states[index] {
+            return;
+        }
         match owner {
             TwoPlayer::PlayerOne => self.buffer.reset_avoiding_player(index),
             TwoPlayer::PlayerTwo => self.buffer.reset_reaching_player(index),
@@ -114,6 +174,6 @@ impl super::AdaptableOwners for SafetyAlgorithmContext {
         &mut self,
         model: &ProbabilisticModel<M>,
     ) {
-        self.buffer.reset_owner_counts(model, TwoPlayer::PlayerTwo);
+        self.reset_buffer(model);
     }
 }
@@ -4,6 +4,9 @@ pub use algorithm_collections::*;
 mod solvable_game;
 pub use solvable_game::*;
 
+mod strategy;
+pub use strategy::MemorylessStrategy;
+
 pub trait ChangeableOwners {
     fn set_owner(&mut self, state: usize, owner: probabilistic_models::TwoPlayer);
 }
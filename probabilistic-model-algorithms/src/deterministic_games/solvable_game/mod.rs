@@ -2,6 +2,11 @@ use super::algorithm_collections::{AdaptableOwners, ChangeableOwners, Nonstochas
 use crate::regions::StateRegion;
 use probabilistic_models::{ModelTypes, ProbabilisticModel, TwoPlayer, VectorPredecessors};
 
+/// A game together with the algorithm that solves it.
+///
+/// The implementations are `Clone` if the game and the solver are, and every clone gets its own
+/// copy of the solver context, so that clones can be solved independently of each other, e.g. on
+/// different threads.
 pub trait SolvableNonstochasticGame {
     type WinningRegionType: StateRegion;
 
@@ -13,6 +18,18 @@ pub trait SolvableNonstochasticGame {
 
     fn get_winning_region(&mut self) -> Self::WinningRegionType;
 
+    /// Like `get_winner`, but may build on the previous call instead of solving the game from
+    /// scratch, see `NonstochasticGameAlgorithm::winning_incremental_with_context`.
+    fn get_winner_incremental(&mut self) -> TwoPlayer {
+        self.get_winner()
+    }
+
+    /// Like `get_winning_region`, but may build on the previous call instead of solving the game
+    /// from scratch.
+    fn get_winning_region_incremental(&mut self) -> Self::WinningRegionType {
+        self.get_winning_region()
+    }
+
     fn get_game(&self) -> &ProbabilisticModel<Self::ModelTypes>;
 }
 
@@ -40,6 +57,22 @@ impl<
     }
 }
 
+impl<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    AC: NonstochasticGameAlgorithm<ModelContext: AdaptableOwners + Clone> + Clone,
+> Clone for NonstochasticGameAndSolver<M, AC>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            game: self.game.clone(),
+            solver: self.solver.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
 impl<
     M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
     AC: NonstochasticGameAlgorithm<ModelContext: AdaptableOwners>,
@@ -64,6 +97,18 @@ impl<
             .winning_region_with_context(&self.game, &mut self.context)
     }
 
+    fn get_winner_incremental(&mut self) -> TwoPlayer {
+        self.context.adapt_to_owners(&self.game);
+        self.solver
+            .winning_incremental_with_context(&self.game, &mut self.context)
+    }
+
+    fn get_winning_region_incremental(&mut self) -> Self::WinningRegionType {
+        self.context.adapt_to_owners(&self.game);
+        self.solver
+            .winning_region_incremental_with_context(&self.game, &mut self.context)
+    }
+
     fn get_game(&self) -> &ProbabilisticModel<Self::ModelTypes> {
         &self.game
     }
@@ -93,6 +138,23 @@ impl<
     }
 }
 
+/// As the owners are stored in the solver context, clones start with the owners of the original.
+impl<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    AC: NonstochasticGameAlgorithm<ModelContext: ChangeableOwners + Clone> + Clone,
+> Clone for NonstochasticGameAndSolverExternalOwners<M, AC>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            game: self.game.clone(),
+            solver: self.solver.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
 impl<
     M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
     AC: NonstochasticGameAlgorithm<ModelContext: ChangeableOwners>,
@@ -115,6 +177,16 @@ impl<
             .winning_region_with_context(&self.game, &mut self.context)
     }
 
+    fn get_winner_incremental(&mut self) -> TwoPlayer {
+        self.solver
+            .winning_incremental_with_context(&self.game, &mut self.context)
+    }
+
+    fn get_winning_region_incremental(&mut self) -> Self::WinningRegionType {
+        self.solver
+            .winning_region_incremental_with_context(&self.game, &mut self.context)
+    }
+
     fn get_game(&self) -> &ProbabilisticModel<Self::ModelTypes> {
         &self.game
     }
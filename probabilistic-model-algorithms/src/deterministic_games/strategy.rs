@@ -0,0 +1,50 @@
+use crate::regions::StateRegion;
+use probabilistic_models::{ActionCollection, Distribution, ModelTypes, ProbabilisticModel};
+
+/// A memoryless strategy that fixes at most one action per state. States without a choice are
+/// either not controlled by the player or lie outside of their winning region.
+#[derive(Clone)]
+pub struct MemorylessStrategy {
+    choices: Vec<Option<usize>>,
+}
+
+impl MemorylessStrategy {
+    pub fn create(model_state_count: usize) -> Self {
+        Self {
+            choices: vec![None; model_state_count],
+        }
+    }
+
+    pub fn model_state_count(&self) -> usize {
+        self.choices.len()
+    }
+
+    pub fn set_choice(&mut self, state: usize, action_index: usize) {
+        self.choices[state] = Some(action_index);
+    }
+
+    pub fn get_choice(&self, state: usize) -> Option<usize> {
+        self.choices[state]
+    }
+
+    /// For each of the given states without a choice, chooses the first action whose successors
+    /// all lie in `region`.
+    pub fn choose_actions_staying_in<M: ModelTypes, R: StateRegion, I: Iterator<Item = usize>>(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        states: I,
+        region: &R,
+    ) {
+        for state in states {
+            if self.choices[state].is_some() {
+                continue;
+            }
+            for (action_index, action) in model.states[state].actions.iter().enumerate() {
+                if action.successors.iter().all(|s| region.contains(s.index)) {
+                    self.choices[state] = Some(action_index);
+                    break;
+                }
+            }
+        }
+    }
+}
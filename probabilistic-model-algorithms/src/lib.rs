@@ -1,5 +1,6 @@
 pub mod attractor;
 pub mod deterministic_games;
+pub mod ltl;
 pub mod mecs;
 pub mod regions;
 pub mod sccs;
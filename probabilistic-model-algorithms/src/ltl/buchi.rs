@@ -0,0 +1,308 @@
+use probabilistic_models::AtomicProposition;
+use probabilistic_models::probabilistic_properties::{LtlFormula, StateFormula};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// A formula in negation normal form whose subformulas are indices into a `FormulaArena`
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Nnf {
+    True,
+    False,
+    Ap(usize),
+    NotAp(usize),
+    And(usize, usize),
+    Or(usize, usize),
+    Next(usize),
+    Until(usize, usize),
+    Release(usize, usize),
+}
+
+/// Stores every subformula once, so that sets of formulas can be represented by sets of indices.
+#[derive(Default)]
+struct FormulaArena {
+    formulas: Vec<Nnf>,
+    indices: HashMap<Nnf, usize>,
+}
+
+impl FormulaArena {
+    fn add(&mut self, formula: Nnf) -> usize {
+        *self.indices.entry(formula).or_insert_with(|| {
+            self.formulas.push(formula);
+            self.formulas.len() - 1
+        })
+    }
+
+    /// Adds the formula, or its negation if `negate` is set, in negation normal form. Returns
+    /// `None` if an atom of the formula is not an atomic proposition.
+    fn add_ltl<I, F>(
+        &mut self,
+        formula: &LtlFormula<I, F, AtomicProposition>,
+        negate: bool,
+    ) -> Option<usize> {
+        let formula = match formula {
+            LtlFormula::StateFormula(StateFormula::Expression(ap)) => {
+                if negate {
+                    Nnf::NotAp(ap.index)
+                } else {
+                    Nnf::Ap(ap.index)
+                }
+            }
+            LtlFormula::StateFormula(_) => return None,
+            LtlFormula::Not(formula) => return self.add_ltl(formula, !negate),
+            LtlFormula::And(left, right) => {
+                let left = self.add_ltl(left, negate)?;
+                let right = self.add_ltl(right, negate)?;
+                if negate {
+                    Nnf::Or(left, right)
+                } else {
+                    Nnf::And(left, right)
+                }
+            }
+            LtlFormula::Or(left, right) => {
+                let left = self.add_ltl(left, negate)?;
+                let right = self.add_ltl(right, negate)?;
+                if negate {
+                    Nnf::And(left, right)
+                } else {
+                    Nnf::Or(left, right)
+                }
+            }
+            // On infinite paths, `!X a` is equivalent to `X !a`.
+            LtlFormula::Next(formula) => Nnf::Next(self.add_ltl(formula, negate)?),
+            // `F a` is `true U a`, `G a` is `false R a`, and both operators are dual to each other.
+            LtlFormula::Eventually(formula) => {
+                let formula = self.add_ltl(formula, negate)?;
+                if negate {
+                    Nnf::Release(self.add(Nnf::False), formula)
+                } else {
+                    Nnf::Until(self.add(Nnf::True), formula)
+                }
+            }
+            LtlFormula::Generally(formula) => {
+                let formula = self.add_ltl(formula, negate)?;
+                if negate {
+                    Nnf::Until(self.add(Nnf::True), formula)
+                } else {
+                    Nnf::Release(self.add(Nnf::False), formula)
+                }
+            }
+            LtlFormula::Until { before, after } => {
+                let before = self.add_ltl(before, negate)?;
+                let after = self.add_ltl(after, negate)?;
+                if negate {
+                    Nnf::Release(before, after)
+                } else {
+                    Nnf::Until(before, after)
+                }
+            }
+        };
+        Some(self.add(formula))
+    }
+}
+
+/// One way of satisfying a set of obligations in the current step: the atomic propositions that
+/// must hold or not hold, the obligations for the next step, and the until-formulas whose right
+/// side was postponed.
+#[derive(Default, Clone)]
+struct Cover {
+    positive: BTreeSet<usize>,
+    negative: BTreeSet<usize>,
+    next: BTreeSet<usize>,
+    postponed: BTreeSet<usize>,
+}
+
+impl FormulaArena {
+    fn covers(&self, obligations: &BTreeSet<usize>) -> Vec<Cover> {
+        let mut covers = Vec::new();
+        self.expand(
+            obligations.iter().copied().collect(),
+            BTreeSet::new(),
+            Cover::default(),
+            &mut covers,
+        );
+        covers
+    }
+
+    fn expand(
+        &self,
+        mut todo: Vec<usize>,
+        mut done: BTreeSet<usize>,
+        mut cover: Cover,
+        covers: &mut Vec<Cover>,
+    ) {
+        while let Some(index) = todo.pop() {
+            if !done.insert(index) {
+                continue;
+            }
+            match self.formulas[index] {
+                Nnf::True => {}
+                Nnf::False => return,
+                Nnf::Ap(ap) => {
+                    if cover.negative.contains(&ap) {
+                        return;
+                    }
+                    cover.positive.insert(ap);
+                }
+                Nnf::NotAp(ap) => {
+                    if cover.positive.contains(&ap) {
+                        return;
+                    }
+                    cover.negative.insert(ap);
+                }
+                Nnf::And(left, right) => todo.extend([left, right]),
+                Nnf::Or(left, right) => {
+                    let mut other_todo = todo.clone();
+                    other_todo.push(right);
+                    self.expand(other_todo, done.clone(), cover.clone(), covers);
+                    todo.push(left);
+                }
+                Nnf::Next(formula) => {
+                    cover.next.insert(formula);
+                }
+                Nnf::Until(before, after) => {
+                    // Either `after` holds now, or `before` holds and the until is postponed.
+                    let mut other_todo = todo.clone();
+                    other_todo.push(before);
+                    let mut other_cover = cover.clone();
+                    other_cover.next.insert(index);
+                    other_cover.postponed.insert(index);
+                    self.expand(other_todo, done.clone(), other_cover, covers);
+                    todo.push(after);
+                }
+                Nnf::Release(release, condition) => {
+                    // Either both hold now, or `condition` holds and the release is postponed.
+                    let mut other_todo = todo.clone();
+                    other_todo.push(condition);
+                    let mut other_cover = cover.clone();
+                    other_cover.next.insert(index);
+                    self.expand(other_todo, done.clone(), other_cover, covers);
+                    todo.extend([release, condition]);
+                }
+            }
+        }
+        covers.push(cover);
+    }
+}
+
+pub(super) struct BuchiTransition {
+    pub positive: Vec<usize>,
+    pub negative: Vec<usize>,
+    pub target: usize,
+}
+
+impl BuchiTransition {
+    pub fn is_enabled<H: Fn(usize) -> bool>(&self, holds: &H) -> bool {
+        self.positive.iter().all(|&ap| holds(ap)) && !self.negative.iter().any(|&ap| holds(ap))
+    }
+}
+
+/// A nondeterministic Büchi automaton over sets of atomic propositions. State `0` is the initial
+/// state.
+pub(super) struct BuchiAutomaton {
+    pub transitions: Vec<Vec<BuchiTransition>>,
+    pub accepting: Vec<bool>,
+    /// The indices of all atomic propositions the automaton reads.
+    pub atomic_propositions: Vec<usize>,
+}
+
+impl BuchiAutomaton {
+    /// Translates the formula, or its negation if `negate` is set, into a Büchi automaton. The
+    /// formula is first translated into a generalised Büchi automaton with one acceptance set per
+    /// until-formula, which is then degeneralised with a counter. Returns `None` if an atom of the
+    /// formula is not an atomic proposition.
+    pub fn from_ltl<I, F>(
+        formula: &LtlFormula<I, F, AtomicProposition>,
+        negate: bool,
+    ) -> Option<Self> {
+        let mut arena = FormulaArena::default();
+        let root = arena.add_ltl(formula, negate)?;
+        let untils: Vec<usize> = (0..arena.formulas.len())
+            .filter(|&index| matches!(arena.formulas[index], Nnf::Until(..)))
+            .collect();
+        let atomic_propositions = arena
+            .formulas
+            .iter()
+            .filter_map(|formula| match formula {
+                Nnf::Ap(ap) | Nnf::NotAp(ap) => Some(*ap),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let initial = (BTreeSet::from([root]), 0);
+        let mut state_indices = HashMap::from([(initial.clone(), 0)]);
+        let mut queue = VecDeque::from([initial]);
+        let mut transitions = Vec::new();
+        let mut accepting = Vec::new();
+        while let Some((obligations, counter)) = queue.pop_front() {
+            accepting.push(counter == untils.len());
+            let first_counter = if counter == untils.len() { 0 } else { counter };
+            let mut state_transitions = Vec::new();
+            for cover in arena.covers(&obligations) {
+                let mut next_counter = first_counter;
+                while next_counter < untils.len()
+                    && !cover.postponed.contains(&untils[next_counter])
+                {
+                    next_counter += 1;
+                }
+                let successor = (cover.next, next_counter);
+                let target = match state_indices.get(&successor) {
+                    Some(&target) => target,
+                    None => {
+                        let target = state_indices.len();
+                        state_indices.insert(successor.clone(), target);
+                        queue.push_back(successor);
+                        target
+                    }
+                };
+                state_transitions.push(BuchiTransition {
+                    positive: cover.positive.into_iter().collect(),
+                    negative: cover.negative.into_iter().collect(),
+                    target,
+                });
+            }
+            transitions.push(state_transitions);
+        }
+
+        Some(Self {
+            transitions,
+            accepting,
+            atomic_propositions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Formula = LtlFormula<(), f64, AtomicProposition>;
+
+    fn ap(index: usize) -> Formula {
+        LtlFormula::StateFormula(StateFormula::Expression(AtomicProposition::new(index)))
+    }
+
+    #[test]
+    fn infinitely_often_automaton_size() {
+        // `G F a` only needs to remember whether `a` has been seen since the last visit of the
+        // accepting state
+        let formula = LtlFormula::Generally(Box::new(LtlFormula::Eventually(Box::new(ap(0)))));
+        let automaton = BuchiAutomaton::from_ltl(&formula, false).unwrap();
+
+        assert_eq!(automaton.transitions.len(), 3);
+        assert_eq!(automaton.accepting, vec![false, false, true]);
+        assert_eq!(automaton.atomic_propositions, vec![0]);
+        for transitions in &automaton.transitions {
+            // Postponing `F a` leads to state 1, reading `a` leads to the accepting state
+            let targets: BTreeSet<usize> = transitions
+                .iter()
+                .map(|transition| transition.target)
+                .collect();
+            assert_eq!(targets, BTreeSet::from([1, 2]));
+            for transition in transitions {
+                assert!(transition.negative.is_empty());
+                assert_eq!(transition.positive.is_empty(), transition.target == 1);
+            }
+        }
+    }
+}
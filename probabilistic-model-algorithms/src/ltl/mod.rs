@@ -0,0 +1,209 @@
+//! LTL model checking of MDPs. The formula is translated into a Büchi automaton, which is
+//! determinised into a Rabin automaton with Safra's construction. The probability of the formula
+//! is then the probability of reaching an accepting end component in the product of the model and
+//! the Rabin automaton.
+
+mod buchi;
+mod rabin;
+
+use crate::mecs;
+use crate::value_iteration::mdp::{ReachabilityObjective, optimistic_reachability_maximise};
+use buchi::BuchiAutomaton;
+use probabilistic_models::probabilistic_properties::LtlFormula;
+use probabilistic_models::{
+    Action, ActionCollection, ActionVector, AtomicProposition, AtomicPropositions, Builder,
+    Distribution, DistributionBuilder, DistributionVector, InitialStates, InitialStatesBuilder,
+    ModelTypes, Predecessors, PredecessorsBuilder, ProbabilisticModel, SinglePlayer, State,
+    Successor, VectorPredecessors,
+};
+use rabin::RabinAutomaton;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// Computes the maximal probability of satisfying the formula for every state of the model.
+/// Returns `None` if an atom of the formula is not an atomic proposition.
+pub fn ltl_probabilities_maximise<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+    I,
+    F,
+>(
+    model: ProbabilisticModel<M>,
+    formula: &LtlFormula<I, F, AtomicProposition>,
+    eps: f64,
+) -> Option<Vec<f64>> {
+    let buchi = BuchiAutomaton::from_ltl(formula, false)?;
+    Some(maximise_acceptance(model, RabinAutomaton::new(buchi), eps))
+}
+
+/// Computes the minimal probability of satisfying the formula for every state of the model as
+/// one minus the maximal probability of satisfying its negation. Returns `None` if an atom of the
+/// formula is not an atomic proposition.
+pub fn ltl_probabilities_minimise<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+    I,
+    F,
+>(
+    model: ProbabilisticModel<M>,
+    formula: &LtlFormula<I, F, AtomicProposition>,
+    eps: f64,
+) -> Option<Vec<f64>> {
+    let buchi = BuchiAutomaton::from_ltl(formula, true)?;
+    let values = maximise_acceptance(model, RabinAutomaton::new(buchi), eps);
+    Some(values.into_iter().map(|value| 1.0 - value).collect())
+}
+
+fn maximise_acceptance<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: ProbabilisticModel<M>,
+    mut automaton: RabinAutomaton,
+    eps: f64,
+) -> Vec<f64> {
+    let state_count = model.states.len();
+    let (product, automaton_states) = build_product(model, &mut automaton);
+
+    // For every Rabin pair, the accepting end components only visit trees containing the node
+    // and visit a tree in which the node is marked.
+    let nodes: Vec<Vec<(usize, bool)>> = automaton_states
+        .iter()
+        .map(|&automaton_state| automaton.nodes(automaton_state))
+        .collect();
+    let names: BTreeSet<usize> = nodes.iter().flatten().map(|&(name, _)| name).collect();
+    let mut target = vec![false; product.states.len()];
+    for name in names {
+        let contains_node: Vec<bool> = nodes
+            .iter()
+            .map(|nodes| nodes.iter().any(|&(other, _)| other == name))
+            .collect();
+        let marked: Vec<bool> = nodes
+            .iter()
+            .map(|nodes| nodes.contains(&(name, true)))
+            .collect();
+        let mecs = mecs::compute_mecs_with_allowed_actions(&product, |state, action| {
+            contains_node[state]
+                && product.states[state]
+                    .actions
+                    .get_action(action)
+                    .successors
+                    .iter()
+                    .all(|successor| contains_node[successor.index])
+        });
+        let state_mecs: Vec<Option<usize>> = (0..product.states.len())
+            .map(|state| mecs.mec_of_state(state))
+            .collect();
+        let mut accepting_mecs = vec![false; mecs.len()];
+        for (mec, marked) in state_mecs.iter().zip(marked) {
+            if let Some(mec) = mec {
+                accepting_mecs[*mec] |= marked;
+            }
+        }
+        for (mec, target) in state_mecs.iter().zip(target.iter_mut()) {
+            if let Some(mec) = mec {
+                *target |= accepting_mecs[*mec];
+            }
+        }
+    }
+
+    let objective = ReachabilityObjective::new(target, vec![false; product.states.len()]);
+    let mut values = optimistic_reachability_maximise(product, &objective, eps);
+    values.truncate(state_count);
+    values
+}
+
+/// Builds the product of the model with the automaton, which reads the label of every state it
+/// enters. The first states of the product correspond to the states of the model, in which the
+/// automaton has only read the label of that state. Also returns the automaton state of every
+/// product state.
+fn build_product<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    mut model: ProbabilisticModel<M>,
+    automaton: &mut RabinAutomaton,
+) -> (ProbabilisticModel<M>, Vec<usize>) {
+    let states = std::mem::take(&mut model.states);
+    let mut step = |automaton_state: usize, state: usize| {
+        automaton.successor(automaton_state, |ap| {
+            states[state].atomic_propositions.get_value(ap)
+        })
+    };
+
+    let mut product_states: Vec<(usize, usize)> = (0..states.len())
+        .map(|state| (state, step(0, state)))
+        .collect();
+    let mut product_indices: HashMap<(usize, usize), usize> = product_states
+        .iter()
+        .enumerate()
+        .map(|(index, &product_state)| (product_state, index))
+        .collect();
+    let mut queue: VecDeque<usize> = (0..states.len()).collect();
+    while let Some(index) = queue.pop_front() {
+        let (state, automaton_state) = product_states[index];
+        let mut actions = <M::ActionCollection as ActionCollection<M::Distribution>>::get_builder();
+        for action in states[state].actions.iter() {
+            let mut distribution = <M::Distribution as Distribution>::get_builder();
+            for successor in action.successors.iter() {
+                let product_state = (successor.index, step(automaton_state, successor.index));
+                let successor_index = match product_indices.get(&product_state) {
+                    Some(&successor_index) => successor_index,
+                    None => {
+                        product_states.push(product_state);
+                        product_indices.insert(product_state, product_states.len() - 1);
+                        queue.push_back(product_states.len() - 1);
+                        product_states.len() - 1
+                    }
+                };
+                distribution.add_successor(Successor {
+                    index: successor_index,
+                    probability: successor.probability,
+                });
+            }
+            actions.add_action(Action {
+                successors: distribution.finish(),
+                action_name_index: action.action_name_index,
+            });
+        }
+        let original = &states[state];
+        model.states.push(State {
+            valuation: original.valuation.clone(),
+            actions: actions.finish(),
+            atomic_propositions: AtomicPropositions::from_other(
+                model.atomic_proposition_count,
+                &original.atomic_propositions,
+            ),
+            owner: (),
+            predecessors: <M::Predecessors as Predecessors>::Builder::create().finish(),
+        });
+    }
+
+    let mut initial_states = <M::InitialStates as InitialStates>::get_builder();
+    initial_states.add_by_index(model.initial_states.get(0));
+    model.initial_states = initial_states.finish();
+    model.reward_structures.clear();
+    model.exit_rates = None;
+    model.rebuild_predecessors();
+
+    let automaton_states = product_states
+        .into_iter()
+        .map(|(_, automaton_state)| automaton_state)
+        .collect();
+    (model, automaton_states)
+}
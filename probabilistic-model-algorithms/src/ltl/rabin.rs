@@ -0,0 +1,288 @@
+use super::buchi::BuchiAutomaton;
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SafraNode {
+    name: usize,
+    label: BTreeSet<usize>,
+    marked: bool,
+    /// The children ordered from the oldest to the youngest.
+    children: Vec<SafraNode>,
+}
+
+impl SafraNode {
+    fn unmark(&mut self) {
+        self.marked = false;
+        self.children.iter_mut().for_each(SafraNode::unmark);
+    }
+
+    fn collect_names(&self, names: &mut BTreeSet<usize>) {
+        names.insert(self.name);
+        for child in &self.children {
+            child.collect_names(names);
+        }
+    }
+
+    fn collect_nodes(&self, nodes: &mut Vec<(usize, bool)>) {
+        nodes.push((self.name, self.marked));
+        for child in &self.children {
+            child.collect_nodes(nodes);
+        }
+    }
+
+    fn add_accepting_children(&mut self, buchi: &BuchiAutomaton, names: &mut BTreeSet<usize>) {
+        for child in &mut self.children {
+            child.add_accepting_children(buchi, names);
+        }
+        let label: BTreeSet<usize> = self
+            .label
+            .iter()
+            .copied()
+            .filter(|&state| buchi.accepting[state])
+            .collect();
+        if !label.is_empty() {
+            let name = (0..).find(|name| !names.contains(name)).unwrap();
+            names.insert(name);
+            self.children.push(SafraNode {
+                name,
+                label,
+                marked: false,
+                children: Vec::new(),
+            });
+        }
+    }
+
+    fn apply_transitions<H: Fn(usize) -> bool>(&mut self, buchi: &BuchiAutomaton, holds: &H) {
+        self.label = self
+            .label
+            .iter()
+            .flat_map(|&state| &buchi.transitions[state])
+            .filter(|transition| transition.is_enabled(holds))
+            .map(|transition| transition.target)
+            .collect();
+        for child in &mut self.children {
+            child.apply_transitions(buchi, holds);
+        }
+    }
+
+    fn remove_states(&mut self, states: &BTreeSet<usize>) {
+        self.label.retain(|state| !states.contains(state));
+        for child in &mut self.children {
+            child.remove_states(states);
+        }
+    }
+
+    /// Removes every state from a node that also occurs in an older sibling of the node.
+    fn merge_horizontally(&mut self) {
+        let mut older = BTreeSet::new();
+        for child in &mut self.children {
+            child.remove_states(&older);
+            older.extend(child.label.iter().copied());
+            child.merge_horizontally();
+        }
+    }
+
+    fn remove_empty_children(&mut self) {
+        self.children.retain(|child| !child.label.is_empty());
+        self.children
+            .iter_mut()
+            .for_each(SafraNode::remove_empty_children);
+    }
+
+    /// Removes the children of every node whose children together contain all of its states, and
+    /// marks the node.
+    fn merge_vertically(&mut self) {
+        let covered: BTreeSet<usize> = self
+            .children
+            .iter()
+            .flat_map(|child| child.label.iter().copied())
+            .collect();
+        if !self.children.is_empty() && covered == self.label {
+            self.children.clear();
+            self.marked = true;
+        } else {
+            self.children
+                .iter_mut()
+                .for_each(SafraNode::merge_vertically);
+        }
+    }
+}
+
+/// A deterministic Rabin automaton obtained from a Büchi automaton with Safra's construction. The
+/// states are constructed on demand. State `0` is the initial state.
+///
+/// Every node name `i` of the Safra trees defines a Rabin pair: a run is accepting if, for some
+/// `i`, it eventually only visits trees that contain a node named `i` and that node is marked
+/// infinitely often.
+pub(super) struct RabinAutomaton {
+    buchi: BuchiAutomaton,
+    trees: Vec<Option<SafraNode>>,
+    tree_indices: HashMap<Option<SafraNode>, usize>,
+    successors: HashMap<(usize, Vec<bool>), usize>,
+}
+
+impl RabinAutomaton {
+    pub fn new(buchi: BuchiAutomaton) -> Self {
+        let initial = Some(SafraNode {
+            name: 0,
+            label: BTreeSet::from([0]),
+            marked: false,
+            children: Vec::new(),
+        });
+        Self {
+            buchi,
+            trees: vec![initial.clone()],
+            tree_indices: HashMap::from([(initial, 0)]),
+            successors: HashMap::new(),
+        }
+    }
+
+    /// Returns the successor of the state when reading the letter in which exactly the atomic
+    /// propositions hold for which `holds` returns `true`.
+    pub fn successor<H: Fn(usize) -> bool>(&mut self, state: usize, holds: H) -> usize {
+        let letter: Vec<bool> = self
+            .buchi
+            .atomic_propositions
+            .iter()
+            .map(|&ap| holds(ap))
+            .collect();
+        if let Some(&successor) = self.successors.get(&(state, letter.clone())) {
+            return successor;
+        }
+
+        let successor = self.trees[state].clone().and_then(|mut root| {
+            root.unmark();
+            let mut names = BTreeSet::new();
+            root.collect_names(&mut names);
+            root.add_accepting_children(&self.buchi, &mut names);
+            root.apply_transitions(&self.buchi, &holds);
+            root.merge_horizontally();
+            root.remove_empty_children();
+            root.merge_vertically();
+            (!root.label.is_empty()).then_some(root)
+        });
+        let successor = match self.tree_indices.get(&successor) {
+            Some(&index) => index,
+            None => {
+                self.trees.push(successor.clone());
+                self.tree_indices.insert(successor, self.trees.len() - 1);
+                self.trees.len() - 1
+            }
+        };
+        self.successors.insert((state, letter), successor);
+        successor
+    }
+
+    /// Returns the name of every node in the tree of the state and whether it is marked.
+    pub fn nodes(&self, state: usize) -> Vec<(usize, bool)> {
+        let mut nodes = Vec::new();
+        if let Some(root) = &self.trees[state] {
+            root.collect_nodes(&mut nodes);
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use probabilistic_models::AtomicProposition;
+    use probabilistic_models::probabilistic_properties::{LtlFormula, StateFormula};
+
+    type Formula = LtlFormula<(), f64, AtomicProposition>;
+    /// A letter lists the atomic propositions that hold
+    type Word<'a> = &'a [&'a [usize]];
+
+    fn ap(index: usize) -> Formula {
+        LtlFormula::StateFormula(StateFormula::Expression(AtomicProposition::new(index)))
+    }
+
+    fn until(before: Formula, after: Formula) -> Formula {
+        LtlFormula::Until {
+            before: Box::new(before),
+            after: Box::new(after),
+        }
+    }
+
+    /// Whether the automaton of the formula, or of its negation if `negate` is set, accepts the
+    /// word that reads the prefix once and then repeats the cycle forever
+    fn accepts(formula: &Formula, negate: bool, prefix: Word, cycle: Word) -> bool {
+        let buchi = BuchiAutomaton::from_ltl(formula, negate).unwrap();
+        let mut automaton = RabinAutomaton::new(buchi);
+        let mut state = 0;
+        for letter in prefix {
+            state = automaton.successor(state, |ap| letter.contains(&ap));
+        }
+
+        // The automaton is deterministic, so once it starts the cycle in the same state twice, it
+        // visits the same states forever
+        let mut visited = Vec::new();
+        let mut cycle_starts: Vec<(usize, usize)> = Vec::new();
+        let recurring = loop {
+            if let Some(&(_, start)) = cycle_starts.iter().find(|&&(other, _)| other == state) {
+                break &visited[start..];
+            }
+            cycle_starts.push((state, visited.len()));
+            for letter in cycle {
+                state = automaton.successor(state, |ap| letter.contains(&ap));
+                visited.push(state);
+            }
+        };
+
+        let nodes: Vec<Vec<(usize, bool)>> = recurring
+            .iter()
+            .map(|&state| automaton.nodes(state))
+            .collect();
+        let names: BTreeSet<usize> = nodes.iter().flatten().map(|&(name, _)| name).collect();
+        names.into_iter().any(|name| {
+            nodes
+                .iter()
+                .all(|nodes| nodes.iter().any(|&(other, _)| other == name))
+                && nodes.iter().any(|nodes| nodes.contains(&(name, true)))
+        })
+    }
+
+    fn assert_accepts(formula: &Formula, prefix: Word, cycle: Word, expected: bool) {
+        assert_eq!(accepts(formula, false, prefix, cycle), expected);
+        assert_eq!(accepts(formula, true, prefix, cycle), !expected);
+    }
+
+    #[test]
+    fn persistence_and_recurrence() {
+        let persistence = LtlFormula::Eventually(Box::new(LtlFormula::Generally(Box::new(ap(0)))));
+        let recurrence = LtlFormula::Generally(Box::new(LtlFormula::Eventually(Box::new(ap(0)))));
+
+        // The prefix, the cycle, and whether `F G a` and `G F a` hold
+        let words: [(Word, Word, bool, bool); 5] = [
+            (&[], &[&[0]], true, true),
+            (&[], &[&[]], false, false),
+            (&[], &[&[0], &[]], false, true),
+            (&[&[], &[]], &[&[0]], true, true),
+            (&[&[0]], &[&[]], false, false),
+        ];
+        for (prefix, cycle, persistent, recurrent) in words {
+            assert_accepts(&persistence, prefix, cycle, persistent);
+            assert_accepts(&recurrence, prefix, cycle, recurrent);
+        }
+    }
+
+    #[test]
+    fn nested_untils() {
+        let (a, b, c) = (0, 1, 2);
+
+        // Every position must be followed by `c`, so both untils have to be fulfilled infinitely
+        // often and the Büchi automaton needs a counter over both of them
+        let formula = LtlFormula::Generally(Box::new(until(ap(a), until(ap(b), ap(c)))));
+        assert_accepts(&formula, &[], &[&[a], &[b], &[c]], true);
+        assert_accepts(&formula, &[&[a], &[b]], &[&[c]], true);
+        assert_accepts(&formula, &[], &[&[a, b]], false);
+        assert_accepts(&formula, &[], &[&[a], &[b]], false);
+        assert_accepts(&formula, &[&[c]], &[&[a]], false);
+
+        let formula = until(until(ap(a), ap(b)), ap(c));
+        assert_accepts(&formula, &[&[a], &[b]], &[&[c]], true);
+        assert_accepts(&formula, &[&[a], &[a, b]], &[&[c]], true);
+        assert_accepts(&formula, &[&[a], &[b], &[a]], &[&[c]], false);
+        assert_accepts(&formula, &[], &[&[a]], false);
+    }
+}
@@ -8,7 +8,29 @@ use probabilistic_models::{
 };
 
 pub fn compute_mecs<M: ModelTypes>(model: &ProbabilisticModel<M>) -> Mecs {
+    compute_mecs_of_sub_mdp(model, SubMdp::new(model))
+}
+
+/// Computes the MECs of the sub-MDP that only contains the actions for which `is_allowed` returns
+/// `true`. The function receives the state index and the action index.
+pub fn compute_mecs_with_allowed_actions<M: ModelTypes, F: Fn(usize, usize) -> bool>(
+    model: &ProbabilisticModel<M>,
+    is_allowed: F,
+) -> Mecs {
     let mut sub_mdp = SubMdp::new(model);
+    for (state_index, state) in sub_mdp.states.iter_mut().enumerate() {
+        for (action_index, enabled) in state.enabled_actions.iter_mut().enumerate() {
+            *enabled = is_allowed(state_index, action_index);
+        }
+        state.enabled = state.any_action_enabled();
+    }
+    compute_mecs_of_sub_mdp(model, sub_mdp)
+}
+
+fn compute_mecs_of_sub_mdp<M: ModelTypes>(
+    model: &ProbabilisticModel<M>,
+    mut sub_mdp: SubMdp,
+) -> Mecs {
     loop {
         let sccs: StateToSccMap = super::sccs::compute_sccs(model, &sub_mdp);
 
@@ -2,6 +2,7 @@ use probabilistic_models::{ActionCollection, Distribution, ModelTypes, Probabili
 
 pub type SccsWithDependencies = SccList<SccWithDependencies>;
 
+#[derive(Clone)]
 pub struct SccList<T = SccWithoutDependencies> {
     pub sccs: Vec<T>,
 }
@@ -146,6 +147,7 @@ impl SccList<SccWithoutDependencies> {
     }
 }
 
+#[derive(Clone)]
 pub struct SccWithDependencies {
     pub members: Vec<usize>,
     pub is_trivial: bool,
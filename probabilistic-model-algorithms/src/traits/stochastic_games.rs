@@ -5,6 +5,9 @@ use probabilistic_models::{
 
 pub trait StochasticGameAlgorithm: Sized {
     type ModelContext;
+    /// Why the model cannot be solved with this algorithm, e.g. because it lacks the reward
+    /// structure that the property refers to
+    type ModelContextError;
 
     fn create_model_context<
         M: ModelTypes<
@@ -16,11 +19,11 @@ pub trait StochasticGameAlgorithm: Sized {
     >(
         &self,
         model: &ProbabilisticModel<M>,
-    ) -> Self::ModelContext;
+    ) -> Result<Self::ModelContext, Self::ModelContextError>;
 
     fn create_if_compatible(
         property: &probabilistic_models::probabilistic_properties::Query<
-            i64,
+            f64,
             f64,
             AtomicProposition,
         >,
@@ -36,9 +39,25 @@ pub trait StochasticGameAlgorithm: Sized {
     >(
         &mut self,
         model: &ProbabilisticModel<M>,
-    ) -> f64 {
-        let mut context = self.create_model_context(model);
-        self.player_one_probability_with_context(model, &mut context)
+    ) -> Result<f64, Self::ModelContextError> {
+        let mut context = self.create_model_context(model)?;
+        Ok(self.player_one_probability_with_context(model, &mut context))
+    }
+
+    /// Computes the probability with which player one wins from every state, indexed by state
+    fn player_one_probabilities<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) -> Result<Vec<f64>, Self::ModelContextError> {
+        let mut context = self.create_model_context(model)?;
+        Ok(self.player_one_probabilities_with_context(model, &mut context))
     }
 
     fn player_one_probability_with_context<
@@ -53,8 +72,60 @@ pub trait StochasticGameAlgorithm: Sized {
         model: &ProbabilisticModel<M>,
         context: &mut Self::ModelContext,
     ) -> f64;
+
+    /// Computes the probability with which player one wins from every state, indexed by state
+    fn player_one_probabilities_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Vec<f64>;
+
+    /// Like `player_one_probability_with_context`, but may build on the previous computation with
+    /// the same context instead of solving the game from scratch. This pays off when solving a
+    /// sequence of games that only differ in the owners of a few states. By default, the game is
+    /// solved from scratch. Only value iteration for probabilities solves games incrementally,
+    /// expected rewards are always solved from scratch.
+    fn player_one_probability_incremental_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> f64 {
+        self.player_one_probability_with_context(model, context)
+    }
+
+    /// Like `player_one_probabilities_with_context`, but may build on the previous computation with
+    /// the same context, see `player_one_probability_incremental_with_context`.
+    fn player_one_probabilities_incremental_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Vec<f64> {
+        self.player_one_probabilities_with_context(model, context)
+    }
 }
 
+/// The stochastic counterpart of `SolvableNonstochasticGame`, with the same cloning behaviour.
 pub trait SolvableStochasticGame {
     type ModelTypes: ModelTypes<
             Predecessors = VectorPredecessors,
@@ -67,6 +138,20 @@ pub trait SolvableStochasticGame {
 
     fn maximum_player_1_probability(&mut self) -> f64;
 
+    fn player_1_probabilities(&mut self) -> Vec<f64>;
+
+    /// Like `maximum_player_1_probability`, but may build on the previous call instead of solving
+    /// the game from scratch, see `StochasticGameAlgorithm::player_one_probability_incremental_with_context`.
+    fn maximum_player_1_probability_incremental(&mut self) -> f64 {
+        self.maximum_player_1_probability()
+    }
+
+    /// Like `player_1_probabilities`, but may build on the previous call instead of solving the
+    /// game from scratch.
+    fn player_1_probabilities_incremental(&mut self) -> Vec<f64> {
+        self.player_1_probabilities()
+    }
+
     fn get_game(&self) -> &ProbabilisticModel<Self::ModelTypes>;
 }
 
@@ -94,13 +179,13 @@ impl<
     A: StochasticGameAlgorithm,
 > StochasticGameAndSolver<M, A>
 {
-    pub fn new(game: ProbabilisticModel<M>, solver: A) -> Self {
-        let context = solver.create_model_context(&game);
-        Self {
+    pub fn new(game: ProbabilisticModel<M>, solver: A) -> Result<Self, A::ModelContextError> {
+        let context = solver.create_model_context(&game)?;
+        Ok(Self {
             game,
             solver,
             context,
-        }
+        })
     }
 
     pub fn with_existing_context(
@@ -116,6 +201,27 @@ impl<
     }
 }
 
+impl<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+    A: StochasticGameAlgorithm<ModelContext: Clone> + Clone,
+> Clone for StochasticGameAndSolver<M, A>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            game: self.game.clone(),
+            solver: self.solver.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
 impl<
     M: ModelTypes<
             Predecessors = VectorPredecessors,
@@ -137,6 +243,21 @@ impl<
             .player_one_probability_with_context(&self.game, &mut self.context)
     }
 
+    fn player_1_probabilities(&mut self) -> Vec<f64> {
+        self.solver
+            .player_one_probabilities_with_context(&self.game, &mut self.context)
+    }
+
+    fn maximum_player_1_probability_incremental(&mut self) -> f64 {
+        self.solver
+            .player_one_probability_incremental_with_context(&self.game, &mut self.context)
+    }
+
+    fn player_1_probabilities_incremental(&mut self) -> Vec<f64> {
+        self.solver
+            .player_one_probabilities_incremental_with_context(&self.game, &mut self.context)
+    }
+
     fn get_game(&self) -> &ProbabilisticModel<Self::ModelTypes> {
         &self.game
     }
@@ -2,6 +2,7 @@ use probabilistic_models::{
     AtomicPropositions, InitialStates, ProbabilisticModel, SingleStateDistribution,
 };
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 
 pub fn is_reachable<M: probabilistic_models::ModelTypes<Distribution = SingleStateDistribution>>(
     model: &ProbabilisticModel<M>,
@@ -10,6 +11,7 @@ pub fn is_reachable<M: probabilistic_models::ModelTypes<Distribution = SingleSta
     let mut open_list = Vec::new();
     let mut predecessors = HashMap::new();
     for &initial in model.initial_states.iter() {
+        predecessors.insert(initial, Predecessor::None);
         if model.states[initial]
             .atomic_propositions
             .get_value(objective_ap_index)
@@ -18,13 +20,17 @@ pub fn is_reachable<M: probabilistic_models::ModelTypes<Distribution = SingleSta
         }
 
         open_list.push(initial);
-        predecessors.insert(initial, Predecessor::None);
     }
 
     while let Some(state) = open_list.pop() {
         for successor in model.states.get(state).unwrap().get_all_successors() {
             let index = successor.target_index;
-            if !predecessors.contains_key(&index) {
+            if let Entry::Vacant(entry) = predecessors.entry(index) {
+                entry.insert(Predecessor::Some {
+                    index: state,
+                    action_index: successor.action_index,
+                });
+
                 if model.states[index]
                     .atomic_propositions
                     .get_value(objective_ap_index)
@@ -33,13 +39,6 @@ pub fn is_reachable<M: probabilistic_models::ModelTypes<Distribution = SingleSta
                 }
 
                 open_list.push(index);
-                predecessors.insert(
-                    index,
-                    Predecessor::Some {
-                        index: state,
-                        action_index: successor.action_index,
-                    },
-                );
             }
         }
     }
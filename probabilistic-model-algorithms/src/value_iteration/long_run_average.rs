@@ -0,0 +1,539 @@
+use super::{Maximiser, Minimiser, StateData, ValueComparator, value_iteration_internal};
+use crate::mecs;
+use crate::sccs::{ExclusionList, NoExclusion, Scc, SccList};
+use probabilistic_models::{
+    ActionCollection, Distribution, ModelTypes, ProbabilisticModel, RewardStructure, SinglePlayer,
+    VectorPredecessors,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Computes, for every state of the Markov chain, the long-run average reward. The gain of every
+/// bottom SCC is obtained by solving a linear equation system, the probabilities of ending up in
+/// the bottom SCCs by value iteration.
+pub fn long_run_average_markov_chain<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    eps: f64,
+) -> Vec<f64> {
+    let sccs: SccList = crate::sccs::compute_sccs(model, &NoExclusion::new());
+    let mut scc_of_state = vec![0; model.states.len()];
+    for (scc_index, scc) in sccs.sccs.iter().enumerate() {
+        for &member in scc.get_members() {
+            scc_of_state[member] = scc_index;
+        }
+    }
+
+    let mut data = vec![StateData::new(); model.states.len()];
+    let mut local_index = vec![0; model.states.len()];
+    let mut excluded = Vec::new();
+    for (scc_index, scc) in sccs.sccs.iter().enumerate() {
+        let members = scc.get_members();
+        let is_bottom = members.iter().all(|&member| {
+            model.states[member].actions.iter().all(|action| {
+                action
+                    .successors
+                    .iter()
+                    .all(|s| scc_of_state[s.index] == scc_index)
+            })
+        });
+        if !is_bottom {
+            continue;
+        }
+
+        for (index, &member) in members.iter().enumerate() {
+            local_index[member] = index;
+        }
+        let gain = end_component_gain(
+            model,
+            rewards,
+            members,
+            &local_index,
+            |member| 0..model.states[member].actions.get_number_of_actions(),
+            eps,
+            Maximiser {},
+        );
+        for &member in members {
+            data[member].value = gain;
+            excluded.push(member);
+        }
+    }
+
+    let sccs: SccList = crate::sccs::compute_sccs(model, &ExclusionList::new(&excluded[..]));
+    let sccs = sccs.compute_dependencies(model);
+    let order = sccs.get_reverse_topological_order();
+    value_iteration_internal(model, &mut data, eps, &sccs, &order[..], Maximiser {});
+
+    data.into_iter().map(|d| d.value).collect()
+}
+
+/// Computes, for every state, the maximal long-run average reward.
+pub fn long_run_average_maximise<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    eps: f64,
+) -> Vec<f64> {
+    long_run_average(model, rewards, eps, Maximiser {})
+}
+
+/// Computes, for every state, the minimal long-run average reward.
+pub fn long_run_average_minimise<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    eps: f64,
+) -> Vec<f64> {
+    long_run_average(model, rewards, eps, Minimiser {})
+}
+
+/// Every path eventually stays in some end component forever, so the optimal long-run average
+/// reward is the optimal expected gain of the MEC a path ends up in. Within a MEC, all states
+/// can reach each other, so its optimal gain is the same for all of its states.
+fn long_run_average<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+    C: ValueComparator<SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    eps: f64,
+    value_comparator: C,
+) -> Vec<f64> {
+    let mecs = mecs::compute_mecs(model);
+    let mut members = vec![Vec::new(); mecs.len()];
+    for state_index in 0..model.states.len() {
+        if let Some(mec_index) = mecs.mec_of_state(state_index) {
+            members[mec_index].push(state_index);
+        }
+    }
+    let mut local_index = vec![0; model.states.len()];
+    for mec_members in &members {
+        for (index, &member) in mec_members.iter().enumerate() {
+            local_index[member] = index;
+        }
+    }
+    let gains: Vec<f64> = members
+        .iter()
+        .map(|mec_members| {
+            end_component_gain(
+                model,
+                rewards,
+                mec_members,
+                &local_index,
+                |member| mecs.enabled_actions(member),
+                eps,
+                value_comparator,
+            )
+        })
+        .collect();
+
+    let mut values = vec![0.0; model.states.len()];
+    // States of a MEC are updated together: they can either stay in the MEC forever and obtain
+    // its gain or leave it by any action of any of its states. Afterwards, no end components
+    // remain, so value iteration from below converges to the optimal value.
+    let mut groups: Vec<(Vec<usize>, Option<f64>)> = members
+        .into_iter()
+        .zip(gains)
+        .map(|(mec_members, gain)| (mec_members, Some(gain)))
+        .collect();
+    for (state_index, state) in model.states.iter().enumerate() {
+        if mecs.mec_of_state(state_index).is_some() {
+            continue;
+        }
+        if state.actions.get_number_of_actions() == 0 {
+            // Deadlocks are treated as if they had a self-loop.
+            values[state_index] = rewards.state_reward(state_index);
+        } else {
+            groups.push((vec![state_index], None));
+        }
+    }
+
+    loop {
+        let mut converged = true;
+        for (group_members, gain) in &groups {
+            let mut best_value = match gain {
+                Some(gain) => *gain,
+                None => value_comparator.initial_value(&()),
+            };
+            for &member in group_members {
+                let leaving_actions = model.states[member]
+                    .actions
+                    .iter()
+                    .enumerate()
+                    .filter(|&(action_index, _)| !is_mec_action(&mecs, member, action_index));
+                for (_, action) in leaving_actions {
+                    let mut value = 0.0;
+                    for successor in action.successors.iter() {
+                        value += successor.probability * values[successor.index];
+                    }
+                    if value_comparator.is_better(&(), best_value, value) {
+                        best_value = value;
+                    }
+                }
+            }
+
+            let previous = values[group_members[0]];
+            if (best_value - previous).abs() > eps * best_value.abs().max(1.0) {
+                converged = false;
+            }
+            for &member in group_members {
+                values[member] = best_value;
+            }
+        }
+        if converged {
+            return values;
+        }
+    }
+}
+
+fn is_mec_action(mecs: &mecs::Mecs, state_index: usize, action_index: usize) -> bool {
+    mecs.mec_of_state(state_index).is_some()
+        && mecs
+            .enabled_actions(state_index)
+            .any(|enabled| enabled == action_index)
+}
+
+/// Computes the optimal gain of an end component by policy iteration, only using the actions
+/// returned by `enabled_actions`. A member without actions behaves like a self-loop. `local_index`
+/// maps every member to its position in `members`. Every evaluated policy has a single recurrent
+/// class, so its gain `g` and bias `h` are the unique solution of `g + h(s) = r(s) + sum_t P(s, t)
+/// h(t)` with `h` fixed to zero in one recurrent state. An action only replaces the current one if
+/// it improves the value by more than `eps`.
+fn end_component_gain<
+    M: ModelTypes<Owners = SinglePlayer>,
+    A: Fn(usize) -> I,
+    I: Iterator<Item = usize>,
+    C: ValueComparator<SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    members: &[usize],
+    local_index: &[usize],
+    enabled_actions: A,
+    eps: f64,
+    value_comparator: C,
+) -> f64 {
+    let component = EndComponent {
+        model,
+        rewards,
+        members,
+        local_index,
+        enabled_actions: members
+            .iter()
+            .map(|&member| enabled_actions(member).collect())
+            .collect(),
+    };
+    let mut policy: Vec<Option<usize>> = component
+        .enabled_actions
+        .iter()
+        .map(|actions| actions.first().copied())
+        .collect();
+    loop {
+        let reference = component.make_unichain(&mut policy, value_comparator);
+        let chain = component.chain(&policy);
+        let all_states: Vec<usize> = (0..members.len()).collect();
+        let (gain, bias) = evaluate_policy(&chain, &all_states, reference);
+
+        let mut changed = false;
+        for (index, &member) in members.iter().enumerate() {
+            let Some(current_action) = policy[index] else {
+                continue;
+            };
+            let mut best_value = component.action_value(member, current_action, &bias);
+            let tolerance = eps * best_value.abs().max(1.0);
+            for &action_index in &component.enabled_actions[index] {
+                let value = component.action_value(member, action_index, &bias);
+                if value_comparator.is_better(&(), best_value, value)
+                    && (value - best_value).abs() > tolerance
+                {
+                    best_value = value;
+                    policy[index] = Some(action_index);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return gain;
+        }
+    }
+}
+
+struct EndComponent<'a, M: ModelTypes> {
+    model: &'a ProbabilisticModel<M>,
+    rewards: &'a RewardStructure,
+    members: &'a [usize],
+    local_index: &'a [usize],
+    enabled_actions: Vec<Vec<usize>>,
+}
+
+/// The Markov chain that a policy induces on an end component, given by the successors with their
+/// probabilities and the reward of every member
+struct PolicyChain {
+    successors: Vec<Vec<(usize, f64)>>,
+    rewards: Vec<f64>,
+}
+
+impl<M: ModelTypes> EndComponent<'_, M> {
+    fn successors(&self, member: usize, action: Option<usize>) -> Vec<(usize, f64)> {
+        match action {
+            Some(action_index) => self.model.states[member]
+                .actions
+                .get_action(action_index)
+                .successors
+                .iter()
+                .map(|successor| (self.local_index[successor.index], successor.probability))
+                .collect(),
+            None => vec![(self.local_index[member], 1.0)],
+        }
+    }
+
+    fn chain(&self, policy: &[Option<usize>]) -> PolicyChain {
+        let successors = self
+            .members
+            .iter()
+            .zip(policy)
+            .map(|(&member, &action)| self.successors(member, action))
+            .collect();
+        let rewards = self
+            .members
+            .iter()
+            .zip(policy)
+            .map(|(&member, &action)| match action {
+                Some(action_index) => self.rewards.step_reward(member, action_index),
+                None => self.rewards.state_reward(member),
+            })
+            .collect();
+        PolicyChain {
+            successors,
+            rewards,
+        }
+    }
+
+    /// The reward of the action plus the expected bias of its successors
+    fn action_value(&self, member: usize, action_index: usize, bias: &[f64]) -> f64 {
+        let mut value = self.rewards.step_reward(member, action_index);
+        for (successor, probability) in self.successors(member, Some(action_index)) {
+            value += probability * bias[successor];
+        }
+        value
+    }
+
+    /// Keeps the best recurrent class of the policy and redirects all other members towards it, so
+    /// that the policy has a single recurrent class and its gain does not get worse. Members keep
+    /// their action if it already leads towards the class. Returns a member of the class.
+    fn make_unichain<C: ValueComparator<SinglePlayer>>(
+        &self,
+        policy: &mut [Option<usize>],
+        value_comparator: C,
+    ) -> usize {
+        let chain = self.chain(policy);
+        let mut classes = recurrent_classes(&chain.successors);
+        let mut best_class = classes.pop().unwrap();
+        if !classes.is_empty() {
+            let mut best_gain = evaluate_policy(&chain, &best_class, best_class[0]).0;
+            for class in classes {
+                let gain = evaluate_policy(&chain, &class, class[0]).0;
+                if value_comparator.is_better(&(), best_gain, gain) {
+                    best_gain = gain;
+                    best_class = class;
+                }
+            }
+        }
+
+        // The end component is strongly connected, so every member can reach the class.
+        let mut reached = vec![false; self.members.len()];
+        for &index in &best_class {
+            reached[index] = true;
+        }
+        let mut remaining: Vec<usize> = (0..self.members.len()).filter(|&i| !reached[i]).collect();
+        while !remaining.is_empty() {
+            let leads_to_class = |successors: &[(usize, f64)], reached: &[bool]| {
+                successors.iter().any(|&(successor, _)| reached[successor])
+            };
+            let mut newly_reached = Vec::new();
+            for &index in &remaining {
+                if leads_to_class(&chain.successors[index], &reached) {
+                    newly_reached.push(index);
+                }
+            }
+            if newly_reached.is_empty() {
+                for &index in &remaining {
+                    let member = self.members[index];
+                    let redirection = self.enabled_actions[index].iter().find(|&&action| {
+                        leads_to_class(&self.successors(member, Some(action)), &reached)
+                    });
+                    if let Some(&action_index) = redirection {
+                        policy[index] = Some(action_index);
+                        newly_reached.push(index);
+                    }
+                }
+            }
+            if newly_reached.is_empty() {
+                break;
+            }
+            for &index in &newly_reached {
+                reached[index] = true;
+            }
+            remaining.retain(|&index| !reached[index]);
+        }
+        best_class[0]
+    }
+}
+
+/// Returns the bottom strongly connected components of the chain, i.e. its recurrent classes
+fn recurrent_classes(successors: &[Vec<(usize, f64)>]) -> Vec<Vec<usize>> {
+    let count = successors.len();
+    let mut visited = vec![false; count];
+    let mut finished = Vec::with_capacity(count);
+    for start in 0..count {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![(start, 0)];
+        while let Some((state, next)) = stack.pop() {
+            if let Some(&(successor, _)) = successors[state].get(next) {
+                stack.push((state, next + 1));
+                if !visited[successor] {
+                    visited[successor] = true;
+                    stack.push((successor, 0));
+                }
+            } else {
+                finished.push(state);
+            }
+        }
+    }
+
+    let mut predecessors = vec![Vec::new(); count];
+    for (state, state_successors) in successors.iter().enumerate() {
+        for &(successor, _) in state_successors {
+            predecessors[successor].push(state);
+        }
+    }
+    let mut component = vec![usize::MAX; count];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    for &start in finished.iter().rev() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        let index = components.len();
+        component[start] = index;
+        let mut members = vec![start];
+        let mut stack = vec![start];
+        while let Some(state) = stack.pop() {
+            for &predecessor in &predecessors[state] {
+                if component[predecessor] == usize::MAX {
+                    component[predecessor] = index;
+                    members.push(predecessor);
+                    stack.push(predecessor);
+                }
+            }
+        }
+        components.push(members);
+    }
+
+    components
+        .into_iter()
+        .enumerate()
+        .filter(|(index, members)| {
+            members.iter().all(|&state| {
+                successors[state]
+                    .iter()
+                    .all(|&(successor, _)| component[successor] == *index)
+            })
+        })
+        .map(|(_, members)| members)
+        .collect()
+}
+
+/// Solves the gain and bias equations of the chain restricted to `states`, which must be closed
+/// under the chain and contain a single recurrent class that includes `reference`. Returns the
+/// gain and the bias of every state of the chain, which is zero outside of `states`.
+fn evaluate_policy(chain: &PolicyChain, states: &[usize], reference: usize) -> (f64, Vec<f64>) {
+    // The variable of the reference state holds the gain, as its bias is fixed to zero.
+    let mut variable = vec![usize::MAX; chain.successors.len()];
+    for (index, &state) in states.iter().enumerate() {
+        variable[state] = index;
+    }
+    let mut rows = Vec::with_capacity(states.len());
+    let mut right_hand_side = Vec::with_capacity(states.len());
+    for &state in states {
+        let mut row = BTreeMap::new();
+        *row.entry(variable[reference]).or_insert(0.0) += 1.0;
+        if state != reference {
+            *row.entry(variable[state]).or_insert(0.0) += 1.0;
+        }
+        for &(successor, probability) in &chain.successors[state] {
+            if successor != reference {
+                *row.entry(variable[successor]).or_insert(0.0) -= probability;
+            }
+        }
+        row.retain(|_, coefficient| *coefficient != 0.0);
+        rows.push(row);
+        right_hand_side.push(chain.rewards[state]);
+    }
+
+    let solution = solve_sparse(rows, right_hand_side);
+    let mut bias = vec![0.0; chain.successors.len()];
+    for &state in states {
+        if state != reference {
+            bias[state] = solution[variable[state]];
+        }
+    }
+    (solution[variable[reference]], bias)
+}
+
+/// Solves a non-singular system of linear equations by Gaussian elimination with partial
+/// pivoting. Every row maps the indices of its variables to their non-zero coefficients.
+fn solve_sparse(mut rows: Vec<BTreeMap<usize, f64>>, mut right_hand_side: Vec<f64>) -> Vec<f64> {
+    let count = rows.len();
+    let mut rows_of_column = vec![BTreeSet::new(); count];
+    for (row_index, row) in rows.iter().enumerate() {
+        for &column in row.keys() {
+            rows_of_column[column].insert(row_index);
+        }
+    }
+
+    let mut pivots = Vec::with_capacity(count);
+    for column in 0..count {
+        let pivot = *rows_of_column[column]
+            .iter()
+            .max_by(|&&a, &&b| rows[a][&column].abs().total_cmp(&rows[b][&column].abs()))
+            .expect("The system of linear equations is singular");
+        for &pivot_column in rows[pivot].keys() {
+            rows_of_column[pivot_column].remove(&pivot);
+        }
+        let pivot_row = rows[pivot].clone();
+        let pivot_value = pivot_row[&column];
+        for row_index in std::mem::take(&mut rows_of_column[column]) {
+            let factor = rows[row_index][&column] / pivot_value;
+            rows[row_index].remove(&column);
+            for (&pivot_column, &pivot_coefficient) in &pivot_row {
+                if pivot_column == column {
+                    continue;
+                }
+                let coefficient = rows[row_index].entry(pivot_column).or_insert(0.0);
+                *coefficient -= factor * pivot_coefficient;
+                rows_of_column[pivot_column].insert(row_index);
+            }
+            right_hand_side[row_index] -= factor * right_hand_side[pivot];
+        }
+        pivots.push(pivot);
+    }
+
+    // Every pivot row only contains its own column and columns that are eliminated later.
+    let mut solution = vec![0.0; count];
+    for (column, &pivot) in pivots.iter().enumerate().rev() {
+        let mut value = right_hand_side[pivot];
+        for (&other_column, &coefficient) in &rows[pivot] {
+            if other_column != column {
+                value -= coefficient * solution[other_column];
+            }
+        }
+        solution[column] = value / rows[pivot][&column];
+    }
+    solution
+}
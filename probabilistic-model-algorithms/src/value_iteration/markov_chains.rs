@@ -0,0 +1,287 @@
+use super::{Maximiser, StateData, value_iteration_internal};
+use crate::sccs::SccList;
+use probabilistic_models::{
+    ActionCollection, AtomicPropositions, Distribution, DistributionVector, Predecessors,
+    ProbabilisticModel, SingleAction, SinglePlayer, VectorPredecessors,
+};
+
+/// Computes, for every state of the Markov chain, the probability of satisfying
+/// `before U after`. If `before_ap_index` is `None`, `before` is considered to be `true`, i.e.
+/// the probability of `F after` is computed.
+pub fn until_probabilities<
+    M: probabilistic_models::ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    before_ap_index: Option<usize>,
+    after_ap_index: usize,
+    eps: f64,
+) -> Vec<f64> {
+    let (is_goal, may_continue) = classify_states(model, before_ap_index, after_ap_index);
+    reachability_probabilities(model, &is_goal, &may_continue, eps)
+}
+
+/// Computes, for every state of the Markov chain, the probability of reaching a goal state while
+/// only passing through states for which `may_continue` is set. Goal states must not be marked as
+/// `may_continue`.
+pub fn reachability_probabilities<
+    M: probabilistic_models::ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    is_goal: &[bool],
+    may_continue: &[bool],
+    eps: f64,
+) -> Vec<f64> {
+    let goal_states = (0..model.states.len()).filter(|&i| is_goal[i]);
+    let can_reach_goal = backward_reachable(model, goal_states, may_continue);
+    let unable_to_reach_goal = (0..model.states.len()).filter(|&i| !can_reach_goal[i]);
+    let can_miss_goal = backward_reachable(model, unable_to_reach_goal, may_continue);
+
+    let mut data = vec![StateData::new(); model.states.len()];
+    let mut excluded = Vec::new();
+    for i in 0..model.states.len() {
+        if !can_reach_goal[i] {
+            excluded.push(i);
+        } else if !can_miss_goal[i] {
+            data[i].value = 1.0;
+            excluded.push(i);
+        }
+    }
+
+    let sccs: SccList =
+        crate::sccs::compute_sccs(model, &crate::sccs::ExclusionList::new(&excluded[..]));
+    let sccs = sccs.compute_dependencies(model);
+    let order = sccs.get_reverse_topological_order();
+
+    value_iteration_internal(model, &mut data, eps, &sccs, &order[..], Maximiser {});
+
+    data.into_iter().map(|d| d.value).collect()
+}
+
+/// Computes, for every state of the Markov chain, the probability that the next state satisfies
+/// the atomic proposition, i.e. the probability of `X ap`.
+pub fn next_probabilities<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    ap_index: usize,
+) -> Vec<f64> {
+    model
+        .states
+        .iter()
+        .map(|state| {
+            state
+                .actions
+                .get_action(0)
+                .successors
+                .iter()
+                .filter(|successor| {
+                    model.states[successor.index]
+                        .atomic_propositions
+                        .get_value(ap_index)
+                })
+                .map(|successor| successor.probability)
+                .sum()
+        })
+        .collect()
+}
+
+/// Computes, for every state of the Markov chain, the probability of reaching a state satisfying
+/// `after` within at most `steps` steps while only passing through states satisfying `before`.
+/// If `before_ap_index` is `None`, `before` is considered to be `true`.
+pub fn bounded_until_probabilities<
+    M: probabilistic_models::ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    before_ap_index: Option<usize>,
+    after_ap_index: usize,
+    steps: usize,
+) -> Vec<f64> {
+    let (is_goal, may_continue) = classify_states(model, before_ap_index, after_ap_index);
+
+    let mut values: Vec<f64> = is_goal.iter().map(|&g| if g { 1.0 } else { 0.0 }).collect();
+    let mut next_values = values.clone();
+    for _ in 0..steps {
+        for (state_index, state) in model.states.iter().enumerate() {
+            if !may_continue[state_index] {
+                continue;
+            }
+            let mut value = 0.0;
+            for successor in state.actions.get_action(0).successors.iter() {
+                value += successor.probability * values[successor.index];
+            }
+            next_values[state_index] = value;
+        }
+        std::mem::swap(&mut values, &mut next_values);
+    }
+    values
+}
+
+/// Computes, for every state of the continuous-time Markov chain, the probability of reaching a
+/// state satisfying `after` within time `time` while only passing through states satisfying
+/// `before`. The chain is uniformised and the step-bounded probabilities of the uniformised chain
+/// are weighted by a Poisson distribution, which is truncated such that the neglected
+/// probability mass is below `eps`. If `before_ap_index` is `None`, `before` is considered to be
+/// `true`.
+pub fn time_bounded_until_probabilities<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    before_ap_index: Option<usize>,
+    after_ap_index: usize,
+    time: f64,
+    eps: f64,
+) -> Vec<f64> {
+    let exit_rates = model
+        .exit_rates
+        .as_ref()
+        .expect("Time-bounded probabilities can only be computed for continuous-time models");
+    let (is_goal, may_continue) = classify_states(model, before_ap_index, after_ap_index);
+
+    let mut values: Vec<f64> = is_goal.iter().map(|&g| if g { 1.0 } else { 0.0 }).collect();
+    let uniformisation_rate = (0..model.states.len())
+        .filter(|&i| may_continue[i])
+        .map(|i| exit_rates[i])
+        .fold(0.0, f64::max);
+    if uniformisation_rate == 0.0 || time <= 0.0 {
+        return values;
+    }
+
+    let (first_step, weights) = poisson_weights(uniformisation_rate * time, eps);
+    let mut result = vec![0.0; model.states.len()];
+    let mut next_values = values.clone();
+    for step in 0..first_step + weights.len() {
+        if step >= first_step {
+            let weight = weights[step - first_step];
+            for (result, value) in result.iter_mut().zip(&values) {
+                *result += weight * value;
+            }
+        }
+        for (state_index, state) in model.states.iter().enumerate() {
+            if !may_continue[state_index] {
+                continue;
+            }
+            let leave_probability = exit_rates[state_index] / uniformisation_rate;
+            let mut value = (1.0 - leave_probability) * values[state_index];
+            for successor in state.actions.get_action(0).successors.iter() {
+                value += leave_probability * successor.probability * values[successor.index];
+            }
+            next_values[state_index] = value;
+        }
+        std::mem::swap(&mut values, &mut next_values);
+    }
+    result
+}
+
+/// Computes the probabilities of the Poisson distribution with the given mean, truncated on both
+/// sides such that the neglected probability mass is below `eps`. Returns the first step that is
+/// not truncated and the normalised probabilities from this step onwards. To avoid underflows,
+/// the probabilities are computed relative to the mode before normalising them.
+fn poisson_weights(mean: f64, eps: f64) -> (usize, Vec<f64>) {
+    let mode = mean.floor() as usize;
+    let mut total = 1.0;
+
+    // Beyond the mode, consecutive probabilities shrink at least by `ratio`, so the remaining
+    // tail is bounded by a geometric series.
+    let mut right_weights = vec![1.0];
+    let mut weight = 1.0;
+    let mut step = mode;
+    loop {
+        let ratio = mean / (step + 1) as f64;
+        if ratio < 1.0 && weight * ratio / (1.0 - ratio) < 0.5 * eps * total {
+            break;
+        }
+        weight *= ratio;
+        step += 1;
+        total += weight;
+        right_weights.push(weight);
+    }
+
+    let mut weights = Vec::new();
+    weight = 1.0;
+    step = mode;
+    while step > 0 {
+        let ratio = step as f64 / mean;
+        if ratio < 1.0 && weight * ratio / (1.0 - ratio) < 0.5 * eps * total {
+            break;
+        }
+        weight *= ratio;
+        step -= 1;
+        total += weight;
+        weights.push(weight);
+    }
+    weights.reverse();
+    weights.extend(right_weights);
+    for weight in weights.iter_mut() {
+        *weight /= total;
+    }
+    (step, weights)
+}
+
+/// Returns which states satisfy `after` and which states satisfy `before` but not `after`, i.e.
+/// in which states a path may continue without having decided the until formula.
+fn classify_states<M: probabilistic_models::ModelTypes>(
+    model: &ProbabilisticModel<M>,
+    before_ap_index: Option<usize>,
+    after_ap_index: usize,
+) -> (Vec<bool>, Vec<bool>) {
+    let mut is_goal = Vec::with_capacity(model.states.len());
+    let mut may_continue = Vec::with_capacity(model.states.len());
+    for state in &model.states {
+        let goal = state.atomic_propositions.get_value(after_ap_index);
+        let before = match before_ap_index {
+            Some(index) => state.atomic_propositions.get_value(index),
+            None => true,
+        };
+        is_goal.push(goal);
+        may_continue.push(before && !goal);
+    }
+    (is_goal, may_continue)
+}
+
+/// Computes the states that can reach one of the given states by only passing through states
+/// for which `may_continue` is set.
+fn backward_reachable<M: probabilistic_models::ModelTypes<Predecessors = VectorPredecessors>>(
+    model: &ProbabilisticModel<M>,
+    states: impl Iterator<Item = usize>,
+    may_continue: &[bool],
+) -> Vec<bool> {
+    let mut reachable = vec![false; model.states.len()];
+    let mut open_list = Vec::new();
+    for state in states {
+        reachable[state] = true;
+        open_list.push(state);
+    }
+
+    while let Some(state) = open_list.pop() {
+        for predecessor in model.states[state].predecessors.iter() {
+            if !reachable[predecessor.from] && may_continue[predecessor.from] {
+                reachable[predecessor.from] = true;
+                open_list.push(predecessor.from);
+            }
+        }
+    }
+    reachable
+}
@@ -1,11 +1,64 @@
-use super::{Maximiser, Minimiser, StateData, ValueComparator, value_iteration_internal};
+use super::{
+    Maximiser, Minimiser, StateData, ValueComparator, one_step_backup, value_iteration_internal,
+};
 use crate::mecs;
 use crate::sccs::{Scc, SccList, SccWithDependencies};
 use probabilistic_models::{
     ActionCollection, ActionVector, AtomicPropositions, Distribution, DistributionVector,
-    ProbabilisticModel, SinglePlayer, VectorPredecessors,
+    InitialStates, ProbabilisticModel, SinglePlayer, VectorPredecessors,
 };
 
+/// A constrained reachability objective: reach a target state without visiting an avoided state
+/// before. Paths that visit neither do not satisfy the objective.
+pub struct ReachabilityObjective {
+    target: Vec<bool>,
+    avoid: Vec<bool>,
+}
+
+impl ReachabilityObjective {
+    pub fn new(target: Vec<bool>, avoid: Vec<bool>) -> Self {
+        assert_eq!(
+            target.len(),
+            avoid.len(),
+            "Target and avoid sets must be defined for the same number of states"
+        );
+        Self { target, avoid }
+    }
+
+    /// The objective `F ap`.
+    pub fn eventually<M: probabilistic_models::ModelTypes>(
+        model: &ProbabilisticModel<M>,
+        ap_index: usize,
+    ) -> Self {
+        Self::until(model, None, ap_index)
+    }
+
+    /// The objective `before U after`. If `before_ap_index` is `None`, `before` is considered to
+    /// be `true`.
+    pub fn until<M: probabilistic_models::ModelTypes>(
+        model: &ProbabilisticModel<M>,
+        before_ap_index: Option<usize>,
+        after_ap_index: usize,
+    ) -> Self {
+        let mut target = Vec::with_capacity(model.states.len());
+        let mut avoid = Vec::with_capacity(model.states.len());
+        for state in &model.states {
+            let after = state.atomic_propositions.get_value(after_ap_index);
+            let before = match before_ap_index {
+                Some(index) => state.atomic_propositions.get_value(index),
+                None => true,
+            };
+            target.push(after);
+            avoid.push(!before && !after);
+        }
+        Self { target, avoid }
+    }
+
+    fn is_decided(&self, state_index: usize) -> bool {
+        self.target[state_index] || self.avoid[state_index]
+    }
+}
+
 pub fn optimistic_value_iteration_maximise<
     M: probabilistic_models::ModelTypes<
             Predecessors = VectorPredecessors,
@@ -18,7 +71,9 @@ pub fn optimistic_value_iteration_maximise<
     objective_ap_index: usize,
     eps: f64,
 ) -> f64 {
-    optimistic_value_iteration(model, objective_ap_index, eps, Maximiser {})
+    let initial_state = model.initial_states.get(0);
+    let objective = ReachabilityObjective::eventually(&model, objective_ap_index);
+    optimistic_value_iteration(model, &objective, eps, Maximiser {})[initial_state]
 }
 pub fn optimistic_value_iteration_minimise<
     M: probabilistic_models::ModelTypes<
@@ -32,7 +87,38 @@ pub fn optimistic_value_iteration_minimise<
     objective_ap_index: usize,
     eps: f64,
 ) -> f64 {
-    optimistic_value_iteration(model, objective_ap_index, eps, Minimiser {})
+    let initial_state = model.initial_states.get(0);
+    let objective = ReachabilityObjective::eventually(&model, objective_ap_index);
+    optimistic_value_iteration(model, &objective, eps, Minimiser {})[initial_state]
+}
+
+pub fn optimistic_reachability_maximise<
+    M: probabilistic_models::ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+    eps: f64,
+) -> Vec<f64> {
+    optimistic_value_iteration(model, objective, eps, Maximiser {})
+}
+pub fn optimistic_reachability_minimise<
+    M: probabilistic_models::ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+    eps: f64,
+) -> Vec<f64> {
+    optimistic_value_iteration(model, objective, eps, Minimiser {})
 }
 
 fn optimistic_value_iteration<
@@ -45,20 +131,17 @@ fn optimistic_value_iteration<
     C: ValueComparator<SinglePlayer>,
 >(
     mut model: ProbabilisticModel<M>,
-    objective_ap_index: usize,
+    objective: &ReachabilityObjective,
     mut eps: f64,
     comparator: C,
-) -> f64 {
-    let start_time = std::time::Instant::now();
-
+) -> Vec<f64> {
+    make_decided_states_absorbing(&mut model, objective);
     let mecs = mecs::compute_mecs(&mut model);
-    let winning_mecs = winning_mecs(&mecs, objective_ap_index, &model);
     mecs.collapse_mecs(&mut model);
 
     let mut data = vec![StateData::new(); model.states.len()];
     let mut upper_bound = vec![0.0; model.states.len()];
-    let excluded =
-        handle_reachability_objective(&model, objective_ap_index, &mecs, &winning_mecs, &mut data);
+    let excluded = handle_reachability_objective(&model, objective, &mut data);
 
     let sccs: SccList =
         crate::sccs::compute_sccs(&model, &crate::sccs::ExclusionList::new(&excluded[..]));
@@ -68,7 +151,6 @@ fn optimistic_value_iteration<
     let initial_eps = eps;
 
     loop {
-        println!("eps={}:", eps);
         value_iteration_internal(&model, &mut data, eps, &sccs, &order[..], comparator);
 
         for i in 0..model.states.len() {
@@ -92,14 +174,7 @@ fn optimistic_value_iteration<
 
         match is_upper_bound {
             OptimisticValueIterationResult::UpperBoundVerified => {
-                println!("Upper bound candidate verified!");
                 for i in 0..model.states.len() {
-                    if i == 0 {
-                        println!(
-                            "Lower bound: {}, upper bound: {}",
-                            data[i].value, upper_bound[i]
-                        );
-                    }
                     data[i].value = 0.5 * (data[i].value + upper_bound[i]);
                 }
                 break;
@@ -110,12 +185,9 @@ fn optimistic_value_iteration<
         }
     }
 
-    println!(
-        "Optimistic value iteration finished in {:?}: {}",
-        start_time.elapsed(),
-        data[0].value
-    );
-    data[0].value
+    (0..model.states.len())
+        .map(|state_index| data[collapsed_state_index(&mecs, state_index)].value)
+        .collect()
 }
 
 fn verify_optimistic<
@@ -134,10 +206,9 @@ fn verify_optimistic<
     sccs: &SccList<SccWithDependencies>,
     value_comparator: C,
 ) -> OptimisticValueIterationResult {
-    let start_time = std::time::Instant::now();
     let verification_steps = (1.0 / eps).max(1.0) as usize;
     let mut error: f64 = 0.0;
-    for i in 0..verification_steps {
+    for _ in 0..verification_steps {
         let mut all_up = true;
         let mut all_down = true;
         error = 0.0;
@@ -177,17 +248,14 @@ fn verify_optimistic<
                 }
 
                 if new_upper_value < new_lower_value {
-                    println!("Crossed in {} steps and {:?}", i, start_time.elapsed());
                     return OptimisticValueIterationResult::UpperBoundRefuted { error };
                 }
             }
         }
 
         if all_down {
-            println!("Verified in {} steps and {:?}", i, start_time.elapsed());
             return OptimisticValueIterationResult::UpperBoundVerified;
         } else if all_up {
-            println!("Refuted in {} steps and {:?}", i, start_time.elapsed());
             return OptimisticValueIterationResult::UpperBoundRefuted { error };
         }
     }
@@ -211,7 +279,8 @@ pub fn value_iteration_maximise<
     objective_ap_index: usize,
     eps: f64,
 ) {
-    value_iteration(model, objective_ap_index, eps, Maximiser {})
+    let objective = ReachabilityObjective::eventually(&model, objective_ap_index);
+    value_iteration(model, &objective, eps, Maximiser {})
 }
 pub fn value_iteration_minimise<
     M: probabilistic_models::ModelTypes<
@@ -225,7 +294,8 @@ pub fn value_iteration_minimise<
     objective_ap_index: usize,
     eps: f64,
 ) {
-    value_iteration(model, objective_ap_index, eps, Minimiser {})
+    let objective = ReachabilityObjective::eventually(&model, objective_ap_index);
+    value_iteration(model, &objective, eps, Minimiser {})
 }
 fn value_iteration<
     M: probabilistic_models::ModelTypes<
@@ -237,19 +307,19 @@ fn value_iteration<
     C: ValueComparator<SinglePlayer>,
 >(
     mut model: ProbabilisticModel<M>,
-    objective_ap_index: usize,
+    objective: &ReachabilityObjective,
     eps: f64,
     value_comparator: C,
 ) {
     let start_time = std::time::Instant::now();
 
+    make_decided_states_absorbing(&mut model, objective);
     let mecs = mecs::compute_mecs(&mut model);
-    let winning_mecs = winning_mecs(&mecs, objective_ap_index, &model);
     mecs.collapse_mecs(&mut model);
+    let initial_state = collapsed_state_index(&mecs, model.initial_states.get(0));
 
     let mut data = vec![StateData::new(); model.states.len()];
-    let excluded =
-        handle_reachability_objective(&model, objective_ap_index, &mecs, &winning_mecs, &mut data);
+    let excluded = handle_reachability_objective(&model, objective, &mut data);
 
     let sccs: SccList =
         crate::sccs::compute_sccs(&model, &crate::sccs::ExclusionList::new(&excluded[..]));
@@ -261,54 +331,177 @@ fn value_iteration<
     println!(
         "Value iteration finished in {:?}: {}",
         start_time.elapsed(),
-        data[0].value
+        data[initial_state].value
     );
 }
 
+/// Removes all actions of states in which the objective is already decided. These states are
+/// excluded from value iteration, so removing their actions ensures that they are not part of any
+/// MEC and that MECs only contain states in which the objective is still open.
+fn make_decided_states_absorbing<
+    M: probabilistic_models::ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+        >,
+>(
+    model: &mut ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+) {
+    for (state_index, state) in model.states.iter_mut().enumerate() {
+        if objective.is_decided(state_index) {
+            state.actions = ActionVector::new();
+        }
+    }
+    model.rebuild_predecessors();
+}
+
+/// Returns the index of the state that represents the given state after collapsing MECs.
+fn collapsed_state_index(mecs: &mecs::Mecs, state_index: usize) -> usize {
+    match mecs.mec_of_state(state_index) {
+        Some(mec_index) => mecs.identified_mec_state_index(mec_index),
+        None => state_index,
+    }
+}
+
+/// Sets the value of target states to one and excludes them from value iteration. Avoided states
+/// and states without actions (including states merged into a MEC) are excluded with value zero.
 fn handle_reachability_objective<M: probabilistic_models::ModelTypes>(
     model: &ProbabilisticModel<M>,
-    objective_ap_index: usize,
-    mecs: &mecs::Mecs,
-    winning_mecs: &[usize],
+    objective: &ReachabilityObjective,
     data: &mut Vec<StateData>,
 ) -> Vec<usize> {
     let mut excluded = Vec::new();
     for (i, state) in model.states.iter().enumerate() {
-        if state.atomic_propositions.get_value(objective_ap_index) {
+        if objective.target[i] {
             data[i].value = 1.0;
             excluded.push(i);
-        }
-    }
-
-    for &winning_mec in winning_mecs {
-        let state_index = mecs.identified_mec_state_index(winning_mec);
-        if data[state_index].value != 1.0 {
-            data[state_index].value = 1.0;
-            excluded.push(state_index);
+        } else if objective.avoid[i] || state.actions.get_number_of_actions() == 0 {
+            excluded.push(i);
         }
     }
     excluded
 }
 
-fn winning_mecs<M: probabilistic_models::ModelTypes>(
-    mecs: &mecs::Mecs,
-    objective_ap_index: usize,
+pub fn bounded_reachability_maximise<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
     model: &ProbabilisticModel<M>,
-) -> Vec<usize> {
-    let mut is_accepting = vec![false; mecs.len()];
+    objective: &ReachabilityObjective,
+    steps: usize,
+) -> Vec<f64> {
+    bounded_reachability(model, objective, steps, Maximiser {})
+}
+pub fn bounded_reachability_minimise<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+    steps: usize,
+) -> Vec<f64> {
+    bounded_reachability(model, objective, steps, Minimiser {})
+}
 
-    for (state_index, state) in model.states.iter().enumerate() {
-        if let Some(mec_index) = mecs.mec_of_state(state_index) {
-            if state.atomic_propositions.get_value(objective_ap_index) {
-                is_accepting[mec_index] = true;
+/// Computes the optimal probabilities of satisfying the objective within `steps` steps from each
+/// state by finite-horizon iteration.
+fn bounded_reachability<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+    C: ValueComparator<SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+    steps: usize,
+    value_comparator: C,
+) -> Vec<f64> {
+    let mut values: Vec<f64> = objective
+        .target
+        .iter()
+        .map(|&t| if t { 1.0 } else { 0.0 })
+        .collect();
+    let mut next_values = values.clone();
+    for _ in 0..steps {
+        for (state_index, state) in model.states.iter().enumerate() {
+            if objective.is_decided(state_index) || state.actions.get_number_of_actions() == 0 {
+                continue;
+            }
+            let mut best_value = value_comparator.initial_value(&state.owner);
+            for action in state.actions.iter() {
+                let mut value = 0.0;
+                for successor in action.successors.iter() {
+                    value += successor.probability * values[successor.index];
+                }
+                if value_comparator.is_better(&state.owner, best_value, value) {
+                    best_value = value;
+                }
             }
+            next_values[state_index] = best_value;
         }
+        std::mem::swap(&mut values, &mut next_values);
     }
+    values
+}
 
-    is_accepting
-        .into_iter()
-        .enumerate()
-        .filter(|(_, acc)| *acc)
-        .map(|(i, _)| i)
-        .collect()
+pub fn next_maximise<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    ap_index: usize,
+) -> Vec<f64> {
+    next(model, ap_index, Maximiser {})
+}
+pub fn next_minimise<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    ap_index: usize,
+) -> Vec<f64> {
+    next(model, ap_index, Minimiser {})
+}
+
+/// Computes the optimal probabilities that the successor of each state satisfies the atomic
+/// proposition, i.e. the probabilities of `X ap`. A deadlock has no successor, so its value is 0.
+fn next<
+    M: probabilistic_models::ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+    C: ValueComparator<SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    ap_index: usize,
+    value_comparator: C,
+) -> Vec<f64> {
+    let successor_values: Vec<f64> = model
+        .states
+        .iter()
+        .map(|state| {
+            if state.atomic_propositions.get_value(ap_index) {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    one_step_backup(model, &successor_values, value_comparator)
 }
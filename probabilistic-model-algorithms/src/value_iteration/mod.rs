@@ -1,4 +1,8 @@
+pub mod long_run_average;
+pub mod markov_chains;
 pub mod mdp;
+pub mod rewards;
+pub mod stochastic_game_rewards;
 pub mod stochastic_games;
 
 use crate::sccs::{Scc, SccList};
@@ -60,6 +64,25 @@ impl ValueComparator<TwoPlayer> for TwoPlayerMaxMin {
     }
 }
 
+#[derive(Copy, Clone)]
+struct TwoPlayerMinMax {}
+
+impl ValueComparator<TwoPlayer> for TwoPlayerMinMax {
+    fn initial_value(&self, state_owner: &TwoPlayer) -> f64 {
+        match state_owner {
+            TwoPlayer::PlayerOne => 1.0,
+            TwoPlayer::PlayerTwo => 0.0,
+        }
+    }
+
+    fn is_better(&self, state_owner: &TwoPlayer, before: f64, new: f64) -> bool {
+        match state_owner {
+            TwoPlayer::PlayerOne => new <= before,
+            TwoPlayer::PlayerTwo => new >= before,
+        }
+    }
+}
+
 fn value_iteration_internal<
     M: probabilistic_models::ModelTypes,
     SCC: Scc,
@@ -147,6 +170,33 @@ fn value_iteration_internal<
     }
 }
 
+/// Performs a single Bellman backup, i.e. computes for every state the optimal expected value of
+/// its successor. A deadlock has no successor, so its value is 0.
+fn one_step_backup<M: probabilistic_models::ModelTypes, C: ValueComparator<M::Owners>>(
+    model: &ProbabilisticModel<M>,
+    successor_values: &[f64],
+    comparator: C,
+) -> Vec<f64> {
+    let mut values = vec![0.0; model.states.len()];
+    for (state_index, state) in model.states.iter().enumerate() {
+        if state.actions.get_number_of_actions() == 0 {
+            continue;
+        }
+        let mut best_value = comparator.initial_value(&state.owner);
+        for action in state.actions.iter() {
+            let mut value = 0.0;
+            for successor in action.successors.iter() {
+                value += successor.probability * successor_values[successor.index];
+            }
+            if comparator.is_better(&state.owner, best_value, value) {
+                best_value = value;
+            }
+        }
+        values[state_index] = best_value;
+    }
+    values
+}
+
 #[derive(Copy, Clone)]
 struct StateData {
     value: f64,
@@ -0,0 +1,343 @@
+use super::{Maximiser, Minimiser, ValueComparator};
+use crate::mecs;
+use probabilistic_models::{
+    ActionCollection, Distribution, ModelTypes, Predecessors, ProbabilisticModel, RewardStructure,
+    SinglePlayer, VectorPredecessors,
+};
+
+/// Computes, for every state, the maximal expected reward accumulated until a target state is
+/// reached. States from which the target is missed with positive probability under some
+/// scheduler have infinite expected reward.
+pub fn expected_reward_maximise<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    target: &[bool],
+    eps: f64,
+) -> Vec<f64> {
+    let finite = reached_almost_surely_under_all_schedulers(model, target);
+    let groups = StateGroups::singletons(model.states.len());
+    expected_reward(model, rewards, target, &finite, &groups, eps, Maximiser {})
+}
+
+/// Computes, for every state, the minimal expected reward accumulated until a target state is
+/// reached. States from which no scheduler reaches the target almost surely have infinite
+/// expected reward.
+pub fn expected_reward_minimise<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    target: &[bool],
+    eps: f64,
+) -> Vec<f64> {
+    let finite = reached_almost_surely_under_some_scheduler(model, target);
+    // Only actions that keep the target reachable almost surely may be used, as all others incur
+    // infinite expected reward.
+    let actions_allowed = |state_index: usize, action_index: usize| {
+        model.states[state_index]
+            .actions
+            .get_action(action_index)
+            .successors
+            .iter()
+            .all(|successor| finite[successor.index])
+    };
+    // Value iteration from below does not converge to the minimal value if the scheduler can
+    // stay in an end component without reward forever. All states of such an end component have
+    // the same value, so they are treated as a single state.
+    let groups = StateGroups::zero_reward_end_components(model, actions_allowed, |s, a| {
+        !target[s] && finite[s] && actions_allowed(s, a) && rewards.step_reward(s, a) == 0.0
+    });
+    expected_reward(model, rewards, target, &finite, &groups, eps, Minimiser {})
+}
+
+pub fn cumulative_reward_maximise<M: ModelTypes<Owners = SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    steps: usize,
+) -> Vec<f64> {
+    cumulative_reward(model, rewards, steps, Maximiser {})
+}
+
+pub fn cumulative_reward_minimise<M: ModelTypes<Owners = SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    steps: usize,
+) -> Vec<f64> {
+    cumulative_reward(model, rewards, steps, Minimiser {})
+}
+
+pub fn instantaneous_reward_maximise<M: ModelTypes<Owners = SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    steps: usize,
+) -> Vec<f64> {
+    instantaneous_reward(model, rewards, steps, Maximiser {})
+}
+
+pub fn instantaneous_reward_minimise<M: ModelTypes<Owners = SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    steps: usize,
+) -> Vec<f64> {
+    instantaneous_reward(model, rewards, steps, Minimiser {})
+}
+
+fn expected_reward<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+    C: ValueComparator<SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    target: &[bool],
+    finite: &[bool],
+    state_groups: &StateGroups,
+    eps: f64,
+    value_comparator: C,
+) -> Vec<f64> {
+    let mut values: Vec<f64> = finite
+        .iter()
+        .map(|&f| if f { 0.0 } else { f64::INFINITY })
+        .collect();
+
+    let mut groups = vec![Vec::new(); state_groups.group_count];
+    for state_index in 0..model.states.len() {
+        if !finite[state_index] || target[state_index] {
+            continue;
+        }
+        match state_groups.group_of_state[state_index] {
+            Some(group_index) => groups[group_index].push(state_index),
+            None => groups.push(vec![state_index]),
+        }
+    }
+
+    loop {
+        let mut converged = true;
+        for members in &groups {
+            let mut best_value = value_comparator.initial_value(&());
+            for &member in members {
+                let state = &model.states[member];
+                for (action_index, action) in state.actions.iter().enumerate() {
+                    if state_groups.is_ignored(member, action_index) {
+                        continue;
+                    }
+                    let mut value = rewards.step_reward(member, action_index);
+                    for successor in action.successors.iter() {
+                        value += successor.probability * values[successor.index];
+                    }
+                    if value_comparator.is_better(&(), best_value, value) {
+                        best_value = value;
+                    }
+                }
+            }
+
+            let state_index = members[0];
+            if (best_value - values[state_index]).abs() > eps * best_value.abs().max(1.0) {
+                converged = false;
+            }
+            for &member in members {
+                values[member] = best_value;
+            }
+        }
+        if converged {
+            return values;
+        }
+    }
+}
+
+fn cumulative_reward<M: ModelTypes<Owners = SinglePlayer>, C: ValueComparator<SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    steps: usize,
+    value_comparator: C,
+) -> Vec<f64> {
+    let mut values = vec![0.0; model.states.len()];
+    let mut next_values = values.clone();
+    for _ in 0..steps {
+        for (state_index, state) in model.states.iter().enumerate() {
+            if state.actions.get_number_of_actions() == 0 {
+                // Deadlocks are treated as if they had a self-loop.
+                next_values[state_index] = rewards.state_reward(state_index) + values[state_index];
+                continue;
+            }
+            let mut best_value = value_comparator.initial_value(&state.owner);
+            for (action_index, action) in state.actions.iter().enumerate() {
+                let mut value = rewards.step_reward(state_index, action_index);
+                for successor in action.successors.iter() {
+                    value += successor.probability * values[successor.index];
+                }
+                if value_comparator.is_better(&state.owner, best_value, value) {
+                    best_value = value;
+                }
+            }
+            next_values[state_index] = best_value;
+        }
+        std::mem::swap(&mut values, &mut next_values);
+    }
+    values
+}
+
+fn instantaneous_reward<M: ModelTypes<Owners = SinglePlayer>, C: ValueComparator<SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    steps: usize,
+    value_comparator: C,
+) -> Vec<f64> {
+    let mut values: Vec<f64> = (0..model.states.len())
+        .map(|s| rewards.state_reward(s))
+        .collect();
+    let mut next_values = values.clone();
+    for _ in 0..steps {
+        for (state_index, state) in model.states.iter().enumerate() {
+            if state.actions.get_number_of_actions() == 0 {
+                next_values[state_index] = values[state_index];
+                continue;
+            }
+            let mut best_value = value_comparator.initial_value(&state.owner);
+            for action in state.actions.iter() {
+                let mut value = 0.0;
+                for successor in action.successors.iter() {
+                    value += successor.probability * values[successor.index];
+                }
+                if value_comparator.is_better(&state.owner, best_value, value) {
+                    best_value = value;
+                }
+            }
+            next_values[state_index] = best_value;
+        }
+        std::mem::swap(&mut values, &mut next_values);
+    }
+    values
+}
+
+/// Computes the states from which the target is reached with probability one under every
+/// scheduler. Deadlocks are treated as if they had a self-loop.
+fn reached_almost_surely_under_all_schedulers<M: ModelTypes<Predecessors = VectorPredecessors>>(
+    model: &ProbabilisticModel<M>,
+    target: &[bool],
+) -> Vec<bool> {
+    // First, compute the states from which some scheduler avoids the target forever. These are
+    // the non-target states that have an action that stays within this set.
+    let mut avoiding: Vec<bool> = target.iter().map(|&t| !t).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (state_index, state) in model.states.iter().enumerate() {
+            if !avoiding[state_index] || state.actions.get_number_of_actions() == 0 {
+                continue;
+            }
+            let can_stay = state
+                .actions
+                .iter()
+                .any(|action| action.successors.iter().all(|s| avoiding[s.index]));
+            if !can_stay {
+                avoiding[state_index] = false;
+                changed = true;
+            }
+        }
+    }
+
+    // Every state that can reach such a state without passing through the target misses the
+    // target with positive probability under some scheduler.
+    let mut may_miss = avoiding.clone();
+    let mut open_list: Vec<usize> = (0..model.states.len()).filter(|&s| avoiding[s]).collect();
+    while let Some(state_index) = open_list.pop() {
+        for predecessor in model.states[state_index].predecessors.iter() {
+            if !may_miss[predecessor.from] && !target[predecessor.from] {
+                may_miss[predecessor.from] = true;
+                open_list.push(predecessor.from);
+            }
+        }
+    }
+
+    may_miss.into_iter().map(|m| !m).collect()
+}
+
+/// Computes the states from which some scheduler reaches the target with probability one.
+fn reached_almost_surely_under_some_scheduler<M: ModelTypes<Predecessors = VectorPredecessors>>(
+    model: &ProbabilisticModel<M>,
+    target: &[bool],
+) -> Vec<bool> {
+    let mut candidates = vec![true; model.states.len()];
+    loop {
+        // States that can reach the target using only actions that stay within the candidates.
+        let mut reaching = target.to_vec();
+        let mut open_list: Vec<usize> = (0..model.states.len()).filter(|&s| target[s]).collect();
+        while let Some(state_index) = open_list.pop() {
+            for predecessor in model.states[state_index].predecessors.iter() {
+                let from = predecessor.from;
+                if reaching[from] || !candidates[from] {
+                    continue;
+                }
+                let action = model.states[from]
+                    .actions
+                    .get_action(predecessor.action_index);
+                if action.successors.iter().all(|s| candidates[s.index]) {
+                    reaching[from] = true;
+                    open_list.push(from);
+                }
+            }
+        }
+
+        if reaching == candidates {
+            return candidates;
+        }
+        candidates = reaching;
+    }
+}
+
+/// States that share their value during expected reward computation, i.e. the states of an end
+/// component in which no reward is earned, together with the actions that are ignored when
+/// computing the value. States that are not part of a group are updated individually.
+struct StateGroups {
+    group_of_state: Vec<Option<usize>>,
+    ignored_actions: Vec<Vec<bool>>,
+    group_count: usize,
+}
+
+impl StateGroups {
+    fn singletons(state_count: usize) -> Self {
+        Self {
+            group_of_state: vec![None; state_count],
+            ignored_actions: vec![Vec::new(); state_count],
+            group_count: 0,
+        }
+    }
+
+    /// Groups the states of the zero-reward end components. Actions inside these end components
+    /// as well as actions that are not allowed are ignored.
+    fn zero_reward_end_components<
+        M: ModelTypes,
+        A: Fn(usize, usize) -> bool,
+        Z: Fn(usize, usize) -> bool,
+    >(
+        model: &ProbabilisticModel<M>,
+        actions_allowed: A,
+        is_zero_reward_action: Z,
+    ) -> Self {
+        let mecs = mecs::compute_mecs_with_allowed_actions(model, is_zero_reward_action);
+        let mut result = Self::singletons(model.states.len());
+        result.group_count = mecs.len();
+        for (state_index, state) in model.states.iter().enumerate() {
+            let mut ignored: Vec<bool> = (0..state.actions.get_number_of_actions())
+                .map(|action_index| !actions_allowed(state_index, action_index))
+                .collect();
+            if let Some(mec_index) = mecs.mec_of_state(state_index) {
+                result.group_of_state[state_index] = Some(mec_index);
+                for action_index in mecs.enabled_actions(state_index) {
+                    ignored[action_index] = true;
+                }
+            }
+            result.ignored_actions[state_index] = ignored;
+        }
+        result
+    }
+
+    fn is_ignored(&self, state_index: usize, action_index: usize) -> bool {
+        self.ignored_actions[state_index]
+            .get(action_index)
+            .copied()
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,344 @@
+use probabilistic_models::probabilistic_properties::{
+    NonDeterminismKind, Query, RewardFormula, StateFormula,
+};
+use probabilistic_models::{
+    ActionCollection, ActionVector, AtomicProposition, AtomicPropositions, Distribution,
+    DistributionVector, InitialStates, ModelTypes, Predecessors, ProbabilisticModel,
+    RewardStructure, TwoPlayer, VectorPredecessors,
+};
+
+#[derive(Clone)]
+pub struct StochasticGameRewardContext {
+    rewards: RewardStructure,
+    target: Vec<bool>,
+    player_one: NonDeterminismKind,
+    eps: f64,
+}
+
+impl StochasticGameRewardContext {
+    /// Creates a context in which player one maximises or minimises the expected reward that is
+    /// accumulated until a target state is reached. Player two pursues the opposite objective.
+    pub fn new(
+        rewards: RewardStructure,
+        target: Vec<bool>,
+        player_one: NonDeterminismKind,
+        eps: f64,
+    ) -> Self {
+        Self {
+            rewards,
+            target,
+            player_one,
+            eps,
+        }
+    }
+}
+
+/// Computes, for every state, the expected reward that is accumulated until a target state is
+/// reached if player one maximises or minimises it and player two pursues the opposite objective.
+///
+/// Runs that miss the target have infinite reward, so the value is infinite in all states from
+/// which the minimising player cannot reach the target almost surely. In the remaining states,
+/// the minimising player has to make sure to leave end components without reward, which is why
+/// the values are computed by strategy iteration over the strategies of the minimising player that
+/// reach the target almost surely.
+pub fn expected_reward_stochastic_game<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    target: &[bool],
+    player_one: NonDeterminismKind,
+    eps: f64,
+) -> Vec<f64> {
+    let minimiser = match player_one {
+        NonDeterminismKind::Maximise => TwoPlayer::PlayerTwo,
+        NonDeterminismKind::Minimise => TwoPlayer::PlayerOne,
+    };
+    let (finite, mut strategy) = reached_almost_surely(model, target, minimiser);
+
+    loop {
+        let values = evaluate_strategy(model, rewards, target, &finite, minimiser, &strategy, eps);
+
+        let mut improved = false;
+        for (state_index, state) in model.states.iter().enumerate() {
+            if !finite[state_index] || target[state_index] || state.owner != minimiser {
+                continue;
+            }
+            let action_value = |action_index: usize| {
+                let action = state.actions.get_action(action_index);
+                let mut value = rewards.step_reward(state_index, action_index);
+                for successor in action.successors.iter() {
+                    value += successor.probability * values[successor.index];
+                }
+                value
+            };
+            // Only switch if the improvement exceeds the precision of the evaluation, as the
+            // strategy iteration might not terminate otherwise
+            let mut best_value = action_value(strategy[state_index]);
+            let mut best_action = strategy[state_index];
+            for (action_index, action) in state.actions.iter().enumerate() {
+                if !action.successors.iter().all(|s| finite[s.index]) {
+                    continue;
+                }
+                let value = action_value(action_index);
+                if value < best_value - eps * best_value.abs().max(1.0) {
+                    best_value = value;
+                    best_action = action_index;
+                }
+            }
+            if best_action != strategy[state_index] {
+                strategy[state_index] = best_action;
+                improved = true;
+            }
+        }
+
+        if !improved {
+            return values;
+        }
+    }
+}
+
+/// Computes the values if the minimising player follows `strategy` and the maximising player
+/// plays optimally. As the strategy reaches the target almost surely, value iteration from below
+/// converges to the expected reward.
+fn evaluate_strategy<M: ModelTypes<Owners = TwoPlayer>>(
+    model: &ProbabilisticModel<M>,
+    rewards: &RewardStructure,
+    target: &[bool],
+    finite: &[bool],
+    minimiser: TwoPlayer,
+    strategy: &[usize],
+    eps: f64,
+) -> Vec<f64> {
+    let mut values: Vec<f64> = finite
+        .iter()
+        .map(|&f| if f { 0.0 } else { f64::INFINITY })
+        .collect();
+    let states: Vec<usize> = (0..model.states.len())
+        .filter(|&s| finite[s] && !target[s])
+        .collect();
+
+    loop {
+        let mut converged = true;
+        for &state_index in &states {
+            let state = &model.states[state_index];
+            let mut best_value = 0.0;
+            for (action_index, action) in state.actions.iter().enumerate() {
+                if state.owner == minimiser && action_index != strategy[state_index] {
+                    continue;
+                }
+                let mut value = rewards.step_reward(state_index, action_index);
+                for successor in action.successors.iter() {
+                    value += successor.probability * values[successor.index];
+                }
+                if value >= best_value {
+                    best_value = value;
+                }
+            }
+
+            if (best_value - values[state_index]).abs() > eps * best_value.abs().max(1.0) {
+                converged = false;
+            }
+            values[state_index] = best_value;
+        }
+        if converged {
+            return values;
+        }
+    }
+}
+
+/// Computes the states from which `player` can reach the target with probability one, no matter
+/// how the other player behaves, together with a strategy for `player` that does so. Deadlocks
+/// are treated as if they had a self-loop.
+fn reached_almost_surely<M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>>(
+    model: &ProbabilisticModel<M>,
+    target: &[bool],
+    player: TwoPlayer,
+) -> (Vec<bool>, Vec<usize>) {
+    let mut candidates: Vec<bool> = model
+        .states
+        .iter()
+        .enumerate()
+        .map(|(state_index, state)| {
+            target[state_index] || state.actions.get_number_of_actions() > 0
+        })
+        .collect();
+    let mut strategy = vec![0; model.states.len()];
+    loop {
+        // Remove the states in which the other player can leave the candidates or in which
+        // `player` cannot stay within them.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (state_index, state) in model.states.iter().enumerate() {
+                if !candidates[state_index] || target[state_index] {
+                    continue;
+                }
+                let mut staying = state
+                    .actions
+                    .iter()
+                    .map(|action| action.successors.iter().all(|s| candidates[s.index]));
+                let can_stay = if state.owner == player {
+                    staying.any(|stays| stays)
+                } else {
+                    staying.all(|stays| stays)
+                };
+                if !can_stay {
+                    candidates[state_index] = false;
+                    changed = true;
+                }
+            }
+        }
+
+        // States from which `player` can reach the target with positive probability while
+        // staying within the candidates.
+        let mut reaching = target.to_vec();
+        let mut open_list: Vec<usize> = (0..model.states.len()).filter(|&s| target[s]).collect();
+        while let Some(state_index) = open_list.pop() {
+            for predecessor in model.states[state_index].predecessors.iter() {
+                let from = predecessor.from;
+                if reaching[from] || !candidates[from] {
+                    continue;
+                }
+                let state = &model.states[from];
+                if state.owner == player {
+                    let action = state.actions.get_action(predecessor.action_index);
+                    if !action.successors.iter().all(|s| candidates[s.index]) {
+                        continue;
+                    }
+                    strategy[from] = predecessor.action_index;
+                } else if !state
+                    .actions
+                    .iter()
+                    .all(|action| action.successors.iter().any(|s| reaching[s.index]))
+                {
+                    continue;
+                }
+                reaching[from] = true;
+                open_list.push(from);
+            }
+        }
+
+        if reaching == candidates {
+            return (candidates, strategy);
+        }
+        candidates = reaching;
+    }
+}
+
+/// The model does not have the reward structure that the property refers to. Without a name, the
+/// model does not have any reward structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRewardStructure {
+    pub name: Option<String>,
+}
+
+/// Solves stochastic games with expected reward objectives of the form `R{"name"}max=? [F goal]`.
+///
+/// Player one maximises the expected reward unless the query asks for minimisation. The values
+/// reported as probabilities through `StochasticGameAlgorithm` are expected rewards.
+#[derive(Clone)]
+pub struct StochasticGameRewardAlgorithm {
+    name: Option<String>,
+    goal_states: AtomicProposition,
+    player_one: NonDeterminismKind,
+}
+
+impl StochasticGameRewardAlgorithm {
+    /// Whether player one maximises or minimises the expected reward
+    pub fn player_one_objective(&self) -> NonDeterminismKind {
+        self.player_one
+    }
+}
+
+impl crate::traits::StochasticGameAlgorithm for StochasticGameRewardAlgorithm {
+    type ModelContext = StochasticGameRewardContext;
+    type ModelContextError = UnknownRewardStructure;
+
+    fn create_model_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &self,
+        model: &ProbabilisticModel<M>,
+    ) -> Result<Self::ModelContext, Self::ModelContextError> {
+        let rewards = model
+            .get_reward_structure(self.name.as_deref())
+            .ok_or_else(|| UnknownRewardStructure {
+                name: self.name.clone(),
+            })?;
+        let target = model
+            .states
+            .iter()
+            .map(|state| state.atomic_propositions.get_value(self.goal_states.index))
+            .collect();
+        Ok(StochasticGameRewardContext::new(
+            rewards.clone(),
+            target,
+            self.player_one,
+            0.000_001,
+        ))
+    }
+
+    fn create_if_compatible(property: &Query<f64, f64, AtomicProposition>) -> Option<Self> {
+        if let Query::RewardValue {
+            non_determinism,
+            name,
+            reward:
+                RewardFormula::Finally {
+                    states: StateFormula::Expression(goal_states),
+                },
+        } = property
+        {
+            // As for probabilities, no non-determinism is considered maximisation
+            Some(Self {
+                name: name.clone(),
+                goal_states: *goal_states,
+                player_one: non_determinism.unwrap_or(NonDeterminismKind::Maximise),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn player_one_probability_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> f64 {
+        let values = self.player_one_probabilities_with_context(model, context);
+        values[*model.initial_states.iter().next().unwrap()]
+    }
+
+    fn player_one_probabilities_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Vec<f64> {
+        expected_reward_stochastic_game(
+            model,
+            &context.rewards,
+            &context.target,
+            context.player_one,
+            context.eps,
+        )
+    }
+}
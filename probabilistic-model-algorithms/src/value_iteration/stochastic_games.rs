@@ -1,18 +1,22 @@
 use crate::sccs::{SccList, SccWithDependencies};
 use crate::value_iteration::StateData;
 use probabilistic_models::probabilistic_properties::{
-    NonDeterminismKind, PathFormula, Query, StateFormula,
+    Bound, BoundOperator, NonDeterminismKind, PathFormula, Query, SafetyComplement, StateFormula,
 };
 use probabilistic_models::{
     ActionCollection, ActionVector, AtomicProposition, AtomicPropositions, DistributionVector,
     InitialStates, ModelTypes, ProbabilisticModel, TwoPlayer, VectorPredecessors,
 };
 
+#[derive(Clone)]
 pub struct StochasticGameValueIterationContext {
     data: Vec<StateData>,
     sccs: SccList<SccWithDependencies>,
     scc_reverse_order: Vec<usize>,
+    player_one: NonDeterminismKind,
     eps: f64,
+    // The owners for which the values in `data` were computed, if any
+    previous_owners: Option<Vec<TwoPlayer>>,
 }
 
 impl StochasticGameValueIterationContext {
@@ -27,14 +31,58 @@ impl StochasticGameValueIterationContext {
         model: &ProbabilisticModel<M>,
         goal_states: AtomicProposition,
         eps: f64,
+    ) -> Self {
+        let goal_states = model
+            .states
+            .iter()
+            .map(|state| state.atomic_propositions.get_value(goal_states.index))
+            .collect();
+        Self::with_objective(model, goal_states, NonDeterminismKind::Maximise, eps)
+    }
+
+    /// Creates a context in which player one maximises or minimises the probability of reaching
+    /// the goal states, which are given as one flag per state. Player two pursues the opposite
+    /// objective.
+    pub fn with_objective<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        model: &ProbabilisticModel<M>,
+        goal_states: Vec<bool>,
+        player_one: NonDeterminismKind,
+        eps: f64,
+    ) -> Self {
+        let blocked_states = vec![false; model.states.len()];
+        Self::with_constrained_objective(model, goal_states, blocked_states, player_one, eps)
+    }
+
+    /// Like `with_objective`, but paths that visit a blocked state before a goal state do not
+    /// reach the goal states, as for `before U after` with the blocked states `!before & !after`.
+    pub fn with_constrained_objective<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        model: &ProbabilisticModel<M>,
+        goal_states: Vec<bool>,
+        blocked_states: Vec<bool>,
+        player_one: NonDeterminismKind,
+        eps: f64,
     ) -> Self {
         let mut data = vec![StateData::new(); model.states.len()];
         let mut excluded_states = Vec::new();
         for (state_index, state) in model.states.iter().enumerate() {
-            if state.atomic_propositions.get_value(goal_states.index) {
+            if goal_states[state_index] {
                 excluded_states.push(state_index);
                 data[state_index].value = 1.0;
-            } else if state.actions.get_number_of_actions() == 0 {
+            } else if blocked_states[state_index] || state.actions.get_number_of_actions() == 0 {
                 excluded_states.push(state_index);
             }
         }
@@ -49,7 +97,9 @@ impl StochasticGameValueIterationContext {
             data,
             sccs,
             scc_reverse_order,
+            player_one,
             eps,
+            previous_owners: None,
         }
     }
 
@@ -60,6 +110,48 @@ impl StochasticGameValueIterationContext {
             }
         }
     }
+
+    /// The values of the previous computation are lower bounds for the values with the current
+    /// owners if the maximising player has not lost any state since then, as they only gained
+    /// choices.
+    fn previous_values_are_lower_bounds<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &self,
+        model: &ProbabilisticModel<M>,
+    ) -> bool {
+        let maximiser = match self.player_one {
+            NonDeterminismKind::Maximise => TwoPlayer::PlayerOne,
+            NonDeterminismKind::Minimise => TwoPlayer::PlayerTwo,
+        };
+        self.previous_owners.as_ref().is_some_and(|owners| {
+            owners
+                .iter()
+                .zip(model.states.iter())
+                .all(|(&owner, state)| owner != maximiser || state.owner == maximiser)
+        })
+    }
+
+    fn remember_owners<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+    ) {
+        let owners = self.previous_owners.get_or_insert_with(Vec::new);
+        owners.clear();
+        owners.extend(model.states.iter().map(|state| state.owner));
+    }
 }
 
 pub fn value_iteration_stochastic_games<
@@ -89,24 +181,239 @@ pub fn value_iteration_stochastic_games_with_context<
     context: &mut StochasticGameValueIterationContext,
 ) -> f64 {
     context.reset();
+    value_iteration_stochastic_games_from_current_values(model, context)
+}
 
-    super::value_iteration_internal(
-        &model,
-        &mut context.data,
-        context.eps,
-        &context.sccs,
-        &context.scc_reverse_order,
-        super::TwoPlayerMaxMin {},
-    );
+/// Like `value_iteration_stochastic_games_with_context`, but starts from the values of the
+/// previous computation with the same context if they are lower bounds for the new values, i.e. if
+/// the maximising player has not lost any state since then. Otherwise, the values are computed from
+/// scratch. Starting from the previous values usually saves many iterations when only the owners of
+/// a few states changed.
+pub fn value_iteration_stochastic_games_incremental_with_context<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    context: &mut StochasticGameValueIterationContext,
+) -> f64 {
+    if !context.previous_values_are_lower_bounds(model) {
+        context.reset();
+    }
+    value_iteration_stochastic_games_from_current_values(model, context)
+}
+
+fn value_iteration_stochastic_games_from_current_values<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    context: &mut StochasticGameValueIterationContext,
+) -> f64 {
+    match context.player_one {
+        NonDeterminismKind::Maximise => super::value_iteration_internal(
+            model,
+            &mut context.data,
+            context.eps,
+            &context.sccs,
+            &context.scc_reverse_order,
+            super::TwoPlayerMaxMin {},
+        ),
+        NonDeterminismKind::Minimise => super::value_iteration_internal(
+            model,
+            &mut context.data,
+            context.eps,
+            &context.sccs,
+            &context.scc_reverse_order,
+            super::TwoPlayerMinMax {},
+        ),
+    }
+    context.remember_owners(model);
     context.data[*model.initial_states.iter().next().unwrap()].value
 }
 
+/// Like `value_iteration_stochastic_games_with_context`, but returns the values of all states
+/// instead of only the value of the initial state.
+pub fn value_iteration_stochastic_games_all_states_with_context<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    context: &mut StochasticGameValueIterationContext,
+) -> Vec<f64> {
+    value_iteration_stochastic_games_with_context(model, context);
+    context.data.iter().map(|d| d.value).collect()
+}
+
+/// Like `value_iteration_stochastic_games_incremental_with_context`, but returns the values of all
+/// states instead of only the value of the initial state.
+pub fn value_iteration_stochastic_games_all_states_incremental_with_context<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    context: &mut StochasticGameValueIterationContext,
+) -> Vec<f64> {
+    value_iteration_stochastic_games_incremental_with_context(model, context);
+    context.data.iter().map(|d| d.value).collect()
+}
+
+/// Computes the probability of `X goal` from the initial state with a single Bellman backup, in
+/// which player one maximises or minimises the probability and player two pursues the opposite
+/// objective. The context must block every state, so that only the goal states have a value.
+pub fn next_step_stochastic_games_with_context<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    context: &mut StochasticGameValueIterationContext,
+) -> f64 {
+    next_step_stochastic_games_all_states_with_context(model, context)
+        [*model.initial_states.iter().next().unwrap()]
+}
+
+/// Like `next_step_stochastic_games_with_context`, but returns the values of all states instead of
+/// only the value of the initial state.
+pub fn next_step_stochastic_games_all_states_with_context<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = TwoPlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    context: &mut StochasticGameValueIterationContext,
+) -> Vec<f64> {
+    let goal_values: Vec<f64> = context.data.iter().map(|d| d.value).collect();
+    match context.player_one {
+        NonDeterminismKind::Maximise => {
+            super::one_step_backup(model, &goal_values, super::TwoPlayerMaxMin {})
+        }
+        NonDeterminismKind::Minimise => {
+            super::one_step_backup(model, &goal_values, super::TwoPlayerMinMax {})
+        }
+    }
+}
+
+/// Solves stochastic games with reachability (`F`, `U`), safety (`G`), weak until (`W`), release
+/// (`R`) and next (`X`) objectives, either computing the probability (`P=? [...]`) or checking a
+/// bound (`P>=0.9 [...]`).
+///
+/// Player one maximises the probability unless the query asks for minimisation or has an upper
+/// bound. Safety, weak until and release objectives are solved via the complementary
+/// reachability objective, next objectives with a single Bellman backup.
+#[derive(Clone)]
 pub struct StochasticGameValueIterationAlgorithm {
-    goal_states: AtomicProposition,
+    path: GamePath,
+    player_one: NonDeterminismKind,
+    bound: Option<Bound<f64>>,
+}
+
+#[derive(Clone)]
+enum GamePath {
+    Until {
+        before: Option<AtomicProposition>,
+        after: AtomicProposition,
+    },
+    // `G`, `W` and `R`, for which the goal states belong to the complementary reachability
+    // objective and the result is complemented
+    Complement(SafetyComplement<AtomicProposition>),
+    Next(AtomicProposition),
+}
+
+impl GamePath {
+    fn is_complemented(&self) -> bool {
+        matches!(self, GamePath::Complement(_))
+    }
+
+    /// Whether a state with the given atomic propositions is a goal state and whether it is
+    /// blocked, i.e. a path visiting it can no longer reach a goal state. For `X`, every state is
+    /// blocked, so that the goal states are only reached in a single step.
+    fn classify<A: AtomicPropositions>(&self, atomic_propositions: &A) -> (bool, bool) {
+        let holds = |ap: &AtomicProposition| atomic_propositions.get_value(ap.index);
+        match self {
+            GamePath::Until { before, after } => {
+                let after = holds(after);
+                let before = before.as_ref().is_none_or(holds);
+                (after, !before && !after)
+            }
+            GamePath::Complement(complement) => complement.classify(holds),
+            GamePath::Next(condition) => (holds(condition), true),
+        }
+    }
+}
+
+impl StochasticGameValueIterationAlgorithm {
+    /// Whether player one maximises or minimises the probability of the path formula
+    pub fn player_one_objective(&self) -> NonDeterminismKind {
+        self.player_one
+    }
+
+    /// The bound of the query, if the query is a bound query rather than a value query
+    pub fn bound(&self) -> Option<&Bound<f64>> {
+        self.bound.as_ref()
+    }
+
+    fn from_path(
+        path: &PathFormula<f64, f64, AtomicProposition>,
+        player_one: NonDeterminismKind,
+        bound: Option<Bound<f64>>,
+    ) -> Option<Self> {
+        let path = if let Some(complement) = path.as_safety_complement() {
+            GamePath::Complement(complement)
+        } else {
+            match path {
+                PathFormula::Eventually { condition } => GamePath::Until {
+                    before: None,
+                    after: *condition.as_expression()?,
+                },
+                PathFormula::Until { before, after } => GamePath::Until {
+                    before: Some(*before.as_expression()?),
+                    after: *after.as_expression()?,
+                },
+                PathFormula::Next { condition } => GamePath::Next(*condition.as_expression()?),
+                _ => return None,
+            }
+        };
+        Some(Self {
+            path,
+            player_one,
+            bound,
+        })
+    }
+
+    fn to_path_probability(&self, reachability_probability: f64) -> f64 {
+        if self.path.is_complemented() {
+            1.0 - reachability_probability
+        } else {
+            reachability_probability
+        }
+    }
 }
 
 impl crate::traits::StochasticGameAlgorithm for StochasticGameValueIterationAlgorithm {
     type ModelContext = StochasticGameValueIterationContext;
+    type ModelContextError = std::convert::Infallible;
 
     fn create_model_context<
         M: ModelTypes<
@@ -118,28 +425,62 @@ impl crate::traits::StochasticGameAlgorithm for StochasticGameValueIterationAlgo
     >(
         &self,
         model: &ProbabilisticModel<M>,
-    ) -> Self::ModelContext {
-        StochasticGameValueIterationContext::new(model, self.goal_states, 0.000_001)
-    }
-
-    fn create_if_compatible(property: &Query<i64, f64, AtomicProposition>) -> Option<Self> {
-        if let Query::ProbabilityValue {
-            non_determinism,
-            path: PathFormula::Eventually { condition },
-        } = property
-        {
-            // TODO: Properly support game formulas. Games always assume that player one maximises
-            // and minimises, so we accept both no non-determinism and maximising non-determinism,
-            // as both can be considered an accurate description (either of the game objective as a
-            // whole or of player one's objective)
-            if non_determinism.is_none() || non_determinism.unwrap() == NonDeterminismKind::Maximise
-            {
-                if let StateFormula::Expression(goal_states) = **condition {
-                    return Some(Self { goal_states });
+    ) -> Result<Self::ModelContext, Self::ModelContextError> {
+        let (goal_states, blocked_states) = model
+            .states
+            .iter()
+            .map(|state| self.path.classify(&state.atomic_propositions))
+            .unzip();
+        // For complemented objectives, the optimisation direction flips
+        let player_one = if self.path.is_complemented() {
+            self.player_one.opposite()
+        } else {
+            self.player_one
+        };
+        Ok(
+            StochasticGameValueIterationContext::with_constrained_objective(
+                model,
+                goal_states,
+                blocked_states,
+                player_one,
+                0.000_001,
+            ),
+        )
+    }
+
+    fn create_if_compatible(property: &Query<f64, f64, AtomicProposition>) -> Option<Self> {
+        match property {
+            Query::ProbabilityValue {
+                non_determinism,
+                path,
+            } => {
+                // TODO: Properly support game formulas. Games always assume that player one
+                // optimises in the given direction and player two in the opposite one, so no
+                // non-determinism is considered an accurate description of maximisation
+                let player_one = non_determinism.unwrap_or(NonDeterminismKind::Maximise);
+                Self::from_path(path, player_one, None)
+            }
+            Query::StateFormula(StateFormula::ProbabilityBound {
+                non_determinism,
+                bound,
+                path,
+            }) => {
+                // Player one tries to satisfy the bound
+                let player_one = match bound.operator {
+                    BoundOperator::GreaterThan | BoundOperator::GreaterOrEqual => {
+                        NonDeterminismKind::Maximise
+                    }
+                    BoundOperator::LessThan | BoundOperator::LessOrEqual => {
+                        NonDeterminismKind::Minimise
+                    }
+                };
+                if non_determinism.is_some_and(|n| n != player_one) {
+                    return None;
                 }
+                Self::from_path(path, player_one, Some(bound.clone()))
             }
+            _ => None,
         }
-        None
     }
 
     fn player_one_probability_with_context<
@@ -154,6 +495,77 @@ impl crate::traits::StochasticGameAlgorithm for StochasticGameValueIterationAlgo
         model: &ProbabilisticModel<M>,
         context: &mut Self::ModelContext,
     ) -> f64 {
-        value_iteration_stochastic_games_with_context(model, context)
+        if let GamePath::Next(_) = self.path {
+            return self
+                .to_path_probability(next_step_stochastic_games_with_context(model, context));
+        }
+        self.to_path_probability(value_iteration_stochastic_games_with_context(
+            model, context,
+        ))
+    }
+
+    fn player_one_probabilities_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Vec<f64> {
+        let values = if let GamePath::Next(_) = self.path {
+            next_step_stochastic_games_all_states_with_context(model, context)
+        } else {
+            value_iteration_stochastic_games_all_states_with_context(model, context)
+        };
+        values
+            .into_iter()
+            .map(|p| self.to_path_probability(p))
+            .collect()
+    }
+
+    fn player_one_probability_incremental_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> f64 {
+        // A single backup needs no previous values to build on
+        if let GamePath::Next(_) = self.path {
+            return self.player_one_probability_with_context(model, context);
+        }
+        self.to_path_probability(value_iteration_stochastic_games_incremental_with_context(
+            model, context,
+        ))
+    }
+
+    fn player_one_probabilities_incremental_with_context<
+        M: ModelTypes<
+                Predecessors = VectorPredecessors,
+                Distribution = DistributionVector,
+                ActionCollection = ActionVector<DistributionVector>,
+                Owners = TwoPlayer,
+            >,
+    >(
+        &mut self,
+        model: &ProbabilisticModel<M>,
+        context: &mut Self::ModelContext,
+    ) -> Vec<f64> {
+        if let GamePath::Next(_) = self.path {
+            return self.player_one_probabilities_with_context(model, context);
+        }
+        value_iteration_stochastic_games_all_states_incremental_with_context(model, context)
+            .into_iter()
+            .map(|p| self.to_path_probability(p))
+            .collect()
     }
 }
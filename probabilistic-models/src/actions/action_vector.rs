@@ -1,5 +1,6 @@
 use crate::{Action, Distribution};
 
+#[derive(Clone)]
 pub struct ActionVector<D: Distribution> {
     actions: Vec<Action<D>>,
 }
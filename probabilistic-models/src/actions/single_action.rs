@@ -1,5 +1,6 @@
 use crate::{Action, Distribution};
 
+#[derive(Clone)]
 pub struct SingleAction<D: Distribution> {
     action: Action<D>,
 }
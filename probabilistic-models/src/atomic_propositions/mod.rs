@@ -15,6 +15,7 @@ pub trait AtomicPropositions {
     }
 }
 
+#[derive(Clone)]
 pub struct BitFlagsAtomicPropositions {
     values: u64,
 }
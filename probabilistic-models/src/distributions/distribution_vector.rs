@@ -1,6 +1,7 @@
 use super::Successor;
 use crate::Distribution;
 
+#[derive(Clone)]
 pub struct DistributionVector {
     successors: Vec<Successor>,
 }
@@ -68,6 +69,10 @@ impl super::DistributionBuilder<DistributionVector> for Builder {
         self.distribution.successors.push(successor);
     }
 
+    fn finish_rates(self) -> DistributionVector {
+        self.distribution
+    }
+
     fn finish(self) -> DistributionVector {
         let sum: f64 = self
             .distribution
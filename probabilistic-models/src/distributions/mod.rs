@@ -24,4 +24,7 @@ pub struct Successor {
 pub trait DistributionBuilder<D> {
     fn add_successor(&mut self, successor: Successor);
     fn finish(self) -> D;
+    /// Finishes a distribution whose successors are weighted by rates rather than probabilities,
+    /// so the weights need not add up to one.
+    fn finish_rates(self) -> D;
 }
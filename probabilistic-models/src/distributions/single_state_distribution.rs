@@ -1,5 +1,6 @@
 use crate::Successor;
 
+#[derive(Clone)]
 pub struct SingleStateDistribution {
     successor: Successor,
 }
@@ -49,6 +50,10 @@ impl super::DistributionBuilder<SingleStateDistribution> for Builder {
         }
     }
 
+    fn finish_rates(self) -> SingleStateDistribution {
+        self.finish()
+    }
+
     fn finish(self) -> SingleStateDistribution {
         match self.successor {
             Some(successor) => SingleStateDistribution { successor },
@@ -3,6 +3,7 @@ pub struct ModelFeatures {
     pub probabilism: bool,
     pub non_determinism: bool,
     pub ownership: Ownership,
+    pub continuous_time: bool,
 }
 
 #[derive(Clone, PartialEq)]
@@ -17,6 +18,7 @@ impl ModelFeatures {
             probabilism: false,
             non_determinism: true,
             ownership: Ownership::SinglePlayer,
+            continuous_time: false,
         }
     }
 
@@ -25,6 +27,7 @@ impl ModelFeatures {
             probabilism: false,
             non_determinism: true,
             ownership: Ownership::TwoPlayer,
+            continuous_time: false,
         }
     }
 
@@ -33,6 +36,7 @@ impl ModelFeatures {
             probabilism: true,
             non_determinism: false,
             ownership: Ownership::SinglePlayer,
+            continuous_time: false,
         }
     }
 
@@ -41,6 +45,7 @@ impl ModelFeatures {
             probabilism: true,
             non_determinism: true,
             ownership: Ownership::SinglePlayer,
+            continuous_time: false,
         }
     }
 
@@ -49,6 +54,16 @@ impl ModelFeatures {
             probabilism: true,
             non_determinism: true,
             ownership: Ownership::TwoPlayer,
+            continuous_time: false,
+        }
+    }
+
+    pub fn continuous_time_markov_chain() -> Self {
+        Self {
+            probabilism: true,
+            non_determinism: false,
+            ownership: Ownership::SinglePlayer,
+            continuous_time: true,
         }
     }
 
@@ -86,6 +101,7 @@ impl ModelFeatures {
             probabilism,
             non_determinism,
             ownership,
+            continuous_time: model.exit_rates.is_some(),
         }
     }
 
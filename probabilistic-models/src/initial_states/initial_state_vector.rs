@@ -0,0 +1,70 @@
+use super::{InitialStates, InitialStatesBuilder};
+
+#[derive(Clone)]
+pub struct InitialStateVector {
+    initial_states: Vec<usize>,
+}
+
+impl InitialStates for InitialStateVector {
+    type Builder = InitialStateVectorBuilder;
+    type Iter<'a>
+        = std::slice::Iter<'a, usize>
+    where
+        Self: 'a;
+
+    fn get_builder() -> Self::Builder {
+        InitialStateVectorBuilder::new()
+    }
+
+    fn count(&self) -> usize {
+        self.initial_states.len()
+    }
+
+    fn get(&self, index: usize) -> usize {
+        match self.initial_states.get(index) {
+            Some(&state) => state,
+            None => panic!(
+                "Initial state index {} out of range (number of initial states: {})",
+                index,
+                self.initial_states.len()
+            ),
+        }
+    }
+
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.initial_states.iter()
+    }
+}
+
+pub struct InitialStateVectorBuilder {
+    states: Vec<usize>,
+}
+
+impl InitialStateVectorBuilder {
+    pub fn new() -> Self {
+        Self { states: Vec::new() }
+    }
+}
+
+impl Default for InitialStateVectorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InitialStatesBuilder<InitialStateVector> for InitialStateVectorBuilder {
+    fn add_by_index(&mut self, index: usize) {
+        if !self.states.contains(&index) {
+            self.states.push(index);
+        }
+    }
+
+    fn finish(self) -> InitialStateVector {
+        if self.states.is_empty() {
+            panic!("Model type requires at least one initial state");
+        }
+        InitialStateVector {
+            initial_states: self.states,
+        }
+    }
+}
@@ -1,6 +1,9 @@
 mod single_initial_state;
 pub use single_initial_state::{SingleInitialState, SingleInitialStateBuilder};
 
+mod initial_state_vector;
+pub use initial_state_vector::{InitialStateVector, InitialStateVectorBuilder};
+
 pub trait InitialStates: Sized {
     type Builder: InitialStatesBuilder<Self>;
     type Iter<'a>: Iterator<Item = &'a usize>
@@ -16,4 +19,4 @@ pub trait InitialStatesBuilder<D> {
     fn add_by_index(&mut self, index: usize);
 
     fn finish(self) -> D;
-}
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
 use super::{InitialStates, InitialStatesBuilder};
 
+#[derive(Clone)]
 pub struct SingleInitialState {
     initial_state: usize,
 }
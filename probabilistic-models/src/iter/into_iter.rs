@@ -1,6 +1,6 @@
 use crate::{
-    Action, ActionCollection, Distribution, InitialStates, ModelTypes, ProbabilisticModel, State,
-    Successor, Valuation,
+    Action, ActionCollection, Distribution, InitialStates, ModelTypes, ProbabilisticModel,
+    RewardStructure, State, Successor, Valuation,
 };
 use std::collections::VecDeque;
 
@@ -11,6 +11,8 @@ pub struct IteratedProbabilisticModel<M: ModelTypes> {
     valuation_context: Option<<M::Valuation as Valuation>::ContextType>,
     actions: Option<Vec<String>>,
     atomic_proposition_count: usize,
+    reward_structures: Option<Vec<RewardStructure>>,
+    exit_rates: Option<Vec<f64>>,
 }
 
 impl<M: ModelTypes> IteratedProbabilisticModel<M> {
@@ -22,6 +24,8 @@ impl<M: ModelTypes> IteratedProbabilisticModel<M> {
             valuation_context: Some(model.valuation_context),
             actions: Some(model.action_names),
             atomic_proposition_count: model.atomic_proposition_count,
+            reward_structures: Some(model.reward_structures),
+            exit_rates: model.exit_rates,
         }
     }
 }
@@ -65,6 +69,14 @@ impl<'a, M: ModelTypes>
     fn take_atomic_proposition_count(&mut self) -> usize {
         self.atomic_proposition_count
     }
+
+    fn take_reward_structures(&mut self) -> Vec<RewardStructure> {
+        self.reward_structures.take().unwrap()
+    }
+
+    fn take_exit_rates(&mut self) -> Option<Vec<f64>> {
+        self.exit_rates.take()
+    }
 }
 
 pub struct IteratedState<M: ModelTypes> {
@@ -1,5 +1,5 @@
 use super::{IterAction, IterProbabilisticModel, IterState};
-use crate::{AtomicPropositions, Owners, Predecessors, Valuation};
+use crate::{AtomicPropositions, Owners, Predecessors, RewardStructure, Valuation};
 
 pub struct MappedOwners<
     V: Valuation,
@@ -67,6 +67,14 @@ impl<
     fn take_atomic_proposition_count(&mut self) -> usize {
         self.base.take_atomic_proposition_count()
     }
+
+    fn take_reward_structures(&mut self) -> Vec<RewardStructure> {
+        self.base.take_reward_structures()
+    }
+
+    fn take_exit_rates(&mut self) -> Option<Vec<f64>> {
+        self.base.take_exit_rates()
+    }
 }
 
 pub struct MappedOwnersState<
@@ -7,7 +7,7 @@ use map_owners::MappedOwners;
 use crate::{
     Action, ActionCollection, AtomicPropositions, Builder, Distribution, DistributionBuilder,
     InitialStates, InitialStatesBuilder, ModelTypes, Owners, Predecessors, ProbabilisticModel,
-    State, Successor, Valuation,
+    RewardStructure, State, Successor, Valuation,
 };
 
 pub trait IterProbabilisticModel<
@@ -24,6 +24,8 @@ pub trait IterProbabilisticModel<
     fn take_valuation_context(&mut self) -> V::ContextType;
     fn take_actions(&mut self) -> Vec<String>;
     fn take_atomic_proposition_count(&mut self) -> usize;
+    fn take_reward_structures(&mut self) -> Vec<RewardStructure>;
+    fn take_exit_rates(&mut self) -> Option<Vec<f64>>;
 
     fn collect<
         M: ModelTypes<Valuation = V, Owners = O, AtomicPropositions = AP, Predecessors = P>,
@@ -47,12 +49,18 @@ pub trait IterProbabilisticModel<
 
         let atomic_proposition_count = self.take_atomic_proposition_count();
 
+        let reward_structures = self.take_reward_structures();
+
+        let exit_rates = self.take_exit_rates();
+
         ProbabilisticModel {
             states,
             initial_states,
             valuation_context,
             action_names: actions,
             atomic_proposition_count,
+            reward_structures,
+            exit_rates,
         }
     }
 }
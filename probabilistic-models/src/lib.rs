@@ -28,6 +28,9 @@ pub use features::{ModelFeatures, Ownership};
 mod predecessors;
 pub use predecessors::*;
 
+mod rewards;
+pub use rewards::RewardStructure;
+
 pub trait ModelTypes: Sized {
     type Valuation: Valuation;
     type Distribution: Distribution;
@@ -44,6 +47,29 @@ pub struct ProbabilisticModel<M: ModelTypes> {
     pub valuation_context: <M::Valuation as Valuation>::ContextType,
     pub atomic_proposition_count: usize,
     pub action_names: Vec<String>,
+    pub reward_structures: Vec<RewardStructure>,
+    /// The exit rate of every state if the model is a continuous-time Markov chain. The successor
+    /// probabilities are then those of the embedded discrete-time Markov chain.
+    pub exit_rates: Option<Vec<f64>>,
+}
+
+impl<M: ModelTypes> Clone for ProbabilisticModel<M>
+where
+    State<M>: Clone,
+    M::InitialStates: Clone,
+    <M::Valuation as Valuation>::ContextType: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            states: self.states.clone(),
+            initial_states: self.initial_states.clone(),
+            valuation_context: self.valuation_context.clone(),
+            atomic_proposition_count: self.atomic_proposition_count,
+            action_names: self.action_names.clone(),
+            reward_structures: self.reward_structures.clone(),
+            exit_rates: self.exit_rates.clone(),
+        }
+    }
 }
 
 impl<M: ModelTypes> std::fmt::Debug for ProbabilisticModel<M> {
@@ -93,6 +119,8 @@ impl<M: ModelTypes> ProbabilisticModel<M> {
             valuation_context,
             atomic_proposition_count,
             action_names: Vec::new(),
+            reward_structures: Vec::new(),
+            exit_rates: None,
         }
     }
 
@@ -144,6 +172,112 @@ impl<M: ModelTypes> ProbabilisticModel<M> {
         res
     }
 
+    /// Returns the reward structure with the given name or, if no name is given, the first reward
+    /// structure of the model.
+    pub fn get_reward_structure(&self, name: Option<&str>) -> Option<&RewardStructure> {
+        match name {
+            Some(name) => self
+                .reward_structures
+                .iter()
+                .find(|r| r.name.as_deref() == Some(name)),
+            None => self.reward_structures.first(),
+        }
+    }
+
+    /// Interprets the successor probabilities as rates, as is the case for models built from
+    /// `ctmc` files. All actions of a state are merged into a single action whose successors are
+    /// weighted by their share of the exit rate, and the exit rates are stored in the model.
+    /// Action rewards are weighted in the same way. Predecessors are not updated, so they need to
+    /// be rebuilt afterwards if they are tracked.
+    pub fn convert_rates_to_probabilities(&mut self) {
+        let mut exit_rates = Vec::with_capacity(self.states.len());
+        let mut action_name_index = None;
+        for state_index in 0..self.states.len() {
+            let state = &self.states[state_index];
+            let mut rates: Vec<(usize, f64)> = Vec::new();
+            let mut action_rewards = vec![0.0; self.reward_structures.len()];
+            for (action_index, action) in state.actions.iter().enumerate() {
+                let mut action_rate = 0.0;
+                for successor in action.successors.iter() {
+                    action_rate += successor.probability;
+                    match rates
+                        .iter_mut()
+                        .find(|(index, _)| *index == successor.index)
+                    {
+                        Some((_, rate)) => *rate += successor.probability,
+                        None => rates.push((successor.index, successor.probability)),
+                    }
+                }
+                for (reward, structure) in action_rewards.iter_mut().zip(&self.reward_structures) {
+                    *reward += action_rate * structure.action_reward(state_index, action_index);
+                }
+            }
+            let exit_rate: f64 = rates.iter().map(|(_, rate)| rate).sum();
+            exit_rates.push(exit_rate);
+            if state.actions.get_number_of_actions() == 0 {
+                continue;
+            }
+
+            let action_name_index =
+                *action_name_index.get_or_insert_with(|| self.get_action_index_or_add("unnamed"));
+            let mut distribution = <M::Distribution as Distribution>::get_builder();
+            if exit_rate == 0.0 {
+                // Without any positive rate, the state is never left.
+                distribution.add_successor(Successor {
+                    index: state_index,
+                    probability: 1.0,
+                });
+            }
+            for (index, rate) in rates.into_iter().filter(|&(_, rate)| rate > 0.0) {
+                distribution.add_successor(Successor {
+                    index,
+                    probability: rate / exit_rate,
+                });
+            }
+            let mut actions =
+                <M::ActionCollection as ActionCollection<M::Distribution>>::get_builder();
+            actions.add_action(Action {
+                successors: distribution.finish(),
+                action_name_index,
+            });
+            self.states[state_index].actions = actions.finish();
+
+            for (reward, structure) in action_rewards.into_iter().zip(&mut self.reward_structures) {
+                structure.clear_action_rewards(state_index);
+                if reward != 0.0 {
+                    structure.set_action_reward(state_index, 0, reward / exit_rate);
+                }
+            }
+        }
+        self.exit_rates = Some(exit_rates);
+    }
+
+    /// Adds a self-loop to every state without outgoing actions. Predecessors are not updated,
+    /// so they need to be rebuilt afterwards if they are tracked.
+    pub fn add_self_loops_to_deadlocks(&mut self) {
+        let mut action_name_index = None;
+        for state_index in 0..self.states.len() {
+            if self.states[state_index].actions.get_number_of_actions() > 0 {
+                continue;
+            }
+            let action_name_index =
+                *action_name_index.get_or_insert_with(|| self.get_action_index_or_add("unnamed"));
+
+            let mut distribution = <M::Distribution as Distribution>::get_builder();
+            distribution.add_successor(Successor {
+                index: state_index,
+                probability: 1.0,
+            });
+            let mut actions =
+                <M::ActionCollection as ActionCollection<M::Distribution>>::get_builder();
+            actions.add_action(Action {
+                successors: distribution.finish(),
+                action_name_index,
+            });
+            self.states[state_index].actions = actions.finish();
+        }
+    }
+
     fn compute_predecessors<P: Predecessors>(&self) -> Vec<P> {
         let mut new_predecessors = Vec::with_capacity(self.states.len());
         for _ in 0..self.states.len() {
@@ -199,6 +333,8 @@ impl<M: ModelTypes> ProbabilisticModel<M> {
             valuation_context: self.valuation_context,
             atomic_proposition_count: self.atomic_proposition_count,
             action_names: self.action_names,
+            reward_structures: self.reward_structures,
+            exit_rates: self.exit_rates,
         }
     }
 
@@ -215,6 +351,25 @@ pub struct State<M: ModelTypes> {
     pub predecessors: M::Predecessors, // TODO: Rename to "incoming transitions" to highlight that a state may be contained multiple times
 }
 
+impl<M: ModelTypes> Clone for State<M>
+where
+    M::Valuation: Clone,
+    M::ActionCollection: Clone,
+    M::AtomicPropositions: Clone,
+    M::Owners: Clone,
+    M::Predecessors: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            valuation: self.valuation.clone(),
+            actions: self.actions.clone(),
+            atomic_propositions: self.atomic_propositions.clone(),
+            owner: self.owner.clone(),
+            predecessors: self.predecessors.clone(),
+        }
+    }
+}
+
 impl<M: ModelTypes> State<M> {
     pub fn get_all_successors(&self) -> StateSuccessorIterator<'_, '_, M> {
         let mut action_iterator = self.actions.iter();
@@ -297,6 +452,7 @@ pub struct StateSuccessor {
     pub probability: f64,
 }
 
+#[derive(Clone)]
 pub struct Action<D: Distribution> {
     pub successors: D,
     pub action_name_index: usize,
@@ -306,13 +462,13 @@ pub type Mdp<
     P = NonTrackedPredecessors,
     V = ValuationVector,
     AP = BitFlagsAtomicPropositions,
-    I = SingleInitialState,
+    I = InitialStateVector,
 > = ProbabilisticModel<MdpType<P, V, AP, I>>;
 pub struct MdpType<
     P: Predecessors = NonTrackedPredecessors,
     V: Valuation = ValuationVector,
     AP: AtomicPropositions = BitFlagsAtomicPropositions,
-    I: InitialStates = SingleInitialState,
+    I: InitialStates = InitialStateVector,
 > {
     _phantom_data: PhantomData<(P, V, AP, I)>,
 }
@@ -332,13 +488,13 @@ pub type Dtmc<
     P = NonTrackedPredecessors,
     V = ValuationVector,
     AP = BitFlagsAtomicPropositions,
-    I = SingleInitialState,
+    I = InitialStateVector,
 > = ProbabilisticModel<DtmcType<P, V, AP, I>>;
 pub struct DtmcType<
     P: Predecessors = NonTrackedPredecessors,
     V: Valuation = ValuationVector,
     AP: AtomicPropositions = BitFlagsAtomicPropositions,
-    I: InitialStates = SingleInitialState,
+    I: InitialStates = InitialStateVector,
 > {
     _phantom_data: PhantomData<(P, V, AP, I)>,
 }
@@ -354,17 +510,45 @@ impl<P: Predecessors, V: Valuation, AP: AtomicPropositions, I: InitialStates> Mo
     type Predecessors = P;
 }
 
+/// A continuous-time Markov chain. Its successor probabilities are those of the embedded
+/// discrete-time Markov chain, while the exit rates are stored in the model.
+pub type Ctmc<
+    P = NonTrackedPredecessors,
+    V = ValuationVector,
+    AP = BitFlagsAtomicPropositions,
+    I = InitialStateVector,
+> = ProbabilisticModel<CtmcType<P, V, AP, I>>;
+pub struct CtmcType<
+    P: Predecessors = NonTrackedPredecessors,
+    V: Valuation = ValuationVector,
+    AP: AtomicPropositions = BitFlagsAtomicPropositions,
+    I: InitialStates = InitialStateVector,
+> {
+    _phantom_data: PhantomData<(P, V, AP, I)>,
+}
+impl<P: Predecessors, V: Valuation, AP: AtomicPropositions, I: InitialStates> ModelTypes
+    for CtmcType<P, V, AP, I>
+{
+    type Valuation = V;
+    type Distribution = DistributionVector;
+    type Owners = SinglePlayer;
+    type ActionCollection = SingleAction<DistributionVector>;
+    type AtomicPropositions = AP;
+    type InitialStates = I;
+    type Predecessors = P;
+}
+
 pub type TransitionSystem<
     P = NonTrackedPredecessors,
     V = ValuationVector,
     AP = BitFlagsAtomicPropositions,
-    I = SingleInitialState,
+    I = InitialStateVector,
 > = ProbabilisticModel<TransitionSystemType<P, V, AP, I>>;
 pub struct TransitionSystemType<
     P: Predecessors = NonTrackedPredecessors,
     V: Valuation = ValuationVector,
     AP: AtomicPropositions = BitFlagsAtomicPropositions,
-    I: InitialStates = SingleInitialState,
+    I: InitialStates = InitialStateVector,
 > {
     _phantom_data: PhantomData<(P, V, AP, I)>,
 }
@@ -384,13 +568,13 @@ pub type TwoPlayerStochasticGame<
     P = NonTrackedPredecessors,
     V = ValuationVector,
     AP = BitFlagsAtomicPropositions,
-    I = SingleInitialState,
+    I = InitialStateVector,
 > = ProbabilisticModel<TwoPlayerStochasticGameType<P, V, AP, I>>;
 pub struct TwoPlayerStochasticGameType<
     P: Predecessors = NonTrackedPredecessors,
     V: Valuation = ValuationVector,
     AP: AtomicPropositions = BitFlagsAtomicPropositions,
-    I: InitialStates = SingleInitialState,
+    I: InitialStates = InitialStateVector,
 > {
     _phantom_data: PhantomData<(P, V, AP, I)>,
 }
@@ -410,13 +594,13 @@ pub type TwoPlayerNonstochasticGame<
     P = NonTrackedPredecessors,
     V = ValuationVector,
     AP = BitFlagsAtomicPropositions,
-    I = SingleInitialState,
+    I = InitialStateVector,
 > = ProbabilisticModel<TwoPlayerNonstochasticGameType<P, V, AP, I>>;
 pub struct TwoPlayerNonstochasticGameType<
     P: Predecessors = NonTrackedPredecessors,
     V: Valuation = ValuationVector,
     AP: AtomicPropositions = BitFlagsAtomicPropositions,
-    I: InitialStates = SingleInitialState,
+    I: InitialStates = InitialStateVector,
 > {
     _phantom_data: PhantomData<(P, V, AP, I)>,
 }
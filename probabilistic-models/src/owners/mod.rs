@@ -1,6 +1,10 @@
 pub trait Owners {
     fn default_owner() -> Self;
     fn max_player_count() -> usize;
+    /// Returns the owner that represents the player with the given index, if there is one.
+    fn from_player_index(index: usize) -> Option<Self>
+    where
+        Self: Sized;
 }
 pub type SinglePlayer = ();
 impl Owners for SinglePlayer {
@@ -10,6 +14,9 @@ impl Owners for SinglePlayer {
     fn max_player_count() -> usize {
         1
     }
+    fn from_player_index(index: usize) -> Option<Self> {
+        (index == 0).then_some(())
+    }
 }
 
 #[derive(PartialEq, Copy, Clone, Debug)]
@@ -25,4 +32,11 @@ impl Owners for TwoPlayer {
     fn max_player_count() -> usize {
         2
     }
+    fn from_player_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(TwoPlayer::PlayerOne),
+            1 => Some(TwoPlayer::PlayerTwo),
+            _ => None,
+        }
+    }
 }
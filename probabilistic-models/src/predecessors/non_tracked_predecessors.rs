@@ -1,5 +1,6 @@
 use super::{Predecessor, Predecessors, PredecessorsBuilder};
 
+#[derive(Clone)]
 pub struct NonTrackedPredecessors {}
 
 impl Predecessors for NonTrackedPredecessors {
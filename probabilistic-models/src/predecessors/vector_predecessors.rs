@@ -1,5 +1,6 @@
 use crate::{Predecessor, Predecessors, PredecessorsBuilder};
 
+#[derive(Clone)]
 pub struct VectorPredecessors {
     predecessors: Vec<Predecessor>,
 }
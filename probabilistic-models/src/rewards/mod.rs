@@ -0,0 +1,62 @@
+/// A reward structure of an explicit model. State rewards are earned in every step that leaves a
+/// state, action rewards whenever the corresponding action is taken. Rewards that were never set
+/// are zero.
+#[derive(Clone, Debug)]
+pub struct RewardStructure {
+    pub name: Option<String>,
+    state_rewards: Vec<f64>,
+    action_rewards: Vec<Vec<f64>>,
+}
+
+impl RewardStructure {
+    pub fn new(name: Option<String>) -> Self {
+        Self {
+            name,
+            state_rewards: Vec::new(),
+            action_rewards: Vec::new(),
+        }
+    }
+
+    pub fn set_state_reward(&mut self, state_index: usize, reward: f64) {
+        if self.state_rewards.len() <= state_index {
+            self.state_rewards.resize(state_index + 1, 0.0);
+        }
+        self.state_rewards[state_index] = reward;
+    }
+
+    pub fn set_action_reward(&mut self, state_index: usize, action_index: usize, reward: f64) {
+        if self.action_rewards.len() <= state_index {
+            self.action_rewards.resize(state_index + 1, Vec::new());
+        }
+        let state_rewards = &mut self.action_rewards[state_index];
+        if state_rewards.len() <= action_index {
+            state_rewards.resize(action_index + 1, 0.0);
+        }
+        state_rewards[action_index] = reward;
+    }
+
+    /// Removes the action rewards of all actions of the given state.
+    pub fn clear_action_rewards(&mut self, state_index: usize) {
+        if let Some(rewards) = self.action_rewards.get_mut(state_index) {
+            rewards.clear();
+        }
+    }
+
+    pub fn state_reward(&self, state_index: usize) -> f64 {
+        self.state_rewards.get(state_index).copied().unwrap_or(0.0)
+    }
+
+    pub fn action_reward(&self, state_index: usize, action_index: usize) -> f64 {
+        self.action_rewards
+            .get(state_index)
+            .and_then(|rewards| rewards.get(action_index))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The reward earned when taking the given action in the given state, i.e. the sum of the
+    /// state reward and the action reward.
+    pub fn step_reward(&self, state_index: usize, action_index: usize) -> f64 {
+        self.state_reward(state_index) + self.action_reward(state_index, action_index)
+    }
+}
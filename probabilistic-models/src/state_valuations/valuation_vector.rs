@@ -119,6 +119,7 @@ impl super::Valuation for ValuationVector {
         write!(f, ")")
     }
 }
+#[derive(Clone)]
 pub struct Context {
     number_of_variables: usize,
     details: Vec<VariableInfo>,
@@ -155,6 +156,7 @@ impl super::Context for Context {
     }
 }
 
+#[derive(Clone)]
 pub struct VariableInfo {
     name: String,
     variable_type: super::VariableType,
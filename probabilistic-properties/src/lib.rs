@@ -25,9 +25,45 @@ pub enum Query<I, F, E> {
         non_determinism: Option<NonDeterminismKind>,
         reward: RewardFormula<I, F, E>,
     },
+    LongRunAverageValue {
+        non_determinism: Option<NonDeterminismKind>,
+        condition: StateFormula<I, F, E>,
+    },
+    /// Aggregates the values of `query` over the states that satisfy `states`, or over all
+    /// states if no filter is given.
+    Filter {
+        operator: FilterOperator,
+        query: Box<Query<I, F, E>>,
+        states: Option<StateFormula<I, F, E>>,
+    },
 }
 
 impl<I, F, E> Query<I, F, E> {
+    /// Calls `visitor` on every state formula of the query. A state formula is visited before
+    /// its subformulas, so the visitor may replace it.
+    pub fn visit_state_formulas_mut<M: FnMut(&mut StateFormula<I, F, E>)>(
+        &mut self,
+        visitor: &mut M,
+    ) {
+        match self {
+            Query::ProbabilityValue { path, .. } => path.visit_state_formulas_mut(visitor),
+            Query::StateFormula(formula) => formula.visit_state_formulas_mut(visitor),
+            Query::RewardBound { reward, .. }
+            | Query::RewardValue { reward, .. }
+            | Query::TimeBound { reward, .. }
+            | Query::TimeValue { reward, .. } => reward.visit_state_formulas_mut(visitor),
+            Query::LongRunAverageValue { condition, .. } => {
+                condition.visit_state_formulas_mut(visitor)
+            }
+            Query::Filter { query, states, .. } => {
+                query.visit_state_formulas_mut(visitor);
+                if let Some(states) = states {
+                    states.visit_state_formulas_mut(visitor);
+                }
+            }
+        }
+    }
+
     pub fn as_mut(&mut self) -> Query<&mut I, &mut F, &mut E> {
         match self {
             Query::ProbabilityValue {
@@ -76,6 +112,22 @@ impl<I, F, E> Query<I, F, E> {
                 non_determinism: *non_determinism,
                 reward: reward.as_mut(),
             },
+            Query::LongRunAverageValue {
+                non_determinism,
+                condition,
+            } => Query::LongRunAverageValue {
+                non_determinism: *non_determinism,
+                condition: condition.as_mut(),
+            },
+            Query::Filter {
+                operator,
+                query,
+                states,
+            } => Query::Filter {
+                operator: *operator,
+                query: Box::new(Query::as_mut(query)),
+                states: states.as_mut().map(|states| states.as_mut()),
+            },
         }
     }
 
@@ -133,6 +185,22 @@ impl<I, F, E> Query<I, F, E> {
                 non_determinism,
                 reward: reward.try_map_i(map)?,
             },
+            Query::LongRunAverageValue {
+                non_determinism,
+                condition,
+            } => Query::LongRunAverageValue {
+                non_determinism,
+                condition: condition.try_map_i(map)?,
+            },
+            Query::Filter {
+                operator,
+                query,
+                states,
+            } => Query::Filter {
+                operator,
+                query: Box::new(query.try_map_i(map)?),
+                states: states.map(|states| states.try_map_i(map)).transpose()?,
+            },
         })
     }
 
@@ -190,6 +258,22 @@ impl<I, F, E> Query<I, F, E> {
                 non_determinism,
                 reward: reward.try_map_f(map)?,
             },
+            Query::LongRunAverageValue {
+                non_determinism,
+                condition,
+            } => Query::LongRunAverageValue {
+                non_determinism,
+                condition: condition.try_map_f(map)?,
+            },
+            Query::Filter {
+                operator,
+                query,
+                states,
+            } => Query::Filter {
+                operator,
+                query: Box::new(query.try_map_f(map)?),
+                states: states.map(|states| states.try_map_f(map)).transpose()?,
+            },
         })
     }
 
@@ -247,6 +331,22 @@ impl<I, F, E> Query<I, F, E> {
                 non_determinism,
                 reward: reward.try_map_e(map)?,
             },
+            Query::LongRunAverageValue {
+                non_determinism,
+                condition,
+            } => Query::LongRunAverageValue {
+                non_determinism,
+                condition: condition.try_map_e(map)?,
+            },
+            Query::Filter {
+                operator,
+                query,
+                states,
+            } => Query::Filter {
+                operator,
+                query: Box::new(query.try_map_e(map)?),
+                states: states.map(|states| states.try_map_e(map)).transpose()?,
+            },
         })
     }
 }
@@ -262,11 +362,33 @@ pub enum StateFormula<I, F, E> {
     LongRunAverage {
         non_determinism: Option<NonDeterminismKind>,
         bound: Bound<F>,
-        path: Box<PathFormula<I, F, E>>,
+        condition: Box<StateFormula<I, F, E>>,
     },
 }
 
 impl<I, F, E> StateFormula<I, F, E> {
+    /// The expression if the state formula is a plain expression, e.g. an atomic proposition
+    pub fn as_expression(&self) -> Option<&E> {
+        match self {
+            StateFormula::Expression(expression) => Some(expression),
+            _ => None,
+        }
+    }
+
+    pub fn visit_state_formulas_mut<M: FnMut(&mut StateFormula<I, F, E>)>(
+        &mut self,
+        visitor: &mut M,
+    ) {
+        visitor(self);
+        match self {
+            StateFormula::Expression(_) => {}
+            StateFormula::ProbabilityBound { path, .. } => path.visit_state_formulas_mut(visitor),
+            StateFormula::LongRunAverage { condition, .. } => {
+                condition.visit_state_formulas_mut(visitor)
+            }
+        }
+    }
+
     pub fn as_mut(&mut self) -> StateFormula<&mut I, &mut F, &mut E> {
         match self {
             StateFormula::Expression(e) => StateFormula::Expression(e),
@@ -282,11 +404,11 @@ impl<I, F, E> StateFormula<I, F, E> {
             StateFormula::LongRunAverage {
                 non_determinism,
                 bound,
-                path,
+                condition,
             } => StateFormula::LongRunAverage {
                 non_determinism: *non_determinism,
                 bound: bound.as_mut(),
-                path: Box::new(PathFormula::as_mut(path)),
+                condition: Box::new(StateFormula::as_mut(condition)),
             },
         }
     }
@@ -314,11 +436,11 @@ impl<I, F, E> StateFormula<I, F, E> {
             StateFormula::LongRunAverage {
                 non_determinism,
                 bound: operator,
-                path,
+                condition,
             } => StateFormula::LongRunAverage {
                 non_determinism,
                 bound: operator,
-                path: Box::new(path.try_map_i(map)?),
+                condition: Box::new(condition.try_map_i(map)?),
             },
         })
     }
@@ -346,11 +468,11 @@ impl<I, F, E> StateFormula<I, F, E> {
             StateFormula::LongRunAverage {
                 non_determinism,
                 bound: operator,
-                path,
+                condition,
             } => StateFormula::LongRunAverage {
                 non_determinism,
                 bound: operator.try_map_value(map)?,
-                path: Box::new(path.try_map_f(map)?),
+                condition: Box::new(condition.try_map_f(map)?),
             },
         })
     }
@@ -378,11 +500,11 @@ impl<I, F, E> StateFormula<I, F, E> {
             StateFormula::LongRunAverage {
                 non_determinism,
                 bound: operator,
-                path,
+                condition,
             } => StateFormula::LongRunAverage {
                 non_determinism,
                 bound: operator,
-                path: Box::new(path.try_map_e(map)?),
+                condition: Box::new(condition.try_map_e(map)?),
             },
         })
     }
@@ -409,9 +531,79 @@ pub enum PathFormula<I, F, E> {
     Generally {
         condition: Box<StateFormula<I, F, E>>,
     },
+    Next {
+        condition: Box<StateFormula<I, F, E>>,
+    },
+    /// `before W after`: like `before U after`, but also holds if `before` holds forever
+    WeakUntil {
+        before: Box<StateFormula<I, F, E>>,
+        after: Box<StateFormula<I, F, E>>,
+    },
+    /// `release R condition`: `condition` holds up to and including the first state in which
+    /// `release` holds, or forever if there is no such state
+    Release {
+        release: Box<StateFormula<I, F, E>>,
+        condition: Box<StateFormula<I, F, E>>,
+    },
+    /// Any other LTL formula, e.g. `G F a` or `F a & F b`
+    Ltl { formula: LtlFormula<I, F, E> },
+}
+
+/// A `G`, `W` or `R` formula over plain expressions. Such a formula holds on a path iff the
+/// complementary reachability objective does not, i.e. iff the path does not reach a target state
+/// without visiting an avoided state before:
+/// - `G safe` holds iff `F !safe` does not,
+/// - `before W after` holds iff `!after U (!before & !after)` does not,
+/// - `release R condition` holds iff `!release U !condition` does not.
+#[derive(Clone, Copy)]
+pub enum SafetyComplement<E> {
+    Generally { safe: E },
+    WeakUntil { before: E, after: E },
+    Release { release: E, condition: E },
+}
+
+impl<E> SafetyComplement<E> {
+    /// Whether a state is a target state of the complementary reachability objective and whether
+    /// it is avoided by it, given which expressions hold in the state. No state is both.
+    pub fn classify<H: Fn(&E) -> bool>(&self, holds: H) -> (bool, bool) {
+        match self {
+            SafetyComplement::Generally { safe } => (!holds(safe), false),
+            SafetyComplement::WeakUntil { before, after } => {
+                let after = holds(after);
+                (!holds(before) && !after, after)
+            }
+            SafetyComplement::Release { release, condition } => {
+                let condition = holds(condition);
+                (!condition, holds(release) && condition)
+            }
+        }
+    }
 }
 
 impl<I, F, E> PathFormula<I, F, E> {
+    /// Rewrites `G`, `W` and `R` formulas whose operands are plain expressions into their
+    /// complementary reachability objective. Returns `None` for all other path formulas.
+    pub fn as_safety_complement(&self) -> Option<SafetyComplement<E>>
+    where
+        E: Clone,
+    {
+        let expression = |formula: &StateFormula<I, F, E>| formula.as_expression().cloned();
+        Some(match self {
+            PathFormula::Generally { condition } => SafetyComplement::Generally {
+                safe: expression(condition)?,
+            },
+            PathFormula::WeakUntil { before, after } => SafetyComplement::WeakUntil {
+                before: expression(before)?,
+                after: expression(after)?,
+            },
+            PathFormula::Release { release, condition } => SafetyComplement::Release {
+                release: expression(release)?,
+                condition: expression(condition)?,
+            },
+            _ => return None,
+        })
+    }
+
     pub fn eventually_condition(&self) -> Option<&StateFormula<I, F, E>> {
         match self {
             PathFormula::Eventually { condition } => Some(condition),
@@ -425,6 +617,29 @@ impl<I, F, E> PathFormula<I, F, E> {
         }
     }
 
+    pub fn visit_state_formulas_mut<M: FnMut(&mut StateFormula<I, F, E>)>(
+        &mut self,
+        visitor: &mut M,
+    ) {
+        match self {
+            PathFormula::Until { before, after }
+            | PathFormula::BoundedUntil { before, after, .. }
+            | PathFormula::WeakUntil { before, after } => {
+                before.visit_state_formulas_mut(visitor);
+                after.visit_state_formulas_mut(visitor);
+            }
+            PathFormula::Release { release, condition } => {
+                release.visit_state_formulas_mut(visitor);
+                condition.visit_state_formulas_mut(visitor);
+            }
+            PathFormula::Eventually { condition }
+            | PathFormula::BoundedEventually { condition, .. }
+            | PathFormula::Generally { condition }
+            | PathFormula::Next { condition } => condition.visit_state_formulas_mut(visitor),
+            PathFormula::Ltl { formula } => formula.visit_state_formulas_mut(visitor),
+        }
+    }
+
     pub fn as_mut(&mut self) -> PathFormula<&mut I, &mut F, &mut E> {
         match self {
             PathFormula::Until { before, after } => PathFormula::Until {
@@ -450,6 +665,20 @@ impl<I, F, E> PathFormula<I, F, E> {
             PathFormula::Generally { condition } => PathFormula::Generally {
                 condition: Box::new(StateFormula::as_mut(condition)),
             },
+            PathFormula::Next { condition } => PathFormula::Next {
+                condition: Box::new(StateFormula::as_mut(condition)),
+            },
+            PathFormula::WeakUntil { before, after } => PathFormula::WeakUntil {
+                before: Box::new(StateFormula::as_mut(before)),
+                after: Box::new(StateFormula::as_mut(after)),
+            },
+            PathFormula::Release { release, condition } => PathFormula::Release {
+                release: Box::new(StateFormula::as_mut(release)),
+                condition: Box::new(StateFormula::as_mut(condition)),
+            },
+            PathFormula::Ltl { formula } => PathFormula::Ltl {
+                formula: formula.as_mut(),
+            },
         }
     }
 
@@ -486,6 +715,20 @@ impl<I, F, E> PathFormula<I, F, E> {
             PathFormula::Generally { condition } => PathFormula::Generally {
                 condition: Box::new(condition.try_map_i(map)?),
             },
+            PathFormula::Next { condition } => PathFormula::Next {
+                condition: Box::new(condition.try_map_i(map)?),
+            },
+            PathFormula::WeakUntil { before, after } => PathFormula::WeakUntil {
+                before: Box::new(before.try_map_i(map)?),
+                after: Box::new(after.try_map_i(map)?),
+            },
+            PathFormula::Release { release, condition } => PathFormula::Release {
+                release: Box::new(release.try_map_i(map)?),
+                condition: Box::new(condition.try_map_i(map)?),
+            },
+            PathFormula::Ltl { formula } => PathFormula::Ltl {
+                formula: formula.try_map_i(map)?,
+            },
         })
     }
 
@@ -522,6 +765,20 @@ impl<I, F, E> PathFormula<I, F, E> {
             PathFormula::Generally { condition } => PathFormula::Generally {
                 condition: Box::new(condition.try_map_f(map)?),
             },
+            PathFormula::Next { condition } => PathFormula::Next {
+                condition: Box::new(condition.try_map_f(map)?),
+            },
+            PathFormula::WeakUntil { before, after } => PathFormula::WeakUntil {
+                before: Box::new(before.try_map_f(map)?),
+                after: Box::new(after.try_map_f(map)?),
+            },
+            PathFormula::Release { release, condition } => PathFormula::Release {
+                release: Box::new(release.try_map_f(map)?),
+                condition: Box::new(condition.try_map_f(map)?),
+            },
+            PathFormula::Ltl { formula } => PathFormula::Ltl {
+                formula: formula.try_map_f(map)?,
+            },
         })
     }
 
@@ -558,9 +815,133 @@ impl<I, F, E> PathFormula<I, F, E> {
             PathFormula::Generally { condition } => PathFormula::Generally {
                 condition: Box::new(condition.try_map_e(map)?),
             },
+            PathFormula::Next { condition } => PathFormula::Next {
+                condition: Box::new(condition.try_map_e(map)?),
+            },
+            PathFormula::WeakUntil { before, after } => PathFormula::WeakUntil {
+                before: Box::new(before.try_map_e(map)?),
+                after: Box::new(after.try_map_e(map)?),
+            },
+            PathFormula::Release { release, condition } => PathFormula::Release {
+                release: Box::new(release.try_map_e(map)?),
+                condition: Box::new(condition.try_map_e(map)?),
+            },
+            PathFormula::Ltl { formula } => PathFormula::Ltl {
+                formula: formula.try_map_e(map)?,
+            },
         })
     }
 }
+
+/// A linear temporal logic formula whose atoms are state formulas. The weak until, release and
+/// implication operators are expressed by the other operators.
+#[derive(Clone)]
+pub enum LtlFormula<I, F, E> {
+    StateFormula(StateFormula<I, F, E>),
+    Not(Box<LtlFormula<I, F, E>>),
+    And(Box<LtlFormula<I, F, E>>, Box<LtlFormula<I, F, E>>),
+    Or(Box<LtlFormula<I, F, E>>, Box<LtlFormula<I, F, E>>),
+    Next(Box<LtlFormula<I, F, E>>),
+    Eventually(Box<LtlFormula<I, F, E>>),
+    Generally(Box<LtlFormula<I, F, E>>),
+    Until {
+        before: Box<LtlFormula<I, F, E>>,
+        after: Box<LtlFormula<I, F, E>>,
+    },
+}
+
+impl<I, F, E> LtlFormula<I, F, E> {
+    pub fn visit_state_formulas_mut<M: FnMut(&mut StateFormula<I, F, E>)>(
+        &mut self,
+        visitor: &mut M,
+    ) {
+        match self {
+            LtlFormula::StateFormula(formula) => formula.visit_state_formulas_mut(visitor),
+            LtlFormula::Not(formula)
+            | LtlFormula::Next(formula)
+            | LtlFormula::Eventually(formula)
+            | LtlFormula::Generally(formula) => formula.visit_state_formulas_mut(visitor),
+            LtlFormula::And(left, right)
+            | LtlFormula::Or(left, right)
+            | LtlFormula::Until {
+                before: left,
+                after: right,
+            } => {
+                left.visit_state_formulas_mut(visitor);
+                right.visit_state_formulas_mut(visitor);
+            }
+        }
+    }
+
+    pub fn as_mut(&mut self) -> LtlFormula<&mut I, &mut F, &mut E> {
+        fn boxed<I, F, E>(
+            formula: &mut LtlFormula<I, F, E>,
+        ) -> Box<LtlFormula<&mut I, &mut F, &mut E>> {
+            Box::new(formula.as_mut())
+        }
+        match self {
+            LtlFormula::StateFormula(formula) => LtlFormula::StateFormula(formula.as_mut()),
+            LtlFormula::Not(formula) => LtlFormula::Not(boxed(formula)),
+            LtlFormula::And(left, right) => LtlFormula::And(boxed(left), boxed(right)),
+            LtlFormula::Or(left, right) => LtlFormula::Or(boxed(left), boxed(right)),
+            LtlFormula::Next(formula) => LtlFormula::Next(boxed(formula)),
+            LtlFormula::Eventually(formula) => LtlFormula::Eventually(boxed(formula)),
+            LtlFormula::Generally(formula) => LtlFormula::Generally(boxed(formula)),
+            LtlFormula::Until { before, after } => LtlFormula::Until {
+                before: boxed(before),
+                after: boxed(after),
+            },
+        }
+    }
+
+    /// Replaces every atom of the formula, keeping the temporal and boolean structure
+    pub fn try_map_state_formulas<Er, I2, F2, E2, M>(
+        self,
+        map: &mut M,
+    ) -> Result<LtlFormula<I2, F2, E2>, Er>
+    where
+        M: FnMut(StateFormula<I, F, E>) -> Result<StateFormula<I2, F2, E2>, Er>,
+    {
+        let mut boxed = |formula: Box<LtlFormula<I, F, E>>| -> Result<_, Er> {
+            Ok(Box::new(formula.try_map_state_formulas(map)?))
+        };
+        Ok(match self {
+            LtlFormula::StateFormula(formula) => LtlFormula::StateFormula(map(formula)?),
+            LtlFormula::Not(formula) => LtlFormula::Not(boxed(formula)?),
+            LtlFormula::And(left, right) => LtlFormula::And(boxed(left)?, boxed(right)?),
+            LtlFormula::Or(left, right) => LtlFormula::Or(boxed(left)?, boxed(right)?),
+            LtlFormula::Next(formula) => LtlFormula::Next(boxed(formula)?),
+            LtlFormula::Eventually(formula) => LtlFormula::Eventually(boxed(formula)?),
+            LtlFormula::Generally(formula) => LtlFormula::Generally(boxed(formula)?),
+            LtlFormula::Until { before, after } => LtlFormula::Until {
+                before: boxed(before)?,
+                after: boxed(after)?,
+            },
+        })
+    }
+
+    pub fn try_map_i<Er, I2, M: FnMut(I) -> Result<I2, Er>>(
+        self,
+        map: &mut M,
+    ) -> Result<LtlFormula<I2, F, E>, Er> {
+        self.try_map_state_formulas(&mut |formula| formula.try_map_i(map))
+    }
+
+    pub fn try_map_f<Er, F2, M: FnMut(F) -> Result<F2, Er>>(
+        self,
+        map: &mut M,
+    ) -> Result<LtlFormula<I, F2, E>, Er> {
+        self.try_map_state_formulas(&mut |formula| formula.try_map_f(map))
+    }
+
+    pub fn try_map_e<Er, E2, M: FnMut(E) -> Result<E2, Er>>(
+        self,
+        map: &mut M,
+    ) -> Result<LtlFormula<I, F, E2>, Er> {
+        self.try_map_state_formulas(&mut |formula| formula.try_map_e(map))
+    }
+}
+
 #[derive(Clone)]
 pub enum RewardFormula<I, F, E> {
     Instantaneous { k: F },
@@ -570,6 +951,15 @@ pub enum RewardFormula<I, F, E> {
 }
 
 impl<I, F, E> RewardFormula<I, F, E> {
+    pub fn visit_state_formulas_mut<M: FnMut(&mut StateFormula<I, F, E>)>(
+        &mut self,
+        visitor: &mut M,
+    ) {
+        if let RewardFormula::Finally { states } = self {
+            states.visit_state_formulas_mut(visitor);
+        }
+    }
+
     pub fn as_mut(&mut self) -> RewardFormula<&mut I, &mut F, &mut E> {
         match self {
             RewardFormula::Instantaneous { k } => RewardFormula::Instantaneous { k },
@@ -671,6 +1061,17 @@ impl<V> Bound<V> {
     }
 }
 
+impl<V: PartialOrd> Bound<V> {
+    pub fn is_satisfied_by(&self, value: &V) -> bool {
+        match self.operator {
+            BoundOperator::LessThan => *value < self.value,
+            BoundOperator::LessOrEqual => *value <= self.value,
+            BoundOperator::GreaterThan => *value > self.value,
+            BoundOperator::GreaterOrEqual => *value >= self.value,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum BoundOperator {
     LessThan,
@@ -679,8 +1080,38 @@ pub enum BoundOperator {
     GreaterOrEqual,
 }
 
+/// How a filter combines the values of its states
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum FilterOperator {
+    Min,
+    Max,
+    Avg,
+    Sum,
+    /// The number of states that satisfy the property
+    Count,
+    /// Whether all states satisfy the property
+    ForAll,
+    /// Whether any state satisfies the property
+    Exists,
+    /// The value of the state with the lowest index
+    First,
+    /// Reports the value of every filter state
+    Print,
+    /// The value of the only state, which must be unique
+    State,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum NonDeterminismKind {
     Maximise,
     Minimise,
 }
+
+impl NonDeterminismKind {
+    pub fn opposite(self) -> Self {
+        match self {
+            NonDeterminismKind::Maximise => NonDeterminismKind::Minimise,
+            NonDeterminismKind::Minimise => NonDeterminismKind::Maximise,
+        }
+    }
+}
@@ -4,7 +4,9 @@ use log::{LevelFilter, info, trace};
 use svabresp::num_traits::ToPrimitive;
 use svabresp::shapley::{
     BruteForceAlgorithm, DiscardingSwitchingPairCollector, FullSwitchingPairCollector,
-    ResponsibilityValues, ShapleyAlgorithm, SwitchingPairCollection, SwitchingPairCollector,
+    MinimalCoalitionAlgorithm, ResponsibilityValues, SampledResponsibilityValues, SamplingScheme,
+    ShapleyAlgorithm, StochasticAlgorithm, SwitchingPairCollection, SwitchingPairCollector,
+    WeightType,
 };
 use svabresp::state_based::grouping::{
     ActionGroupExtractionScheme, GroupExtractionScheme, IndividualGroupExtractionScheme,
@@ -19,7 +21,8 @@ use svabresp::state_based::refinement::{
     WinningRegionSizeSelectionHeuristics,
 };
 use svabresp::{
-    CoopGameType, CounterexampleFile, ModelAndPropertySource, ModelFromFile, ResponsibilityTask,
+    BackwardResponsibilityKind, CoopGameType, CounterexampleFile, ModelAndPropertySource,
+    ModelFromFile, ResponsibilityTask,
 };
 
 pub struct ComputeResponsibilityCommand {
@@ -27,20 +30,41 @@ pub struct ComputeResponsibilityCommand {
     property: String,
     constants: String,
     algorithm: AlgorithmKind,
+    weight_type: WeightType,
+    thread_count: usize,
+    stochastic_options: StochasticOptions,
     refinement_initial_partition: RefinementInitialPartition,
     refinement_block_selection: RefinementBlockSelection,
     refinement_splitting: RefinementSplitting,
     grouping: GroupingKind,
+    coop_game_kind: CoopGameKind,
     output: OutputKind,
     logging_level: LoggingLevel,
 }
 
+enum CoopGameKind {
+    Forward,
+    Backward {
+        counterexample: String,
+        kind: BackwardResponsibilityKind,
+    },
+}
+
 enum AlgorithmKind {
     BruteForce,
+    MinimalCoalitions,
     Stochastic,
     Refinement,
 }
 
+struct StochasticOptions {
+    sampling_scheme: SamplingScheme,
+    confidence_level: f64,
+    target_half_width: f64,
+    max_rounds: usize,
+    seed: Option<u64>,
+}
+
 enum RefinementInitialPartition {
     Singleton,
     Random { block_count: usize },
@@ -100,14 +124,23 @@ enum LoggingLevel {
 impl ComputeResponsibilityCommand {
     pub fn get_command() -> Command {
         Command::new("svabresp").about("Computes responsibility values")
-            .arg(arg!(-a --algorithm <ALGORITHM> "The algorithm that is used to compute the responsibility values. Legal values are `brute-force`, `stochastic`, `refinement`.").default_value("brute-force"))
+            .arg(arg!(-a --algorithm <ALGORITHM> "The algorithm that is used to compute the responsibility values. Legal values are `brute-force`, `minimal-coalitions` (enumerates the minimal winning coalitions, only for non-probabilistic models), `stochastic`, `refinement`.").default_value("brute-force"))
+            .arg(arg!(-i --index <INDEX> "The power index that is computed. Legal values are `shapley`, `banzhaf`, `normalised-banzhaf`, `count` (number of critical pairs), `deegan-packel` and `johnston`. The last two are only supported for non-probabilistic models and not by the stochastic algorithm. The stochastic algorithm requires `--sampling stratified` for indices other than `shapley`, and does not support the normalised indices.").default_value("shapley"))
             .arg(arg!(-g --grouping <GROUPING> "The scheme that is used to group states. Legal values are `individual`, `labels([space-separated list of label names])`, `modules`, `actions`, `variables([space-separated list of variable names])`.").default_value("individual"))
             .arg(arg!(-o --output <OUTPUT> "How the output should be presented. Legal values are `human-readable`, `parsable` (simple format that can be processed by other tools), `syntax-highlight` (html file highlighting the responsible syntax elements), `syntax-highlight-json` (json file describing syntax highlighting) and `silent` (no output).").default_value("human-readable"))
             .arg(arg!(-c --constants <CONSTANTS> "Values for the undefined constants in the model").required(false))
             .arg(arg!(-l --logging <LEVEL> "The level of detail for the logs. Legal values are `error`, `warn`, `info`, `debug` and `trace`.").default_value("warn"))
+            .arg(arg!(-t --threads <INTEGER> "Brute-force and refinement algorithms: The number of threads on which the coalitions are evaluated. Each thread solves the games of its coalitions incrementally, so for probabilistic models, the results for different numbers of threads only agree up to the precision of the solver.").default_value("1"))
+            .arg(arg!(--sampling <SCHEME> "Stochastic algorithm: How marginal contributions are sampled. Legal values are `permutations` and `stratified` (samples coalitions of every size equally often).").default_value("permutations"))
+            .arg(arg!(--confidence <LEVEL> "Stochastic algorithm: The confidence level of the reported confidence intervals, strictly between 0 and 1.").default_value("0.95"))
+            .arg(arg!(--error <HALF_WIDTH> "Stochastic algorithm: Sampling stops once the confidence intervals of all groups have at most this half-width.").default_value("0.01"))
+            .arg(arg!(--maxrounds <INTEGER> "Stochastic algorithm: The maximum number of sampling rounds, after which sampling stops even if the confidence intervals are too wide.").default_value("10000"))
+            .arg(arg!(--seed <INTEGER> "Stochastic algorithm: Seed for the random number generator. If omitted, a random seed is used.").required(false))
             .arg(arg!(--initialpartition <HEURISTICS> "Refinement algorithm: The heuristics used to construct the initial partition. Legal values are `singleton` and `random(<INTEGER>)`, where <INTEGER> is a positive integer.").default_value("singleton"))
             .arg(arg!(--blockselection <HEURISTICS> "Refinement algorithm: The heuristics used to select a block for refinement. Legal values are `random`, `min-delta`, `max-delta`, `min-frontier`. Every value may be succeeded immediately by `(<INTEGER>)`, where <INTEGER> is a positive integer. This indicates how many blocks should be refined in a single iteration.").default_value("random(1)"))
             .arg(arg!(--splitting <HEURISTICS> "Refinement algorithm: The heuristics used to split a block. Legal values are `random`, `frontier(random)`, `frontier(most-edges-to-winning-and-losing)`, `frontier(most-edges-to-winning)` and `frontier(most-edges-to-losing)`.").default_value("frontier(random)"))
+            .arg(arg!(--counterexample <FILE> "File containing a counterexample, i.e. a run violating the property given as one state valuation per line, e.g. `(x=1, y=true)`. If given, the backward responsibility of the states along the counterexample is computed. Only supported for non-probabilistic models.").required(false))
+            .arg(arg!(--backwardkind <KIND> "Backward responsibility: How states that are not on the counterexample behave. Legal values are `optimistic` (they cooperate) and `pessimistic` (they are adversarial).").default_value("optimistic"))
             .arg(Arg::new("model").required(true).help("File name of the PRISM model file"))
             .arg(Arg::new("property").required(true).help("Property to be checked, given in PRISM property language"))
     }
@@ -123,13 +156,26 @@ impl ComputeResponsibilityCommand {
             .clone();
         let algorithm = match matches.get_one::<String>("algorithm").unwrap().as_str() {
             "brute-force" => AlgorithmKind::BruteForce,
+            "minimal-coalitions" => AlgorithmKind::MinimalCoalitions,
             "stochastic" => AlgorithmKind::Stochastic,
             "refinement" => AlgorithmKind::Refinement,
             a => panic!(
-                "Unknown algorithm `{}`. Legal values are `brute-force`, `stochastic` and `refinement`.",
+                "Unknown algorithm `{}`. Legal values are `brute-force`, `minimal-coalitions`, `stochastic` and `refinement`.",
                 a
             ),
         };
+        let weight_type = match matches.get_one::<String>("index").unwrap().as_str() {
+            "shapley" => WeightType::Shapley,
+            "banzhaf" => WeightType::Banzhaf,
+            "normalised-banzhaf" => WeightType::NormalisedBanzhaf,
+            "count" => WeightType::Count,
+            "deegan-packel" => WeightType::DeeganPackel,
+            "johnston" => WeightType::Johnston,
+            i => panic!(
+                "Unknown power index `{}`. Legal values are `shapley`, `banzhaf`, `normalised-banzhaf`, `count`, `deegan-packel` and `johnston`.",
+                i
+            ),
+        };
         let grouping = match matches.get_one::<String>("grouping").unwrap().as_str() {
             "individual" => GroupingKind::Individual,
             g if g.starts_with("labels") => {
@@ -166,6 +212,23 @@ impl ComputeResponsibilityCommand {
                 o
             ),
         };
+        let coop_game_kind = match matches.get_one::<String>("counterexample") {
+            None => CoopGameKind::Forward,
+            Some(counterexample) => {
+                let kind = match matches.get_one::<String>("backwardkind").unwrap().as_str() {
+                    "optimistic" => BackwardResponsibilityKind::Optimistic,
+                    "pessimistic" => BackwardResponsibilityKind::Pessimistic,
+                    k => panic!(
+                        "Unknown backward responsibility kind `{}`. Legal values are `optimistic` and `pessimistic`.",
+                        k
+                    ),
+                };
+                CoopGameKind::Backward {
+                    counterexample: counterexample.clone(),
+                    kind,
+                }
+            }
+        };
         let constants = match matches.get_one::<String>("constants") {
             Some(c) => c.clone(),
             None => "".to_string(),
@@ -182,6 +245,66 @@ impl ComputeResponsibilityCommand {
             ),
         };
 
+        let thread_count = Self::parse_number::<usize>(matches, "threads");
+        if thread_count == 0 {
+            panic!("Invalid value `0` for --threads. At least one thread is required.");
+        }
+
+        let sampling_scheme = match matches.get_one::<String>("sampling").unwrap().as_str() {
+            "permutations" => SamplingScheme::Permutations,
+            "stratified" => SamplingScheme::StratifiedBySize,
+            s => panic!(
+                "Unknown sampling scheme `{}`. Legal values are `permutations` and `stratified`.",
+                s
+            ),
+        };
+        let confidence_level = Self::parse_number::<f64>(matches, "confidence");
+        if !(confidence_level > 0.0 && confidence_level < 1.0) {
+            panic!(
+                "Invalid confidence level `{}`. The confidence level must be strictly between 0 and 1.",
+                confidence_level
+            );
+        }
+        let target_half_width = Self::parse_number::<f64>(matches, "error");
+        if !(target_half_width >= 0.0) {
+            panic!(
+                "Invalid value `{}` for --error. The half-width must not be negative.",
+                target_half_width
+            );
+        }
+        let max_rounds = Self::parse_number::<usize>(matches, "maxrounds");
+        if max_rounds < 2 {
+            panic!(
+                "Invalid value `{}` for --maxrounds. At least two rounds are required to estimate confidence intervals.",
+                max_rounds
+            );
+        }
+        let seed = matches
+            .get_one::<String>("seed")
+            .map(|_| Self::parse_number::<u64>(matches, "seed"));
+        if let AlgorithmKind::Stochastic = algorithm {
+            if !weight_type.is_size_based() || weight_type.is_normalised() {
+                panic!(
+                    "The stochastic algorithm does not support the power index `{}`",
+                    matches.get_one::<String>("index").unwrap()
+                );
+            }
+            if weight_type != WeightType::Shapley
+                && sampling_scheme != SamplingScheme::StratifiedBySize
+            {
+                panic!(
+                    "The stochastic algorithm can only estimate power indices other than `shapley` with `--sampling stratified`"
+                );
+            }
+        }
+        let stochastic_options = StochasticOptions {
+            sampling_scheme,
+            confidence_level,
+            target_half_width,
+            max_rounds,
+            seed,
+        };
+
         let refinement_initial_partition = match matches
             .get_one::<String>("initialpartition")
             .unwrap()
@@ -262,15 +385,27 @@ impl ComputeResponsibilityCommand {
             property,
             constants,
             algorithm,
+            weight_type,
+            thread_count,
+            stochastic_options,
             refinement_initial_partition,
             refinement_block_selection,
             refinement_splitting,
             grouping,
+            coop_game_kind,
             output,
             logging_level,
         }
     }
 
+    fn parse_number<T: std::str::FromStr>(matches: &ArgMatches, argument: &str) -> T {
+        let value = matches.get_one::<String>(argument).unwrap().trim();
+        match value.parse::<T>() {
+            Ok(val) => val,
+            Err(_) => panic!("Could not parse `{}` as a number for --{}", value, argument),
+        }
+    }
+
     fn parse_space_separated_names(a: &str, error_no_parentheses: &str) -> Vec<String> {
         let names = a.trim();
         if !names.starts_with("(") || !names.ends_with(")") {
@@ -348,16 +483,43 @@ impl ComputeResponsibilityCommand {
         model_description: M,
         grouping_scheme: G,
     ) {
+        let weight_type = self.weight_type;
+        let thread_count = self.thread_count;
         match self.algorithm {
             AlgorithmKind::BruteForce => self.execute_with_algorithm(
                 model_description,
                 grouping_scheme,
-                BruteForceAlgorithm::new(),
+                BruteForceAlgorithm::new()
+                    .with_weight_type(weight_type)
+                    .with_threads(thread_count),
+                ResponsibilityValuesPrinter {},
+                IdentityGroupBlockingProvider::new(),
+            ),
+            AlgorithmKind::MinimalCoalitions => self.execute_with_algorithm(
+                model_description,
+                grouping_scheme,
+                MinimalCoalitionAlgorithm::new().with_weight_type(weight_type),
                 ResponsibilityValuesPrinter {},
                 IdentityGroupBlockingProvider::new(),
             ),
             AlgorithmKind::Stochastic => {
-                panic!("Stochastic algorithm not implemented in self yet")
+                let options = &self.stochastic_options;
+                let mut algorithm = StochasticAlgorithm::new()
+                    .with_sampling_scheme(options.sampling_scheme)
+                    .with_weight_type(self.weight_type)
+                    .with_confidence_level(options.confidence_level)
+                    .with_target_half_width(options.target_half_width)
+                    .with_round_limits(options.max_rounds.min(30), options.max_rounds);
+                if let Some(seed) = options.seed {
+                    algorithm = algorithm.with_seed(seed);
+                }
+                self.execute_with_algorithm(
+                    model_description,
+                    grouping_scheme,
+                    algorithm,
+                    SampledResponsibilityValuesPrinter {},
+                    IdentityGroupBlockingProvider::new(),
+                )
             }
             AlgorithmKind::Refinement => match self.refinement_initial_partition {
                 RefinementInitialPartition::Singleton => self
@@ -485,10 +647,14 @@ impl ComputeResponsibilityCommand {
         block_selection_heuristics: B,
         block_splitting_heuristics: S,
     ) {
+        let weight_type = self.weight_type;
+        let thread_count = self.thread_count;
         self.execute_with_algorithm(
             model_description,
             grouping_scheme,
-            BruteForceAlgorithm::new(),
+            BruteForceAlgorithm::new()
+                .with_weight_type(weight_type)
+                .with_threads(thread_count),
             ResponsibilityValuesPrinter {},
             RefinementGroupBlockingProvider::new(
                 initial_partition_provider,
@@ -553,10 +719,21 @@ impl ComputeResponsibilityCommand {
 
         let model_source = model_description.get_source_code();
 
+        let coop_game_type = match self.coop_game_kind {
+            CoopGameKind::Forward => CoopGameType::Forward,
+            CoopGameKind::Backward {
+                counterexample,
+                kind,
+            } => CoopGameType::Backward {
+                counterexample: CounterexampleFile::new(counterexample),
+                kind,
+            },
+        };
+
         let task = ResponsibilityTask {
             model_description,
             constants: self.constants,
-            coop_game_type: CoopGameType::<CounterexampleFile>::Forward, // TODO: Make this configurable
+            coop_game_type,
             algorithm,
             grouping_scheme: &mut grouping_scheme,
             refinement,
@@ -564,7 +741,13 @@ impl ComputeResponsibilityCommand {
         };
 
         trace!("Finished preparing responsibility task");
-        let output = task.run();
+        let output = match task.run() {
+            Ok(output) => output,
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        };
 
         match self.output {
             OutputKind::HumanReadable => {
@@ -738,3 +921,88 @@ impl IntoSwitchingPairCollection for FullSwitchingPairCollector {
         FullSwitchingPairCollector::into_switching_pair_collection(self)
     }
 }
+
+struct SampledResponsibilityValuesPrinter {}
+
+impl<PD: std::fmt::Display> OutputPrinter<SampledResponsibilityValues<PD>>
+    for SampledResponsibilityValuesPrinter
+{
+    fn print_human_readable(self, output: SampledResponsibilityValues<PD>) {
+        println!(
+            "Estimated responsibility values ({}% confidence intervals, {} samples per group):",
+            output.confidence_level * 100.0,
+            output.samples_per_player
+        );
+        let mut counter = 0;
+        for (player, interval) in output
+            .values
+            .players
+            .into_iter()
+            .zip(output.confidence_intervals)
+        {
+            println!(
+                " {}: {:.6} ± {:.6} [{:.6}, {:.6}]",
+                player.player_info,
+                player.value,
+                interval.half_width(),
+                interval.lower,
+                interval.upper
+            );
+            if player.value > 0.0 {
+                counter += 1;
+            }
+        }
+        println!("{} entities have estimated responsibility", counter);
+        if !output.converged {
+            println!(
+                "Sampling stopped before the confidence intervals reached the requested width"
+            );
+        }
+    }
+
+    fn print_parsable(self, output: SampledResponsibilityValues<PD>) {
+        for (player, interval) in output
+            .values
+            .players
+            .into_iter()
+            .zip(output.confidence_intervals)
+        {
+            println!(
+                "{}:{}:{}:{}",
+                player.player_info, player.value, interval.lower, interval.upper
+            );
+        }
+    }
+
+    fn print_syntax_highlighting<G: GroupExtractionScheme>(
+        self,
+        grouping_scheme: &G,
+        output: SampledResponsibilityValues<PD>,
+        source: &str,
+        switching_pairs: &SwitchingPairCollection,
+        groups: VectorStateGroups,
+    ) {
+        ResponsibilityValuesPrinter {}.print_syntax_highlighting(
+            grouping_scheme,
+            output.values,
+            source,
+            switching_pairs,
+            groups,
+        )
+    }
+
+    fn print_syntax_highlighting_json<G: GroupExtractionScheme>(
+        self,
+        grouping_scheme: &G,
+        output: SampledResponsibilityValues<PD>,
+        switching_pairs: &SwitchingPairCollection,
+        groups: VectorStateGroups,
+    ) {
+        ResponsibilityValuesPrinter {}.print_syntax_highlighting_json(
+            grouping_scheme,
+            output.values,
+            switching_pairs,
+            groups,
+        )
+    }
+}
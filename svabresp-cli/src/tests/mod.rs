@@ -2,17 +2,23 @@ use std::str::FromStr;
 use svabresp::num_rational::BigRational;
 use svabresp::num_traits::ToPrimitive;
 use svabresp::shapley::{
-    BruteForceAlgorithm, DiscardingSwitchingPairCollector, ResponsibilityValues,
+    BruteForceAlgorithm, CoalitionSpecifier, DiscardingSwitchingPairCollector,
+    FullSwitchingPairCollector, MinimalCoalitionAlgorithm, ResponsibilityValues,
+    SampledResponsibilityValues, SamplingScheme, ShapleyAlgorithm, SimpleCooperativeGame,
+    StochasticAlgorithm, SwitchingPairCollection, WeightType,
 };
 use svabresp::state_based::grouping::{
-    ActionGroupExtractionScheme, IndividualGroupExtractionScheme, LabelGroupExtractionScheme,
-    ModuleGroupExtractionScheme, ValueGroupExtractionScheme,
+    ActionGroupExtractionScheme, GroupExtractionScheme, IndividualGroupExtractionScheme,
+    LabelGroupExtractionScheme, ModuleGroupExtractionScheme, ValueGroupExtractionScheme,
 };
 use svabresp::state_based::refinement::{
     FrontierSplittingHeuristics, IdentityGroupBlockingProvider, RandomBlockSelectionHeuristics,
-    RefinementGroupBlockingProvider, SingletonInitialPartition,
+    RandomSplittingHeuristics, RefinementGroupBlockingProvider, SingletonInitialPartition,
+};
+use svabresp::{
+    BackwardResponsibilityKind, CounterexampleFile, CounterexampleFromString, ModelFromString,
+    ResponsibilityError, ResponsibilityTask,
 };
-use svabresp::{CounterexampleFile, ModelFromString, ResponsibilityTask};
 
 #[test]
 fn small_network_explicit() {
@@ -29,7 +35,7 @@ fn small_network_explicit() {
         refinement: IdentityGroupBlockingProvider::new(),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
 
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
@@ -41,6 +47,124 @@ fn small_network_explicit() {
     assert_res("(loc=4)", "7/12", &result);
 }
 
+fn small_network_with_weight_type(
+    weight_type: WeightType,
+) -> ResponsibilityValues<String, f64, f64> {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "small-network.prism",
+            include_str!("files/small-network.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new().with_weight_type(weight_type),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    task.run().unwrap().shapley_output
+}
+
+#[test]
+fn small_network_banzhaf() {
+    let result = small_network_with_weight_type(WeightType::Banzhaf);
+    assert_res("(loc=1)", "1/8", &result);
+    assert_res("(loc=2)", "1/8", &result);
+    assert_res("(loc=3)", "3/8", &result);
+    assert_res("(loc=4)", "5/8", &result);
+
+    let result = small_network_with_weight_type(WeightType::NormalisedBanzhaf);
+    assert_res("(loc=1)", "1/10", &result);
+    assert_res("(loc=2)", "1/10", &result);
+    assert_res("(loc=3)", "3/10", &result);
+    assert_res("(loc=4)", "1/2", &result);
+}
+
+#[test]
+fn small_network_deegan_packel_and_johnston() {
+    // Minimal winning coalitions: {3, 4} and {1, 2, 4}
+    let result = small_network_with_weight_type(WeightType::DeeganPackel);
+    assert_res("(loc=1)", "1/6", &result);
+    assert_res("(loc=2)", "1/6", &result);
+    assert_res("(loc=3)", "1/4", &result);
+    assert_res("(loc=4)", "5/12", &result);
+
+    let result = small_network_with_weight_type(WeightType::Johnston);
+    assert_res("(loc=1)", "1/15", &result);
+    assert_res("(loc=2)", "1/15", &result);
+    assert_res("(loc=3)", "3/10", &result);
+    assert_res("(loc=4)", "17/30", &result);
+}
+
+#[test]
+fn small_network_reweighted() {
+    let shapley = small_network_with_weight_type(WeightType::Shapley);
+
+    let count = shapley.reweighted(WeightType::Count);
+    assert_res("(loc=1)", "1", &count);
+    assert_res("(loc=2)", "1", &count);
+    assert_res("(loc=3)", "3", &count);
+    assert_res("(loc=4)", "5", &count);
+
+    let normalised_banzhaf = shapley.reweighted(WeightType::NormalisedBanzhaf);
+    assert_res("(loc=3)", "3/10", &normalised_banzhaf);
+    assert_res("(loc=4)", "1/2", &normalised_banzhaf);
+
+    let shapley = count.reweighted(WeightType::Shapley);
+    assert_res("(loc=4)", "7/12", &shapley);
+}
+
+#[test]
+fn small_network_stochastic() {
+    small_network_stochastic_internal(SamplingScheme::Permutations)
+}
+
+#[test]
+fn small_network_stochastic_stratified() {
+    small_network_stochastic_internal(SamplingScheme::StratifiedBySize)
+}
+
+fn small_network_stochastic_internal(sampling_scheme: SamplingScheme) {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "small-network.prism",
+            include_str!("files/small-network.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: StochasticAlgorithm::new()
+            .with_seed(42)
+            .with_sampling_scheme(sampling_scheme)
+            .with_confidence_level(0.99)
+            .with_target_half_width(0.01)
+            .with_round_limits(30, 50_000),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    let result = task.run().unwrap().shapley_output;
+
+    for (res, interval) in result
+        .values
+        .players
+        .iter()
+        .zip(result.confidence_intervals.iter())
+    {
+        println!(
+            "{}: {} [{}, {}]",
+            res.player_info, res.value, interval.lower, interval.upper
+        );
+    }
+
+    assert!(result.converged);
+    assert_sampled_res("(loc=1)", "1/12", &result);
+    assert_sampled_res("(loc=2)", "1/12", &result);
+    assert_sampled_res("(loc=3)", "1/4", &result);
+    assert_sampled_res("(loc=4)", "7/12", &result);
+}
+
 #[test]
 fn labelled_groups() {
     labelled_groups_internal(
@@ -72,7 +196,7 @@ fn labelled_groups_internal(filename: &'static str, source: &'static str) {
         refinement: IdentityGroupBlockingProvider::new(),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
 
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
@@ -114,7 +238,7 @@ fn value_groups_internal(filename: &'static str, source: &'static str) {
         refinement: IdentityGroupBlockingProvider::new(),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
 
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
@@ -152,7 +276,7 @@ fn module_groups_internal(filename: &'static str, source: &'static str) {
         refinement: IdentityGroupBlockingProvider::new(),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
 
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
@@ -194,7 +318,7 @@ fn action_groups_internal(filename: &'static str, source: &'static str) {
         refinement: IdentityGroupBlockingProvider::new(),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
     }
@@ -224,7 +348,7 @@ fn simple_refinement() {
         ),
         switching_pair_collector: &mut  DiscardingSwitchingPairCollector::new()
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
     }
@@ -233,6 +357,31 @@ fn simple_refinement() {
     assert_res("[unnamed group of states]", "0", &result);
 }
 
+#[test]
+fn simple_refinement_random_splitting() {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "simple-refinement.prism",
+            include_str!("files/simple-refinement.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::including_irrelevant_states(),
+        refinement: RefinementGroupBlockingProvider::new(
+            SingletonInitialPartition::new(),
+            RandomBlockSelectionHeuristics::new(1),
+            RandomSplittingHeuristics::new(),
+        ),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    let result = task.run().unwrap().shapley_output;
+
+    assert_res("(x=4)", "1", &result);
+    assert_res("[unnamed group of states]", "0", &result);
+}
+
 #[test]
 fn refinement_from_paper() {
     let task = ResponsibilityTask {
@@ -252,7 +401,7 @@ fn refinement_from_paper() {
         ),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
 
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
@@ -263,6 +412,102 @@ fn refinement_from_paper() {
     assert_res("(s=6)", "1/12", &result);
     assert_res("(s=8)", "1/12", &result);
 }
+#[test]
+fn minimal_coalitions_from_paper() {
+    for weight_type in [
+        WeightType::Shapley,
+        WeightType::NormalisedBanzhaf,
+        WeightType::DeeganPackel,
+        WeightType::Johnston,
+    ] {
+        let task = ResponsibilityTask {
+            model_description: ModelFromString::new(
+                "refinement-example-paper.prism",
+                include_str!("files/refinement-example-paper.prism"),
+                "P>=1 [G !\"obj\"]",
+            ),
+            constants: "".to_string(),
+            coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+            algorithm: MinimalCoalitionAlgorithm::new().with_weight_type(weight_type),
+            grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+            refinement: IdentityGroupBlockingProvider::new(),
+            switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+        };
+        let result = task.run().unwrap().shapley_output;
+
+        let task = ResponsibilityTask {
+            model_description: ModelFromString::new(
+                "refinement-example-paper.prism",
+                include_str!("files/refinement-example-paper.prism"),
+                "P>=1 [G !\"obj\"]",
+            ),
+            constants: "".to_string(),
+            coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+            algorithm: BruteForceAlgorithm::new().with_weight_type(weight_type),
+            grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+            refinement: IdentityGroupBlockingProvider::new(),
+            switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+        };
+        let expected = task.run().unwrap().shapley_output;
+
+        for (res, expected) in result.players.iter().zip(expected.players.iter()) {
+            println!("{:?} {}: {}", weight_type, res.player_info, res.value);
+            assert_eq!(res.player_info, expected.player_info);
+            assert!(
+                (res.value - expected.value).abs() < 0.000_000_001,
+                "Incorrect {:?} value for `{}`. Got {}, expected {}",
+                weight_type,
+                res.player_info,
+                res.value,
+                expected.value
+            );
+        }
+    }
+}
+
+/// A simple game that is won by exactly one of two players, which is not monotone
+struct ExactlyOneGame {
+    players: Vec<String>,
+}
+
+impl SimpleCooperativeGame for ExactlyOneGame {
+    type PlayerDescriptions = Vec<String>;
+
+    fn get_player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    fn player_descriptions(&self) -> &Self::PlayerDescriptions {
+        &self.players
+    }
+
+    fn player_descriptions_mut(&mut self) -> &mut Self::PlayerDescriptions {
+        &mut self.players
+    }
+
+    fn is_winning<C: CoalitionSpecifier>(&mut self, coalition: C) -> bool {
+        coalition.is_in_coalition(0) != coalition.is_in_coalition(1)
+    }
+}
+
+#[test]
+fn minimal_coalitions_non_monotone_game() {
+    // The grand coalition loses, so the enumeration of the minimal winning coalitions, which
+    // assumes monotonicity, would not find any winning coalition. The game must be solved by the
+    // brute-force algorithm instead.
+    let players = vec!["a".to_string(), "b".to_string()];
+    let result = MinimalCoalitionAlgorithm::new().compute_simple(&mut ExactlyOneGame {
+        players: players.clone(),
+    });
+    let expected = BruteForceAlgorithm::new().compute_simple(&mut ExactlyOneGame { players });
+    for (res, expected) in result.players.iter().zip(expected.players.iter()) {
+        assert_eq!(res.player_info, expected.player_info);
+        assert_eq!(res.value, expected.value);
+    }
+    assert_res("a", "1/2", &result);
+    assert_res("b", "1/2", &result);
+}
+
 #[test]
 fn probabilistic() {
     let task = ResponsibilityTask {
@@ -278,7 +523,7 @@ fn probabilistic() {
         refinement: IdentityGroupBlockingProvider::new(),
         switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
     };
-    let result = task.run().shapley_output;
+    let result = task.run().unwrap().shapley_output;
 
     for res in result.players.iter() {
         println!("{}: {}", res.player_info, res.value);
@@ -293,6 +538,505 @@ fn probabilistic() {
     assert_res_with_eps("(x=4)", "0", &result, probabilistic_eps);
 }
 
+fn probabilistic_with_property(property: &str) -> ResponsibilityValues<String, f64, f64> {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "probabilistic.prism",
+            include_str!("files/probabilistic.prism"),
+            property,
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::including_irrelevant_states(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    task.run().unwrap().shapley_output
+}
+
+#[test]
+fn probabilistic_objectives() {
+    let probabilistic_eps = 0.000_1;
+
+    // Only (x=0) can choose between reaching "obj" with probability 0.6 and 0.7, so its
+    // responsibility is the difference in the probability of achieving the objective
+    for property in [
+        "Pmin=? [F \"obj\"]",
+        "Pmax=? [G !\"obj\"]",
+        "Pmin=? [G !\"obj\"]",
+    ] {
+        let result = probabilistic_with_property(property);
+        assert_res_with_eps("(x=0)", "1/10", &result, probabilistic_eps);
+        assert_res_with_eps("(x=1)", "0", &result, probabilistic_eps);
+    }
+
+    // With a threshold between 0.6 and 0.7, the game is simple and (x=0) alone is responsible
+    for property in ["P>=0.65 [F \"obj\"]", "P<=0.65 [F \"obj\"]"] {
+        let result = probabilistic_with_property(property);
+        assert_res("(x=0)", "1", &result);
+        assert_res("(x=1)", "0", &result);
+    }
+
+    // Thresholds that are met or missed regardless of the coalition make nobody responsible
+    for property in ["P>=0.55 [F \"obj\"]", "P>0.7 [F \"obj\"]"] {
+        let result = probabilistic_with_property(property);
+        assert_res("(x=0)", "0", &result);
+    }
+}
+
+fn rewards_with_grouping<G: GroupExtractionScheme>(
+    property: &str,
+    grouping_scheme: &mut G,
+) -> ResponsibilityValues<String, f64, f64> {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "rewards.prism",
+            include_str!("files/rewards.prism"),
+            property,
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme,
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    task.run().unwrap().shapley_output
+}
+
+#[test]
+fn expected_rewards() {
+    let eps = 0.000_1;
+    for property in [
+        "R{\"cost\"}min=? [F \"goal\"]",
+        "R{\"cost\"}max=? [F \"goal\"]",
+    ] {
+        // Either state can avoid the expensive action on its own, so both are equally responsible
+        // for the difference between the best and the worst expected cost
+        let result = rewards_with_grouping(property, &mut IndividualGroupExtractionScheme::new());
+        assert_res_with_eps("(s=0)", "3/2", &result, eps);
+        assert_res_with_eps("(s=1)", "3/2", &result, eps);
+
+        let result = rewards_with_grouping(
+            property,
+            &mut ValueGroupExtractionScheme::new(vec!["s".to_string()]),
+        );
+        assert_res_with_eps("(s=0)", "3/2", &result, eps);
+        assert_res_with_eps("(s=1)", "3/2", &result, eps);
+
+        let result = rewards_with_grouping(property, &mut ModuleGroupExtractionScheme::new());
+        assert_res_with_eps("scheduler", "0", &result, eps);
+        assert_res_with_eps("route", "3", &result, eps);
+    }
+
+    // Taking the cheap action suffices to minimise the cost, whereas maximising it requires both
+    // the detour and the expensive action
+    let result = rewards_with_grouping(
+        "R{\"cost\"}min=? [F \"goal\"]",
+        &mut ActionGroupExtractionScheme::new(),
+    );
+    assert_res_with_eps("cheap", "3", &result, eps);
+    assert_res_with_eps("detour", "0", &result, eps);
+    assert_res_with_eps("expensive", "0", &result, eps);
+
+    let result = rewards_with_grouping(
+        "R{\"cost\"}max=? [F \"goal\"]",
+        &mut ActionGroupExtractionScheme::new(),
+    );
+    assert_res_with_eps("cheap", "0", &result, eps);
+    assert_res_with_eps("detour", "3/2", &result, eps);
+    assert_res_with_eps("expensive", "3/2", &result, eps);
+}
+
+fn unreachable_rewards<A: ShapleyAlgorithm>(
+    property: &str,
+    algorithm: A,
+) -> Result<A::Output<String>, ResponsibilityError> {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "unreachable-rewards.prism",
+            include_str!("files/unreachable-rewards.prism"),
+            property,
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm,
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    task.run().map(|output| output.shapley_output)
+}
+
+#[test]
+fn infinite_expected_rewards() {
+    // Without control over the initial state, the adversary can stray into the trap state, so
+    // the expected cost of one coalition is infinite and that of the other one is finite
+    for property in [
+        "R{\"cost\"}min=? [F \"goal\"]",
+        "R{\"cost\"}max=? [F \"goal\"]",
+    ] {
+        let result = unreachable_rewards(property, BruteForceAlgorithm::new()).unwrap();
+        let value = result.get(&"(s=0)".to_string()).unwrap().value;
+        assert_eq!(value, f64::INFINITY);
+
+        let result = unreachable_rewards(
+            property,
+            StochasticAlgorithm::new()
+                .with_seed(42)
+                .with_round_limits(30, 1_000),
+        )
+        .unwrap();
+        assert!(result.converged);
+        assert_eq!(result.values.players[0].value, f64::INFINITY);
+        assert_eq!(result.confidence_intervals[0].lower, f64::INFINITY);
+    }
+
+    assert!(matches!(
+        unreachable_rewards("R{\"time\"}min=? [F \"goal\"]", BruteForceAlgorithm::new()),
+        Err(ResponsibilityError::UnknownRewardStructure(Some(name))) if name == "time"
+    ));
+}
+
+fn with_threads(
+    filename: &'static str,
+    source: &'static str,
+    property: &str,
+    thread_count: usize,
+) -> (
+    ResponsibilityValues<String, f64, f64>,
+    SwitchingPairCollection,
+) {
+    let mut switching_pair_collector = FullSwitchingPairCollector::new();
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(filename, source, property),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new().with_threads(thread_count),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::including_irrelevant_states(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut switching_pair_collector,
+    };
+    let result = task.run().unwrap().shapley_output;
+    (
+        result,
+        switching_pair_collector.into_switching_pair_collection(),
+    )
+}
+
+#[test]
+fn parallel_evaluation() {
+    for (filename, source, property) in [
+        (
+            "small-network.prism",
+            include_str!("files/small-network.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        (
+            "probabilistic.prism",
+            include_str!("files/probabilistic.prism"),
+            "P=? [F \"obj\"]",
+        ),
+        (
+            "probabilistic.prism",
+            include_str!("files/probabilistic.prism"),
+            "P>=0.65 [F \"obj\"]",
+        ),
+    ] {
+        let (sequential, sequential_pairs) = with_threads(filename, source, property, 1);
+        // More threads than coalitions leave some threads without work
+        for thread_count in [2, 3, 1000] {
+            let (parallel, parallel_pairs) = with_threads(filename, source, property, thread_count);
+            assert_eq!(parallel.players.len(), sequential.players.len());
+            for (index, player) in sequential.players.iter().enumerate() {
+                let parallel_value = parallel.get(&player.player_info).unwrap().value;
+                assert!((parallel_value - player.value).abs() < 0.000_000_001);
+
+                // The switching pairs are registered in the same order as without threads
+                let coalitions = |pairs: &SwitchingPairCollection| {
+                    pairs
+                        .switching_pairs(index)
+                        .iter()
+                        .map(|pair| (pair.coalition, pair.value_without, pair.value_with))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(coalitions(&parallel_pairs), coalitions(&sequential_pairs));
+            }
+        }
+    }
+}
+
+#[test]
+fn probabilistic_deegan_packel_requires_bound() {
+    // Without a bound, the cooperative game is not simple and the index is undefined
+    for weight_type in [WeightType::DeeganPackel, WeightType::Johnston] {
+        let task = ResponsibilityTask {
+            model_description: ModelFromString::new(
+                "probabilistic.prism",
+                include_str!("files/probabilistic.prism"),
+                "P=? [F \"obj\"]",
+            ),
+            constants: "".to_string(),
+            coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+            algorithm: BruteForceAlgorithm::new().with_weight_type(weight_type),
+            grouping_scheme: &mut IndividualGroupExtractionScheme::including_irrelevant_states(),
+            refinement: IdentityGroupBlockingProvider::new(),
+            switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+        };
+        assert!(matches!(
+            task.run(),
+            Err(ResponsibilityError::IndexRequiresSimpleGame { .. })
+        ));
+    }
+
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "probabilistic.prism",
+            include_str!("files/probabilistic.prism"),
+            "P>=0.65 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new().with_weight_type(WeightType::DeeganPackel),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::including_irrelevant_states(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    let result = task.run().unwrap().shapley_output;
+    assert_res("(x=0)", "1", &result);
+}
+
+#[test]
+fn long_chain_stochastic() {
+    // Every state of the chain must cooperate to reach the goal with probability 1/2, so this is a
+    // game with more than 64 players in which all players are equally responsible.
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "long-chain.prism",
+            include_str!("files/long-chain.prism"),
+            "P=? [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: StochasticAlgorithm::new()
+            .with_seed(42)
+            .with_confidence_level(0.99)
+            .with_target_half_width(0.01)
+            .with_round_limits(30, 50_000),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    let result = task.run().unwrap().shapley_output;
+
+    assert_eq!(result.values.players.len(), 79);
+    assert_sampled_res("(x=1)", "1/158", &result);
+    assert_sampled_res("(x=40)", "1/158", &result);
+    assert_sampled_res("(x=79)", "1/158", &result);
+}
+
+#[test]
+fn long_choice_chain_refinement() {
+    // The goal is only reached if all 70 states of the chain continue, so every state is equally
+    // responsible. Both the refinement and the sampling work on more than 64 groups.
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "long-choice-chain.prism",
+            include_str!("files/long-choice-chain.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: StochasticAlgorithm::new()
+            .with_seed(42)
+            .with_target_half_width(0.01)
+            .with_round_limits(30, 50_000),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: RefinementGroupBlockingProvider::new(
+            SingletonInitialPartition::new(),
+            RandomBlockSelectionHeuristics::new(1),
+            FrontierSplittingHeuristics::random_state(),
+        ),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    let result = task.run().unwrap().shapley_output;
+
+    // The splitting heuristics break ties randomly, so the order of the blocks and with it the
+    // estimate of each individual state differ between runs. Every sampled permutation distributes
+    // the full value, though, so the estimates always sum to 1.
+    assert_eq!(result.values.players.len(), 70);
+    assert!(result.converged);
+    let total: f64 = result
+        .values
+        .players
+        .iter()
+        .map(|player| player.value)
+        .sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    // Enumerating all coalitions is not possible for that many groups
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "long-choice-chain.prism",
+            include_str!("files/long-choice-chain.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    assert!(matches!(
+        task.run(),
+        Err(ResponsibilityError::TooManyGroups {
+            group_count: 70,
+            max_group_count: 63
+        })
+    ));
+}
+
+#[test]
+fn probabilistic_refinement() {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "probabilistic.prism",
+            include_str!("files/probabilistic.prism"),
+            "P=? [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::<CounterexampleFile>::Forward,
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::including_irrelevant_states(),
+        refinement: RefinementGroupBlockingProvider::new(
+            SingletonInitialPartition::new(),
+            RandomBlockSelectionHeuristics::new(1),
+            FrontierSplittingHeuristics::random_state(),
+        ),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    let result = task.run().unwrap().shapley_output;
+
+    for res in result.players.iter() {
+        println!("{}: {}", res.player_info, res.value);
+    }
+
+    let probabilistic_eps = 0.000_1;
+
+    assert_eq!(result.players.len(), 2);
+    assert_res_with_eps("(x=0)", "1/10", &result, probabilistic_eps);
+    assert_res_with_eps("[unnamed group of states]", "0", &result, probabilistic_eps);
+}
+
+fn backward_with_kind(kind: BackwardResponsibilityKind) -> ResponsibilityValues<String, f64, f64> {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "backward.prism",
+            include_str!("files/backward.prism"),
+            "P>=1 [F \"goal\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::Backward {
+            counterexample: CounterexampleFromString::new(include_str!(
+                "files/backward-counterexample.txt"
+            )),
+            kind,
+        },
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    task.run().unwrap().shapley_output
+}
+
+#[test]
+fn backward_optimistic_and_pessimistic() {
+    // Both states can deviate towards the goal if the state off the counterexample cooperates
+    let result = backward_with_kind(BackwardResponsibilityKind::Optimistic);
+    assert_res("(s=0)", "1/2", &result);
+    assert_res("(s=2)", "1/2", &result);
+
+    // If the state off the counterexample is adversarial, only s=2 can enforce the goal
+    let result = backward_with_kind(BackwardResponsibilityKind::Pessimistic);
+    assert_res("(s=0)", "0", &result);
+    assert_res("(s=2)", "1", &result);
+}
+
+fn backward_error(counterexample: &str) -> ResponsibilityError {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "backward.prism",
+            include_str!("files/backward.prism"),
+            "P>=1 [F \"goal\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::Backward {
+            counterexample: CounterexampleFromString::new(counterexample),
+            kind: BackwardResponsibilityKind::Optimistic,
+        },
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    match task.run() {
+        Ok(_) => panic!("The counterexample `{}` was accepted", counterexample),
+        Err(error) => error,
+    }
+}
+
+#[test]
+fn backward_invalid_counterexamples() {
+    assert!(matches!(
+        backward_error("(s=0)\n(s=)"),
+        ResponsibilityError::InvalidCounterexample(_)
+    ));
+    assert!(matches!(
+        backward_error("(s=1)"),
+        ResponsibilityError::UnmatchedCounterexampleState { position: 1, .. }
+    ));
+    assert!(matches!(
+        backward_error("(s=0)\n(s=3)"),
+        ResponsibilityError::UnmatchedCounterexampleState { position: 2, .. }
+    ));
+    // Both successors of the initial state match a state without assignments
+    assert!(matches!(
+        backward_error("(s=0)\n()"),
+        ResponsibilityError::AmbiguousCounterexampleState { position: 2, .. }
+    ));
+    assert!(matches!(
+        backward_error("(s=0)\n(t=1)"),
+        ResponsibilityError::UnknownCounterexampleVariable(name) if name == "t"
+    ));
+}
+
+#[test]
+fn backward_probabilistic_model() {
+    let task = ResponsibilityTask {
+        model_description: ModelFromString::new(
+            "probabilistic.prism",
+            include_str!("files/probabilistic.prism"),
+            "P>=1 [F \"obj\"]",
+        ),
+        constants: "".to_string(),
+        coop_game_type: svabresp::CoopGameType::Backward {
+            counterexample: CounterexampleFromString::new("(x=0)"),
+            kind: BackwardResponsibilityKind::Optimistic,
+        },
+        algorithm: BruteForceAlgorithm::new(),
+        grouping_scheme: &mut IndividualGroupExtractionScheme::new(),
+        refinement: IdentityGroupBlockingProvider::new(),
+        switching_pair_collector: &mut DiscardingSwitchingPairCollector::new(),
+    };
+    assert!(matches!(
+        task.run(),
+        Err(ResponsibilityError::BackwardRequiresNonprobabilisticModel)
+    ));
+}
+
 fn assert_res(name: &str, value: &str, result: &ResponsibilityValues<String, f64, f64>) {
     assert_res_with_eps(name, value, result, 0.000_000_001)
 }
@@ -314,3 +1058,23 @@ fn assert_res_with_eps(
         );
     }
 }
+
+fn assert_sampled_res(name: &str, value: &str, result: &SampledResponsibilityValues<String>) {
+    let index = result
+        .values
+        .get_index(&(name.to_string()))
+        .unwrap_or_else(|| panic!("No responsibility value entry for `{}`", name));
+    let interval = result.confidence_intervals[index];
+    let expected = BigRational::from_str(value).unwrap().to_f64().unwrap();
+    assert!(
+        interval.half_width() <= 0.01,
+        "Confidence interval for `{}` is too wide",
+        name
+    );
+    if expected < interval.lower || expected > interval.upper {
+        panic!(
+            "Incorrect responsibility value for `{}`. Got {} [{}, {}], expected {}",
+            name, result.values.players[index].value, interval.lower, interval.upper, expected
+        );
+    }
+}
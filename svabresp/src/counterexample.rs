@@ -0,0 +1,215 @@
+use std::fmt::{Display, Formatter};
+
+/// A run of the model that violates the property, given as a sequence of (partial) state
+/// valuations.
+///
+/// In the textual format, every non-empty line describes one state of the run, e.g.
+/// `(x=1, y=true)`. The parentheses are optional and variables that are not mentioned may take any
+/// value, as long as the state is uniquely determined. A line may be prefixed with the name of the
+/// action that is used to reach the state, e.g. `[send] (x=2)`. Everything after `//` is ignored.
+/// A run that ends in a state that already occurred before describes a lasso.
+pub struct Counterexample {
+    pub steps: Vec<CounterexampleStep>,
+}
+
+pub struct CounterexampleStep {
+    pub action: Option<String>,
+    pub assignments: Vec<(String, CounterexampleValue)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CounterexampleValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+#[derive(Debug)]
+pub enum CounterexampleParsingError {
+    Empty,
+    InvalidAction {
+        line: usize,
+        text: String,
+    },
+    InvalidAssignment {
+        line: usize,
+        assignment: String,
+    },
+    InvalidValue {
+        line: usize,
+        name: String,
+        value: String,
+    },
+}
+
+impl Display for CounterexampleParsingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error parsing counterexample: ")?;
+        match self {
+            CounterexampleParsingError::Empty => write!(f, "The counterexample contains no states"),
+            CounterexampleParsingError::InvalidAction { line, text } => {
+                write!(f, "Invalid action in line {}: `{}`", line, text)
+            }
+            CounterexampleParsingError::InvalidAssignment { line, assignment } => {
+                write!(f, "Invalid assignment in line {}: `{}`", line, assignment)
+            }
+            CounterexampleParsingError::InvalidValue { line, name, value } => write!(
+                f,
+                "Invalid value `{}` for variable `{}` in line {}",
+                value, name, line
+            ),
+        }
+    }
+}
+
+impl Counterexample {
+    pub fn parse(input: &str) -> Result<Self, CounterexampleParsingError> {
+        let mut steps = Vec::new();
+        for (line_index, line) in input.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = match line.find("//") {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+            let mut line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let action = if line.starts_with('[') {
+                let Some(end) = line.find(']') else {
+                    return Err(CounterexampleParsingError::InvalidAction {
+                        line: line_number,
+                        text: line.to_string(),
+                    });
+                };
+                let name = line[1..end].trim().to_string();
+                line = line[end + 1..].trim();
+                Some(name)
+            } else {
+                None
+            };
+
+            if line.starts_with('(') && line.ends_with(')') {
+                line = line[1..line.len() - 1].trim();
+            }
+
+            let mut assignments = Vec::new();
+            for assignment in line.split(',').filter(|a| !a.trim().is_empty()) {
+                let Some((lhs, rhs)) = assignment.split_once('=') else {
+                    return Err(CounterexampleParsingError::InvalidAssignment {
+                        line: line_number,
+                        assignment: assignment.trim().to_string(),
+                    });
+                };
+                let name = lhs.trim().to_string();
+                let rhs = rhs.trim();
+                let value = if let Ok(i) = rhs.parse::<i64>() {
+                    CounterexampleValue::Int(i)
+                } else if let Ok(f) = rhs.parse::<f64>() {
+                    CounterexampleValue::Float(f)
+                } else if let Ok(b) = rhs.parse::<bool>() {
+                    CounterexampleValue::Bool(b)
+                } else {
+                    return Err(CounterexampleParsingError::InvalidValue {
+                        line: line_number,
+                        name,
+                        value: rhs.to_string(),
+                    });
+                };
+                assignments.push((name, value));
+            }
+
+            steps.push(CounterexampleStep {
+                action,
+                assignments,
+            });
+        }
+
+        if steps.is_empty() {
+            return Err(CounterexampleParsingError::Empty);
+        }
+        Ok(Self { steps })
+    }
+}
+
+impl Display for CounterexampleStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(action) = &self.action {
+            write!(f, "[{}] ", action)?;
+        }
+        write!(f, "(")?;
+        for (i, (name, value)) in self.assignments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match value {
+                CounterexampleValue::Int(v) => write!(f, "{}={}", name, v)?,
+                CounterexampleValue::Float(v) => write!(f, "{}={}", name, v)?,
+                CounterexampleValue::Bool(v) => write!(f, "{}={}", name, v)?,
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counterexample, CounterexampleParsingError, CounterexampleValue};
+
+    #[test]
+    fn parse_steps() {
+        let counterexample = Counterexample::parse(
+            "// A comment\n(x=1, y=true)\n\n[send] (x=2, p=0.5) // The first send\nx=-3",
+        )
+        .unwrap();
+        assert_eq!(counterexample.steps.len(), 3);
+
+        assert_eq!(counterexample.steps[0].action, None);
+        assert_eq!(
+            counterexample.steps[0].assignments,
+            vec![
+                ("x".to_string(), CounterexampleValue::Int(1)),
+                ("y".to_string(), CounterexampleValue::Bool(true)),
+            ]
+        );
+
+        assert_eq!(counterexample.steps[1].action, Some("send".to_string()));
+        assert_eq!(
+            counterexample.steps[1].assignments,
+            vec![
+                ("x".to_string(), CounterexampleValue::Int(2)),
+                ("p".to_string(), CounterexampleValue::Float(0.5)),
+            ]
+        );
+
+        // Parentheses are optional
+        assert_eq!(
+            counterexample.steps[2].assignments,
+            vec![("x".to_string(), CounterexampleValue::Int(-3))]
+        );
+        assert_eq!(counterexample.steps[1].to_string(), "[send] (x=2, p=0.5)");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            Counterexample::parse("// Only a comment\n\n"),
+            Err(CounterexampleParsingError::Empty)
+        ));
+        assert!(matches!(
+            Counterexample::parse("(x=1)\n[send (x=2)"),
+            Err(CounterexampleParsingError::InvalidAction { line: 2, .. })
+        ));
+        assert!(matches!(
+            Counterexample::parse("(x=1, y)"),
+            Err(CounterexampleParsingError::InvalidAssignment { line: 1, .. })
+        ));
+        match Counterexample::parse("(x=1)\n(x=one)") {
+            Err(CounterexampleParsingError::InvalidValue { line, name, value }) => {
+                assert_eq!((line, name.as_str(), value.as_str()), (2, "x", "one"));
+            }
+            _ => panic!("Expected an invalid value"),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::CounterexampleParsingError;
+use crate::shapley::WeightType;
+use std::fmt::{Display, Formatter};
+
+/// Reasons why the responsibility for a model and property cannot be computed
+#[derive(Debug)]
+pub enum ResponsibilityError {
+    /// The index is only defined for simple games, but the property asks for a probability or an
+    /// expected reward instead of comparing it with a bound.
+    IndexRequiresSimpleGame { weight_type: WeightType },
+    /// The algorithm cannot handle as many groups as there are after grouping and refinement.
+    TooManyGroups {
+        group_count: usize,
+        max_group_count: usize,
+    },
+    /// Backward responsibility was requested for a model with probabilistic transitions.
+    BackwardRequiresNonprobabilisticModel,
+    /// The counterexample file cannot be read.
+    UnreadableCounterexample {
+        file_name: String,
+        error: std::io::Error,
+    },
+    /// The counterexample is not in the textual counterexample format.
+    InvalidCounterexample(CounterexampleParsingError),
+    /// A state of the counterexample matches no initial state (at position 1) or no successor of
+    /// the previous state. Positions start at 1.
+    UnmatchedCounterexampleState { position: usize, state: String },
+    /// A state of the counterexample matches several states of the model.
+    AmbiguousCounterexampleState { position: usize, state: String },
+    /// The counterexample assigns a variable that the model does not have.
+    UnknownCounterexampleVariable(String),
+    /// The model does not have the reward structure that the property refers to. Without a name,
+    /// the model does not have any reward structure.
+    UnknownRewardStructure(Option<String>),
+}
+
+impl Display for ResponsibilityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error computing responsibility: ")?;
+        match self {
+            ResponsibilityError::IndexRequiresSimpleGame { weight_type } => write!(
+                f,
+                "{:?} is only defined for simple cooperative games, use a property with a bound",
+                weight_type
+            ),
+            ResponsibilityError::TooManyGroups {
+                group_count,
+                max_group_count,
+            } => write!(
+                f,
+                "There are {} groups, but the algorithm can only handle up to {} groups, use the stochastic algorithm or refinement instead",
+                group_count, max_group_count
+            ),
+            ResponsibilityError::BackwardRequiresNonprobabilisticModel => write!(
+                f,
+                "Backward responsibility is only supported for non-probabilistic models"
+            ),
+            ResponsibilityError::UnreadableCounterexample { file_name, error } => write!(
+                f,
+                "Failed to read counterexample file `{}`: {}",
+                file_name, error
+            ),
+            ResponsibilityError::InvalidCounterexample(error) => write!(f, "{}", error),
+            ResponsibilityError::UnmatchedCounterexampleState { position: 1, state } => write!(
+                f,
+                "The first state of the counterexample ({}) is not an initial state",
+                state
+            ),
+            ResponsibilityError::UnmatchedCounterexampleState { position, state } => write!(
+                f,
+                "State {} of the counterexample ({}) does not match any successor of the previous state",
+                position, state
+            ),
+            ResponsibilityError::AmbiguousCounterexampleState { position, state } => write!(
+                f,
+                "State {} of the counterexample ({}) is ambiguous. Specify the values of more variables",
+                position, state
+            ),
+            ResponsibilityError::UnknownCounterexampleVariable(name) => {
+                write!(f, "Unknown variable `{}` in counterexample", name)
+            }
+            ResponsibilityError::UnknownRewardStructure(Some(name)) => {
+                write!(f, "Unknown reward structure `{}`", name)
+            }
+            ResponsibilityError::UnknownRewardStructure(None) => {
+                write!(f, "The model does not have a reward structure")
+            }
+        }
+    }
+}
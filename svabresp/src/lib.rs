@@ -2,6 +2,12 @@ pub use num_bigint;
 pub use num_rational;
 pub use num_traits;
 
+mod counterexample;
+pub use counterexample::*;
+
+mod error;
+pub use error::*;
+
 mod responsibility_task;
 pub use responsibility_task::*;
 
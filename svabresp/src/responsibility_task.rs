@@ -1,8 +1,9 @@
+use crate::counterexample::Counterexample;
 use crate::shapley::{ShapleyAlgorithm, SwitchingPairCollector};
-use crate::state_based::StateBasedOutput;
 use crate::state_based::grouping::{GroupExtractionScheme, VectorStateGroups};
 use crate::state_based::refinement::GroupBlockingProvider;
-use crate::{PrismModel, PrismProperty};
+use crate::state_based::{ResponsibilityOptions, StateBasedOutput};
+use crate::{PrismModel, PrismProperty, ResponsibilityError};
 use log::trace;
 use prism_parser::CharacterToLineMap;
 
@@ -34,7 +35,9 @@ impl<
     SPC: SwitchingPairCollector,
 > ResponsibilityTask<'a, M, C, A, G, R, SPC>
 {
-    pub fn run(mut self) -> StateBasedOutput<A::Output<String>, VectorStateGroups> {
+    pub fn run(
+        mut self,
+    ) -> Result<StateBasedOutput<A::Output<String>, VectorStateGroups>, ResponsibilityError> {
         trace!("Loading model and property");
         let (prism_model, property, character_to_line_map) =
             self.model_description.get_model_and_property();
@@ -42,18 +45,37 @@ impl<
         let constants = tiny_pmc::parsing::parse_const_assignments(&self.constants)
             .expect("Failed to parse constants");
 
-        let responsibility = crate::state_based::compute_for_prism(
-            prism_model,
-            &character_to_line_map,
-            property,
-            self.grouping_scheme,
-            self.refinement,
-            &mut self.algorithm,
+        let options = ResponsibilityOptions {
+            grouping_scheme: self.grouping_scheme,
+            group_blocking_provider: self.refinement,
+            switching_pair_collector: self.switching_pair_collector,
             constants,
-            self.switching_pair_collector,
-        );
-
-        responsibility
+        };
+        match self.coop_game_type {
+            CoopGameType::Forward => crate::state_based::compute_for_prism(
+                prism_model,
+                &character_to_line_map,
+                property,
+                &mut self.algorithm,
+                options,
+            ),
+            CoopGameType::Backward {
+                counterexample,
+                kind,
+            } => {
+                trace!("Loading counterexample");
+                let counterexample = counterexample.get_counterexample()?;
+                crate::state_based::compute_backward_for_prism(
+                    prism_model,
+                    &character_to_line_map,
+                    property,
+                    &mut self.algorithm,
+                    options,
+                    &counterexample,
+                    kind,
+                )
+            }
+        }
     }
 }
 
@@ -151,16 +173,58 @@ pub enum CoopGameType<C: CounterexampleSource> {
     },
 }
 
-pub trait CounterexampleSource {}
+pub trait CounterexampleSource {
+    fn get_counterexample(self) -> Result<Counterexample, ResponsibilityError>;
+}
 
 pub struct CounterexampleFile {
-    #[allow(unused)]
     file_name: String,
 }
 
-impl CounterexampleSource for CounterexampleFile {}
+impl CounterexampleFile {
+    pub fn new<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            file_name: file_name.into(),
+        }
+    }
+}
+
+impl CounterexampleSource for CounterexampleFile {
+    fn get_counterexample(self) -> Result<Counterexample, ResponsibilityError> {
+        let file = std::fs::read_to_string(&self.file_name).map_err(|error| {
+            ResponsibilityError::UnreadableCounterexample {
+                file_name: self.file_name,
+                error,
+            }
+        })?;
+        CounterexampleFromString::new(file).get_counterexample()
+    }
+}
+
+pub struct CounterexampleFromString {
+    counterexample: String,
+}
+
+impl CounterexampleFromString {
+    pub fn new<S: Into<String>>(counterexample: S) -> Self {
+        Self {
+            counterexample: counterexample.into(),
+        }
+    }
+}
+
+impl CounterexampleSource for CounterexampleFromString {
+    fn get_counterexample(self) -> Result<Counterexample, ResponsibilityError> {
+        Counterexample::parse(&self.counterexample)
+            .map_err(ResponsibilityError::InvalidCounterexample)
+    }
+}
 
+/// Determines how the states that are not on the counterexample behave in backward responsibility
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BackwardResponsibilityKind {
+    /// States off the counterexample cooperate with the coalition
     Optimistic,
+    /// States off the counterexample act against the coalition
     Pessimistic,
 }
@@ -1,61 +1,91 @@
+use crate::shapley::auxiliary::{WeightType, compute_weights, marginal_contribution};
+use crate::shapley::parallel::map_coalition_ranges;
 use crate::shapley::responsibility_values::{CriticalPairCounter, ResponsibilityValues};
-use crate::shapley::{CooperativeGame, PlayerDescriptions, SimpleCooperativeGame};
+use crate::shapley::{
+    CooperativeGame, PlayerDescriptions, SimpleCooperativeGame, SwitchingPairCollector,
+};
 use log::info;
+use num_rational::BigRational;
 use num_traits::ToPrimitive;
+use std::ops::Range;
 
-pub struct BruteForceAlgorithm {}
+// All coalitions are enumerated as `u64` bit masks
+const MAX_PLAYER_COUNT: usize = 63;
+
+pub struct BruteForceAlgorithm {
+    weight_type: WeightType,
+    thread_count: usize,
+}
 
 impl BruteForceAlgorithm {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            weight_type: WeightType::Shapley,
+            thread_count: 1,
+        }
+    }
+
+    pub fn with_weight_type(mut self, weight_type: WeightType) -> Self {
+        self.weight_type = weight_type;
+        self
+    }
+
+    /// Splits the coalitions among `thread_count` threads whenever the game can be cloned, see
+    /// `ShapleyAlgorithm::compute_parallel_with_switching_pairs`
+    pub fn with_threads(mut self, thread_count: usize) -> Self {
+        assert!(thread_count > 0, "At least one thread is required");
+        self.thread_count = thread_count;
+        self
     }
 
     fn get_n_and_coalition_count<G: CooperativeGame>(&self, game: &G) -> (usize, u64) {
         let n = game.get_player_count();
         info!("Running brute-force algorithm for n={} groups", n);
-        if n >= 64 {
+        if n > MAX_PLAYER_COUNT {
             panic!(
-                "The brute-force Shapley algorithm can only handle cooperative games with up to 63 players "
+                "The brute-force Shapley algorithm can only handle cooperative games with up to {} players, use the stochastic algorithm for larger games",
+                MAX_PLAYER_COUNT
             )
         }
         let coalition_count = 1u64 << n;
         (n, coalition_count)
     }
-}
-
-impl super::super::ShapleyAlgorithm for BruteForceAlgorithm {
-    type Output<PD> = ResponsibilityValues<PD, f64, f64>;
-
-    fn compute_with_switching_pairs<
-        G: CooperativeGame,
-        SPC: crate::shapley::SwitchingPairCollector,
-    >(
-        &mut self,
-        game: &mut G,
-        switching_pair_collector: &mut SPC,
-    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
-        let (n, coalition_count) = self.get_n_and_coalition_count(game);
 
-        let mut counts = CriticalPairCounter::new(n);
-
-        let weights = super::super::auxiliary::compute_weights(n);
+    fn weights(&self, n: usize) -> (Vec<BigRational>, Vec<f64>) {
+        let weights = compute_weights(n, self.weight_type);
         let weights_float = weights
             .iter()
             .map(|w| w.to_f64().unwrap())
             .collect::<Vec<_>>();
+        (weights, weights_float)
+    }
+
+    /// Counts the value differences of the switching pairs whose coalition without the added
+    /// player is in `base_coalitions`
+    fn count_in_range<G: CooperativeGame, SPC: SwitchingPairCollector>(
+        game: &mut G,
+        base_coalitions: Range<u64>,
+        weights_float: &[f64],
+        switching_pair_collector: &mut SPC,
+    ) -> CriticalPairCounter<f64> {
+        let n = game.get_player_count();
+        let mut counts = CriticalPairCounter::new(n);
 
         let start = std::time::Instant::now();
-        let mut next_round_number = 100_000;
-        for base_coalition in 0..coalition_count {
+        let first_coalition = base_coalitions.start;
+        let coalition_count = base_coalitions.end - first_coalition;
+        let mut next_round_number = first_coalition + 100_000;
+        for base_coalition in base_coalitions {
             // TODO: Factor out status reporting for both simple and non-simple algorithm
-            if base_coalition == next_round_number && base_coalition > 0 {
+            if base_coalition == next_round_number {
                 next_round_number += 100_000;
                 if start.elapsed().as_secs_f32() > 5.0 {
+                    let checked = base_coalition - first_coalition;
                     info!(
                         "Checked {}k/{:.1} ({:.2}%) switching pairs",
-                        base_coalition / 1_000,
+                        checked / 1_000,
                         coalition_count as f64 / 1_000.0,
-                        (base_coalition as f64 / coalition_count as f64) * 100.0
+                        (checked as f64 / coalition_count as f64) * 100.0
                     );
                 }
             }
@@ -65,9 +95,9 @@ impl super::super::ShapleyAlgorithm for BruteForceAlgorithm {
                 let coalition = base_coalition | 1 << added_state;
                 if coalition != base_coalition {
                     let coalition_value = game.get_value(coalition);
-                    counts.increase_by(added_state, size + 1, coalition_value - base_value);
+                    let pair_value = marginal_contribution(base_value, coalition_value);
+                    counts.increase_by(added_state, size + 1, pair_value);
                     if coalition_value > base_value {
-                        let pair_value = coalition_value - base_value;
                         switching_pair_collector.register_switching_pair(
                             added_state,
                             base_coalition,
@@ -79,41 +109,33 @@ impl super::super::ShapleyAlgorithm for BruteForceAlgorithm {
                 }
             }
         }
-
-        info!("Finished brute-force algorithm in {:?}", start.elapsed());
-
-        counts.to_responsibility_values(weights, game.player_descriptions().clone())
+        counts
     }
 
-    fn compute_simple_with_switching_pairs<
-        G: SimpleCooperativeGame,
-        SPC: crate::shapley::SwitchingPairCollector,
-    >(
-        &mut self,
+    /// Counts the critical pairs whose losing coalition is in `base_coalitions`
+    fn count_simple_in_range<G: SimpleCooperativeGame, SPC: SwitchingPairCollector>(
         game: &mut G,
+        base_coalitions: Range<u64>,
+        weights_float: &[f64],
         switching_pair_collector: &mut SPC,
-    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
-        let (n, coalition_count) = self.get_n_and_coalition_count(game);
-
+    ) -> CriticalPairCounter<usize> {
+        let n = game.get_player_count();
         let mut counts = CriticalPairCounter::new(n);
 
-        let weights = super::super::auxiliary::compute_weights(n);
-        let weights_float = weights
-            .iter()
-            .map(|w| w.to_f64().unwrap())
-            .collect::<Vec<_>>();
-
         let start = std::time::Instant::now();
-        let mut next_round_number = 10_000_000;
-        for base_coalition in 0..coalition_count {
-            if base_coalition == next_round_number && base_coalition > 0 {
+        let first_coalition = base_coalitions.start;
+        let coalition_count = base_coalitions.end - first_coalition;
+        let mut next_round_number = first_coalition + 10_000_000;
+        for base_coalition in base_coalitions {
+            if base_coalition == next_round_number {
                 next_round_number += 10_000_000;
                 if start.elapsed().as_secs_f32() > 5.0 {
+                    let checked = base_coalition - first_coalition;
                     info!(
                         "Checked {}m/{:.1}m ({:.2}%) switching pairs",
-                        base_coalition / 1000_000,
-                        coalition_count as f64 / 1000_000.0,
-                        (base_coalition as f64 / coalition_count as f64) * 100.0
+                        checked / 1_000_000,
+                        coalition_count as f64 / 1_000_000.0,
+                        (checked as f64 / coalition_count as f64) * 100.0
                     );
                 }
             }
@@ -134,9 +156,321 @@ impl super::super::ShapleyAlgorithm for BruteForceAlgorithm {
                 }
             }
         }
+        counts
+    }
 
+    /// Splits the base coalitions among the threads and merges the counts and switching pairs of
+    /// the threads in the order of the coalitions, so that the result does not depend on the
+    /// scheduling of the threads
+    fn count_in_parallel<G: Clone + Send, V: Default + Send + std::ops::AddAssign, SPC, F>(
+        &self,
+        game: &mut G,
+        n: usize,
+        coalition_count: u64,
+        switching_pair_collector: &mut SPC,
+        count_in_range: F,
+    ) -> CriticalPairCounter<V>
+    where
+        SPC: SwitchingPairCollector,
+        F: Fn(&mut G, Range<u64>, &mut BufferedSwitchingPairs) -> CriticalPairCounter<V> + Sync,
+    {
+        info!("Evaluating the coalitions on {} threads", self.thread_count);
+        let buffer_switching_pairs = !switching_pair_collector.discards_switching_pairs();
+        let results = map_coalition_ranges(
+            game,
+            coalition_count,
+            self.thread_count,
+            |game, coalitions| {
+                let mut switching_pairs = BufferedSwitchingPairs::new(buffer_switching_pairs);
+                let counts = count_in_range(game, coalitions, &mut switching_pairs);
+                (counts, switching_pairs)
+            },
+        );
+
+        let mut counts = CriticalPairCounter::new(n);
+        for (range_counts, switching_pairs) in results {
+            counts.merge(range_counts);
+            switching_pairs.register_with(switching_pair_collector);
+        }
         counts
+    }
+}
+
+/// Stores the switching pairs found by a thread until they are registered with the actual
+/// collector, unless that collector discards them anyway
+struct BufferedSwitchingPairs {
+    enabled: bool,
+    pairs: Vec<(usize, u64, f64, f64, f64)>,
+}
+
+impl BufferedSwitchingPairs {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            pairs: Vec::new(),
+        }
+    }
+
+    fn register_with<SPC: SwitchingPairCollector>(self, switching_pair_collector: &mut SPC) {
+        for (state, coalition, value_without, value_with, contribution) in self.pairs {
+            switching_pair_collector.register_switching_pair(
+                state,
+                coalition,
+                value_without,
+                value_with,
+                contribution,
+            );
+        }
+    }
+}
+
+impl SwitchingPairCollector for BufferedSwitchingPairs {
+    fn register_switching_pair(
+        &mut self,
+        state: usize,
+        coalition: u64,
+        value_without: f64,
+        value_with: f64,
+        contribution: f64,
+    ) {
+        if self.enabled {
+            self.pairs
+                .push((state, coalition, value_without, value_with, contribution));
+        }
+    }
+
+    fn discards_switching_pairs(&self) -> bool {
+        !self.enabled
+    }
+}
+
+impl BruteForceAlgorithm {
+    /// Computes the Deegan-Packel or Johnston index. Both distribute the value of the winning
+    /// coalitions among their critical players, i.e. the players whose removal makes the coalition
+    /// losing, instead of weighting each critical pair by the size of its coalition.
+    fn compute_by_winning_coalitions<G: SimpleCooperativeGame, SPC: SwitchingPairCollector>(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> ResponsibilityValues<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType, f64, f64>
+    {
+        let (n, coalition_count) = self.get_n_and_coalition_count(game);
+
+        let mut counts = CriticalPairCounter::new(n);
+        let mut values = vec![0.0; n];
+        // Switching pairs as (player, coalition without player, unnormalised contribution)
+        let mut switching_pairs = Vec::new();
+        let mut coalitions_forming = 0usize;
+
+        let start = std::time::Instant::now();
+        let mut critical_players = Vec::with_capacity(n);
+        for coalition in 0..coalition_count {
+            if !game.is_winning(coalition) {
+                continue;
+            }
+            critical_players.clear();
+            for player in 0..n {
+                if coalition & 1 << player != 0 && !game.is_winning(coalition ^ 1 << player) {
+                    critical_players.push(player);
+                }
+            }
+            for &player in &critical_players {
+                counts.increase_by(player, coalition.count_ones() as usize, 1.0);
+            }
+
+            if critical_players.is_empty() {
+                continue;
+            }
+            // In the Deegan-Packel index, only minimal winning coalitions (where every member is
+            // critical) form
+            if self.weight_type == WeightType::DeeganPackel
+                && critical_players.len() != coalition.count_ones() as usize
+            {
+                continue;
+            }
+            coalitions_forming += 1;
+            let share = 1.0 / critical_players.len() as f64;
+            for &player in &critical_players {
+                values[player] += share;
+                switching_pairs.push((player, coalition ^ 1 << player, share));
+            }
+        }
+
+        if coalitions_forming > 0 {
+            let normalisation = coalitions_forming as f64;
+            for value in values.iter_mut() {
+                *value /= normalisation;
+            }
+            for (player, coalition, share) in switching_pairs {
+                switching_pair_collector.register_switching_pair(
+                    player,
+                    coalition,
+                    0.0,
+                    1.0,
+                    share / normalisation,
+                );
+            }
+        }
+
+        info!(
+            "Finished brute-force algorithm ({:?}) in {:?}",
+            self.weight_type,
+            start.elapsed()
+        );
+
+        counts.into_responsibility_values_with_values(values, game.player_descriptions().clone())
+    }
+}
+
+impl super::super::ShapleyAlgorithm for BruteForceAlgorithm {
+    type Output<PD> = ResponsibilityValues<PD, f64, f64>;
+
+    fn weight_type(&self) -> WeightType {
+        self.weight_type
+    }
+
+    fn max_player_count(&self, _simple_game: bool) -> Option<usize> {
+        Some(MAX_PLAYER_COUNT)
+    }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn compute_with_switching_pairs<
+        G: CooperativeGame,
+        SPC: crate::shapley::SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        assert!(
+            self.weight_type.is_size_based(),
+            "{:?} is only defined for simple cooperative games",
+            self.weight_type
+        );
+        let (n, coalition_count) = self.get_n_and_coalition_count(game);
+        let (weights, weights_float) = self.weights(n);
+
+        let start = std::time::Instant::now();
+        let counts = Self::count_in_range(
+            game,
+            0..coalition_count,
+            &weights_float,
+            switching_pair_collector,
+        );
+        info!("Finished brute-force algorithm in {:?}", start.elapsed());
+
+        let mut values =
+            counts.into_responsibility_values(weights, game.player_descriptions().clone());
+        if self.weight_type.is_normalised() {
+            values.normalise();
+        }
+        values
+    }
+
+    fn compute_simple_with_switching_pairs<
+        G: SimpleCooperativeGame,
+        SPC: crate::shapley::SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        if !self.weight_type.is_size_based() {
+            return self.compute_by_winning_coalitions(game, switching_pair_collector);
+        }
+        let (n, coalition_count) = self.get_n_and_coalition_count(game);
+        let (weights, weights_float) = self.weights(n);
+
+        let counts = Self::count_simple_in_range(
+            game,
+            0..coalition_count,
+            &weights_float,
+            switching_pair_collector,
+        );
+
+        let mut values = counts
             .map_counts(|c| c as f64)
-            .to_responsibility_values(weights, game.player_descriptions().clone())
+            .into_responsibility_values(weights, game.player_descriptions().clone());
+        if self.weight_type.is_normalised() {
+            values.normalise();
+        }
+        values
+    }
+
+    fn compute_parallel_with_switching_pairs<
+        G: CooperativeGame + Clone + Send,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        if self.thread_count == 1 {
+            return self.compute_with_switching_pairs(game, switching_pair_collector);
+        }
+        assert!(
+            self.weight_type.is_size_based(),
+            "{:?} is only defined for simple cooperative games",
+            self.weight_type
+        );
+        let (n, coalition_count) = self.get_n_and_coalition_count(game);
+        let (weights, weights_float) = self.weights(n);
+
+        let start = std::time::Instant::now();
+        let counts = self.count_in_parallel(
+            game,
+            n,
+            coalition_count,
+            switching_pair_collector,
+            |game, coalitions, switching_pairs| {
+                Self::count_in_range(game, coalitions, &weights_float, switching_pairs)
+            },
+        );
+        info!("Finished brute-force algorithm in {:?}", start.elapsed());
+
+        let mut values =
+            counts.into_responsibility_values(weights, game.player_descriptions().clone());
+        if self.weight_type.is_normalised() {
+            values.normalise();
+        }
+        values
+    }
+
+    fn compute_simple_parallel_with_switching_pairs<
+        G: SimpleCooperativeGame + Clone + Send,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        // The Deegan-Packel and Johnston indices are normalised by the number of forming
+        // coalitions, so they are always computed sequentially
+        if self.thread_count == 1 || !self.weight_type.is_size_based() {
+            return self.compute_simple_with_switching_pairs(game, switching_pair_collector);
+        }
+        let (n, coalition_count) = self.get_n_and_coalition_count(game);
+        let (weights, weights_float) = self.weights(n);
+
+        let counts = self.count_in_parallel(
+            game,
+            n,
+            coalition_count,
+            switching_pair_collector,
+            |game, coalitions, switching_pairs| {
+                Self::count_simple_in_range(game, coalitions, &weights_float, switching_pairs)
+            },
+        );
+
+        let mut values = counts
+            .map_counts(|c| c as f64)
+            .into_responsibility_values(weights, game.player_descriptions().clone());
+        if self.weight_type.is_normalised() {
+            values.normalise();
+        }
+        values
     }
 }
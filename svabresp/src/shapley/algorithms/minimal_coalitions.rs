@@ -0,0 +1,319 @@
+use crate::shapley::auxiliary::{WeightType, compute_weights};
+use crate::shapley::responsibility_values::{CriticalPairCounter, ResponsibilityValues};
+use crate::shapley::{
+    BruteForceAlgorithm, Coalition, CoalitionSpecifier, CooperativeGame, GameValueCache,
+    MinimalCoalitionCache, MonotoneCooperativeGame, PlayerDescriptions, ShapleyAlgorithm,
+    SimpleCooperativeGame, SwitchingPairCollector,
+};
+use log::{info, trace, warn};
+use num_traits::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+
+/// Computes power indices of monotone simple games from their minimal winning coalitions.
+///
+/// The minimal winning coalitions are enumerated together with the maximal losing coalitions:
+/// every coalition that contains none of the minimal winning coalitions found so far is a subset
+/// of the complement of a minimal transversal (a minimal set of players that intersects every
+/// minimal winning coalition found so far). If one of these complements is winning, it is shrunk
+/// to a new minimal winning coalition, otherwise all of them are maximal losing coalitions and the
+/// enumeration is complete. The number of evaluated coalitions therefore depends on the number of
+/// minimal winning and maximal losing coalitions rather than on the number of all coalitions.
+///
+/// The values are then computed from the Harsanyi dividends of the game, which are non-zero only
+/// for unions of minimal winning coalitions.
+///
+/// The algorithm requires the game to be monotone. Games that are not simple or not known to be
+/// monotone are delegated to the brute-force algorithm. The Johnston index is computed by the brute-force algorithm on a cache of
+/// the minimal winning coalitions, which avoids evaluating the original game more than necessary.
+pub struct MinimalCoalitionAlgorithm {
+    weight_type: WeightType,
+}
+
+impl MinimalCoalitionAlgorithm {
+    pub fn new() -> Self {
+        Self {
+            weight_type: WeightType::Shapley,
+        }
+    }
+
+    pub fn with_weight_type(mut self, weight_type: WeightType) -> Self {
+        self.weight_type = weight_type;
+        self
+    }
+
+    /// Returns the minimal winning coalitions of a monotone simple game.
+    pub fn minimal_winning_coalitions<G: SimpleCooperativeGame + MonotoneCooperativeGame>(
+        game: &mut G,
+    ) -> Vec<Coalition> {
+        let n = game.get_player_count();
+
+        let mut minimal_winning = Vec::new();
+        // Minimal transversals of the minimal winning coalitions found so far. Initially, the
+        // empty set is the only (vacuous) transversal.
+        let mut transversals = vec![Coalition::empty(n)];
+        let mut known_losing = HashSet::new();
+        let mut evaluated_coalitions = 0usize;
+
+        loop {
+            let mut found = None;
+            for transversal in &transversals {
+                let candidate = transversal.complement();
+                if known_losing.contains(&candidate) {
+                    continue;
+                }
+                evaluated_coalitions += 1;
+                if game.is_winning(&candidate) {
+                    found = Some(candidate);
+                    break;
+                } else {
+                    known_losing.insert(candidate);
+                }
+            }
+
+            let Some(mut coalition) = found else {
+                break;
+            };
+
+            // Shrink the winning coalition until it is minimal
+            for player in 0..n {
+                if coalition.contains(player) {
+                    let smaller = coalition.without(player);
+                    evaluated_coalitions += 1;
+                    if game.is_winning(&smaller) {
+                        coalition = smaller;
+                    }
+                }
+            }
+            trace!(
+                "Found minimal winning coalition {:?}",
+                coalition.members().collect::<Vec<_>>()
+            );
+            transversals = Self::extend_transversals(transversals, &coalition);
+            minimal_winning.push(coalition);
+        }
+
+        info!(
+            "Found {} minimal winning and {} maximal losing coalitions by evaluating {} coalitions",
+            minimal_winning.len(),
+            known_losing.len(),
+            evaluated_coalitions
+        );
+
+        minimal_winning
+    }
+
+    // Berge's algorithm: updates the minimal transversals of a set family after adding a set
+    fn extend_transversals(transversals: Vec<Coalition>, added: &Coalition) -> Vec<Coalition> {
+        let mut candidates = Vec::new();
+        for transversal in transversals {
+            if transversal.intersects(added) {
+                candidates.push(transversal);
+            } else {
+                for player in added.members() {
+                    candidates.push(transversal.with(player));
+                }
+            }
+        }
+        candidates.sort_unstable_by_key(Coalition::size);
+        candidates.dedup();
+
+        let mut minimal: Vec<Coalition> = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            if !minimal.iter().any(|m| m.is_subset_of(&candidate)) {
+                minimal.push(candidate);
+            }
+        }
+        minimal
+    }
+
+    /// Computes the Harsanyi dividends of the simple game with the given minimal winning
+    /// coalitions by inclusion-exclusion. Coalitions that are not contained in the result have a
+    /// dividend of zero.
+    pub fn harsanyi_dividends(minimal_winning: &[Coalition]) -> HashMap<Coalition, i64> {
+        let mut dividends: HashMap<Coalition, i64> = HashMap::new();
+        for coalition in minimal_winning {
+            let mut updated = dividends.clone();
+            *updated.entry(coalition.clone()).or_insert(0) += 1;
+            for (union, &dividend) in &dividends {
+                *updated.entry(union.union(coalition)).or_insert(0) -= dividend;
+            }
+            updated.retain(|_, dividend| *dividend != 0);
+            dividends = updated;
+        }
+        dividends
+    }
+
+    // Only the switching pairs of the minimal winning coalitions are reported. The remaining
+    // switching pairs are supersets of these.
+    fn register_minimal_switching_pairs<SPC: SwitchingPairCollector>(
+        coalition: &Coalition,
+        contribution: f64,
+        switching_pair_collector: &mut SPC,
+    ) {
+        if switching_pair_collector.discards_switching_pairs() {
+            return;
+        }
+        for player in coalition.members() {
+            switching_pair_collector.register_switching_pair(
+                player,
+                coalition.without(player).to_mask(),
+                0.0,
+                1.0,
+                contribution,
+            );
+        }
+    }
+}
+
+impl Default for MinimalCoalitionAlgorithm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShapleyAlgorithm for MinimalCoalitionAlgorithm {
+    type Output<PD> = ResponsibilityValues<PD, f64, f64>;
+
+    fn weight_type(&self) -> WeightType {
+        self.weight_type
+    }
+
+    /// Games that are not simple and the Johnston index are delegated to the brute-force algorithm
+    fn max_player_count(&self, simple_game: bool) -> Option<usize> {
+        if simple_game && self.weight_type != WeightType::Johnston {
+            None
+        } else {
+            BruteForceAlgorithm::new().max_player_count(simple_game)
+        }
+    }
+
+    fn uses_exhaustive_caches(&self) -> bool {
+        false
+    }
+
+    fn compute_with_switching_pairs<G: CooperativeGame, SPC: SwitchingPairCollector>(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        warn!(
+            "The minimal coalition algorithm only supports simple games, falling back to the brute-force algorithm"
+        );
+        let mut cached_game = GameValueCache::create(game);
+        BruteForceAlgorithm::new()
+            .with_weight_type(self.weight_type)
+            .compute_with_switching_pairs(&mut cached_game, switching_pair_collector)
+    }
+
+    fn compute_simple_with_switching_pairs<
+        G: SimpleCooperativeGame,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        warn!(
+            "The minimal coalition algorithm only supports monotone games, falling back to the brute-force algorithm"
+        );
+        BruteForceAlgorithm::new()
+            .with_weight_type(self.weight_type)
+            .compute_simple_with_switching_pairs(game, switching_pair_collector)
+    }
+
+    fn compute_monotone_simple_with_switching_pairs<
+        G: SimpleCooperativeGame + MonotoneCooperativeGame,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        let n = game.get_player_count();
+        info!("Running minimal coalition algorithm for n={} groups", n);
+        let start = std::time::Instant::now();
+
+        let minimal_winning = Self::minimal_winning_coalitions(game);
+
+        if self.weight_type == WeightType::Johnston {
+            info!(
+                "Computing the Johnston index from the minimal winning coalitions by brute force"
+            );
+            let mut cached_game = MinimalCoalitionCache::from_minimal_coalitions(
+                minimal_winning,
+                n,
+                game.player_descriptions().clone(),
+            );
+            return BruteForceAlgorithm::new()
+                .with_weight_type(self.weight_type)
+                .compute_simple_with_switching_pairs(&mut cached_game, switching_pair_collector);
+        }
+        let dividends = Self::harsanyi_dividends(&minimal_winning);
+        info!("The game has {} non-zero dividends", dividends.len());
+
+        // The number of critical pairs of each player, by the size of the coalition including the
+        // player. A dividend of coalition U contributes to all coalitions of size k that contain U.
+        let mut binomials = vec![vec![0.0; n + 1]; n + 1];
+        for m in 0..=n {
+            binomials[m][0] = 1.0;
+            for k in 1..=m {
+                binomials[m][k] =
+                    binomials[m - 1][k - 1] + if k < m { binomials[m - 1][k] } else { 0.0 };
+            }
+        }
+        let mut counts = CriticalPairCounter::new(n);
+        for (coalition, &dividend) in &dividends {
+            let size = coalition.size();
+            for player in coalition.members() {
+                for k in size..=n {
+                    counts.increase_by(player, k, dividend as f64 * binomials[n - size][k - size]);
+                }
+            }
+        }
+
+        let mut values = if self.weight_type == WeightType::DeeganPackel {
+            let mut values = vec![0.0; n];
+            for coalition in &minimal_winning {
+                let share = 1.0 / coalition.size() as f64;
+                for player in coalition.members() {
+                    values[player] += share;
+                }
+            }
+            if !minimal_winning.is_empty() {
+                for value in values.iter_mut() {
+                    *value /= minimal_winning.len() as f64;
+                }
+            }
+            for coalition in &minimal_winning {
+                let contribution = 1.0 / (coalition.size() * minimal_winning.len()) as f64;
+                Self::register_minimal_switching_pairs(
+                    coalition,
+                    contribution,
+                    switching_pair_collector,
+                );
+            }
+            counts
+                .into_responsibility_values_with_values(values, game.player_descriptions().clone())
+        } else {
+            let weights = compute_weights(n, self.weight_type);
+            for coalition in &minimal_winning {
+                let contribution = weights[coalition.size()].to_f64().unwrap();
+                Self::register_minimal_switching_pairs(
+                    coalition,
+                    contribution,
+                    switching_pair_collector,
+                );
+            }
+            counts.into_responsibility_values(weights, game.player_descriptions().clone())
+        };
+        if self.weight_type == WeightType::NormalisedBanzhaf {
+            values.normalise();
+        }
+
+        info!(
+            "Finished minimal coalition algorithm in {:?}",
+            start.elapsed()
+        );
+        values
+    }
+}
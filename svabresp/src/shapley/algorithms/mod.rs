@@ -1,3 +1,7 @@
 mod brute_force;
+mod minimal_coalitions;
+mod stochastic;
 
 pub use brute_force::BruteForceAlgorithm;
+pub use minimal_coalitions::MinimalCoalitionAlgorithm;
+pub use stochastic::{SamplingScheme, StochasticAlgorithm};
@@ -0,0 +1,494 @@
+use crate::shapley::auxiliary::{
+    WeightType, compute_weights, marginal_contribution, standard_normal_quantile,
+};
+use crate::shapley::responsibility_values::{
+    ConfidenceInterval, CriticalPairCounterState, ResponsibilityValue, ResponsibilityValues,
+    SampledResponsibilityValues,
+};
+use crate::shapley::{
+    CoalitionSpecifier, CooperativeGame, PlayerDescriptions, SimpleCooperativeGame,
+    SwitchingPairCollector,
+};
+use log::{info, warn};
+use num_traits::ToPrimitive;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SamplingScheme {
+    /// Every round samples a random permutation of all players. Walking along the permutation
+    /// yields one marginal contribution per player.
+    Permutations,
+    /// Every round samples, for every player and every coalition size, a random coalition of that
+    /// size that does not contain the player. The sizes are weighted according to the weight type,
+    /// so this scheme supports all size-based indices.
+    StratifiedBySize,
+}
+
+/// Estimates Shapley values by Monte Carlo sampling of marginal contributions.
+///
+/// In contrast to the brute-force algorithm, the number of evaluated coalitions does not grow
+/// exponentially in the number of players. Sampling proceeds in rounds until the confidence
+/// intervals of all players are at most `target_half_width` wide on either side (after at least
+/// `min_rounds` rounds), or until `max_rounds` rounds have been performed.
+pub struct StochasticAlgorithm {
+    rng: StdRng,
+    sampling_scheme: SamplingScheme,
+    weight_type: WeightType,
+    confidence_level: f64,
+    target_half_width: f64,
+    min_rounds: usize,
+    max_rounds: usize,
+}
+
+impl StochasticAlgorithm {
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::from_rng(&mut rand::rng()),
+            sampling_scheme: SamplingScheme::Permutations,
+            weight_type: WeightType::Shapley,
+            confidence_level: 0.95,
+            target_half_width: 0.01,
+            min_rounds: 30,
+            max_rounds: 10_000,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn with_sampling_scheme(mut self, sampling_scheme: SamplingScheme) -> Self {
+        self.sampling_scheme = sampling_scheme;
+        self
+    }
+
+    /// Sets the index that is estimated. Only Shapley values can be estimated from permutations;
+    /// the other unnormalised size-based indices require stratified sampling.
+    pub fn with_weight_type(mut self, weight_type: WeightType) -> Self {
+        self.weight_type = weight_type;
+        self
+    }
+
+    pub fn with_confidence_level(mut self, confidence_level: f64) -> Self {
+        assert!(
+            confidence_level > 0.0 && confidence_level < 1.0,
+            "The confidence level must be strictly between 0 and 1"
+        );
+        self.confidence_level = confidence_level;
+        self
+    }
+
+    pub fn with_target_half_width(mut self, target_half_width: f64) -> Self {
+        assert!(
+            target_half_width >= 0.0,
+            "The target half-width of the confidence intervals must not be negative"
+        );
+        self.target_half_width = target_half_width;
+        self
+    }
+
+    pub fn with_round_limits(mut self, min_rounds: usize, max_rounds: usize) -> Self {
+        // At least two samples are required to estimate the variance
+        assert!(min_rounds >= 2, "At least two rounds must be performed");
+        assert!(
+            min_rounds <= max_rounds,
+            "The minimum number of rounds must not exceed the maximum number of rounds"
+        );
+        self.min_rounds = min_rounds;
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    fn permutation_round<G: CooperativeGame, SPC: SwitchingPairCollector>(
+        &mut self,
+        game: &mut G,
+        estimator: &mut MarginalContributionEstimator,
+        empty_value: f64,
+        order: &mut [usize],
+        coalition: &mut [bool],
+        switching_pair_collector: &mut SPC,
+    ) {
+        order.shuffle(&mut self.rng);
+        coalition.fill(false);
+
+        let mut value_without = empty_value;
+        for (size, &player) in order.iter().enumerate() {
+            coalition[player] = true;
+            let value_with = game.get_value(&coalition[..]);
+            coalition[player] = false;
+            estimator.record(
+                player,
+                size,
+                coalition,
+                value_without,
+                value_with,
+                switching_pair_collector,
+            );
+            coalition[player] = true;
+            value_without = value_with;
+        }
+    }
+
+    fn stratified_round<G: CooperativeGame, SPC: SwitchingPairCollector>(
+        &mut self,
+        game: &mut G,
+        estimator: &mut MarginalContributionEstimator,
+        others: &mut Vec<usize>,
+        coalition: &mut [bool],
+        switching_pair_collector: &mut SPC,
+    ) {
+        let n = coalition.len();
+        for player in 0..n {
+            others.clear();
+            others.extend((0..n).filter(|&p| p != player));
+
+            for size in 0..n {
+                coalition.fill(false);
+                let (members, _) = others.partial_shuffle(&mut self.rng, size);
+                for &member in members.iter() {
+                    coalition[member] = true;
+                }
+
+                let value_without = game.get_value(&coalition[..]);
+                coalition[player] = true;
+                let value_with = game.get_value(&coalition[..]);
+                coalition[player] = false;
+
+                estimator.record(
+                    player,
+                    size,
+                    coalition,
+                    value_without,
+                    value_with,
+                    switching_pair_collector,
+                );
+            }
+        }
+    }
+}
+
+impl Default for StochasticAlgorithm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl super::super::ShapleyAlgorithm for StochasticAlgorithm {
+    type Output<PD> = SampledResponsibilityValues<PD>;
+
+    fn weight_type(&self) -> WeightType {
+        self.weight_type
+    }
+
+    fn uses_exhaustive_caches(&self) -> bool {
+        false
+    }
+
+    fn compute_with_switching_pairs<G: CooperativeGame, SPC: SwitchingPairCollector>(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        assert!(
+            self.weight_type.is_size_based() && !self.weight_type.is_normalised(),
+            "The stochastic algorithm cannot estimate {:?}",
+            self.weight_type
+        );
+        assert!(
+            self.weight_type == WeightType::Shapley
+                || self.sampling_scheme == SamplingScheme::StratifiedBySize,
+            "Only Shapley values can be estimated from permutations, use stratified sampling to estimate {:?}",
+            self.weight_type
+        );
+        let n = game.get_player_count();
+        info!(
+            "Running stochastic algorithm ({:?}, {:?}) for n={} groups",
+            self.sampling_scheme, self.weight_type, n
+        );
+
+        let z = standard_normal_quantile(0.5 + self.confidence_level / 2.0);
+        let mut estimator =
+            MarginalContributionEstimator::new(n, self.sampling_scheme, self.weight_type);
+
+        let mut order = (0..n).collect::<Vec<_>>();
+        let mut others = Vec::with_capacity(n);
+        let mut coalition = vec![false; n];
+        let empty_value = game.get_value(&coalition[..]);
+
+        let start = std::time::Instant::now();
+        let mut last_report = start;
+        let mut rounds = 0;
+        let mut converged = false;
+        while rounds < self.max_rounds {
+            match self.sampling_scheme {
+                SamplingScheme::Permutations => self.permutation_round(
+                    game,
+                    &mut estimator,
+                    empty_value,
+                    &mut order,
+                    &mut coalition,
+                    switching_pair_collector,
+                ),
+                SamplingScheme::StratifiedBySize => self.stratified_round(
+                    game,
+                    &mut estimator,
+                    &mut others,
+                    &mut coalition,
+                    switching_pair_collector,
+                ),
+            }
+            rounds += 1;
+
+            if rounds >= self.min_rounds {
+                let max_half_width = estimator.max_half_width(z);
+                if last_report.elapsed().as_secs_f32() > 5.0 {
+                    last_report = std::time::Instant::now();
+                    info!(
+                        "Sampled {} rounds, largest confidence interval half-width is {:.6}",
+                        rounds, max_half_width
+                    );
+                }
+                if max_half_width <= self.target_half_width {
+                    converged = true;
+                    break;
+                }
+            }
+        }
+
+        if converged {
+            info!(
+                "Stochastic algorithm converged after {} rounds in {:?}",
+                rounds,
+                start.elapsed()
+            );
+        } else {
+            warn!(
+                "Stochastic algorithm stopped after {} rounds without reaching a confidence interval half-width of {}",
+                rounds, self.target_half_width
+            );
+        }
+
+        estimator.into_sampled_responsibility_values(
+            game.player_descriptions().clone(),
+            z,
+            self.confidence_level,
+            rounds,
+            converged,
+        )
+    }
+
+    fn compute_simple_with_switching_pairs<
+        G: SimpleCooperativeGame,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        self.compute_with_switching_pairs(game, switching_pair_collector)
+    }
+}
+
+#[derive(Clone, Default)]
+struct RunningStatistics {
+    count: usize,
+    mean: f64,
+    squared_deviations: f64,
+}
+
+impl RunningStatistics {
+    // Welford's online algorithm
+    fn add(&mut self, sample: f64) {
+        self.count += 1;
+        if sample.is_infinite() || self.mean.is_infinite() {
+            // A single infinite contribution makes the expected contribution infinite
+            self.mean += sample;
+            self.squared_deviations = 0.0;
+            return;
+        }
+        let delta = sample - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squared_deviations += delta * (sample - self.mean);
+    }
+
+    fn variance_of_mean(&self) -> f64 {
+        if self.mean.is_infinite() {
+            0.0
+        } else if self.count < 2 {
+            f64::INFINITY
+        } else {
+            self.squared_deviations / ((self.count - 1) * self.count) as f64
+        }
+    }
+}
+
+struct MarginalContributionEstimator {
+    sampling_scheme: SamplingScheme,
+    // Marginal contributions per player, independent of the coalition size
+    overall: Vec<RunningStatistics>,
+    // Marginal contributions per player and size of the coalition the player joins
+    per_size: Vec<Vec<RunningStatistics>>,
+    // The natural logarithm of the number of coalitions of each size that a player can join
+    log_coalition_counts: Vec<f64>,
+    // The weight of the average marginal contribution of each size in a stratified estimate
+    stratum_weights: Vec<f64>,
+    // Switching pairs are only reported for games whose coalitions fit into a bit mask
+    switching_pair_weights: Option<Vec<f64>>,
+    registered_switching_pairs: HashSet<(usize, u64)>,
+}
+
+impl MarginalContributionEstimator {
+    fn new(n: usize, sampling_scheme: SamplingScheme, weight_type: WeightType) -> Self {
+        // Computed in log space, as the binomial coefficients overflow for large n
+        let mut log_coalition_counts = Vec::with_capacity(n);
+        let mut log_binomial = 0.0;
+        for size in 0..n {
+            log_coalition_counts.push(log_binomial);
+            log_binomial += ((n - 1 - size) as f64).ln() - ((size + 1) as f64).ln();
+        }
+        let stratum_weights = log_coalition_counts
+            .iter()
+            .map(|log_count| match weight_type {
+                WeightType::Shapley => 1.0 / n as f64,
+                WeightType::Banzhaf => (log_count - (n - 1) as f64 * 2f64.ln()).exp(),
+                WeightType::Count => log_count.exp(),
+                w => unreachable!("{:?} cannot be estimated by sampling", w),
+            })
+            .collect();
+
+        let switching_pair_weights = if n <= 64 {
+            Some(
+                compute_weights(n, weight_type)
+                    .iter()
+                    .map(|w| w.to_f64().unwrap())
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        Self {
+            sampling_scheme,
+            overall: vec![RunningStatistics::default(); n],
+            per_size: vec![vec![RunningStatistics::default(); n]; n],
+            log_coalition_counts,
+            stratum_weights,
+            switching_pair_weights,
+            registered_switching_pairs: HashSet::new(),
+        }
+    }
+
+    fn record<SPC: SwitchingPairCollector>(
+        &mut self,
+        player: usize,
+        size: usize,
+        coalition_without: &[bool],
+        value_without: f64,
+        value_with: f64,
+        switching_pair_collector: &mut SPC,
+    ) {
+        let contribution = marginal_contribution(value_without, value_with);
+        self.overall[player].add(contribution);
+        self.per_size[player][size].add(contribution);
+
+        if let Some(weights) = &self.switching_pair_weights
+            && value_with > value_without
+        {
+            let mask = coalition_without.to_mask();
+            if self.registered_switching_pairs.insert((player, mask)) {
+                switching_pair_collector.register_switching_pair(
+                    player,
+                    mask,
+                    value_without,
+                    value_with,
+                    contribution * weights[size + 1],
+                );
+            }
+        }
+    }
+
+    fn estimate(&self, player: usize) -> (f64, f64) {
+        match self.sampling_scheme {
+            SamplingScheme::Permutations => {
+                let statistics = &self.overall[player];
+                (statistics.mean, statistics.variance_of_mean())
+            }
+            SamplingScheme::StratifiedBySize => {
+                let strata = self.per_size[player]
+                    .iter()
+                    .zip(self.stratum_weights.iter());
+                let mut mean = 0.0;
+                let mut variance = 0.0;
+                for (statistics, &weight) in strata {
+                    mean += weight * statistics.mean;
+                    variance += weight * weight * statistics.variance_of_mean();
+                }
+                (mean, variance)
+            }
+        }
+    }
+
+    fn max_half_width(&self, z: f64) -> f64 {
+        (0..self.overall.len())
+            .map(|player| z * self.estimate(player).1.sqrt())
+            .fold(0.0, f64::max)
+    }
+
+    fn into_sampled_responsibility_values<P: PlayerDescriptions>(
+        self,
+        player_infos: P,
+        z: f64,
+        confidence_level: f64,
+        rounds: usize,
+        converged: bool,
+    ) -> SampledResponsibilityValues<P::PlayerType> {
+        let n = self.overall.len();
+
+        // The details contain the estimated sum of marginal contributions over all coalitions of
+        // the respective size, analogous to the counts of the brute-force algorithm.
+        let coalition_counts = self
+            .log_coalition_counts
+            .iter()
+            .map(|c| c.exp())
+            .collect::<Vec<_>>();
+
+        let mut players = Vec::with_capacity(n);
+        let mut confidence_intervals = Vec::with_capacity(n);
+        for (player, player_info) in player_infos.into_iterator().enumerate().take(n) {
+            let (value, variance) = self.estimate(player);
+            let half_width = z * variance.sqrt();
+            confidence_intervals.push(ConfidenceInterval {
+                lower: value - half_width,
+                upper: value + half_width,
+            });
+
+            let mut counts = Vec::with_capacity(n + 1);
+            counts.push(0.0);
+            for (statistics, count) in self.per_size[player].iter().zip(coalition_counts.iter()) {
+                counts.push(statistics.mean * count);
+            }
+
+            players.push(ResponsibilityValue {
+                player_info,
+                value,
+                details: CriticalPairCounterState::from_counts(counts),
+            });
+        }
+
+        let samples_per_player = match self.sampling_scheme {
+            SamplingScheme::Permutations => rounds,
+            SamplingScheme::StratifiedBySize => rounds * n,
+        };
+
+        SampledResponsibilityValues {
+            values: ResponsibilityValues { players },
+            confidence_intervals,
+            confidence_level,
+            samples_per_player,
+            converged,
+        }
+    }
+}
@@ -3,8 +3,56 @@ use num_bigint::BigInt;
 use num_rational::BigRational;
 use num_traits::identities::{One, Zero};
 
-pub fn compute_weights(n: usize) -> Vec<BigRational> {
+/// The power index that is computed from the marginal contributions of the players.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightType {
+    /// Every marginal contribution to a coalition of size `k` (including the player) has weight
+    /// `(k-1)!(n-k)!/n!`.
+    Shapley,
+    /// Every marginal contribution has weight `1/2^(n-1)`.
+    Banzhaf,
+    /// The Banzhaf values, divided by their sum such that they add up to one.
+    NormalisedBanzhaf,
+    /// Every marginal contribution has weight one, i.e. the values are the (weighted) number of
+    /// critical pairs.
+    Count,
+    /// Every minimal winning coalition is equally likely to form and shares its value equally among
+    /// its members. Only defined for simple games.
+    DeeganPackel,
+    /// Every winning coalition with at least one critical player is equally likely to form and
+    /// shares its value equally among its critical players. Only defined for simple games.
+    Johnston,
+}
+
+impl WeightType {
+    /// Whether the index is a weighted sum of marginal contributions, where the weight only depends
+    /// on the size of the coalition. Only these indices can be computed from
+    /// [compute_weights] and the counts of a critical pair counter.
+    pub fn is_size_based(&self) -> bool {
+        match self {
+            WeightType::Shapley
+            | WeightType::Banzhaf
+            | WeightType::NormalisedBanzhaf
+            | WeightType::Count => true,
+            WeightType::DeeganPackel | WeightType::Johnston => false,
+        }
+    }
+
+    pub fn is_normalised(&self) -> bool {
+        match self {
+            WeightType::NormalisedBanzhaf | WeightType::DeeganPackel | WeightType::Johnston => true,
+            WeightType::Shapley | WeightType::Banzhaf | WeightType::Count => false,
+        }
+    }
+}
+
+pub fn compute_weights(n: usize, weight_type: WeightType) -> Vec<BigRational> {
     trace!("Computing weights");
+    assert!(
+        weight_type.is_size_based(),
+        "The weights of {:?} do not only depend on the coalition size",
+        weight_type
+    );
     let mut factorials = Vec::with_capacity(n + 1);
     let mut current_value = BigInt::one();
     factorials.push(BigInt::one());
@@ -20,17 +68,103 @@ pub fn compute_weights(n: usize) -> Vec<BigRational> {
     // avoid this
     weights.push(BigRational::zero());
     for i in 1..=n {
-        weights.push(
-            //match weight_type {
-            // WeightType::Shapley =>
-            BigRational::new(
+        weights.push(match weight_type {
+            WeightType::Shapley => BigRational::new(
                 factorials[n - i].clone() * factorials[i - 1].clone(),
                 factorials[n].clone(),
             ),
-            // WeightType::Banzhaf => BigRational::new(1.into(), BigInt::from(2).pow(n as u32 - 1)),
-            // WeightType::Count => BigRational::one(),
-            //}
-        )
+            WeightType::Banzhaf | WeightType::NormalisedBanzhaf => {
+                BigRational::new(1.into(), BigInt::from(2).pow(n as u32 - 1))
+            }
+            WeightType::Count => BigRational::one(),
+            WeightType::DeeganPackel | WeightType::Johnston => unreachable!(),
+        })
     }
     weights
 }
+
+/// The increase of the value of a coalition when a player joins it. Expected rewards may be
+/// infinite, and a player that leaves an infinite value unchanged does not contribute anything.
+pub fn marginal_contribution(value_without: f64, value_with: f64) -> f64 {
+    if value_with == value_without {
+        0.0
+    } else {
+        value_with - value_without
+    }
+}
+
+/// Divides all values by their sum, unless the sum is zero. If some values are infinite, they
+/// share the total responsibility equally.
+pub fn normalise(values: &mut [f64]) {
+    let infinite_count = values.iter().filter(|value| value.is_infinite()).count();
+    if infinite_count > 0 {
+        for value in values.iter_mut() {
+            *value = if value.is_infinite() {
+                1.0 / infinite_count as f64
+            } else {
+                0.0
+            };
+        }
+        return;
+    }
+
+    let sum = values.iter().sum::<f64>();
+    if sum != 0.0 {
+        for value in values.iter_mut() {
+            *value /= sum;
+        }
+    }
+}
+
+/// Computes the quantile function of the standard normal distribution using Acklam's rational
+/// approximation (relative error below 1.15e-9).
+pub fn standard_normal_quantile(p: f64) -> f64 {
+    assert!(
+        p > 0.0 && p < 1.0,
+        "The normal quantile is only defined for probabilities strictly between 0 and 1"
+    );
+
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -standard_normal_quantile(1.0 - p)
+    }
+}
@@ -0,0 +1,180 @@
+use crate::shapley::CoalitionSpecifier;
+
+const WORD_SIZE: usize = u64::BITS as usize;
+
+/// A coalition of an arbitrary number of players, stored as a bitset.
+///
+/// In contrast to `u64` bit masks, coalitions of this type are not restricted to 64 players. They
+/// can be hashed, so they are suitable as keys of sparse value caches.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Coalition {
+    player_count: usize,
+    words: Vec<u64>,
+}
+
+impl Coalition {
+    pub fn empty(player_count: usize) -> Self {
+        Self {
+            player_count,
+            words: vec![0; player_count.div_ceil(WORD_SIZE)],
+        }
+    }
+
+    pub fn full(player_count: usize) -> Self {
+        let mut coalition = Self::empty(player_count);
+        for player in 0..player_count {
+            coalition.insert(player);
+        }
+        coalition
+    }
+
+    /// Copies the members of any other coalition specifier. Only the first `player_count` players
+    /// are considered.
+    pub fn from_specifier<C: CoalitionSpecifier>(coalition: &C, player_count: usize) -> Self {
+        let mut result = Self::empty(player_count);
+        for player in 0..player_count {
+            if coalition.is_in_coalition(player) {
+                result.insert(player);
+            }
+        }
+        result
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.player_count
+    }
+
+    pub fn contains(&self, player: usize) -> bool {
+        player < self.player_count
+            && self.words[player / WORD_SIZE] & 1 << (player % WORD_SIZE) != 0
+    }
+
+    pub fn insert(&mut self, player: usize) {
+        assert!(
+            player < self.player_count,
+            "Player {} does not exist in a game with {} players",
+            player,
+            self.player_count
+        );
+        self.words[player / WORD_SIZE] |= 1 << (player % WORD_SIZE);
+    }
+
+    pub fn remove(&mut self, player: usize) {
+        if player < self.player_count {
+            self.words[player / WORD_SIZE] &= !(1 << (player % WORD_SIZE));
+        }
+    }
+
+    /// Returns the number of players in the coalition
+    pub fn size(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(i, &w)| w & !other.words.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    /// Whether every member of this coalition is also a member of the other coalition, which may
+    /// be of any coalition specifier type
+    pub fn is_subset_of_specifier<C: CoalitionSpecifier>(&self, other: &C) -> bool {
+        self.members().all(|player| other.is_in_coalition(player))
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .any(|(&w, &other_w)| w & other_w != 0)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        assert_eq!(
+            self.player_count, other.player_count,
+            "Only coalitions of the same game can be united"
+        );
+        Self {
+            player_count: self.player_count,
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&w, &other_w)| w | other_w)
+                .collect(),
+        }
+    }
+
+    /// Returns the coalition of all players that are not in this coalition
+    pub fn complement(&self) -> Self {
+        let mut complement = Self::full(self.player_count);
+        for (word, &w) in complement.words.iter_mut().zip(&self.words) {
+            *word &= !w;
+        }
+        complement
+    }
+
+    /// Returns a copy of the coalition to which the player is added
+    pub fn with(&self, player: usize) -> Self {
+        let mut coalition = self.clone();
+        coalition.insert(player);
+        coalition
+    }
+
+    /// Returns a copy of the coalition from which the player is removed
+    pub fn without(&self, player: usize) -> Self {
+        let mut coalition = self.clone();
+        coalition.remove(player);
+        coalition
+    }
+
+    /// Iterates over the members in increasing order. Only the set bits are visited, so this is
+    /// cheap for small coalitions of large games.
+    pub fn members(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    None
+                } else {
+                    let bit = remaining.trailing_zeros() as usize;
+                    remaining &= remaining - 1;
+                    Some(index * WORD_SIZE + bit)
+                }
+            })
+        })
+    }
+}
+
+impl CoalitionSpecifier for Coalition {
+    fn max_size() -> usize {
+        usize::MAX
+    }
+
+    fn is_in_coalition(&self, index: usize) -> bool {
+        self.contains(index)
+    }
+
+    fn to_mask(&self) -> u64 {
+        assert!(
+            self.player_count <= 64,
+            "Can only create a bit mask for coalitions that have a size of at most 64"
+        );
+        self.words.first().copied().unwrap_or(0)
+    }
+}
+
+impl CoalitionSpecifier for &Coalition {
+    fn max_size() -> usize {
+        usize::MAX
+    }
+
+    fn is_in_coalition(&self, index: usize) -> bool {
+        self.contains(index)
+    }
+
+    fn to_mask(&self) -> u64 {
+        (*self).to_mask()
+    }
+}
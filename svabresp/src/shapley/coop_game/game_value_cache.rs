@@ -1,17 +1,21 @@
 use crate::shapley::{CoalitionSpecifier, CooperativeGame, PlayerDescriptions};
 use log::info;
+use std::sync::Arc;
 
+/// Stores the values of all 2^n coalitions of a cooperative game. Clones share the values, so the
+/// cache can be handed to several threads cheaply.
+#[derive(Clone)]
 pub struct GameValueCache<P: PlayerDescriptions> {
     player_descriptions: P,
     player_count: usize,
-    pub values: Vec<f64>,
+    pub values: Arc<Vec<f64>>,
 }
 
 impl<P: PlayerDescriptions + Clone> GameValueCache<P> {
     pub fn create<C: CooperativeGame<PlayerDescriptions = P>>(coop_game: &mut C) -> Self {
         let mut values = Vec::new();
 
-        let n = coop_game.get_player_count();
+        let n = Self::check_player_count(coop_game);
 
         info!("Building game value cache for n={} players", n);
         let start = std::time::Instant::now();
@@ -27,9 +31,56 @@ impl<P: PlayerDescriptions + Clone> GameValueCache<P> {
         Self {
             player_descriptions: coop_game.player_descriptions().clone(),
             player_count: coop_game.get_player_count(),
-            values,
+            values: Arc::new(values),
+        }
+    }
+
+    /// Like `create`, but evaluates the coalitions on `thread_count` threads. Every thread works on
+    /// its own clone of the game, i.e. with its own solver context. The values agree with those
+    /// computed by `create` up to the precision of the solver, which may build on the previously
+    /// evaluated coalition.
+    pub fn create_parallel<C: CooperativeGame<PlayerDescriptions = P> + Clone + Send>(
+        coop_game: &C,
+        thread_count: usize,
+    ) -> Self {
+        let n = Self::check_player_count(coop_game);
+
+        info!(
+            "Building game value cache for n={} players on {} threads",
+            n, thread_count
+        );
+        let start = std::time::Instant::now();
+        let values = super::super::parallel::map_coalition_ranges(
+            coop_game,
+            1u64 << n,
+            thread_count,
+            |game, coalitions| {
+                coalitions
+                    .map(|coalition| game.get_value(coalition))
+                    .collect::<Vec<_>>()
+            },
+        )
+        .concat();
+        info!(
+            "Finished building game value cache in {:?}",
+            start.elapsed()
+        );
+
+        Self {
+            player_descriptions: coop_game.player_descriptions().clone(),
+            player_count: coop_game.get_player_count(),
+            values: Arc::new(values),
         }
     }
+
+    fn check_player_count<C: CooperativeGame>(coop_game: &C) -> usize {
+        let n = coop_game.get_player_count();
+        assert!(
+            n < 64,
+            "The game value cache stores all 2^n values and only supports up to 63 players, use a SparseGameValueCache instead"
+        );
+        n
+    }
 }
 
 impl<P: PlayerDescriptions + Clone> CooperativeGame for GameValueCache<P> {
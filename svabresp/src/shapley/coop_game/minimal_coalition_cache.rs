@@ -1,12 +1,15 @@
 use crate::shapley::coop_game::PlayerDescriptions;
-use crate::shapley::{CoalitionSpecifier, MonotoneCooperativeGame, SimpleCooperativeGame};
+use crate::shapley::{
+    Coalition, CoalitionSpecifier, MonotoneCooperativeGame, SimpleCooperativeGame,
+};
 use log::{info, trace};
-use std::io::Write;
+use std::ops::Range;
 
+#[derive(Clone)]
 pub struct MinimalCoalitionCache<P: PlayerDescriptions> {
     player_descriptions: P,
     player_count: usize,
-    pub minimal_coalitions: Vec<u64>,
+    pub minimal_coalitions: Vec<Coalition>,
 }
 
 impl<P: PlayerDescriptions + Clone> MinimalCoalitionCache<P> {
@@ -37,21 +40,112 @@ impl<P: PlayerDescriptions + Clone> MinimalCoalitionCache<P> {
         }
         losing_coalitions
     }
+
+    /// Iterates over all coalitions of the game and evaluates those whose value does not follow
+    /// from monotonicity, so the game must have fewer than 64 players. The minimal winning
+    /// coalitions of larger games can be computed by
+    /// `MinimalCoalitionAlgorithm::minimal_winning_coalitions` and then be cached with
+    /// `from_minimal_coalitions`.
     pub fn create<C: SimpleCooperativeGame<PlayerDescriptions = P> + MonotoneCooperativeGame>(
         coop_game: &mut C,
     ) -> Self {
         trace!("Building minimal coalition cache");
+        let max_coalition = Self::coalition_count(coop_game.get_player_count());
+
+        let large_losing_coalitions = Self::large_losing_coalitions(coop_game, 4);
+
+        let minimal_coalitions = Self::minimal_coalitions_in_range(
+            coop_game,
+            0..max_coalition,
+            &large_losing_coalitions,
+        );
+
+        trace!(
+            "Minimal coalition cache contains {} coalitions",
+            minimal_coalitions.len()
+        );
+
+        let player_count = coop_game.get_player_count();
+        Self {
+            player_count,
+            minimal_coalitions: minimal_coalitions
+                .iter()
+                .map(|coalition| coalition.to_coalition(player_count))
+                .collect(),
+            player_descriptions: coop_game.player_descriptions().clone(),
+        }
+    }
+
+    /// Like `create`, but evaluates the coalitions on `thread_count` threads, each of which works
+    /// on its own clone of the game. Every thread only skips the supersets of the winning
+    /// coalitions it found itself, so the coalitions found by the threads are afterwards reduced
+    /// to the minimal ones, which are the same as those found by `create`.
+    pub fn create_parallel<
+        C: SimpleCooperativeGame<PlayerDescriptions = P> + MonotoneCooperativeGame + Clone + Send,
+    >(
+        coop_game: &C,
+        thread_count: usize,
+    ) -> Self {
+        trace!(
+            "Building minimal coalition cache on {} threads",
+            thread_count
+        );
+        let max_coalition = Self::coalition_count(coop_game.get_player_count());
+
+        let large_losing_coalitions = Self::large_losing_coalitions(&mut coop_game.clone(), 4);
+
+        let candidates = super::super::parallel::map_coalition_ranges(
+            coop_game,
+            max_coalition,
+            thread_count,
+            |game, coalitions| {
+                Self::minimal_coalitions_in_range(game, coalitions, &large_losing_coalitions)
+            },
+        );
+        // The candidates are sorted, so all subsets of a candidate have been considered before it
+        let mut minimal_coalitions: Vec<u64> = Vec::new();
+        for coalition in candidates.into_iter().flatten() {
+            if !minimal_coalitions
+                .iter()
+                .any(|&other_coalition| Self::subset_of(other_coalition, coalition))
+            {
+                minimal_coalitions.push(coalition);
+            }
+        }
+
+        trace!(
+            "Minimal coalition cache contains {} coalitions",
+            minimal_coalitions.len()
+        );
+
+        let player_count = coop_game.get_player_count();
+        Self {
+            player_count,
+            minimal_coalitions: minimal_coalitions
+                .iter()
+                .map(|coalition| coalition.to_coalition(player_count))
+                .collect(),
+            player_descriptions: coop_game.player_descriptions().clone(),
+        }
+    }
+
+    /// Returns the winning coalitions in `coalitions` that have no winning subset in
+    /// `coalitions`, in increasing order
+    fn minimal_coalitions_in_range<
+        C: SimpleCooperativeGame<PlayerDescriptions = P> + MonotoneCooperativeGame,
+    >(
+        coop_game: &mut C,
+        coalitions: Range<u64>,
+        large_losing_coalitions: &[u64],
+    ) -> Vec<u64> {
         let mut minimal_coalitions = Vec::new();
 
-        let max_coalition = 1u64 << coop_game.get_player_count();
+        let max_coalition = coalitions.end;
 
         let mut game_counter = 0;
         let mut skipped_counter = 0;
 
-        let large_losing_coalitions = Self::large_losing_coalitions(coop_game, 4);
-
-        std::io::stdout().flush().unwrap();
-        for coalition in 0..max_coalition {
+        for coalition in coalitions {
             if coalition % 10_000_000 == 0 && coalition > 0 {
                 info!(
                     "{}m/{:.1}m ({:.2}%)  (solved {} games, skipped {})",
@@ -70,7 +164,7 @@ impl<P: PlayerDescriptions + Clone> MinimalCoalitionCache<P> {
                 }
             }
             let mut superset_losing = false;
-            for &other_coalition in &large_losing_coalitions {
+            for &other_coalition in large_losing_coalitions {
                 if Self::subset_of(coalition, other_coalition) {
                     superset_losing = true;
                     break;
@@ -87,19 +181,30 @@ impl<P: PlayerDescriptions + Clone> MinimalCoalitionCache<P> {
                 minimal_coalitions.push(coalition)
             }
         }
+        minimal_coalitions
+    }
 
-        trace!(
-            "Minimal coalition cache contains {} coalitions",
-            minimal_coalitions.len()
-        );
-
+    /// Creates the cache from minimal winning coalitions that are already known.
+    pub fn from_minimal_coalitions(
+        minimal_coalitions: Vec<Coalition>,
+        player_count: usize,
+        player_descriptions: P,
+    ) -> Self {
         Self {
-            player_count: coop_game.get_player_count(),
+            player_count,
             minimal_coalitions,
-            player_descriptions: coop_game.player_descriptions().clone(),
+            player_descriptions,
         }
     }
 
+    fn coalition_count(player_count: usize) -> u64 {
+        assert!(
+            player_count < 64,
+            "Can only evaluate all coalitions of cooperative games with up to 63 players"
+        );
+        1u64 << player_count
+    }
+
     fn subset_of(a: u64, b: u64) -> bool {
         (a | b) == b
     }
@@ -121,12 +226,9 @@ impl<P: PlayerDescriptions + Clone> SimpleCooperativeGame for MinimalCoalitionCa
     }
 
     fn is_winning<C: CoalitionSpecifier>(&mut self, coalition: C) -> bool {
-        for &other_coalition in &self.minimal_coalitions {
-            if Self::subset_of(other_coalition, coalition.to_mask()) {
-                return true;
-            }
-        }
-        false
+        self.minimal_coalitions
+            .iter()
+            .any(|other_coalition| other_coalition.is_subset_of_specifier(&coalition))
     }
 }
 
@@ -1,8 +1,14 @@
+mod coalition;
 mod game_value_cache;
 mod minimal_coalition_cache;
+mod sparse_game_value_cache;
+mod threshold_game;
 
+pub use coalition::Coalition;
 pub use game_value_cache::GameValueCache;
 pub use minimal_coalition_cache::MinimalCoalitionCache;
+pub use sparse_game_value_cache::SparseGameValueCache;
+pub use threshold_game::ThresholdGame;
 
 pub trait PlayerDescriptions {
     type IntoIter: Iterator<Item = Self::PlayerType>;
@@ -86,6 +92,14 @@ pub trait CoalitionSpecifier {
         mask
     }
 
+    /// Converts the coalition into a bitset, which is not restricted to 64 players
+    fn to_coalition(&self, player_count: usize) -> Coalition
+    where
+        Self: Sized,
+    {
+        Coalition::from_specifier(self, player_count)
+    }
+
     fn to_string<S: AsRef<str>>(&self, group_names: &[S]) -> String {
         let mut result = vec!["{".to_string()];
         let mut is_first = true;
@@ -116,3 +130,28 @@ impl CoalitionSpecifier for u64 {
         *self
     }
 }
+
+impl CoalitionSpecifier for &[bool] {
+    fn max_size() -> usize {
+        usize::MAX
+    }
+
+    fn is_in_coalition(&self, index: usize) -> bool {
+        self.get(index).copied().unwrap_or(false)
+    }
+
+    fn to_mask(&self) -> u64 {
+        assert!(
+            self.len() <= 64,
+            "Can only create a bit mask for coalitions that have a size of at most 64"
+        );
+
+        let mut mask = 0;
+        for (i, &is_member) in self.iter().enumerate() {
+            if is_member {
+                mask |= 1 << i;
+            }
+        }
+        mask
+    }
+}
@@ -0,0 +1,111 @@
+use crate::shapley::{Coalition, CoalitionSpecifier, CooperativeGame, MonotoneCooperativeGame};
+use log::info;
+use std::collections::{BTreeMap, HashMap};
+
+/// Caches the values of a cooperative game lazily, i.e. only for the coalitions that are actually
+/// evaluated.
+///
+/// Unlike `GameValueCache`, which evaluates all 2^n coalitions up front, this cache is suitable for
+/// games with many players, e.g. when using sampling algorithms. If a capacity limit is set, the
+/// least recently used values are evicted once the limit is reached.
+pub struct SparseGameValueCache<'a, G: CooperativeGame> {
+    game: &'a mut G,
+    capacity_limit: Option<usize>,
+    // The cached values, together with the time they were last used
+    values: HashMap<Coalition, (f64, u64)>,
+    // The cached coalitions, ordered by the time they were last used
+    last_used: BTreeMap<u64, Coalition>,
+    clock: u64,
+    hits: usize,
+    misses: usize,
+}
+
+impl<'a, G: CooperativeGame> SparseGameValueCache<'a, G> {
+    pub fn new(game: &'a mut G) -> Self {
+        Self {
+            game,
+            capacity_limit: None,
+            values: HashMap::new(),
+            last_used: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Creates a cache that holds the values of at most `capacity_limit` coalitions
+    pub fn with_capacity_limit(game: &'a mut G, capacity_limit: usize) -> Self {
+        assert!(capacity_limit > 0, "The capacity limit must be positive");
+        let mut cache = Self::new(game);
+        cache.capacity_limit = Some(capacity_limit);
+        cache
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn log_statistics(&self) {
+        info!(
+            "Sparse game value cache: {} hits, {} misses, {} cached values",
+            self.hits,
+            self.misses,
+            self.values.len()
+        );
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, coalition)) = self.last_used.pop_first() {
+            self.values.remove(&coalition);
+        }
+    }
+}
+
+impl<'a, G: CooperativeGame> CooperativeGame for SparseGameValueCache<'a, G> {
+    type PlayerDescriptions = G::PlayerDescriptions;
+
+    fn get_player_count(&self) -> usize {
+        self.game.get_player_count()
+    }
+
+    fn player_descriptions(&self) -> &Self::PlayerDescriptions {
+        self.game.player_descriptions()
+    }
+
+    fn player_descriptions_mut(&mut self) -> &mut Self::PlayerDescriptions {
+        self.game.player_descriptions_mut()
+    }
+
+    fn get_value<C: CoalitionSpecifier>(&mut self, coalition: C) -> f64 {
+        let coalition = Coalition::from_specifier(&coalition, self.game.get_player_count());
+        self.clock += 1;
+
+        if let Some((value, last_used)) = self.values.get_mut(&coalition) {
+            self.hits += 1;
+            let key = self.last_used.remove(last_used).unwrap();
+            *last_used = self.clock;
+            self.last_used.insert(self.clock, key);
+            return *value;
+        }
+
+        self.misses += 1;
+        let value = self.game.get_value(&coalition);
+        if let Some(limit) = self.capacity_limit
+            && self.values.len() >= limit
+        {
+            self.evict_least_recently_used();
+        }
+        self.last_used.insert(self.clock, coalition.clone());
+        self.values.insert(coalition, (value, self.clock));
+        value
+    }
+}
+
+impl<'a, G: CooperativeGame + MonotoneCooperativeGame> MonotoneCooperativeGame
+    for SparseGameValueCache<'a, G>
+{
+}
@@ -0,0 +1,73 @@
+use crate::shapley::{
+    CoalitionSpecifier, CooperativeGame, MonotoneCooperativeGame, SimpleCooperativeGame,
+};
+
+/// A simple game in which a coalition is winning if its value in an underlying cooperative game
+/// reaches a threshold.
+///
+/// Values that differ from the threshold by at most the tolerance are considered equal to it, which
+/// accounts for games whose values are only computed approximately. If the underlying game is
+/// monotone, so is the threshold game.
+pub struct ThresholdGame<'a, G: CooperativeGame> {
+    game: &'a mut G,
+    threshold: f64,
+    strict: bool,
+    tolerance: f64,
+}
+
+impl<'a, G: CooperativeGame> ThresholdGame<'a, G> {
+    /// Coalitions win if their value is at least `threshold`
+    pub fn at_least(game: &'a mut G, threshold: f64) -> Self {
+        Self {
+            game,
+            threshold,
+            strict: false,
+            tolerance: 0.0,
+        }
+    }
+
+    /// Coalitions win if their value is strictly greater than `threshold`
+    pub fn greater_than(game: &'a mut G, threshold: f64) -> Self {
+        Self {
+            game,
+            threshold,
+            strict: true,
+            tolerance: 0.0,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl<'a, G: CooperativeGame> SimpleCooperativeGame for ThresholdGame<'a, G> {
+    type PlayerDescriptions = G::PlayerDescriptions;
+
+    fn get_player_count(&self) -> usize {
+        self.game.get_player_count()
+    }
+
+    fn player_descriptions(&self) -> &Self::PlayerDescriptions {
+        self.game.player_descriptions()
+    }
+
+    fn player_descriptions_mut(&mut self) -> &mut Self::PlayerDescriptions {
+        self.game.player_descriptions_mut()
+    }
+
+    fn is_winning<C: CoalitionSpecifier>(&mut self, coalition: C) -> bool {
+        let value = self.game.get_value(coalition);
+        if self.strict {
+            value > self.threshold + self.tolerance
+        } else {
+            value >= self.threshold - self.tolerance
+        }
+    }
+}
+
+impl<'a, G: CooperativeGame + MonotoneCooperativeGame> MonotoneCooperativeGame
+    for ThresholdGame<'a, G>
+{
+}
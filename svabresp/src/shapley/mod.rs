@@ -5,15 +5,22 @@ use std::collections::HashMap;
 use std::fmt::Display;
 
 mod auxiliary;
+pub use auxiliary::WeightType;
+pub(crate) use auxiliary::marginal_contribution;
+
+mod parallel;
 
 mod coop_game;
 pub use coop_game::{
-    CoalitionSpecifier, CooperativeGame, GameValueCache, MinimalCoalitionCache,
-    MonotoneCooperativeGame, PlayerDescriptions, SimpleCooperativeGame,
+    Coalition, CoalitionSpecifier, CooperativeGame, GameValueCache, MinimalCoalitionCache,
+    MonotoneCooperativeGame, PlayerDescriptions, SimpleCooperativeGame, SparseGameValueCache,
+    ThresholdGame,
 };
 
 mod responsibility_values;
-pub use responsibility_values::{ResponsibilityValue, ResponsibilityValues};
+pub use responsibility_values::{
+    ConfidenceInterval, ResponsibilityValue, ResponsibilityValues, SampledResponsibilityValues,
+};
 
 pub trait SwitchingPairCollector {
     fn register_switching_pair(
@@ -24,6 +31,12 @@ pub trait SwitchingPairCollector {
         value_with: f64,    // The value of the coalition and state working together
         contribution: f64,  // How much this switching pair adds to the coalition
     );
+
+    /// Whether all switching pairs are ignored, in which case algorithms do not need to buffer
+    /// them before registering them
+    fn discards_switching_pairs(&self) -> bool {
+        false
+    }
 }
 
 pub struct DiscardingSwitchingPairCollector {}
@@ -45,6 +58,10 @@ impl SwitchingPairCollector for DiscardingSwitchingPairCollector {
     ) {
         let _ = (state, coalition, value_without, value_with, contribution);
     }
+
+    fn discards_switching_pairs(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Clone)]
@@ -57,7 +74,7 @@ pub struct SwitchingPair<C: CoalitionSpecifier> {
 
 impl<C: CoalitionSpecifier> SwitchingPair<C> {
     pub fn value(&self) -> f64 {
-        self.value_with - self.value_without
+        marginal_contribution(self.value_without, self.value_with)
     }
 }
 
@@ -378,7 +395,7 @@ impl<C: CoalitionSpecifier> AggregatedSwitchingPair<C> {
     }
 
     pub fn value(&self) -> f64 {
-        self.value_with - self.value_without
+        marginal_contribution(self.value_without, self.value_with)
     }
 
     pub fn is_base_of_switching_pair(&self, other: &SwitchingPair<C>) -> bool {
@@ -391,6 +408,27 @@ impl<C: CoalitionSpecifier> AggregatedSwitchingPair<C> {
 pub trait ShapleyAlgorithm {
     type Output<PD>;
 
+    /// The index that is computed
+    fn weight_type(&self) -> WeightType;
+
+    /// The largest number of players of the games that the algorithm can handle, if it is limited.
+    /// Algorithms that enumerate all coalitions cannot handle games with 64 or more players.
+    fn max_player_count(&self, _simple_game: bool) -> Option<usize> {
+        None
+    }
+
+    /// Whether the algorithm benefits from caches that evaluate every coalition up front. Sampling
+    /// algorithms only look at a small fraction of the coalitions and should return `false`.
+    fn uses_exhaustive_caches(&self) -> bool {
+        true
+    }
+
+    /// The number of threads on which the coalitions are evaluated, see
+    /// `compute_parallel_with_switching_pairs`. Exhaustive caches are built on as many threads.
+    fn thread_count(&self) -> usize {
+        1
+    }
+
     fn compute<G: CooperativeGame>(
         &mut self,
         game: &mut G,
@@ -415,4 +453,45 @@ pub trait ShapleyAlgorithm {
         game: &mut G,
         switching_pair_collector: &mut SPC,
     ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType>;
+
+    /// Like `compute_simple_with_switching_pairs`, but for monotone games, in which every superset
+    /// of a winning coalition is winning. Algorithms that rely on monotonicity only apply to these
+    /// games. By default, the monotonicity is not used.
+    fn compute_monotone_simple_with_switching_pairs<
+        G: SimpleCooperativeGame + MonotoneCooperativeGame,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        self.compute_simple_with_switching_pairs(game, switching_pair_collector)
+    }
+
+    /// Like `compute_with_switching_pairs`, but algorithms may evaluate the coalitions on several
+    /// threads, each with its own clone of the game. The results must not depend on the
+    /// scheduling of the threads. By default, the coalitions are evaluated sequentially.
+    fn compute_parallel_with_switching_pairs<
+        G: CooperativeGame + Clone + Send,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        self.compute_with_switching_pairs(game, switching_pair_collector)
+    }
+
+    /// Like `compute_simple_with_switching_pairs`, but algorithms may evaluate the coalitions on
+    /// several threads, see `compute_parallel_with_switching_pairs`.
+    fn compute_simple_parallel_with_switching_pairs<
+        G: SimpleCooperativeGame + Clone + Send,
+        SPC: SwitchingPairCollector,
+    >(
+        &mut self,
+        game: &mut G,
+        switching_pair_collector: &mut SPC,
+    ) -> Self::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+        self.compute_simple_with_switching_pairs(game, switching_pair_collector)
+    }
 }
@@ -0,0 +1,37 @@
+use std::ops::Range;
+
+/// Splits the coalitions `0..coalition_count` into `thread_count` contiguous ranges and calls `f`
+/// for every range on its own thread, passing a separate clone of `game` to each thread.
+///
+/// The results are returned in the order of the ranges, so they do not depend on how the threads
+/// are scheduled.
+pub(crate) fn map_coalition_ranges<G, T, F>(
+    game: &G,
+    coalition_count: u64,
+    thread_count: usize,
+    f: F,
+) -> Vec<T>
+where
+    G: Clone + Send,
+    T: Send,
+    F: Fn(&mut G, Range<u64>) -> T + Sync,
+{
+    assert!(thread_count > 0, "At least one thread is required");
+    let range_count = (thread_count as u64).min(coalition_count).max(1);
+    let range_size = coalition_count.div_ceil(range_count);
+
+    std::thread::scope(|scope| {
+        let f = &f;
+        let handles = (0..range_count)
+            .map(|i| {
+                let range = i * range_size..((i + 1) * range_size).min(coalition_count);
+                let mut game = game.clone();
+                scope.spawn(move || f(&mut game, range))
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
@@ -1,4 +1,5 @@
 use crate::shapley::PlayerDescriptions;
+use crate::shapley::auxiliary::{WeightType, compute_weights, normalise};
 use log::trace;
 use num_rational::BigRational;
 use num_traits::{FromPrimitive, ToPrimitive, Zero};
@@ -34,11 +35,24 @@ impl<V: Default> CriticalPairCounter<V> {
                 .collect(),
         }
     }
+
+    /// Adds the counts of another counter for the same number of states, e.g. one that counted
+    /// the critical pairs of a different range of coalitions.
+    pub fn merge(&mut self, other: CriticalPairCounter<V>)
+    where
+        V: std::ops::AddAssign,
+    {
+        for (state, other_state) in self.states.iter_mut().zip(other.states) {
+            for (count, other_count) in state.counts.iter_mut().zip(other_state.counts) {
+                *count += other_count;
+            }
+        }
+    }
 }
 
 impl CriticalPairCounter<usize> {
     #[allow(unused)] // TODO: Properly support both integer and floating-point critical pair counting
-    pub fn to_responsibility_values<P: PlayerDescriptions>(
+    pub fn into_responsibility_values<P: PlayerDescriptions>(
         self,
         weights: Vec<BigRational>,
         player_infos: P,
@@ -47,7 +61,7 @@ impl CriticalPairCounter<usize> {
         let mut states = Vec::with_capacity(self.states.len());
 
         for (state, player_info) in self.states.into_iter().zip(player_infos.into_iterator()) {
-            states.push(state.to_responsibility_value(player_info, &weights));
+            states.push(state.into_responsibility_value(player_info, &weights));
         }
 
         ResponsibilityValues { players: states }
@@ -59,7 +73,7 @@ impl CriticalPairCounter<usize> {
 }
 
 impl CriticalPairCounter<f64> {
-    pub fn to_responsibility_values<P: PlayerDescriptions>(
+    pub fn into_responsibility_values<P: PlayerDescriptions>(
         self,
         weights: Vec<BigRational>,
         player_infos: P,
@@ -68,7 +82,7 @@ impl CriticalPairCounter<f64> {
         let mut states = Vec::with_capacity(self.states.len());
 
         for (state, player_info) in self.states.into_iter().zip(player_infos.into_iterator()) {
-            states.push(state.to_responsibility_value(player_info, &weights));
+            states.push(state.into_responsibility_value(player_info, &weights));
         }
 
         ResponsibilityValues { players: states }
@@ -77,6 +91,27 @@ impl CriticalPairCounter<f64> {
     pub fn increase_by(&mut self, state: usize, size: usize, amount: f64) {
         self.states[state].counts[size] += amount;
     }
+
+    /// Attaches the counts as details to values that are not a weighted sum of the counts, such as
+    /// the Deegan-Packel or the Johnston index.
+    pub fn into_responsibility_values_with_values<P: PlayerDescriptions>(
+        self,
+        values: Vec<f64>,
+        player_infos: P,
+    ) -> ResponsibilityValues<P::PlayerType, f64, f64> {
+        let players = self
+            .states
+            .into_iter()
+            .zip(values)
+            .zip(player_infos.into_iterator())
+            .map(|((details, value), player_info)| ResponsibilityValue {
+                player_info,
+                value,
+                details,
+            })
+            .collect();
+        ResponsibilityValues { players }
+    }
 }
 
 #[derive(Debug)]
@@ -84,11 +119,17 @@ pub struct CriticalPairCounterState<V> {
     counts: Vec<V>,
 }
 
+impl<V> CriticalPairCounterState<V> {
+    pub(crate) fn from_counts(counts: Vec<V>) -> Self {
+        Self { counts }
+    }
+}
+
 impl CriticalPairCounterState<usize> {
-    pub fn to_responsibility_value<P>(
+    pub fn into_responsibility_value<P>(
         self,
         player_info: P,
-        weights: &Vec<BigRational>,
+        weights: &[BigRational],
     ) -> ResponsibilityValue<P, BigRational, usize> {
         let mut value = BigRational::zero();
 
@@ -105,17 +146,23 @@ impl CriticalPairCounterState<usize> {
 }
 
 impl CriticalPairCounterState<f64> {
-    pub fn to_responsibility_value<P>(
-        self,
-        player_info: P,
-        weights: &Vec<BigRational>,
-    ) -> ResponsibilityValue<P, f64, f64> {
+    fn weighted_sum(&self, weights: &[BigRational]) -> f64 {
         let mut value = 0.0;
 
         for (weight, &count) in weights.iter().zip(self.counts.iter()) {
             value += weight.to_f64().unwrap() * count;
         }
 
+        value
+    }
+
+    pub fn into_responsibility_value<P>(
+        self,
+        player_info: P,
+        weights: &[BigRational],
+    ) -> ResponsibilityValue<P, f64, f64> {
+        let value = self.weighted_sum(weights);
+
         ResponsibilityValue {
             player_info,
             value,
@@ -151,6 +198,48 @@ where
         None
     }
 }
+impl<P: Clone> ResponsibilityValues<P, f64, f64> {
+    /// Recomputes the values for another coalition-size-based index from the critical pair counts
+    /// that are stored in the details, without evaluating the game again. Panics if the index is
+    /// not size-based.
+    pub fn reweighted(&self, weight_type: WeightType) -> Self {
+        let weights = compute_weights(self.players.len(), weight_type);
+        let mut values = self
+            .players
+            .iter()
+            .map(|p| p.details.weighted_sum(&weights))
+            .collect::<Vec<_>>();
+        if weight_type.is_normalised() {
+            normalise(&mut values);
+        }
+
+        ResponsibilityValues {
+            players: self
+                .players
+                .iter()
+                .zip(values)
+                .map(|(p, value)| ResponsibilityValue {
+                    player_info: p.player_info.clone(),
+                    value,
+                    details: CriticalPairCounterState {
+                        counts: p.details.counts.clone(),
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<P> ResponsibilityValues<P, f64, f64> {
+    pub(crate) fn normalise(&mut self) {
+        let mut values = self.players.iter().map(|p| p.value).collect::<Vec<_>>();
+        normalise(&mut values);
+        for (player, value) in self.players.iter_mut().zip(values) {
+            player.value = value;
+        }
+    }
+}
+
 impl<P, V, VD> ResponsibilityValues<P, V, VD> {
     pub fn map_player_info<P2, F: FnMut(P) -> P2>(
         self,
@@ -176,3 +265,38 @@ pub struct ResponsibilityValue<P, V, VD> {
     pub value: V,
     pub details: CriticalPairCounterState<VD>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    pub fn half_width(&self) -> f64 {
+        (self.upper - self.lower) / 2.0
+    }
+}
+
+/// Responsibility values that were estimated by sampling. The confidence intervals are stored in
+/// the same order as the players in `values`.
+#[derive(Debug)]
+pub struct SampledResponsibilityValues<P> {
+    pub values: ResponsibilityValues<P, f64, f64>,
+    pub confidence_intervals: Vec<ConfidenceInterval>,
+    pub confidence_level: f64,
+    pub samples_per_player: usize,
+    pub converged: bool,
+}
+
+impl<P> SampledResponsibilityValues<P> {
+    pub fn map_player_info<P2, F: FnMut(P) -> P2>(self, map: F) -> SampledResponsibilityValues<P2> {
+        SampledResponsibilityValues {
+            values: self.values.map_player_info(map),
+            confidence_intervals: self.confidence_intervals,
+            confidence_level: self.confidence_level,
+            samples_per_player: self.samples_per_player,
+            converged: self.converged,
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use super::{ResponsibilityOptions, StateBasedOutput, build_model, compute_for_nonstochastic_game};
+use crate::shapley::{ShapleyAlgorithm, SwitchingPairCollector};
+use crate::state_based::grouping::{
+    GroupExtractionScheme, GroupsAndAuxiliary, StateGroups, VectorStateGroups,
+};
+use crate::state_based::refinement::GroupBlockingProvider;
+use crate::{
+    BackwardResponsibilityKind, Counterexample, CounterexampleStep, CounterexampleValue,
+    PrismModel, PrismProperty, ResponsibilityError,
+};
+use log::{info, trace, warn};
+use prism_parser::CharacterToLineMap;
+use probabilistic_model_algorithms::deterministic_games::winning_region;
+use probabilistic_models::{
+    Action, ActionCollection, ActionVector, AtomicProposition, Builder, Context, Distribution,
+    DistributionBuilder, InitialStates, IterFunctions, IterProbabilisticModel,
+    SingleStateDistribution, Successor, TwoPlayer, TwoPlayerNonstochasticGame, Valuation,
+    VectorPredecessors,
+};
+use probabilistic_properties::Query;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Computes the backward responsibility of the states along a counterexample.
+///
+/// The game is restricted to the counterexample: States on the counterexample that are not part of
+/// the coalition follow the counterexample, whereas states in the coalition may deviate from it.
+/// Depending on `kind`, states off the counterexample either cooperate with the coalition or act
+/// against it. Only non-probabilistic models are supported.
+pub fn compute_backward_for_prism<
+    G: GroupExtractionScheme,
+    S: ShapleyAlgorithm,
+    B: GroupBlockingProvider,
+    SPC: SwitchingPairCollector,
+>(
+    mut prism_model: PrismModel,
+    character_to_line_map: &CharacterToLineMap,
+    prism_property: PrismProperty,
+    shapley: &mut S,
+    options: ResponsibilityOptions<G, B, SPC>,
+    counterexample: &Counterexample,
+    kind: BackwardResponsibilityKind,
+) -> Result<StateBasedOutput<S::Output<String>, VectorStateGroups>, ResponsibilityError> {
+    let ResponsibilityOptions {
+        grouping_scheme,
+        group_blocking_provider,
+        switching_pair_collector,
+        constants,
+    } = options;
+    let (model, property) = build_model(
+        &mut prism_model,
+        character_to_line_map,
+        prism_property,
+        grouping_scheme,
+        constants,
+    );
+    if model.get_model_features().probabilism {
+        return Err(ResponsibilityError::BackwardRequiresNonprobabilisticModel);
+    }
+
+    trace!("Transforming transition system into game");
+    let mut game: TwoPlayerNonstochasticGame<VectorPredecessors> = model
+        .into_iter()
+        .map_owners(|_| TwoPlayer::PlayerTwo)
+        .collect();
+
+    trace!("Restricting game to counterexample");
+    let run_actions = resolve_counterexample(&game, counterexample)?;
+    let restriction = restrict_to_counterexample(&mut game, &run_actions);
+    info!(
+        "The coalition can deviate from the counterexample in {} of {} states",
+        restriction.deviation_states.len(),
+        run_actions.len()
+    );
+
+    trace!("Computing state groups");
+    let grouping = grouping_scheme.create_groups(&mut game, &property);
+    let grouping = restrict_grouping(grouping, &restriction, kind);
+    info!("There are {} state groups", grouping.groups.get_count());
+
+    if is_winning_without_deviations(&mut game, &property, &grouping) {
+        warn!(
+            "The property is satisfied even if no state deviates from the counterexample, so all states have responsibility 0"
+        );
+    }
+
+    compute_for_nonstochastic_game(
+        game,
+        &property,
+        grouping,
+        group_blocking_provider,
+        shapley,
+        switching_pair_collector,
+    )
+}
+
+struct CounterexampleRestriction {
+    // The states that follow the counterexample unless they are part of the coalition
+    follows_counterexample: Vec<bool>,
+    // The states from which the coalition may deviate from the counterexample, indexed by state
+    can_deviate: Vec<bool>,
+    deviation_states: Vec<usize>,
+}
+
+/// Finds the states visited by the counterexample and returns, for every state that has a successor
+/// on the counterexample, the indices of the actions that are used by the counterexample
+fn resolve_counterexample(
+    game: &TwoPlayerNonstochasticGame<VectorPredecessors>,
+    counterexample: &Counterexample,
+) -> Result<BTreeMap<usize, BTreeSet<usize>>, ResponsibilityError> {
+    let mut steps = counterexample.steps.iter();
+    // Parsing ensures that the counterexample contains at least one state.
+    let first_step = steps.next().unwrap();
+    let mut initial_candidates = Vec::new();
+    for &state in game.initial_states.iter() {
+        if state_matches(game, state, first_step)? {
+            initial_candidates.push(state);
+        }
+    }
+    let mut current = unique_match(initial_candidates, first_step, 0)?;
+
+    let mut run_actions: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for (position, step) in steps.enumerate() {
+        let mut candidates = Vec::new();
+        let mut actions_by_target: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+        for (action_index, action) in game.states[current].actions.iter().enumerate() {
+            if let Some(name) = &step.action
+                && game.action_names[action.action_name_index] != *name
+            {
+                continue;
+            }
+            for successor in action.successors.iter() {
+                if state_matches(game, successor.index, step)? {
+                    if !candidates.contains(&successor.index) {
+                        candidates.push(successor.index);
+                    }
+                    actions_by_target
+                        .entry(successor.index)
+                        .or_default()
+                        .insert(action_index);
+                }
+            }
+        }
+        let next = unique_match(candidates, step, position + 1)?;
+        run_actions
+            .entry(current)
+            .or_default()
+            .extend(&actions_by_target[&next]);
+        current = next;
+    }
+    Ok(run_actions)
+}
+
+fn unique_match(
+    candidates: Vec<usize>,
+    step: &CounterexampleStep,
+    position: usize,
+) -> Result<usize, ResponsibilityError> {
+    match candidates.len() {
+        0 => Err(ResponsibilityError::UnmatchedCounterexampleState {
+            position: position + 1,
+            state: step.to_string(),
+        }),
+        1 => Ok(candidates[0]),
+        _ => Err(ResponsibilityError::AmbiguousCounterexampleState {
+            position: position + 1,
+            state: step.to_string(),
+        }),
+    }
+}
+
+fn state_matches(
+    game: &TwoPlayerNonstochasticGame<VectorPredecessors>,
+    state: usize,
+    step: &CounterexampleStep,
+) -> Result<bool, ResponsibilityError> {
+    let context = &game.valuation_context;
+    let valuation = &game.states[state].valuation;
+    for (name, value) in &step.assignments {
+        let Some(index) = context.get_index_by_name(name) else {
+            return Err(ResponsibilityError::UnknownCounterexampleVariable(
+                name.clone(),
+            ));
+        };
+        let matches = match *value {
+            CounterexampleValue::Bool(b) => {
+                context.is_bool(index) && valuation.evaluate_bool(index) == b
+            }
+            CounterexampleValue::Int(i) => {
+                if context.is_bounded_int(index) {
+                    valuation.evaluate_bounded_int(index) == i
+                } else if context.is_unbounded_int(index) {
+                    valuation.evaluate_unbounded_int(index) == i
+                } else if context.is_float(index) {
+                    valuation.evaluate_float(index) == i as f64
+                } else {
+                    false
+                }
+            }
+            CounterexampleValue::Float(f) => {
+                context.is_float(index) && valuation.evaluate_float(index) == f
+            }
+        };
+        if !matches {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Restricts every state with a successor on the counterexample to the actions used by the
+/// counterexample. If the state has other actions, it additionally gets an action to a copy of
+/// itself, from which all original actions are available. The copies are always owned by player
+/// one, so player two never benefits from deviating and effectively follows the counterexample.
+fn restrict_to_counterexample(
+    game: &mut TwoPlayerNonstochasticGame<VectorPredecessors>,
+    run_actions: &BTreeMap<usize, BTreeSet<usize>>,
+) -> CounterexampleRestriction {
+    let mut follows_counterexample = vec![false; game.states.len()];
+    let mut can_deviate = vec![false; game.states.len()];
+    let mut deviation_states = Vec::new();
+
+    let deviate_action_index = game.get_action_index_or_add("deviate_from_counterexample");
+    for (&state, actions) in run_actions {
+        follows_counterexample[state] = true;
+        if actions.len() == game.states[state].actions.get_number_of_actions() {
+            continue;
+        }
+
+        can_deviate[state] = true;
+        let deviation_state = game.states.len();
+        deviation_states.push(deviation_state);
+        let copy = game.states[state].clone();
+
+        let mut restricted_actions = ActionVector::<SingleStateDistribution>::get_builder();
+        for &action in actions {
+            restricted_actions.add_action(game.states[state].actions.get_action(action).clone());
+        }
+        let mut deviation = SingleStateDistribution::get_builder();
+        deviation.add_successor(Successor {
+            index: deviation_state,
+            probability: 1.0,
+        });
+        restricted_actions.add_action(Action {
+            successors: deviation.finish(),
+            action_name_index: deviate_action_index,
+        });
+        game.states[state].actions = restricted_actions.finish();
+        game.states.push(copy);
+    }
+    follows_counterexample.resize(game.states.len(), false);
+    can_deviate.resize(game.states.len(), false);
+    game.rebuild_predecessors();
+
+    CounterexampleRestriction {
+        follows_counterexample,
+        can_deviate,
+        deviation_states,
+    }
+}
+
+/// Only keeps the states in the groups that can deviate from the counterexample. States that are
+/// not on the counterexample (or where it ends) behave according to `kind`, except for the copies
+/// created for deviations, which always help.
+fn restrict_grouping<GR: StateGroups>(
+    grouping: GroupsAndAuxiliary<GR>,
+    restriction: &CounterexampleRestriction,
+    kind: BackwardResponsibilityKind,
+) -> GroupsAndAuxiliary<VectorStateGroups> {
+    let mut builder = VectorStateGroups::get_builder();
+    for group in 0..grouping.groups.get_count() {
+        let states = grouping
+            .groups
+            .get_states(group)
+            .filter(|&s| s < restriction.can_deviate.len() && restriction.can_deviate[s])
+            .collect::<Vec<_>>();
+        if states.is_empty() {
+            continue;
+        }
+        builder.create_group_from_vec(states, grouping.groups.get_label(group));
+    }
+    for state in grouping.groups.get_dummy_states() {
+        builder.add_dummy_state(state);
+    }
+
+    let mut always_helping = grouping.always_helping;
+    let mut always_adversarial = grouping.always_adversarial;
+    always_helping.extend(&restriction.deviation_states);
+    let first_deviation_state = restriction.can_deviate.len() - restriction.deviation_states.len();
+    for state in (0..first_deviation_state).filter(|&s| !restriction.follows_counterexample[s]) {
+        match kind {
+            BackwardResponsibilityKind::Optimistic => always_helping.push(state),
+            BackwardResponsibilityKind::Pessimistic => always_adversarial.push(state),
+        }
+    }
+
+    GroupsAndAuxiliary::with_auxiliary(builder.finish(), always_helping, always_adversarial)
+}
+
+/// Checks whether the empty coalition wins, i.e. whether the counterexample actually violates the
+/// property
+fn is_winning_without_deviations(
+    game: &mut TwoPlayerNonstochasticGame<VectorPredecessors>,
+    property: &Query<f64, f64, AtomicProposition>,
+    grouping: &GroupsAndAuxiliary<VectorStateGroups>,
+) -> bool {
+    for state in grouping.groups.get_dummy_states() {
+        game.states[state].owner = TwoPlayer::PlayerOne;
+    }
+    for &state in &grouping.always_helping {
+        game.states[state].owner = TwoPlayer::PlayerOne;
+    }
+    for &state in &grouping.always_adversarial {
+        game.states[state].owner = TwoPlayer::PlayerTwo;
+    }
+    for group in 0..grouping.groups.get_count() {
+        for state in grouping.groups.get_states(group) {
+            game.states[state].owner = TwoPlayer::PlayerTwo;
+        }
+    }
+
+    let winning_region = winning_region(game, property);
+    game.initial_states
+        .iter()
+        .all(|&state| winning_region.contains(state))
+}
@@ -2,11 +2,12 @@ use crate::shapley::{ResponsibilityValues, SwitchingPairCollection};
 use crate::state_based::grouping::GroupsAndAuxiliary;
 use crate::{PrismModel, PrismProperty};
 use chumsky::prelude::SimpleSpan;
-use prism_model::{Expression, VariableRange, VariableReference};
+use prism_model::{Expression, RewardsElement, RewardsTarget, VariableRange, VariableReference};
 use probabilistic_models::{
     Action, ActionCollection, AtomicProposition, AtomicPropositions, Builder, Context,
     Distribution, DistributionBuilder, ModelTypes, Predecessors, PredecessorsBuilder,
-    ProbabilisticModel, State, Successor, TwoPlayer, Valuation, VectorPredecessors,
+    ProbabilisticModel, RewardStructure, State, Successor, TwoPlayer, Valuation,
+    VectorPredecessors,
 };
 use probabilistic_properties::Query;
 use std::collections::HashMap;
@@ -65,6 +66,7 @@ impl super::GroupExtractionScheme for ActionGroupExtractionScheme {
 
         let mut last_line = None;
         let mut in_line_counter = 0;
+        let mut generated_names = Vec::new();
         prism_model.name_unnamed_actions_with_custom_name(|_, l| {
             let line = character_to_line.get_line(l.start);
             if last_line == Some(line) {
@@ -78,9 +80,31 @@ impl super::GroupExtractionScheme for ActionGroupExtractionScheme {
             } else {
                 format!("_{}", in_line_counter)
             };
-            format!("unnamed_action_line_{}{}", line, suffix)
+            let name = format!("unnamed_action_line_{}{}", line, suffix);
+            generated_names.push(name.clone());
+            name
         });
 
+        // Rewards for unnamed actions now apply to the actions that were named above
+        for rewards in &mut prism_model.rewards.rewards {
+            let mut entries = Vec::with_capacity(rewards.entries.len());
+            for entry in rewards.entries.drain(..) {
+                if let RewardsTarget::Action(None) = entry.target {
+                    for name in &generated_names {
+                        entries.push(RewardsElement::with_action(
+                            entry.condition.clone(),
+                            entry.value.clone(),
+                            Some(Identifier::new_potentially_reserved(name.clone(), span).unwrap()),
+                            entry.span,
+                        ));
+                    }
+                } else {
+                    entries.push(entry);
+                }
+            }
+            rewards.entries = entries;
+        }
+
         for module in &prism_model.modules.modules {
             for command in &module.commands {
                 let span = match &command.action {
@@ -107,7 +131,7 @@ impl super::GroupExtractionScheme for ActionGroupExtractionScheme {
     fn create_groups<M: ModelTypes<Owners = TwoPlayer, Predecessors = VectorPredecessors>>(
         &mut self,
         game: &mut ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) -> GroupsAndAuxiliary<Self::GroupType> {
         let _ = property;
 
@@ -137,6 +161,14 @@ impl super::GroupExtractionScheme for ActionGroupExtractionScheme {
         let back_action_index = game.action_names.len();
         game.action_names.push("do_not_use_action".to_string());
 
+        // The rewards of an action are earned when it is executed, either from the questionmark
+        // state or from the adversarial state. All other steps are auxiliary and have no reward.
+        let original_rewards = std::mem::take(&mut game.reward_structures);
+        let mut reward_structures: Vec<RewardStructure> = original_rewards
+            .iter()
+            .map(|rewards| RewardStructure::new(rewards.name.clone()))
+            .collect();
+
         for state_index in 0..game.states.len() {
             let state = &game.states[state_index];
             let base_owner = state.owner;
@@ -251,6 +283,13 @@ impl super::GroupExtractionScheme for ActionGroupExtractionScheme {
                         predecessors: <<M::Predecessors as Predecessors>::Builder>::create()
                             .finish(),
                     };
+                    for (original, rewards) in original_rewards.iter().zip(&mut reward_structures) {
+                        rewards.set_action_reward(
+                            game.states.len(),
+                            1,
+                            original.step_reward(state_index, action_index),
+                        );
+                    }
                     helper_state_group.push(game.states.len());
                     game.states.push(questionmark_state);
                 }
@@ -281,11 +320,21 @@ impl super::GroupExtractionScheme for ActionGroupExtractionScheme {
                     owner: base_owner,
                     predecessors: <<M::Predecessors as Predecessors>::Builder>::create().finish(),
                 };
+                for (original, rewards) in original_rewards.iter().zip(&mut reward_structures) {
+                    for action_index in 0..action_count {
+                        rewards.set_action_reward(
+                            game.states.len(),
+                            action_index,
+                            original.step_reward(state_index, action_index),
+                        );
+                    }
+                }
                 adversary_state_group.push(game.states.len());
                 game.states.push(adversarial_state);
             }
         }
 
+        game.reward_structures = reward_structures;
         game.rebuild_predecessors();
 
         let mut builder = Self::GroupType::get_builder();
@@ -27,7 +27,7 @@ impl IndividualGroupExtractionScheme {
         &self,
         builder: &mut VectorStateGroupBuilder,
         game: &mut ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) {
         let relevant_states = super::RelevantStates::compute(game, property);
 
@@ -67,7 +67,7 @@ impl super::GroupExtractionScheme for IndividualGroupExtractionScheme {
     fn create_groups<M: ModelTypes<Owners = TwoPlayer, Predecessors = VectorPredecessors>>(
         &mut self,
         game: &mut ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) -> GroupsAndAuxiliary<Self::GroupType> {
         let mut builder = Self::GroupType::get_builder();
 
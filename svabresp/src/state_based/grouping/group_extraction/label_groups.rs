@@ -84,7 +84,7 @@ impl super::GroupExtractionScheme for LabelGroupExtractionScheme {
     fn create_groups<M: ModelTypes<Owners = TwoPlayer, Predecessors = VectorPredecessors>>(
         &mut self,
         game: &mut ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) -> GroupsAndAuxiliary<Self::GroupType> {
         let _ = property;
         let label_atomic_propositions = self.label_atomic_propositions.as_ref().unwrap();
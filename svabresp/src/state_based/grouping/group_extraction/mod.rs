@@ -42,7 +42,7 @@ pub trait GroupExtractionScheme {
     fn create_groups<M: ModelTypes<Owners = TwoPlayer, Predecessors = VectorPredecessors>>(
         &mut self,
         game: &mut ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) -> GroupsAndAuxiliary<Self::GroupType>;
 
     fn get_syntax_elements<S: AsRef<str>>(
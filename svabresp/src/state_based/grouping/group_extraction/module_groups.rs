@@ -3,8 +3,8 @@ use crate::state_based::grouping::GroupsAndAuxiliary;
 use crate::{PrismModel, PrismProperty};
 use chumsky::span::SimpleSpan;
 use prism_model::{
-    Assignment, Command, Expression, Identifier, Module, Update, VariableInfo, VariableRange,
-    VariableReference,
+    Assignment, Command, Expression, Identifier, Module, RewardsElement, RewardsTarget, Update,
+    VariableInfo, VariableRange, VariableReference,
 };
 use probabilistic_models::{
     AtomicProposition, Context, ModelTypes, ProbabilisticModel, TwoPlayer, Valuation,
@@ -126,6 +126,8 @@ impl super::GroupExtractionScheme for ModuleGroupExtractionScheme {
             ));
             let execute_action = format!("execute_module_{}", module_index);
             let mut guard = Expression::Bool(false, span);
+            // Named actions keep their names, so that action rewards still refer to them
+            let mut activated_actions = Vec::new();
             for command in &mut module.commands {
                 if command.action.is_none()
                     || !action_infos[&command.action.as_ref().unwrap().name].is_synchronising()
@@ -135,7 +137,13 @@ impl super::GroupExtractionScheme for ModuleGroupExtractionScheme {
                         Box::new(command.guard.clone()),
                         span,
                     );
-                    command.action = Some(Identifier::new(execute_action.clone(), span).unwrap());
+                    let action = command.action.get_or_insert_with(|| {
+                        Identifier::new(execute_action.clone(), span).unwrap()
+                    });
+                    let name = &action.name;
+                    if !activated_actions.contains(name) {
+                        activated_actions.push(name.clone());
+                    }
                 }
             }
 
@@ -162,27 +170,29 @@ impl super::GroupExtractionScheme for ModuleGroupExtractionScheme {
             ));
             scheduler.commands.push(select_command);
 
-            let mut activate_command = Command::new(
-                Some(Identifier::new(execute_action.clone(), span).unwrap()),
-                span,
-                Expression::Equals(
-                    Box::new(Expression::VarOrConst(selected_module_variable, span)),
-                    Box::new(Expression::Int(module_index as i64 + 1, span)),
+            for action in activated_actions {
+                let mut activate_command = Command::new(
+                    Some(Identifier::new(action, span).unwrap()),
                     span,
-                ),
-                span,
-            );
-            activate_command.updates.push(Update::with_assignments(
-                Expression::Int(1, span),
-                vec![Assignment::new(
-                    selected_module_variable,
-                    Expression::Int(0, span),
+                    Expression::Equals(
+                        Box::new(Expression::VarOrConst(selected_module_variable, span)),
+                        Box::new(Expression::Int(module_index as i64 + 1, span)),
+                        span,
+                    ),
                     span,
+                );
+                activate_command.updates.push(Update::with_assignments(
+                    Expression::Int(1, span),
+                    vec![Assignment::new(
+                        selected_module_variable,
+                        Expression::Int(0, span),
+                        span,
+                        span,
+                    )],
                     span,
-                )],
-                span,
-            ));
-            scheduler.commands.push(activate_command);
+                ));
+                scheduler.commands.push(activate_command);
+            }
         }
 
         let mut index = 1 + prism_model.modules.modules.len();
@@ -239,6 +249,42 @@ impl super::GroupExtractionScheme for ModuleGroupExtractionScheme {
             }
         }
 
+        // Every step of the original model now consists of selecting a module and executing it,
+        // so state rewards are only earned when selecting. Unnamed actions were renamed above.
+        let module_count = prism_model.modules.modules.len();
+        for rewards in &mut prism_model.rewards.rewards {
+            let mut entries = Vec::with_capacity(rewards.entries.len());
+            for entry in rewards.entries.drain(..) {
+                match entry.target {
+                    RewardsTarget::State => {
+                        let condition = Expression::Conjunction(
+                            Box::new(entry.condition),
+                            Box::new(Expression::Equals(
+                                Box::new(Expression::VarOrConst(selected_module_variable, span)),
+                                Box::new(Expression::Int(0, span)),
+                                span,
+                            )),
+                            span,
+                        );
+                        entries.push(RewardsElement::new(condition, entry.value, entry.span));
+                    }
+                    RewardsTarget::Action(None) => {
+                        for module_index in 0..module_count {
+                            let action = format!("execute_module_{}", module_index);
+                            entries.push(RewardsElement::with_action(
+                                entry.condition.clone(),
+                                entry.value.clone(),
+                                Some(Identifier::new(action, span).unwrap()),
+                                entry.span,
+                            ));
+                        }
+                    }
+                    RewardsTarget::Action(Some(_)) => entries.push(entry),
+                }
+            }
+            rewards.entries = entries;
+        }
+
         prism_model.modules.add(scheduler).unwrap();
 
         self.group_count = Some(index);
@@ -248,7 +294,7 @@ impl super::GroupExtractionScheme for ModuleGroupExtractionScheme {
     fn create_groups<M: ModelTypes<Owners = TwoPlayer, Predecessors = VectorPredecessors>>(
         &mut self,
         game: &mut ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) -> GroupsAndAuxiliary<Self::GroupType> {
         let _ = property;
 
@@ -13,11 +13,15 @@ pub struct RelevantStates {
 impl RelevantStates {
     pub fn compute<M: ModelTypes<Owners = TwoPlayer, Predecessors = VectorPredecessors>>(
         model: &mut probabilistic_models::ProbabilisticModel<M>,
-        property: &Query<i64, f64, AtomicProposition>,
+        property: &Query<f64, f64, AtomicProposition>,
     ) -> Self {
         let mut relevant_states = HashSet::new();
         let mut dummy_states = Vec::new();
-        if model.get_model_features().probabilism {
+        // Relevance is determined qualitatively, which is not possible for probabilities and
+        // expected rewards. In these cases, every state with a choice is relevant.
+        let quantitative =
+            model.get_model_features().probabilism || matches!(property, Query::RewardValue { .. });
+        if quantitative {
             for (i, state) in model.states.iter().enumerate() {
                 if state.actions.get_number_of_actions() <= 1 {
                     dummy_states.push(i);
@@ -17,6 +17,7 @@ pub trait StateGroups {
     fn to_vector_state_groups(self) -> VectorStateGroups;
 }
 
+#[derive(Clone)]
 pub struct VectorStateGroups {
     groups: Vec<VectorStateGroup>,
     dummy_states: VectorStateGroup,
@@ -28,6 +29,7 @@ impl VectorStateGroups {
     }
 }
 
+#[derive(Clone)]
 pub struct VectorStateGroup {
     states: Vec<usize>,
     label: String,
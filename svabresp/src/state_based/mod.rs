@@ -1,14 +1,21 @@
 use log::{info, trace};
-use probabilistic_model_algorithms::traits::{StochasticGameAlgorithm, StochasticGameAndSolver};
+use probabilistic_model_algorithms::traits::{
+    SolvableStochasticGame, StochasticGameAlgorithm, StochasticGameAndSolver,
+};
 use probabilistic_models::{
-    IterFunctions, IterProbabilisticModel, MdpType, TwoPlayer, Valuation, VectorPredecessors,
+    AtomicProposition, IterFunctions, IterProbabilisticModel, Mdp, MdpType, TwoPlayer,
+    TwoPlayerNonstochasticGame, Valuation, VectorPredecessors,
 };
+use probabilistic_properties::{Bound, BoundOperator, Query};
 
 mod nonstochastic_game;
 pub use nonstochastic_game::StateBasedResponsibilityNonstochasticGame;
 
 pub mod grouping;
 
+mod backward;
+pub use backward::compute_backward_for_prism;
+
 mod group_names;
 pub use group_names::GroupNames;
 
@@ -16,26 +23,49 @@ pub mod refinement;
 mod stochastic_game;
 
 use crate::shapley::{
-    GameValueCache, MinimalCoalitionCache, ShapleyAlgorithm, SwitchingPairCollector,
+    CooperativeGame, GameValueCache, MinimalCoalitionCache, MonotoneCooperativeGame,
+    PlayerDescriptions, ShapleyAlgorithm, SimpleCooperativeGame, SparseGameValueCache,
+    SwitchingPairCollector, ThresholdGame,
 };
-use crate::state_based::grouping::{StateGroups, VectorStateGroups};
+use crate::state_based::grouping::{GroupsAndAuxiliary, StateGroups, VectorStateGroups};
 use crate::state_based::refinement::GroupBlockingProvider;
-use crate::{PrismModel, PrismProperty};
+use crate::{PrismModel, PrismProperty, ResponsibilityError};
 use grouping::GroupExtractionScheme;
 use prism_model_builder::UserProvidedConstValue;
 use prism_parser::CharacterToLineMap;
 use probabilistic_model_algorithms::deterministic_games::{
-    BuechiAlgorithmCollection, NonstochasticGameAlgorithm,
+    BuechiAlgorithmCollection, NextAlgorithmCollection, NonstochasticGameAlgorithm,
     NonstochasticGameAndSolverExternalOwners, ReachabilityAlgorithmCollection,
     SafetyAlgorithmCollection,
 };
+use probabilistic_model_algorithms::value_iteration::stochastic_game_rewards::StochasticGameRewardAlgorithm;
 use probabilistic_model_algorithms::value_iteration::stochastic_games::StochasticGameValueIterationAlgorithm;
 
+// The number of game values that are retained when the coalitions are not evaluated exhaustively
+const SPARSE_CACHE_CAPACITY: usize = 1 << 20;
+
+// Value iteration only approximates probabilities, so probabilities this close to a threshold are
+// considered to be equal to it
+const THRESHOLD_TOLERANCE: f64 = 0.000_001;
+
 pub struct StateBasedOutput<O, G: StateGroups> {
     pub shapley_output: O,
     pub grouping: G,
 }
 
+/// The inputs of a responsibility computation apart from the model, the property and the algorithm
+pub struct ResponsibilityOptions<
+    'a,
+    G: GroupExtractionScheme,
+    B: GroupBlockingProvider,
+    SPC: SwitchingPairCollector,
+> {
+    pub grouping_scheme: &'a mut G,
+    pub group_blocking_provider: B,
+    pub switching_pair_collector: &'a mut SPC,
+    pub constants: std::collections::HashMap<String, UserProvidedConstValue>,
+}
+
 pub fn compute_for_prism<
     G: GroupExtractionScheme,
     S: ShapleyAlgorithm,
@@ -44,42 +74,29 @@ pub fn compute_for_prism<
 >(
     mut prism_model: PrismModel,
     character_to_line_map: &CharacterToLineMap,
-    mut prism_property: PrismProperty,
-    grouping_scheme: &mut G,
-    group_blocking_provider: B,
+    prism_property: PrismProperty,
     shapley: &mut S,
-    constants: std::collections::HashMap<String, UserProvidedConstValue>,
-    switching_pair_collector: &mut SPC,
-) -> StateBasedOutput<S::Output<String>, VectorStateGroups> {
-    let mut atomic_propositions = Vec::new();
-    grouping_scheme.transform_prism(
+    options: ResponsibilityOptions<G, B, SPC>,
+) -> Result<StateBasedOutput<S::Output<String>, VectorStateGroups>, ResponsibilityError> {
+    let ResponsibilityOptions {
+        grouping_scheme,
+        group_blocking_provider,
+        switching_pair_collector,
+        constants,
+    } = options;
+    let (model, property) = build_model(
         &mut prism_model,
-        &mut prism_property,
-        &mut atomic_propositions,
         character_to_line_map,
+        prism_property,
+        grouping_scheme,
+        constants,
     );
-    let properties = tiny_pmc::building::prism_objectives_to_atomic_propositions(
-        &mut atomic_propositions,
-        vec![prism_property],
-    );
-    trace!("Building model");
-    let builder_results = prism_model_builder::build_model::<_, MdpType<VectorPredecessors>, _>(
-        &mut prism_model,
-        &atomic_propositions[..],
-        properties.into_iter(),
-        &constants,
-    )
-    .unwrap();
-
-    let properties = builder_results.properties;
-    assert_eq!(properties.len(), 1);
-    let property = properties.into_iter().nth(0).unwrap();
-
-    let model = builder_results.model;
     let features = model.get_model_features();
     if features.probabilism {
         info!("Model exhibits probabilistic behaviour");
-
+    }
+    // Expected rewards are computed on stochastic games, even if the model is not probabilistic
+    if features.probabilism || matches!(property, Query::RewardValue { .. }) {
         let mut game: probabilistic_models::TwoPlayerStochasticGame<VectorPredecessors> = model
             .into_iter()
             .map_owners(|_| TwoPlayer::PlayerTwo)
@@ -89,34 +106,52 @@ pub fn compute_for_prism<
 
         if let Some(solver) = StochasticGameValueIterationAlgorithm::create_if_compatible(&property)
         {
-            let solvable_game = StochasticGameAndSolver::new(game, solver);
+            let objective = solver.player_one_objective();
+            let bound = solver.bound().cloned();
+            let Ok(solvable_game) = StochasticGameAndSolver::new(game, solver);
 
-            let mut coop_game = stochastic_game::StateBasedResponsibilityStochasticGame::new(
+            let coop_game = stochastic_game::StateBasedResponsibilityStochasticGame::new(
                 solvable_game,
                 grouping.groups,
                 grouping.always_helping,
                 grouping.always_adversarial,
-            );
-
-            // TODO: Support blocking?
-            // let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
-            // let coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+            )
+            .with_objective(objective);
 
-            let mut cached_value_game = GameValueCache::create(&mut coop_game);
+            compute_for_stochastic_game_in_parallel(
+                coop_game,
+                bound,
+                group_blocking_provider,
+                shapley,
+                switching_pair_collector,
+            )
+        } else if let Some(solver) = StochasticGameRewardAlgorithm::create_if_compatible(&property)
+        {
+            let objective = solver.player_one_objective();
+            let solvable_game = StochasticGameAndSolver::new(game, solver)
+                .map_err(|error| ResponsibilityError::UnknownRewardStructure(error.name))?;
 
-            let shapley_output = shapley
-                .compute_with_switching_pairs(&mut cached_value_game, switching_pair_collector);
+            let coop_game = stochastic_game::StateBasedResponsibilityStochasticGame::new(
+                solvable_game,
+                grouping.groups,
+                grouping.always_helping,
+                grouping.always_adversarial,
+            )
+            .with_reward_objective(objective);
 
-            StateBasedOutput {
-                shapley_output,
-                grouping: coop_game.grouping.to_vector_state_groups(),
-            }
+            compute_for_stochastic_game_in_parallel(
+                coop_game,
+                None,
+                group_blocking_provider,
+                shapley,
+                switching_pair_collector,
+            )
         } else {
             panic!("Unsupported property type");
         }
     } else {
         trace!("Transforming transition system into game");
-        let mut game: probabilistic_models::TwoPlayerNonstochasticGame<VectorPredecessors> = model
+        let mut game: TwoPlayerNonstochasticGame<VectorPredecessors> = model
             .into_iter()
             .map_owners(|_| TwoPlayer::PlayerTwo)
             .collect();
@@ -140,75 +175,368 @@ pub fn compute_for_prism<
             }
         }
 
-        if let Some(solver) = ReachabilityAlgorithmCollection::create_if_compatible(&property) {
-            let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
-            let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
-                solvable_game,
-                grouping.groups,
-                grouping.always_helping,
-                grouping.always_adversarial,
-            );
+        compute_for_nonstochastic_game(
+            game,
+            &property,
+            grouping,
+            group_blocking_provider,
+            shapley,
+            switching_pair_collector,
+        )
+    }
+}
 
-            let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
+fn build_model<G: GroupExtractionScheme>(
+    prism_model: &mut PrismModel,
+    character_to_line_map: &CharacterToLineMap,
+    mut prism_property: PrismProperty,
+    grouping_scheme: &mut G,
+    constants: std::collections::HashMap<String, UserProvidedConstValue>,
+) -> (Mdp<VectorPredecessors>, Query<f64, f64, AtomicProposition>) {
+    let mut atomic_propositions = Vec::new();
+    grouping_scheme.transform_prism(
+        prism_model,
+        &mut prism_property,
+        &mut atomic_propositions,
+        character_to_line_map,
+    );
+    let properties = tiny_pmc::building::prism_objectives_to_atomic_propositions(
+        &mut atomic_propositions,
+        vec![prism_property],
+    );
+    trace!("Building model");
+    let builder_results = prism_model_builder::build_model::<_, MdpType<VectorPredecessors>, _>(
+        prism_model,
+        &atomic_propositions[..],
+        properties.into_iter(),
+        &constants,
+    )
+    .unwrap();
 
-            let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+    let properties = builder_results.properties;
+    assert_eq!(properties.len(), 1);
+    let property = properties.into_iter().next().unwrap();
 
-            let mut cached_coop_game = MinimalCoalitionCache::create(&mut coop_game);
+    (builder_results.model, property)
+}
 
-            let shapley_output = shapley.compute_simple_with_switching_pairs(
-                &mut cached_coop_game,
-                switching_pair_collector,
-            );
-            StateBasedOutput {
-                shapley_output,
-                grouping: coop_game.grouping,
-            }
-        } else if let Some(solver) = SafetyAlgorithmCollection::create_if_compatible(&property) {
-            let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
-            let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
-                solvable_game,
-                grouping.groups,
-                grouping.always_helping,
-                grouping.always_adversarial,
-            );
+fn compute_for_nonstochastic_game<
+    GR: StateGroups,
+    S: ShapleyAlgorithm,
+    B: GroupBlockingProvider,
+    SPC: SwitchingPairCollector,
+>(
+    game: TwoPlayerNonstochasticGame<VectorPredecessors>,
+    property: &Query<f64, f64, AtomicProposition>,
+    grouping: GroupsAndAuxiliary<GR>,
+    group_blocking_provider: B,
+    shapley: &mut S,
+    switching_pair_collector: &mut SPC,
+) -> Result<StateBasedOutput<S::Output<String>, VectorStateGroups>, ResponsibilityError> {
+    if let Some(solver) = ReachabilityAlgorithmCollection::create_if_compatible(property) {
+        let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
+        let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
+            solvable_game,
+            grouping.groups,
+            grouping.always_helping,
+            grouping.always_adversarial,
+        );
 
-            let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
-            let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+        let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
 
-            let mut cached_coop_game = MinimalCoalitionCache::create(&mut coop_game);
+        let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
 
-            let shapley_output = shapley.compute_simple_with_switching_pairs(
-                &mut cached_coop_game,
-                switching_pair_collector,
-            );
-            StateBasedOutput {
-                shapley_output,
-                grouping: coop_game.grouping,
-            }
-        } else if let Some(solver) = BuechiAlgorithmCollection::create_if_compatible(&property) {
-            let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
-            let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
-                solvable_game,
-                grouping.groups,
-                grouping.always_helping,
-                grouping.always_adversarial,
-            );
+        let shapley_output = compute_simple_with_optional_parallel_cache(
+            shapley,
+            &mut coop_game,
+            switching_pair_collector,
+        )?;
+        Ok(StateBasedOutput {
+            shapley_output,
+            grouping: coop_game.grouping,
+        })
+    } else if let Some(solver) = SafetyAlgorithmCollection::create_if_compatible(property) {
+        let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
+        let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
+            solvable_game,
+            grouping.groups,
+            grouping.always_helping,
+            grouping.always_adversarial,
+        );
+
+        let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
+        let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+
+        let shapley_output = compute_simple_with_optional_parallel_cache(
+            shapley,
+            &mut coop_game,
+            switching_pair_collector,
+        )?;
+        Ok(StateBasedOutput {
+            shapley_output,
+            grouping: coop_game.grouping,
+        })
+    } else if let Some(solver) = BuechiAlgorithmCollection::create_if_compatible(property) {
+        let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
+        let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
+            solvable_game,
+            grouping.groups,
+            grouping.always_helping,
+            grouping.always_adversarial,
+        );
 
-            let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
-            let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+        let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
+        let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
 
-            let mut cached_coop_game = MinimalCoalitionCache::create(&mut coop_game);
+        let shapley_output = compute_simple_with_optional_parallel_cache(
+            shapley,
+            &mut coop_game,
+            switching_pair_collector,
+        )?;
+        Ok(StateBasedOutput {
+            shapley_output,
+            grouping: coop_game.grouping,
+        })
+    } else if let Some(solver) = NextAlgorithmCollection::create_if_compatible(property) {
+        let solvable_game = NonstochasticGameAndSolverExternalOwners::new(game, solver);
+        let mut coop_game = nonstochastic_game::StateBasedResponsibilityNonstochasticGame::new(
+            solvable_game,
+            grouping.groups,
+            grouping.always_helping,
+            grouping.always_adversarial,
+        );
+
+        let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
+        let mut coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+
+        let shapley_output = compute_simple_with_optional_parallel_cache(
+            shapley,
+            &mut coop_game,
+            switching_pair_collector,
+        )?;
+        Ok(StateBasedOutput {
+            shapley_output,
+            grouping: coop_game.grouping,
+        })
+    } else {
+        panic!("Unsupported property type");
+    }
+}
 
-            let shapley_output = shapley.compute_simple_with_switching_pairs(
-                &mut cached_coop_game,
+fn compute_for_stochastic_game<
+    GR: StateGroups,
+    A: SolvableStochasticGame,
+    S: ShapleyAlgorithm,
+    B: GroupBlockingProvider,
+    SPC: SwitchingPairCollector,
+>(
+    coop_game: stochastic_game::StateBasedResponsibilityStochasticGame<GR, A>,
+    bound: Option<Bound<f64>>,
+    group_blocking_provider: B,
+    shapley: &mut S,
+    switching_pair_collector: &mut SPC,
+) -> Result<StateBasedOutput<S::Output<String>, VectorStateGroups>, ResponsibilityError> {
+    let mut coop_game =
+        block_stochastic_game(coop_game, bound.as_ref(), group_blocking_provider, shapley)?;
+
+    let shapley_output = match bound {
+        None if shapley.uses_exhaustive_caches() => {
+            let mut cached_value_game = GameValueCache::create(&mut coop_game);
+            shapley.compute_with_switching_pairs(&mut cached_value_game, switching_pair_collector)
+        }
+        None => {
+            let mut cached_value_game =
+                SparseGameValueCache::with_capacity_limit(&mut coop_game, SPARSE_CACHE_CAPACITY);
+            let output = shapley
+                .compute_with_switching_pairs(&mut cached_value_game, switching_pair_collector);
+            cached_value_game.log_statistics();
+            output
+        }
+        Some(bound) if shapley.uses_exhaustive_caches() => {
+            let mut threshold_game = create_threshold_game(&mut coop_game, &bound);
+            compute_simple_with_optional_cache(
+                shapley,
+                &mut threshold_game,
+                switching_pair_collector,
+            )
+        }
+        Some(bound) => {
+            let mut cached_value_game =
+                SparseGameValueCache::with_capacity_limit(&mut coop_game, SPARSE_CACHE_CAPACITY);
+            let mut threshold_game = create_threshold_game(&mut cached_value_game, &bound);
+            let output = shapley.compute_monotone_simple_with_switching_pairs(
+                &mut threshold_game,
                 switching_pair_collector,
             );
-            StateBasedOutput {
-                shapley_output,
-                grouping: coop_game.grouping,
-            }
-        } else {
-            panic!("Unsupported property type");
+            cached_value_game.log_statistics();
+            output
+        }
+    };
+
+    Ok(StateBasedOutput {
+        shapley_output,
+        grouping: coop_game.grouping,
+    })
+}
+
+/// Like `compute_for_stochastic_game`, but builds the exhaustive cache and runs the algorithm on
+/// several threads if the algorithm asks for it. Each thread works on its own clone of the game.
+fn compute_for_stochastic_game_in_parallel<
+    GR: StateGroups,
+    A: SolvableStochasticGame + Clone + Send,
+    S: ShapleyAlgorithm,
+    B: GroupBlockingProvider,
+    SPC: SwitchingPairCollector,
+>(
+    coop_game: stochastic_game::StateBasedResponsibilityStochasticGame<GR, A>,
+    bound: Option<Bound<f64>>,
+    group_blocking_provider: B,
+    shapley: &mut S,
+    switching_pair_collector: &mut SPC,
+) -> Result<StateBasedOutput<S::Output<String>, VectorStateGroups>, ResponsibilityError> {
+    let thread_count = shapley.thread_count();
+    if thread_count <= 1 || !shapley.uses_exhaustive_caches() {
+        return compute_for_stochastic_game(
+            coop_game,
+            bound,
+            group_blocking_provider,
+            shapley,
+            switching_pair_collector,
+        );
+    }
+
+    let coop_game =
+        block_stochastic_game(coop_game, bound.as_ref(), group_blocking_provider, shapley)?;
+
+    let mut cached_value_game = GameValueCache::create_parallel(&coop_game, thread_count);
+    let shapley_output = match bound {
+        None => shapley.compute_parallel_with_switching_pairs(
+            &mut cached_value_game,
+            switching_pair_collector,
+        ),
+        Some(bound) => {
+            // All values are computed on several threads up front, so the threshold game only
+            // looks them up
+            let mut threshold_game = create_threshold_game(&mut cached_value_game, &bound);
+            shapley
+                .compute_simple_with_switching_pairs(&mut threshold_game, switching_pair_collector)
+        }
+    };
+
+    Ok(StateBasedOutput {
+        shapley_output,
+        grouping: coop_game.grouping,
+    })
+}
+
+/// Combines the groups of the game into blocks and checks that the algorithm can handle the
+/// resulting number of blocks
+fn block_stochastic_game<
+    GR: StateGroups,
+    A: SolvableStochasticGame,
+    S: ShapleyAlgorithm,
+    B: GroupBlockingProvider,
+>(
+    mut coop_game: stochastic_game::StateBasedResponsibilityStochasticGame<GR, A>,
+    bound: Option<&Bound<f64>>,
+    group_blocking_provider: B,
+    shapley: &S,
+) -> Result<
+    stochastic_game::StateBasedResponsibilityStochasticGame<VectorStateGroups, A>,
+    ResponsibilityError,
+> {
+    // Without a bound, the cooperative game is not simple
+    if bound.is_none() && !shapley.weight_type().is_size_based() {
+        return Err(ResponsibilityError::IndexRequiresSimpleGame {
+            weight_type: shapley.weight_type(),
+        });
+    }
+
+    let blocking = group_blocking_provider.compute_blocks(&mut coop_game);
+    let coop_game = coop_game.map_grouping(|g| blocking.apply_to_grouping(g));
+    check_group_count(shapley, coop_game.get_player_count(), bound.is_some())?;
+    Ok(coop_game)
+}
+
+/// Creates the simple game in which a coalition wins if it satisfies the probability bound. The game
+/// values are the probabilities with which player one achieves its objective, so upper bounds on
+/// the probability become lower bounds on the game value.
+fn create_threshold_game<'a, G: CooperativeGame>(
+    game: &'a mut G,
+    bound: &Bound<f64>,
+) -> ThresholdGame<'a, G> {
+    let threshold_game = match bound.operator {
+        BoundOperator::GreaterOrEqual => ThresholdGame::at_least(game, bound.value),
+        BoundOperator::GreaterThan => ThresholdGame::greater_than(game, bound.value),
+        BoundOperator::LessOrEqual => ThresholdGame::at_least(game, 1.0 - bound.value),
+        BoundOperator::LessThan => ThresholdGame::greater_than(game, 1.0 - bound.value),
+    };
+    threshold_game.with_tolerance(THRESHOLD_TOLERANCE)
+}
+
+fn compute_simple_with_optional_cache<
+    S: ShapleyAlgorithm,
+    G: SimpleCooperativeGame + MonotoneCooperativeGame,
+    SPC: SwitchingPairCollector,
+>(
+    shapley: &mut S,
+    coop_game: &mut G,
+    switching_pair_collector: &mut SPC,
+) -> S::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType> {
+    if shapley.uses_exhaustive_caches() {
+        let mut cached_coop_game = MinimalCoalitionCache::create(coop_game);
+        shapley.compute_monotone_simple_with_switching_pairs(
+            &mut cached_coop_game,
+            switching_pair_collector,
+        )
+    } else {
+        shapley.compute_monotone_simple_with_switching_pairs(coop_game, switching_pair_collector)
+    }
+}
+
+/// Like `compute_simple_with_optional_cache`, but builds the cache and runs the algorithm on
+/// several threads if the algorithm asks for it. Fails if the algorithm cannot handle the number
+/// of groups.
+fn compute_simple_with_optional_parallel_cache<
+    S: ShapleyAlgorithm,
+    G: SimpleCooperativeGame<PlayerDescriptions: Send> + MonotoneCooperativeGame + Clone + Send,
+    SPC: SwitchingPairCollector,
+>(
+    shapley: &mut S,
+    coop_game: &mut G,
+    switching_pair_collector: &mut SPC,
+) -> Result<S::Output<<G::PlayerDescriptions as PlayerDescriptions>::PlayerType>, ResponsibilityError>
+{
+    check_group_count(shapley, coop_game.get_player_count(), true)?;
+
+    let thread_count = shapley.thread_count();
+    if thread_count > 1 && shapley.uses_exhaustive_caches() {
+        let mut cached_coop_game = MinimalCoalitionCache::create_parallel(coop_game, thread_count);
+        Ok(shapley.compute_simple_parallel_with_switching_pairs(
+            &mut cached_coop_game,
+            switching_pair_collector,
+        ))
+    } else {
+        Ok(compute_simple_with_optional_cache(
+            shapley,
+            coop_game,
+            switching_pair_collector,
+        ))
+    }
+}
+
+fn check_group_count<S: ShapleyAlgorithm>(
+    shapley: &S,
+    group_count: usize,
+    simple_game: bool,
+) -> Result<(), ResponsibilityError> {
+    match shapley.max_player_count(simple_game) {
+        Some(max_group_count) if group_count > max_group_count => {
+            Err(ResponsibilityError::TooManyGroups {
+                group_count,
+                max_group_count,
+            })
         }
+        _ => Ok(()),
     }
 }
@@ -3,6 +3,7 @@ use crate::state_based::grouping::StateGroups;
 use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
 use probabilistic_models::TwoPlayer;
 
+#[derive(Clone)]
 pub struct StateBasedResponsibilityNonstochasticGame<G: StateGroups, A: SolvableNonstochasticGame> {
     solvable: A,
     pub grouping: G,
@@ -87,7 +88,7 @@ impl<'a, G: StateGroups, A: SolvableNonstochasticGame>
     // would be nicer if this were handled by passing a suitable CoalitionSpecifier to the main
     // function instead of setting the owners explicitly.
     pub fn is_winning_with_current_owners(&mut self) -> bool {
-        self.solvable.get_winner() == TwoPlayer::PlayerOne
+        self.solvable.get_winner_incremental() == TwoPlayer::PlayerOne
     }
 
     pub fn get_winning_region<C: CoalitionSpecifier>(
@@ -122,7 +123,7 @@ impl<G: StateGroups, A: SolvableNonstochasticGame> SimpleCooperativeGame
 
     fn is_winning<C: CoalitionSpecifier>(&mut self, coalition: C) -> bool {
         self.set_state_owners(coalition);
-        self.solvable.get_winner() == TwoPlayer::PlayerOne
+        self.solvable.get_winner_incremental() == TwoPlayer::PlayerOne
     }
 }
 
@@ -1,10 +1,6 @@
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
-use crate::state_based::grouping::StateGroups;
 use crate::state_based::refinement::{
-    BlockSelectionHeuristics, BlockSwitchingPair, PlayerPartition,
+    BlockSelectionHeuristics, BlockSwitchingPair, PlayerPartition, RefinableGame,
 };
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
-use probabilistic_model_algorithms::regions::StateRegion;
 use probabilistic_models::{ActionCollection, Distribution};
 
 pub struct FrontierSizeSelectionHeuristics {
@@ -20,34 +16,30 @@ impl FrontierSizeSelectionHeuristics {
 }
 
 impl BlockSelectionHeuristics for FrontierSizeSelectionHeuristics {
-    fn select_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn select_blocks<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &PlayerPartition,
-        refinement_candidates: Vec<BlockSwitchingPair<A::WinningRegionType>>,
-    ) -> Vec<BlockSwitchingPair<A::WinningRegionType>> {
+        refinement_candidates: Vec<BlockSwitchingPair<RG::Signal>>,
+    ) -> Vec<BlockSwitchingPair<RG::Signal>> {
         let _ = (game, partition);
 
         let mut res = Vec::new();
 
-        let game = game.get_solvable().get_game();
+        let game = game.get_model();
         for refinement_candidate in refinement_candidates {
-            let mut frontier_size = 0;
+            // For winning regions, this counts the transitions from states that only win with the
+            // block to states that are winning with the block or losing without it. For values,
+            // transitions are weighted by how winning or losing their destination is, relative to
+            // the range of values.
+            let mut frontier_size = 0.0;
             for state in 0..game.states.len() {
-                if refinement_candidate.winning_region_with.contains(state)
-                    && !refinement_candidate.winning_region_without.contains(state)
-                {
+                if refinement_candidate.improvement(state) > 0.0 {
                     for action in game.states[state].actions.iter() {
                         for transition in action.successors.iter() {
-                            if refinement_candidate
-                                .winning_region_with
-                                .contains(transition.index)
-                                || !refinement_candidate
-                                    .winning_region_without
-                                    .contains(transition.index)
-                            {
-                                frontier_size += 1;
-                            }
+                            let with = refinement_candidate.normalised_with(transition.index);
+                            let without = refinement_candidate.normalised_without(transition.index);
+                            frontier_size += with.max(1.0 - without);
                         }
                     }
                 }
@@ -56,7 +48,9 @@ impl BlockSelectionHeuristics for FrontierSizeSelectionHeuristics {
         }
 
         res.sort_by(|(_, frontier_size_1), (_, frontier_size_2)| {
-            frontier_size_1.cmp(frontier_size_2)
+            frontier_size_1
+                .partial_cmp(frontier_size_2)
+                .expect("Encountered NaN while sorting refinement candidates")
         });
 
         while res.len() > self.blocks_per_iteration {
@@ -7,15 +7,13 @@ pub use random::RandomBlockSelectionHeuristics;
 mod winning_region_size;
 pub use winning_region_size::{WinningRegionSizeCriterion, WinningRegionSizeSelectionHeuristics};
 
-use super::{BlockSwitchingPair, PlayerPartition};
-use crate::state_based::{StateBasedResponsibilityNonstochasticGame, grouping::StateGroups};
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
+use super::{BlockSwitchingPair, PlayerPartition, RefinableGame};
 
 pub trait BlockSelectionHeuristics {
-    fn select_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn select_blocks<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &PlayerPartition,
-        block_switching_pairs: Vec<BlockSwitchingPair<A::WinningRegionType>>,
-    ) -> Vec<BlockSwitchingPair<A::WinningRegionType>>;
+        block_switching_pairs: Vec<BlockSwitchingPair<RG::Signal>>,
+    ) -> Vec<BlockSwitchingPair<RG::Signal>>;
 }
@@ -1,9 +1,6 @@
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
-use crate::state_based::grouping::StateGroups;
 use crate::state_based::refinement::{
-    BlockSelectionHeuristics, BlockSwitchingPair, PlayerPartition,
+    BlockSelectionHeuristics, BlockSwitchingPair, PlayerPartition, RefinableGame,
 };
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
 use rand::Rng;
 
 pub struct RandomBlockSelectionHeuristics {
@@ -19,12 +16,12 @@ impl RandomBlockSelectionHeuristics {
 }
 
 impl BlockSelectionHeuristics for RandomBlockSelectionHeuristics {
-    fn select_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn select_blocks<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &PlayerPartition,
-        mut refinement_candidates: Vec<BlockSwitchingPair<A::WinningRegionType>>,
-    ) -> Vec<BlockSwitchingPair<A::WinningRegionType>> {
+        mut refinement_candidates: Vec<BlockSwitchingPair<RG::Signal>>,
+    ) -> Vec<BlockSwitchingPair<RG::Signal>> {
         let _ = (game, partition);
 
         let mut res = Vec::new();
@@ -1,9 +1,6 @@
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
-use crate::state_based::grouping::StateGroups;
 use crate::state_based::refinement::{
-    BlockSelectionHeuristics, BlockSwitchingPair, PlayerPartition,
+    BlockSelectionHeuristics, BlockSwitchingPair, PlayerPartition, RefinableGame,
 };
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
 
 pub struct WinningRegionSizeSelectionHeuristics {
     blocks_per_iteration: usize,
@@ -31,17 +28,18 @@ impl WinningRegionSizeSelectionHeuristics {
 }
 
 impl BlockSelectionHeuristics for WinningRegionSizeSelectionHeuristics {
-    fn select_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn select_blocks<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &PlayerPartition,
-        mut refinement_candidates: Vec<BlockSwitchingPair<A::WinningRegionType>>,
-    ) -> Vec<BlockSwitchingPair<A::WinningRegionType>> {
+        mut refinement_candidates: Vec<BlockSwitchingPair<RG::Signal>>,
+    ) -> Vec<BlockSwitchingPair<RG::Signal>> {
         let _ = (game, partition);
 
         refinement_candidates.sort_by(|r1, r2| {
-            r1.winning_region_size_delta()
-                .cmp(&r2.winning_region_size_delta())
+            r1.signal_delta()
+                .partial_cmp(&r2.signal_delta())
+                .expect("Encountered NaN while sorting refinement candidates")
         });
 
         let count = self.blocks_per_iteration.min(refinement_candidates.len());
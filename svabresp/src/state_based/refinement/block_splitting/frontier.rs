@@ -1,8 +1,6 @@
 use super::{BlockSplittingHeuristics, PlayerPartition};
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
 use crate::state_based::grouping::StateGroups;
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
-use probabilistic_model_algorithms::regions::StateRegion;
+use crate::state_based::refinement::{RefinableGame, RefinementSignal};
 use probabilistic_models::{ActionCollection, Distribution, Valuation};
 use rand::Rng;
 
@@ -43,31 +41,38 @@ impl FrontierSplittingHeuristics {
     }
 }
 
+// For winning regions, the overlaps count transitions. For values, every transition is weighted by
+// how winning or losing its destination is.
 struct OverlapData {
-    states_to_winning: usize,
-    states_to_losing: usize,
+    states_to_winning: f64,
+    states_to_losing: f64,
     random_value: usize,
 }
 
 impl OverlapData {
     fn new(random_value: usize) -> Self {
         Self {
-            states_to_winning: 0,
-            states_to_losing: 0,
+            states_to_winning: 0.0,
+            states_to_losing: 0.0,
             random_value,
         }
     }
-    fn total_overlap(&self) -> usize {
+    fn total_overlap(&self) -> f64 {
         self.states_to_losing + self.states_to_winning
     }
 }
 
+fn compare(a: f64, b: f64) -> std::cmp::Ordering {
+    a.partial_cmp(&b)
+        .expect("Encountered NaN while comparing overlaps")
+}
+
 impl BlockSplittingHeuristics for FrontierSplittingHeuristics {
-    fn split_block<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn split_block<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &mut PlayerPartition,
-        bsp: super::super::BlockSwitchingPair<A::WinningRegionType>,
+        bsp: super::super::BlockSwitchingPair<RG::Signal>,
     ) {
         let players = &partition.entries[bsp.block_index].players;
 
@@ -78,18 +83,21 @@ impl BlockSplittingHeuristics for FrontierSplittingHeuristics {
         if print {
             print!("Splitting (");
             partition.entries[bsp.block_index].print(game.get_grouping());
-            println!(") with coalition {:b}", bsp.coalition_bitmap);
+            println!(
+                ") with coalition {:?}",
+                bsp.coalition.members().collect::<Vec<_>>()
+            );
 
             println!("Winning regions:");
-            for state in 0..bsp.winning_region_with.size() {
-                if bsp.winning_region_with.contains(state) {
+            for state in 0..game.get_model().states.len() {
+                if bsp.signal_with.value(state) > 0.0 {
                     print!(
                         "  {}",
-                        game.get_solvable().get_game().states[state]
+                        game.get_model().states[state]
                             .valuation
-                            .displayable(&game.get_solvable().get_game().valuation_context)
+                            .displayable(&game.get_model().valuation_context)
                     );
-                    if bsp.winning_region_without.contains(state) {
+                    if bsp.signal_without.value(state) > 0.0 {
                         print!(" (also in region without)");
                     }
                     println!();
@@ -102,14 +110,14 @@ impl BlockSplittingHeuristics for FrontierSplittingHeuristics {
             let mut overlap_value = OverlapData::new(rand::rng().random_range(0..1_000_000));
 
             for state in game.get_grouping().get_states(player) {
-                if !bsp.winning_region_without.contains(state)
-                    && bsp.winning_region_with.contains(state)
-                {
-                    let game = game.get_solvable().get_game();
+                if bsp.improvement(state) > 0.0 {
+                    let game = game.get_model();
                     for action in game.states[state].actions.iter() {
                         for destination in action.successors.iter() {
-                            if bsp.winning_region_without.contains(destination.index) {
-                                overlap_value.states_to_winning += 1;
+                            let winning = bsp.normalised_without(destination.index);
+                            let losing = 1.0 - bsp.normalised_with(destination.index);
+                            if winning > 0.0 {
+                                overlap_value.states_to_winning += winning;
                                 if print {
                                     println!(
                                         "  {} has transition to {} (winning)",
@@ -122,8 +130,8 @@ impl BlockSplittingHeuristics for FrontierSplittingHeuristics {
                                     )
                                 }
                             }
-                            if !bsp.winning_region_with.contains(destination.index) {
-                                overlap_value.states_to_losing += 1;
+                            if losing > 0.0 {
+                                overlap_value.states_to_losing += losing;
                                 if print {
                                     println!(
                                         "  {} has transition to {} (losing)",
@@ -149,19 +157,19 @@ impl BlockSplittingHeuristics for FrontierSplittingHeuristics {
             .max_by(|(_, o1), (_, o2)| {
                 match self.variant {
                     FrontierSplittingVariant::RandomState => {
-                        o1.total_overlap().min(1).cmp(&o2.total_overlap().min(1))
+                        compare(o1.total_overlap().min(1.0), o2.total_overlap().min(1.0))
                     }
                     FrontierSplittingVariant::MostEdgesToWinningAndLosing => {
-                        o1.total_overlap().cmp(&o2.total_overlap())
+                        compare(o1.total_overlap(), o2.total_overlap())
+                    }
+                    FrontierSplittingVariant::MostEdgesToLosing => {
+                        compare(o1.states_to_losing, o2.states_to_losing)
+                            .then(compare(o1.states_to_winning, o2.states_to_winning))
+                    }
+                    FrontierSplittingVariant::MostEdgesToWinning => {
+                        compare(o1.states_to_winning, o2.states_to_winning)
+                            .then(compare(o1.states_to_losing, o2.states_to_losing))
                     }
-                    FrontierSplittingVariant::MostEdgesToLosing => o1
-                        .states_to_losing
-                        .cmp(&o2.states_to_losing)
-                        .then(o1.states_to_winning.cmp(&o2.states_to_winning)),
-                    FrontierSplittingVariant::MostEdgesToWinning => o1
-                        .states_to_winning
-                        .cmp(&o2.states_to_winning)
-                        .then(o1.states_to_losing.cmp(&o2.states_to_losing)),
                 }
                 .then(o1.random_value.cmp(&o2.random_value))
             })
@@ -4,15 +4,13 @@ pub use frontier::{FrontierSplittingHeuristics, FrontierSplittingVariant};
 mod random;
 pub use random::RandomSplittingHeuristics;
 
-use super::PlayerPartition;
-use crate::state_based::{StateBasedResponsibilityNonstochasticGame, grouping::StateGroups};
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
+use super::{PlayerPartition, RefinableGame};
 
 pub trait BlockSplittingHeuristics {
-    fn split_block<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn split_block<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &mut PlayerPartition,
-        bsp: super::BlockSwitchingPair<A::WinningRegionType>,
+        bsp: super::BlockSwitchingPair<RG::Signal>,
     );
 }
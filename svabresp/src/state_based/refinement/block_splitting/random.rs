@@ -1,9 +1,6 @@
 use super::BlockSplittingHeuristics;
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
 use crate::state_based::grouping::StateGroups;
-use crate::state_based::refinement::{BlockSwitchingPair, PlayerPartition};
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
-use probabilistic_model_algorithms::regions::StateRegion;
+use crate::state_based::refinement::{BlockSwitchingPair, PlayerPartition, RefinableGame};
 use rand::Rng;
 
 pub struct RandomSplittingHeuristics {}
@@ -15,19 +12,17 @@ impl RandomSplittingHeuristics {
 }
 
 impl BlockSplittingHeuristics for RandomSplittingHeuristics {
-    fn split_block<G: StateGroups, A: SolvableNonstochasticGame>(
+    fn split_block<RG: RefinableGame>(
         &mut self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &RG,
         partition: &mut PlayerPartition,
-        bsp: BlockSwitchingPair<A::WinningRegionType>,
+        bsp: BlockSwitchingPair<RG::Signal>,
     ) {
         let mut overlapping_players = Vec::new();
         let players = &partition.entries[bsp.block_index].players;
         for &player in players {
             for state in game.get_grouping().get_states(player) {
-                if !bsp.winning_region_without.contains(state)
-                    && bsp.winning_region_with.contains(state)
-                {
+                if bsp.improvement(state) > 0.0 {
                     overlapping_players.push(player);
                 }
             }
@@ -37,7 +32,8 @@ impl BlockSplittingHeuristics for RandomSplittingHeuristics {
             panic!("None of the players overlap with the winning region.")
         }
 
-        let split_player = rand::rng().random_range(0..overlapping_players.len());
+        let split_player =
+            overlapping_players[rand::rng().random_range(0..overlapping_players.len())];
         partition.split_entry(bsp.block_index, |p| if p == split_player { 1 } else { 0 });
     }
 }
@@ -1,22 +1,22 @@
-use super::PlayerPartition;
+use super::{PlayerPartition, RefinableGame};
 use crate::shapley::{
-    CoalitionSpecifier, MonotoneCooperativeGame, PlayerDescriptions, SimpleCooperativeGame,
+    CoalitionSpecifier, CooperativeGame, MonotoneCooperativeGame, PlayerDescriptions,
+    SimpleCooperativeGame,
 };
-use crate::state_based::{StateBasedResponsibilityNonstochasticGame, grouping::StateGroups};
+use crate::state_based::StateBasedResponsibilityNonstochasticGame;
+use crate::state_based::grouping::StateGroups;
+use crate::state_based::stochastic_game::StateBasedResponsibilityStochasticGame;
 use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
-use probabilistic_models::TwoPlayer;
+use probabilistic_model_algorithms::traits::SolvableStochasticGame;
 
-pub struct GroupedGame<'a, G: StateGroups, A: SolvableNonstochasticGame> {
-    game: &'a mut StateBasedResponsibilityNonstochasticGame<G, A>,
+pub struct GroupedGame<'a, RG: RefinableGame> {
+    game: &'a mut RG,
     partition: &'a PlayerPartition,
     player_description: GroupedGamePlayerDescriptions,
 }
 
-impl<'a, G: StateGroups, A: SolvableNonstochasticGame> GroupedGame<'a, G, A> {
-    pub fn new(
-        game: &'a mut StateBasedResponsibilityNonstochasticGame<G, A>,
-        partition: &'a PlayerPartition,
-    ) -> Self {
+impl<'a, RG: RefinableGame> GroupedGame<'a, RG> {
+    pub fn new(game: &'a mut RG, partition: &'a PlayerPartition) -> Self {
         let players = GroupedGamePlayerDescriptions::new(partition.entries.len());
         Self {
             game,
@@ -24,34 +24,10 @@ impl<'a, G: StateGroups, A: SolvableNonstochasticGame> GroupedGame<'a, G, A> {
             player_description: players,
         }
     }
-
-    fn set_owners<C: CoalitionSpecifier>(&mut self, coalition: C) {
-        self.game.set_auxiliary_state_owners();
-        for (player_index, player) in self.partition.entries.iter().enumerate() {
-            if coalition.is_in_coalition(player_index) {
-                for &entry in &player.players {
-                    self.game.set_group_owners(entry, TwoPlayer::PlayerOne);
-                }
-            } else {
-                for &entry in &player.players {
-                    self.game.set_group_owners(entry, TwoPlayer::PlayerTwo);
-                }
-            }
-        }
-    }
-
-    pub fn get_winning_region<C: CoalitionSpecifier>(
-        &mut self,
-        coalition: C,
-    ) -> A::WinningRegionType {
-        self.set_owners(coalition);
-
-        self.game.get_winning_region_with_current_owners()
-    }
 }
 
 impl<'a, G: StateGroups, A: SolvableNonstochasticGame> SimpleCooperativeGame
-    for GroupedGame<'a, G, A>
+    for GroupedGame<'a, StateBasedResponsibilityNonstochasticGame<G, A>>
 {
     type PlayerDescriptions = GroupedGamePlayerDescriptions;
 
@@ -68,7 +44,7 @@ impl<'a, G: StateGroups, A: SolvableNonstochasticGame> SimpleCooperativeGame
     }
 
     fn is_winning<C: CoalitionSpecifier>(&mut self, coalition: C) -> bool {
-        self.set_owners(coalition);
+        self.game.set_block_owners(self.partition, coalition);
 
         let result = self.game.is_winning_with_current_owners();
         result
@@ -76,10 +52,33 @@ impl<'a, G: StateGroups, A: SolvableNonstochasticGame> SimpleCooperativeGame
 }
 
 impl<'a, G: StateGroups, A: SolvableNonstochasticGame> MonotoneCooperativeGame
-    for GroupedGame<'a, G, A>
+    for GroupedGame<'a, StateBasedResponsibilityNonstochasticGame<G, A>>
 {
 }
 
+impl<'a, G: StateGroups, A: SolvableStochasticGame> CooperativeGame
+    for GroupedGame<'a, StateBasedResponsibilityStochasticGame<G, A>>
+{
+    type PlayerDescriptions = GroupedGamePlayerDescriptions;
+
+    fn get_player_count(&self) -> usize {
+        self.partition.entries.len()
+    }
+
+    fn player_descriptions(&self) -> &Self::PlayerDescriptions {
+        &self.player_description
+    }
+
+    fn player_descriptions_mut(&mut self) -> &mut Self::PlayerDescriptions {
+        &mut self.player_description
+    }
+
+    fn get_value<C: CoalitionSpecifier>(&mut self, coalition: C) -> f64 {
+        self.game.set_block_owners(self.partition, coalition);
+        self.game.get_value_with_current_owners()
+    }
+}
+
 #[derive(Clone)]
 pub struct GroupedGamePlayerDescriptions {
     players: Vec<usize>,
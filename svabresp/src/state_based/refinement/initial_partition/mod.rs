@@ -4,13 +4,8 @@ pub use random::RandomInitialPartition;
 mod singleton;
 pub use singleton::SingletonInitialPartition;
 
-use super::PlayerPartition;
-use crate::state_based::{StateBasedResponsibilityNonstochasticGame, grouping::StateGroups};
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
+use super::{PlayerPartition, RefinableGame};
 
 pub trait InitialPartitionProvider {
-    fn get_initial_coalition<G: StateGroups, A: SolvableNonstochasticGame>(
-        self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
-    ) -> PlayerPartition;
+    fn get_initial_coalition<RG: RefinableGame>(self, game: &RG) -> PlayerPartition;
 }
@@ -1,10 +1,7 @@
-use crate::shapley::SimpleCooperativeGame;
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
 use crate::state_based::grouping::StateGroups;
 use crate::state_based::refinement::{
-    InitialPartitionProvider, PlayerPartition, PlayerPartitionEntry,
+    InitialPartitionProvider, PlayerPartition, PlayerPartitionEntry, RefinableGame,
 };
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
 use rand::Rng;
 
 pub struct RandomInitialPartition {
@@ -18,15 +15,12 @@ impl RandomInitialPartition {
 }
 
 impl InitialPartitionProvider for RandomInitialPartition {
-    fn get_initial_coalition<G: StateGroups, A: SolvableNonstochasticGame>(
-        self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
-    ) -> PlayerPartition {
+    fn get_initial_coalition<RG: RefinableGame>(self, game: &RG) -> PlayerPartition {
         let mut blocks = PlayerPartition::new();
         for _ in 0..self.block_count {
             blocks.add_entry(PlayerPartitionEntry::new());
         }
-        for player in 0..game.get_player_count() {
+        for player in 0..game.get_grouping().get_count() {
             let block = rand::rng().random_range(0..self.block_count);
             blocks.entries[block].players.push(player);
         }
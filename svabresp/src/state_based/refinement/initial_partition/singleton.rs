@@ -1,10 +1,7 @@
-use crate::shapley::SimpleCooperativeGame;
-use crate::state_based::StateBasedResponsibilityNonstochasticGame;
 use crate::state_based::grouping::StateGroups;
 use crate::state_based::refinement::{
-    InitialPartitionProvider, PlayerPartition, PlayerPartitionEntry,
+    InitialPartitionProvider, PlayerPartition, PlayerPartitionEntry, RefinableGame,
 };
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
 
 pub struct SingletonInitialPartition {}
 
@@ -15,12 +12,9 @@ impl SingletonInitialPartition {
 }
 
 impl InitialPartitionProvider for SingletonInitialPartition {
-    fn get_initial_coalition<G: StateGroups, A: SolvableNonstochasticGame>(
-        self,
-        game: &StateBasedResponsibilityNonstochasticGame<G, A>,
-    ) -> PlayerPartition {
-        let mut players = Vec::with_capacity(game.get_player_count());
-        for i in 0..game.get_player_count() {
+    fn get_initial_coalition<RG: RefinableGame>(self, game: &RG) -> PlayerPartition {
+        let mut players = Vec::with_capacity(game.get_grouping().get_count());
+        for i in 0..game.get_grouping().get_count() {
             players.push(i);
         }
         let entry = PlayerPartitionEntry::with_players(players);
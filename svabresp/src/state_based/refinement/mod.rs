@@ -10,19 +10,16 @@ pub use initial_partition::*;
 mod grouped_game;
 mod partition;
 
-use super::StateBasedResponsibilityNonstochasticGame;
-use crate::shapley::MinimalCoalitionCache;
+mod refinable_game;
+pub use refinable_game::{RefinableGame, RefinementSignal, StateValues};
+
+use crate::shapley::{Coalition, marginal_contribution};
 use crate::state_based::grouping::StateGroups;
 use log::trace;
 pub use partition::{PlayerPartition, PlayerPartitionEntry};
-use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
-use probabilistic_model_algorithms::regions::StateRegion;
 
 pub trait GroupBlockingProvider {
-    fn compute_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
-        self,
-        game: &mut StateBasedResponsibilityNonstochasticGame<G, A>,
-    ) -> PlayerPartition;
+    fn compute_blocks<RG: RefinableGame>(self, game: &mut RG) -> PlayerPartition;
 }
 
 pub struct IdentityGroupBlockingProvider {}
@@ -34,10 +31,7 @@ impl IdentityGroupBlockingProvider {
 }
 
 impl GroupBlockingProvider for IdentityGroupBlockingProvider {
-    fn compute_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
-        self,
-        game: &mut StateBasedResponsibilityNonstochasticGame<G, A>,
-    ) -> PlayerPartition {
+    fn compute_blocks<RG: RefinableGame>(self, game: &mut RG) -> PlayerPartition {
         let mut partition = PlayerPartition::new();
         for player in 0..game.get_grouping().get_count() {
             partition.add_entry(PlayerPartitionEntry::with_players(vec![player]));
@@ -82,10 +76,7 @@ impl<
 > GroupBlockingProvider
     for RefinementGroupBlockingProvider<InitialPartition, SelectionHeuristics, SplittingHeuristics>
 {
-    fn compute_blocks<G: StateGroups, A: SolvableNonstochasticGame>(
-        self,
-        game: &mut StateBasedResponsibilityNonstochasticGame<G, A>,
-    ) -> PlayerPartition {
+    fn compute_blocks<RG: RefinableGame>(self, game: &mut RG) -> PlayerPartition {
         let mut algorithm = RefinementAlgorithm::new(
             game,
             self.initial_partition,
@@ -97,53 +88,99 @@ impl<
     }
 }
 
-pub struct BlockSwitchingPair<R: StateRegion> {
+/// A block together with a coalition of other blocks to which it contributes. For non-stochastic
+/// games, the signals are the winning regions of the coalition with and without the block; for
+/// stochastic games, they are the values of all states.
+pub struct BlockSwitchingPair<S: RefinementSignal> {
     block_index: usize,
-    #[allow(unused)]
-    // It might be useful to have the coalition available in the future, even if it currently is not used
-    coalition_bitmap: u64,
-    winning_region_without: R,
-    winning_region_with: R,
+    coalition: Coalition,
+    signal_without: S,
+    signal_with: S,
+    value_range: (f64, f64),
 }
 
-impl<R: StateRegion> BlockSwitchingPair<R> {
-    fn winning_region_size_delta(&self) -> usize {
-        self.winning_region_with.size() - self.winning_region_without.size()
+impl<S: RefinementSignal> BlockSwitchingPair<S> {
+    fn new(block_index: usize, coalition: Coalition, signal_without: S, signal_with: S) -> Self {
+        let (min_without, max_without) = signal_without.value_range();
+        let (min_with, max_with) = signal_with.value_range();
+        Self {
+            block_index,
+            coalition,
+            signal_without,
+            signal_with,
+            value_range: (min_without.min(min_with), max_without.max(max_with)),
+        }
+    }
+
+    /// Maps a value of either signal to the interval from 0 to 1, so that the heuristics treat
+    /// probabilities and expected rewards alike
+    fn normalise(&self, value: f64) -> f64 {
+        let (min, max) = self.value_range;
+        if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The normalised value of the state when the block is part of the coalition
+    fn normalised_with(&self, state: usize) -> f64 {
+        self.normalise(self.signal_with.value(state))
+    }
+
+    /// The normalised value of the state when the block is not part of the coalition
+    fn normalised_without(&self, state: usize) -> f64 {
+        self.normalise(self.signal_without.value(state))
+    }
+
+    fn signal_delta(&self) -> f64 {
+        marginal_contribution(
+            self.signal_without.total_value(),
+            self.signal_with.total_value(),
+        )
+    }
+
+    /// How much the value of the state increases when the block joins the coalition
+    fn improvement(&self, state: usize) -> f64 {
+        marginal_contribution(
+            self.signal_without.value(state),
+            self.signal_with.value(state),
+        )
     }
 }
 
 pub struct RefinementAlgorithm<
     'a,
-    G: StateGroups,
-    A: SolvableNonstochasticGame,
+    RG: RefinableGame,
     SelectionHeuristics: BlockSelectionHeuristics,
     SplittingHeuristics: BlockSplittingHeuristics,
 > {
-    game: &'a mut StateBasedResponsibilityNonstochasticGame<G, A>,
+    game: &'a mut RG,
     current_partition: PlayerPartition,
+    coalition_values: RG::CoalitionValues,
     selection_heuristics: SelectionHeuristics,
     splitting_heuristics: SplittingHeuristics,
 }
 
 impl<
     'a,
-    G: StateGroups,
-    A: SolvableNonstochasticGame,
+    RG: RefinableGame,
     SelectionHeuristics: BlockSelectionHeuristics,
     SplittingHeuristics: BlockSplittingHeuristics,
-> RefinementAlgorithm<'a, G, A, SelectionHeuristics, SplittingHeuristics>
+> RefinementAlgorithm<'a, RG, SelectionHeuristics, SplittingHeuristics>
 {
     pub fn new<I: InitialPartitionProvider>(
-        game: &'a mut StateBasedResponsibilityNonstochasticGame<G, A>,
+        game: &'a mut RG,
         initial_coalition_provider: I,
         selection_heuristics: SelectionHeuristics,
         splitting_heuristics: SplittingHeuristics,
     ) -> Self {
-        let initial_partition = initial_coalition_provider.get_initial_coalition(&game);
+        let initial_partition = initial_coalition_provider.get_initial_coalition(game);
 
         Self {
             game,
             current_partition: initial_partition,
+            coalition_values: RG::CoalitionValues::default(),
             selection_heuristics,
             splitting_heuristics,
         }
@@ -158,12 +195,12 @@ impl<
         trace!("Finished refinement");
     }
 
-    pub fn iteration(&mut self, bsps: Vec<BlockSwitchingPair<A::WinningRegionType>>) {
+    pub fn iteration(&mut self, bsps: Vec<BlockSwitchingPair<RG::Signal>>) {
         trace!("Performing refinement iteration");
         trace!("Selecting refinement targets");
         let to_refine =
             self.selection_heuristics
-                .select_blocks(&self.game, &self.current_partition, bsps);
+                .select_blocks(self.game, &self.current_partition, bsps);
 
         let n = to_refine.len();
         trace!(
@@ -174,54 +211,37 @@ impl<
         for (index, block) in to_refine.into_iter().enumerate() {
             trace!("Splitting block {}/{}", index, n);
             self.splitting_heuristics
-                .split_block(&self.game, &mut self.current_partition, block);
+                .split_block(self.game, &mut self.current_partition, block);
         }
     }
 
-    pub fn compute_refinement_candidates(
-        &mut self,
-    ) -> Option<Vec<BlockSwitchingPair<A::WinningRegionType>>> {
+    pub fn compute_refinement_candidates(&mut self) -> Option<Vec<BlockSwitchingPair<RG::Signal>>> {
         trace!("Computing refinement candidates");
-        let mut bsps = Vec::with_capacity(self.current_partition.entries.len());
-        for _ in 0..self.current_partition.entries.len() {
-            bsps.push(None);
-        }
-
-        let mut game = grouped_game::GroupedGame::new(&mut self.game, &self.current_partition);
-        let cached_group_game = MinimalCoalitionCache::create(&mut game);
-        // Create another copy of the game so we can compute winning regions for the blocks with BSPs
-        let mut game = grouped_game::GroupedGame::new(&mut self.game, &self.current_partition);
-
-        for coalition in cached_group_game.minimal_coalitions {
-            for block in 0..self.current_partition.entries.len() {
-                if self.current_partition.entries[block].players.len() > 1 {
-                    if coalition & 1 << block != 0 {
-                        if bsps[block].is_none() {
-                            let coalition_without = coalition & !(1 << block);
-                            let winning_region_without = game.get_winning_region(coalition_without);
-                            let winning_region_with = game.get_winning_region(coalition);
-
-                            bsps[block] = Some(BlockSwitchingPair {
-                                block_index: block,
-                                coalition_bitmap: coalition_without,
-                                winning_region_without,
-                                winning_region_with,
-                            })
-                        }
-                    }
-                }
-            }
-        }
-
-        trace!("Found {} refinement candidates", bsps.len());
+        let coalitions = self
+            .game
+            .find_block_switching_coalitions(&self.current_partition, &mut self.coalition_values);
 
         let mut res = Vec::new();
-        for bsp in bsps {
-            if let Some(bsp) = bsp {
-                res.push(bsp);
+        for (block, coalition_without) in coalitions.into_iter().enumerate() {
+            if let Some(coalition_without) = coalition_without {
+                let signal_without = self
+                    .game
+                    .get_block_signal(&self.current_partition, &coalition_without);
+                let signal_with = self
+                    .game
+                    .get_block_signal(&self.current_partition, coalition_without.with(block));
+
+                res.push(BlockSwitchingPair::new(
+                    block,
+                    coalition_without,
+                    signal_without,
+                    signal_with,
+                ))
             }
         }
 
+        trace!("Found {} refinement candidates", res.len());
+
         if res.len() == 0 { None } else { Some(res) }
     }
 }
@@ -0,0 +1,259 @@
+use super::PlayerPartition;
+use super::grouped_game::GroupedGame;
+use crate::shapley::{
+    Coalition, CoalitionSpecifier, MinimalCoalitionAlgorithm, marginal_contribution,
+};
+use crate::state_based::StateBasedResponsibilityNonstochasticGame;
+use crate::state_based::grouping::StateGroups;
+use crate::state_based::stochastic_game::StateBasedResponsibilityStochasticGame;
+use probabilistic_model_algorithms::deterministic_games::SolvableNonstochasticGame;
+use probabilistic_model_algorithms::regions::StateRegion;
+use probabilistic_model_algorithms::traits::SolvableStochasticGame;
+use probabilistic_models::{ModelTypes, ProbabilisticModel, TwoPlayer, VectorPredecessors};
+use std::collections::HashMap;
+
+// Value differences below this threshold are attributed to the imprecision of value iteration
+const VALUE_TOLERANCE: f64 = 0.000_001;
+
+/// The per-state information that the refinement heuristics use to decide which blocks to split.
+///
+/// For winning regions, the value is 1 for states in the region and 0 for all other states. For
+/// stochastic games, the value is a probability or an expected reward, so heuristics that compare
+/// values across states should normalise them with the value range.
+pub trait RefinementSignal {
+    fn value(&self, state: usize) -> f64;
+
+    fn total_value(&self) -> f64;
+
+    /// The smallest and the largest finite value of any state
+    fn value_range(&self) -> (f64, f64);
+}
+
+impl<R: StateRegion> RefinementSignal for R {
+    fn value(&self, state: usize) -> f64 {
+        if self.contains(state) { 1.0 } else { 0.0 }
+    }
+
+    fn total_value(&self) -> f64 {
+        self.size() as f64
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+}
+
+/// The value of player one in each state of a stochastic game, i.e. the probability with which
+/// player one wins or the (possibly negated) expected reward
+pub struct StateValues {
+    pub values: Vec<f64>,
+}
+
+impl RefinementSignal for StateValues {
+    fn value(&self, state: usize) -> f64 {
+        self.values[state]
+    }
+
+    fn total_value(&self) -> f64 {
+        self.values.iter().sum()
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        self.values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    }
+}
+
+/// A responsibility game whose groups can be combined into blocks by the refinement algorithm.
+pub trait RefinableGame {
+    type Grouping: StateGroups;
+    type ModelTypes: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>;
+    type Signal: RefinementSignal;
+    /// The information that `find_block_switching_coalitions` keeps between the iterations of the
+    /// refinement
+    type CoalitionValues: Default;
+
+    fn get_grouping(&self) -> &Self::Grouping;
+
+    fn get_model(&self) -> &ProbabilisticModel<Self::ModelTypes>;
+
+    fn set_auxiliary_state_owners(&mut self);
+
+    fn set_group_owners(&mut self, group_index: usize, owner: TwoPlayer);
+
+    fn get_signal_with_current_owners(&mut self) -> Self::Signal;
+
+    /// Returns, for every block of the partition that contains more than one group, a coalition of
+    /// blocks that does not contain the block, but to whose value the block contributes. Returns
+    /// `None` for blocks for which no such coalition exists.
+    fn find_block_switching_coalitions(
+        &mut self,
+        partition: &PlayerPartition,
+        coalition_values: &mut Self::CoalitionValues,
+    ) -> Vec<Option<Coalition>>;
+
+    /// Assigns the states of all blocks in the coalition to player one and the states of all other
+    /// blocks to player two
+    fn set_block_owners<C: CoalitionSpecifier>(
+        &mut self,
+        partition: &PlayerPartition,
+        coalition: C,
+    ) {
+        self.set_auxiliary_state_owners();
+        for (block_index, block) in partition.entries.iter().enumerate() {
+            let owner = if coalition.is_in_coalition(block_index) {
+                TwoPlayer::PlayerOne
+            } else {
+                TwoPlayer::PlayerTwo
+            };
+            for &group in &block.players {
+                self.set_group_owners(group, owner);
+            }
+        }
+    }
+
+    fn get_block_signal<C: CoalitionSpecifier>(
+        &mut self,
+        partition: &PlayerPartition,
+        coalition: C,
+    ) -> Self::Signal {
+        self.set_block_owners(partition, coalition);
+        self.get_signal_with_current_owners()
+    }
+}
+
+impl<G: StateGroups, A: SolvableNonstochasticGame> RefinableGame
+    for StateBasedResponsibilityNonstochasticGame<G, A>
+{
+    type Grouping = G;
+    type ModelTypes = A::ModelTypes;
+    type Signal = A::WinningRegionType;
+    type CoalitionValues = ();
+
+    fn get_grouping(&self) -> &G {
+        &self.grouping
+    }
+
+    fn get_model(&self) -> &ProbabilisticModel<A::ModelTypes> {
+        self.get_solvable().get_game()
+    }
+
+    fn set_auxiliary_state_owners(&mut self) {
+        StateBasedResponsibilityNonstochasticGame::set_auxiliary_state_owners(self)
+    }
+
+    fn set_group_owners(&mut self, group_index: usize, owner: TwoPlayer) {
+        StateBasedResponsibilityNonstochasticGame::set_group_owners(self, group_index, owner)
+    }
+
+    fn get_signal_with_current_owners(&mut self) -> A::WinningRegionType {
+        self.get_winning_region_with_current_owners()
+    }
+
+    /// The minimal winning coalitions are enumerated without evaluating every coalition, so this
+    /// also works for partitions with many blocks.
+    fn find_block_switching_coalitions(
+        &mut self,
+        partition: &PlayerPartition,
+        _coalition_values: &mut (),
+    ) -> Vec<Option<Coalition>> {
+        let mut game = GroupedGame::new(self, partition);
+        let minimal_coalitions = MinimalCoalitionAlgorithm::minimal_winning_coalitions(&mut game);
+
+        let mut coalitions = vec![None; partition.entries.len()];
+        for coalition in minimal_coalitions {
+            for block in coalition.members() {
+                if partition.entries[block].players.len() > 1 && coalitions[block].is_none() {
+                    coalitions[block] = Some(coalition.without(block));
+                }
+            }
+        }
+        coalitions
+    }
+}
+
+impl<G: StateGroups, A: SolvableStochasticGame> RefinableGame
+    for StateBasedResponsibilityStochasticGame<G, A>
+{
+    type Grouping = G;
+    type ModelTypes = A::ModelTypes;
+    type Signal = StateValues;
+    type CoalitionValues = HashMap<Coalition, f64>;
+
+    fn get_grouping(&self) -> &G {
+        &self.grouping
+    }
+
+    fn get_model(&self) -> &ProbabilisticModel<A::ModelTypes> {
+        self.get_solvable().get_game()
+    }
+
+    fn set_auxiliary_state_owners(&mut self) {
+        StateBasedResponsibilityStochasticGame::set_auxiliary_state_owners(self)
+    }
+
+    fn set_group_owners(&mut self, group_index: usize, owner: TwoPlayer) {
+        StateBasedResponsibilityStochasticGame::set_group_owners(self, group_index, owner)
+    }
+
+    fn get_signal_with_current_owners(&mut self) -> StateValues {
+        self.get_state_values_with_current_owners()
+    }
+
+    /// In stochastic games, the coalition to which the block contributes the largest increase in
+    /// value is selected. All coalitions of blocks without the block are considered, so there must
+    /// be fewer than 64 blocks.
+    ///
+    /// The values are cached by the groups in the coalition, so they remain valid after blocks
+    /// have been split. Only the coalitions that contain a part of a split block, but not all of
+    /// its parts, are evaluated again in the next iteration.
+    fn find_block_switching_coalitions(
+        &mut self,
+        partition: &PlayerPartition,
+        coalition_values: &mut HashMap<Coalition, f64>,
+    ) -> Vec<Option<Coalition>> {
+        let group_count = self.grouping.get_count();
+        let mut get_value = |game: &mut Self, coalition: u64| {
+            let mut groups = Coalition::empty(group_count);
+            for (block_index, block) in partition.entries.iter().enumerate() {
+                if coalition.is_in_coalition(block_index) {
+                    for &group in &block.players {
+                        groups.insert(group);
+                    }
+                }
+            }
+            *coalition_values.entry(groups).or_insert_with(|| {
+                game.set_block_owners(partition, coalition);
+                game.get_value_with_current_owners()
+            })
+        };
+
+        let block_count = partition.entries.len();
+        let mut coalitions = Vec::with_capacity(block_count);
+        for (block, entry) in partition.entries.iter().enumerate() {
+            let mut best_coalition = None;
+            if entry.players.len() > 1 {
+                let mut best_delta = VALUE_TOLERANCE;
+                for coalition in 0..1u64 << block_count {
+                    if coalition & 1 << block != 0 {
+                        continue;
+                    }
+                    let delta = marginal_contribution(
+                        get_value(self, coalition),
+                        get_value(self, coalition | 1 << block),
+                    );
+                    if delta > best_delta {
+                        best_delta = delta;
+                        best_coalition = Some(coalition.to_coalition(block_count));
+                    }
+                }
+            }
+            coalitions.push(best_coalition);
+        }
+        coalitions
+    }
+}
@@ -1,14 +1,23 @@
-use crate::shapley::{CoalitionSpecifier, CooperativeGame};
+use crate::shapley::{CoalitionSpecifier, CooperativeGame, MonotoneCooperativeGame};
 use crate::state_based::grouping::StateGroups;
+use crate::state_based::refinement::StateValues;
 use probabilistic_model_algorithms::traits::SolvableStochasticGame;
 use probabilistic_models::TwoPlayer;
+use probabilistic_properties::NonDeterminismKind;
 
+/// The value of a coalition is the probability with which it achieves its objective. If player one
+/// minimises the probability computed by the solver, this is the complementary probability, so the
+/// value grows with the coalition in either case. For expected reward objectives, the value is the
+/// expected reward, negated if player one minimises it.
+#[derive(Clone)]
 pub struct StateBasedResponsibilityStochasticGame<G: StateGroups, A: SolvableStochasticGame> {
     solvable: A,
     pub grouping: G,
     always_helping: Vec<usize>,
     always_adversarial: Vec<usize>,
     group_names: super::GroupNames,
+    objective: NonDeterminismKind,
+    rewards: bool,
 }
 impl<G: StateGroups, A: SolvableStochasticGame> StateBasedResponsibilityStochasticGame<G, A> {
     pub fn new(
@@ -24,9 +33,46 @@ impl<G: StateGroups, A: SolvableStochasticGame> StateBasedResponsibilityStochast
             always_helping,
             always_adversarial,
             group_names: group_info,
+            objective: NonDeterminismKind::Maximise,
+            rewards: false,
         }
     }
 
+    /// Sets whether player one maximises or minimises the probability computed by the solver
+    pub fn with_objective(mut self, objective: NonDeterminismKind) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Sets whether player one maximises or minimises the expected reward computed by the solver
+    pub fn with_reward_objective(mut self, objective: NonDeterminismKind) -> Self {
+        self.objective = objective;
+        self.rewards = true;
+        self
+    }
+
+    pub fn map_grouping<G2: StateGroups, F: Fn(G) -> G2>(
+        self,
+        map: F,
+    ) -> StateBasedResponsibilityStochasticGame<G2, A> {
+        let grouping = map(self.grouping);
+        let group_names = super::GroupNames::from_grouping(&grouping);
+
+        StateBasedResponsibilityStochasticGame {
+            solvable: self.solvable,
+            grouping,
+            always_helping: self.always_helping,
+            always_adversarial: self.always_adversarial,
+            group_names,
+            objective: self.objective,
+            rewards: self.rewards,
+        }
+    }
+
+    pub fn get_solvable(&self) -> &A {
+        &self.solvable
+    }
+
     // TODO: Currently, there is some duplication with the non-stochastic case. Can this be unified without introducing even more confusing traits?
     pub fn set_state_owners<C: CoalitionSpecifier>(&mut self, coalition: C) {
         self.set_auxiliary_state_owners();
@@ -56,6 +102,29 @@ impl<G: StateGroups, A: SolvableStochasticGame> StateBasedResponsibilityStochast
             self.solvable.set_owner(state, owner);
         }
     }
+
+    pub fn get_value_with_current_owners(&mut self) -> f64 {
+        let value = self.solvable.maximum_player_1_probability_incremental();
+        self.to_coalition_value(value)
+    }
+
+    pub fn get_state_values_with_current_owners(&mut self) -> StateValues {
+        let values = self.solvable.player_1_probabilities();
+        StateValues {
+            values: values
+                .into_iter()
+                .map(|v| self.to_coalition_value(v))
+                .collect(),
+        }
+    }
+
+    fn to_coalition_value(&self, value: f64) -> f64 {
+        match (self.objective, self.rewards) {
+            (NonDeterminismKind::Maximise, _) => value,
+            (NonDeterminismKind::Minimise, false) => 1.0 - value,
+            (NonDeterminismKind::Minimise, true) => -value,
+        }
+    }
 }
 
 impl<G: StateGroups, A: SolvableStochasticGame> CooperativeGame
@@ -77,6 +146,11 @@ impl<G: StateGroups, A: SolvableStochasticGame> CooperativeGame
 
     fn get_value<C: CoalitionSpecifier>(&mut self, coalition: C) -> f64 {
         self.set_state_owners(coalition);
-        self.solvable.maximum_player_1_probability()
+        self.get_value_with_current_owners()
     }
 }
+
+impl<G: StateGroups, A: SolvableStochasticGame> MonotoneCooperativeGame
+    for StateBasedResponsibilityStochasticGame<G, A>
+{
+}
@@ -5,8 +5,17 @@ use clap::Parser;
 pub struct Arguments {
     #[arg(short, long)]
     pub model: String,
-    #[arg(short, long)]
-    pub property: String,
+    /// A property to check. May be given multiple times.
+    #[arg(short, long, required_unless_present = "properties_file")]
+    pub property: Vec<String>,
+    /// A PRISM properties file. All properties in the file are checked.
+    #[arg(long, conflicts_with = "property")]
+    pub properties_file: Option<String>,
     #[arg(short, long, default_value_t = String::new())]
     pub constants: String,
+    /// Name of a label whose states are owned by player two. If set, the model is checked as a
+    /// two-player game in which player one maximises and player two minimises. Games of type
+    /// `smg` are always checked as games, with the first declared player as player one.
+    #[arg(long)]
+    pub player_two: Option<String>,
 }
@@ -1,6 +1,9 @@
 use clap::Parser;
+use prism_model::ModelType;
 use prism_model_builder::{ModelBuildingError, ModelBuildingOutput};
-use probabilistic_models::MdpType;
+use probabilistic_models::{
+    Mdp, MdpType, TwoPlayerStochasticGame, TwoPlayerStochasticGameType, Valuation,
+};
 use tiny_pmc::CheckerError;
 use tiny_pmc::parsing::ConstParsingError;
 
@@ -25,44 +28,169 @@ fn checker() -> Result<(), ModelCheckerError> {
     let source = read_model_file(&arguments.model)?;
     let constants = tiny_pmc::parsing::parse_const_assignments(&arguments.constants)?;
 
-    let parsed_model_and_objectives = tiny_pmc::parsing::parse_prism_and_print_errors(
-        Some(&arguments.model),
-        &source,
-        &[&arguments.property],
-    );
-    let (mut prism_model, properties) = match parsed_model_and_objectives {
-        None => return Err(ModelCheckerError::ModelAndPropertyParsingError),
-        Some((prism_model, properties, _)) => (prism_model, properties),
+    let (mut prism_model, property_names, properties) = match &arguments.properties_file {
+        Some(properties_file) => {
+            let properties_source = read_model_file(properties_file)?;
+            let parsed = tiny_pmc::parsing::parse_prism_with_properties_file_and_print_errors(
+                Some(&arguments.model),
+                &source,
+                Some(properties_file),
+                &properties_source,
+            );
+            let (prism_model, named_properties) = match parsed {
+                None => return Err(ModelCheckerError::ModelAndPropertyParsingError),
+                Some((prism_model, named_properties, _)) => (prism_model, named_properties),
+            };
+            let mut property_names = Vec::with_capacity(named_properties.len());
+            let mut properties = Vec::with_capacity(named_properties.len());
+            for (i, property) in named_properties.into_iter().enumerate() {
+                property_names.push(match property.name {
+                    Some(name) => format!("\"{}\"", name.name),
+                    None => format!("Property {}", i + 1),
+                });
+                properties.push(property.query);
+            }
+            (prism_model, property_names, properties)
+        }
+        None => {
+            let parsed = tiny_pmc::parsing::parse_prism_and_print_errors(
+                Some(&arguments.model),
+                &source,
+                &arguments.property,
+            );
+            let (prism_model, properties) = match parsed {
+                None => return Err(ModelCheckerError::ModelAndPropertyParsingError),
+                Some((prism_model, properties, _)) => (prism_model, properties),
+            };
+            (prism_model, arguments.property.clone(), properties)
+        }
     };
 
+    let is_game = matches!(prism_model.model_type, ModelType::Smg(_));
     let mut atomic_propositions = Vec::new();
+    let player_two_states = match &arguments.player_two {
+        None => None,
+        Some(_) if is_game => return Err(ModelCheckerError::PlayerLabelForGame),
+        Some(label) => Some(
+            tiny_pmc::building::player_two_label_to_atomic_proposition(
+                &prism_model,
+                label,
+                &mut atomic_propositions,
+            )
+            .ok_or_else(|| ModelCheckerError::UnknownPlayerLabel(label.clone()))?,
+        ),
+    };
     let properties = tiny_pmc::building::prism_objectives_to_atomic_propositions(
         &mut atomic_propositions,
         properties,
     );
-    let builder_output: ModelBuildingOutput<MdpType> = prism_model_builder::build_model(
-        &mut prism_model,
-        &atomic_propositions[..],
-        properties.into_iter(),
-        &constants,
-    )?;
-    let model = builder_output.model;
-    let properties = builder_output.properties;
-
-    println!("Model has {} states", model.states.len());
-
-    if properties.len() > 1 {
-        panic!("Checking multiple properties is temporarily unsupported");
+    // The owners of the states of games are determined by their players
+    let (model, properties) = if is_game {
+        let builder_output: ModelBuildingOutput<TwoPlayerStochasticGameType> =
+            prism_model_builder::build_model(
+                &mut prism_model,
+                &atomic_propositions[..],
+                properties.into_iter(),
+                &constants,
+            )?;
+        (
+            BuiltModel::Game(builder_output.model),
+            builder_output.properties,
+        )
+    } else {
+        let builder_output: ModelBuildingOutput<MdpType> = prism_model_builder::build_model(
+            &mut prism_model,
+            &atomic_propositions[..],
+            properties.into_iter(),
+            &constants,
+        )?;
+        (
+            BuiltModel::SinglePlayer(builder_output.model),
+            builder_output.properties,
+        )
+    };
+
+    println!("Model has {} states", model.state_count());
+
+    let mut failed_properties = 0;
+    for (i, (name, property)) in property_names.iter().zip(properties).enumerate() {
+        println!(
+            "Checking property {} of {}: {}",
+            i + 1,
+            property_names.len(),
+            name
+        );
+        let result = match (&model, player_two_states) {
+            (BuiltModel::Game(game), _) => tiny_pmc::checking::check_game(game.clone(), property),
+            (BuiltModel::SinglePlayer(model), None) => {
+                tiny_pmc::checking::check(model.clone(), property)
+            }
+            (BuiltModel::SinglePlayer(model), Some(player_two_states)) => {
+                let game = tiny_pmc::building::assign_owners(model.clone(), player_two_states);
+                tiny_pmc::checking::check_game(game, property)
+            }
+        };
+        match result {
+            Ok(result) => {
+                for (state, value) in &result.printed_states {
+                    println!("{}:{}={}", state, model.state_valuation(*state), value);
+                }
+                if result.initial_values.len() > 1 {
+                    for (state, value) in &result.initial_values {
+                        println!(
+                            "Initial state {}:{}={}",
+                            state,
+                            model.state_valuation(*state),
+                            value
+                        );
+                    }
+                }
+                println!("Result: {}", result.value)
+            }
+            Err(err) => {
+                println!("Error during model checking: {:?}", err);
+                failed_properties += 1;
+            }
+        }
     }
-    // for (i, property) in properties.iter().enumerate() {
-    println!("Checking property {} of {}", 0 + 1, properties.len());
-    tiny_pmc::checking::check(model, properties[0].clone())?;
-    // }
 
     println!("Finished in {:?}", start_time.elapsed());
+    if failed_properties > 0 {
+        return Err(ModelCheckerError::PropertiesFailed {
+            failed: failed_properties,
+            total: property_names.len(),
+        });
+    }
     Ok(())
 }
 
+enum BuiltModel {
+    SinglePlayer(Mdp),
+    Game(TwoPlayerStochasticGame),
+}
+
+impl BuiltModel {
+    fn state_count(&self) -> usize {
+        match self {
+            BuiltModel::SinglePlayer(model) => model.states.len(),
+            BuiltModel::Game(game) => game.states.len(),
+        }
+    }
+
+    fn state_valuation(&self, index: usize) -> String {
+        match self {
+            BuiltModel::SinglePlayer(model) => model.states[index]
+                .valuation
+                .displayable(&model.valuation_context)
+                .to_string(),
+            BuiltModel::Game(game) => game.states[index]
+                .valuation
+                .displayable(&game.valuation_context)
+                .to_string(),
+        }
+    }
+}
+
 fn read_model_file(path: &str) -> Result<String, std::io::Error> {
     std::fs::read_to_string(path)
 }
@@ -71,8 +199,11 @@ enum ModelCheckerError {
     InputFileError(std::io::Error),
     ConstParsingError(ConstParsingError),
     ModelAndPropertyParsingError,
+    UnknownPlayerLabel(String),
+    PlayerLabelForGame,
     ModelBuildingError(ModelBuildingError),
     ModelCheckingError(CheckerError),
+    PropertiesFailed { failed: usize, total: usize },
 }
 
 impl ModelCheckerError {
@@ -95,6 +226,18 @@ impl ModelCheckerError {
                 println!("Error during model checking: {:?}", err);
                 5
             }
+            ModelCheckerError::PropertiesFailed { failed, total } => {
+                println!("{failed} of {total} properties could not be checked");
+                5
+            }
+            ModelCheckerError::UnknownPlayerLabel(label) => {
+                println!("The model does not contain a label with name `{label}`");
+                6
+            }
+            ModelCheckerError::PlayerLabelForGame => {
+                println!("The owners of the states of games are given by their players");
+                6
+            }
         }
     }
 }
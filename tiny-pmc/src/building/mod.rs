@@ -1,2 +1,5 @@
 mod atomic_propositions;
 pub use atomic_propositions::prism_objectives_to_atomic_propositions;
+
+mod owners;
+pub use owners::{assign_owners, player_two_label_to_atomic_proposition};
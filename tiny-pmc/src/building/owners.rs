@@ -0,0 +1,45 @@
+use crate::PrismModel;
+use chumsky::prelude::SimpleSpan;
+use prism_model::{Expression, VariableReference};
+use probabilistic_models::{
+    AtomicProposition, AtomicPropositions, IterFunctions, IterProbabilisticModel, ModelTypes,
+    ProbabilisticModel, SinglePlayer, TwoPlayer, TwoPlayerStochasticGame,
+};
+
+/// Adds the condition of the label with the given name as an atomic proposition. States that
+/// satisfy it are owned by player two once the model is turned into a game. Returns `None` if the
+/// model has no label of that name.
+pub fn player_two_label_to_atomic_proposition(
+    prism_model: &PrismModel,
+    label: &str,
+    atomic_propositions: &mut Vec<Expression<VariableReference, SimpleSpan>>,
+) -> Option<AtomicProposition> {
+    let label = prism_model.labels.by_name(label)?;
+    let res = AtomicProposition::new(atomic_propositions.len());
+    atomic_propositions.push(label.condition.clone());
+    Some(res)
+}
+
+/// Turns a single-player model into a two-player game in which all states satisfying
+/// `player_two_states` are owned by player two and all other states by player one.
+pub fn assign_owners<M: ModelTypes<Owners = SinglePlayer>>(
+    model: ProbabilisticModel<M>,
+    player_two_states: AtomicProposition,
+) -> TwoPlayerStochasticGame<M::Predecessors, M::Valuation, M::AtomicPropositions, M::InitialStates>
+{
+    let mut game: TwoPlayerStochasticGame<
+        M::Predecessors,
+        M::Valuation,
+        M::AtomicPropositions,
+        M::InitialStates,
+    > = model
+        .into_iter()
+        .map_owners(|_| TwoPlayer::PlayerOne)
+        .collect();
+    for state in &mut game.states {
+        if state.atomic_propositions.get_value(player_two_states.index) {
+            state.owner = TwoPlayer::PlayerTwo;
+        }
+    }
+    game
+}
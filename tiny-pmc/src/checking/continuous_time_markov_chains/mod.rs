@@ -0,0 +1,73 @@
+use super::{as_atomic_proposition, check_markov_chain};
+use crate::CheckerError;
+use probabilistic_model_algorithms::value_iteration::markov_chains::time_bounded_until_probabilities;
+use probabilistic_models::{
+    AtomicProposition, DistributionVector, ModelTypes, ProbabilisticModel, SingleAction,
+    SinglePlayer, VectorPredecessors,
+};
+use probabilistic_properties::{Bound, BoundOperator, PathFormula, Query};
+
+pub fn check_ctmc<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: ProbabilisticModel<M>,
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, CheckerError> {
+    if let Query::ProbabilityValue { path, .. } = &query {
+        let values = match path {
+            PathFormula::BoundedEventually { condition, bound } => {
+                let after = as_atomic_proposition(condition)?;
+                let time = bound_to_time(bound)?;
+                time_bounded_until_probabilities(&model, None, after.index, time, 0.000_001)
+            }
+            PathFormula::BoundedUntil {
+                before,
+                after,
+                bound,
+            } => {
+                let before = as_atomic_proposition(before)?;
+                let after = as_atomic_proposition(after)?;
+                let time = bound_to_time(bound)?;
+                time_bounded_until_probabilities(
+                    &model,
+                    Some(before.index),
+                    after.index,
+                    time,
+                    0.000_001,
+                )
+            }
+            // Unbounded reachability does not depend on timing, so it can be computed on the
+            // embedded discrete-time Markov chain.
+            PathFormula::Eventually { .. }
+            | PathFormula::Until { .. }
+            | PathFormula::WeakUntil { .. }
+            | PathFormula::Release { .. } => {
+                return check_markov_chain(model, query);
+            }
+            // The successor distribution of the embedded chain does not depend on timing either
+            PathFormula::Next { .. } => return check_markov_chain(model, query),
+            PathFormula::Generally { .. } | PathFormula::Ltl { .. } => {
+                return Err(CheckerError::NoSuitableAlgorithm);
+            }
+        };
+        return Ok(values);
+    }
+
+    Err(CheckerError::NoSuitableAlgorithm)
+}
+
+/// Converts an upper time bound into the time within which the condition must be reached. In
+/// continuous time, strict and non-strict bounds coincide. Lower bounds are not supported.
+fn bound_to_time(bound: &Bound<f64>) -> Result<f64, CheckerError> {
+    match bound.operator {
+        BoundOperator::LessOrEqual | BoundOperator::LessThan if bound.value >= 0.0 => {
+            Ok(bound.value)
+        }
+        _ => Err(CheckerError::NoSuitableAlgorithm),
+    }
+}
@@ -1,8 +1,14 @@
+use super::rewards::{check_reward, check_steady_state};
+use super::{as_atomic_proposition, bound_to_steps, safety_complement_states};
+use probabilistic_model_algorithms::value_iteration::markov_chains::{
+    bounded_until_probabilities, next_probabilities, reachability_probabilities,
+    until_probabilities,
+};
 use probabilistic_models::{
     AtomicProposition, DistributionVector, ModelTypes, ProbabilisticModel, SingleAction,
     SinglePlayer, VectorPredecessors,
 };
-use probabilistic_properties::Query;
+use probabilistic_properties::{PathFormula, Query};
 
 pub fn check_markov_chain<
     M: ModelTypes<
@@ -13,8 +19,84 @@ pub fn check_markov_chain<
         >,
 >(
     model: ProbabilisticModel<M>,
-    query: Query<i64, f64, AtomicProposition>,
-) -> Result<f64, super::CheckerError> {
-    let _ = (model, query);
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, super::CheckerError> {
+    // Without non-determinism, minimising and maximising coincide, so the kind is ignored.
+    if let Query::ProbabilityValue { path, .. } = query {
+        let values = match path {
+            PathFormula::Eventually { condition } => {
+                let after = as_atomic_proposition(&condition)?;
+                until_probabilities(&model, None, after.index, 0.000_001)
+            }
+            PathFormula::Until { before, after } => {
+                let before = as_atomic_proposition(&before)?;
+                let after = as_atomic_proposition(&after)?;
+                until_probabilities(&model, Some(before.index), after.index, 0.000_001)
+            }
+            PathFormula::BoundedEventually { condition, bound } => {
+                let after = as_atomic_proposition(&condition)?;
+                let steps = bound_to_steps(&bound)?;
+                bounded_until_probabilities(&model, None, after.index, steps)
+            }
+            PathFormula::BoundedUntil {
+                before,
+                after,
+                bound,
+            } => {
+                let before = as_atomic_proposition(&before)?;
+                let after = as_atomic_proposition(&after)?;
+                let steps = bound_to_steps(&bound)?;
+                bounded_until_probabilities(&model, Some(before.index), after.index, steps)
+            }
+            PathFormula::Ltl { .. } => {
+                return Err(super::CheckerError::NoSuitableAlgorithm);
+            }
+            PathFormula::Next { condition } => {
+                let condition = as_atomic_proposition(&condition)?;
+                next_probabilities(&model, condition.index)
+            }
+            path @ (PathFormula::Generally { .. }
+            | PathFormula::WeakUntil { .. }
+            | PathFormula::Release { .. }) => {
+                let complement = path
+                    .as_safety_complement()
+                    .ok_or(super::CheckerError::NoSuitableAlgorithm)?;
+                let (target, avoid) = safety_complement_states(&model, &complement);
+                complement_probabilities(&model, &target, &avoid)
+            }
+        };
+        return Ok(values);
+    }
+    if let Query::RewardValue { name, reward, .. } = query {
+        return check_reward(&model, None, name.as_deref(), &reward);
+    }
+    if let Query::LongRunAverageValue { condition, .. } = query {
+        return check_steady_state(&model, None, &condition);
+    }
+
     Err(super::CheckerError::NoSuitableAlgorithm)
 }
+
+/// Computes the probabilities of not reaching a target state before an avoided state
+fn complement_probabilities<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = SingleAction<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    target: &[bool],
+    avoid: &[bool],
+) -> Vec<f64> {
+    let may_continue: Vec<bool> = target
+        .iter()
+        .zip(avoid)
+        .map(|(&target, &avoid)| !target && !avoid)
+        .collect();
+    reachability_probabilities(model, target, &may_continue, 0.000_001)
+        .into_iter()
+        .map(|p| 1.0 - p)
+        .collect()
+}
@@ -1,8 +1,16 @@
+use super::rewards::{check_reward, check_steady_state};
+use super::{as_atomic_proposition, bound_to_steps, safety_complement_states};
+use probabilistic_model_algorithms::ltl::{ltl_probabilities_maximise, ltl_probabilities_minimise};
+use probabilistic_model_algorithms::value_iteration::mdp::{
+    ReachabilityObjective, bounded_reachability_maximise, bounded_reachability_minimise,
+    next_maximise, next_minimise, optimistic_reachability_maximise,
+    optimistic_reachability_minimise,
+};
 use probabilistic_models::{
     ActionVector, AtomicProposition, DistributionVector, ModelTypes, ProbabilisticModel,
     SinglePlayer, VectorPredecessors,
 };
-use probabilistic_properties::{NonDeterminismKind, PathFormula, Query, StateFormula};
+use probabilistic_properties::{NonDeterminismKind, PathFormula, Query};
 
 pub fn check_mdp<
     M: ModelTypes<
@@ -13,25 +21,137 @@ pub fn check_mdp<
         >,
 >(
     model: ProbabilisticModel<M>,
-    query: Query<i64, f64, AtomicProposition>,
-) -> Result<f64, super::CheckerError> {
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, super::CheckerError> {
     if let Query::ProbabilityValue {
         non_determinism: Some(non_determinism),
-        path: PathFormula::Eventually { condition },
+        path,
     } = query
     {
-        if let StateFormula::Expression(ap) = *condition {
-            let result = match non_determinism {
-                NonDeterminismKind::Maximise => {probabilistic_model_algorithms::value_iteration::mdp::optimistic_value_iteration_maximise(
-                    model, ap.index, 0.000_001,
-                )}
-                NonDeterminismKind::Minimise => {probabilistic_model_algorithms::value_iteration::mdp::optimistic_value_iteration_minimise(
-                    model, ap.index, 0.000_001,
-                )}
-            } ;
-            return Ok(result);
-        }
+        let result = match path {
+            PathFormula::Eventually { condition } => {
+                let after = as_atomic_proposition(&condition)?;
+                let objective = ReachabilityObjective::eventually(&model, after.index);
+                unbounded(model, &objective, non_determinism)
+            }
+            PathFormula::Until { before, after } => {
+                let before = as_atomic_proposition(&before)?;
+                let after = as_atomic_proposition(&after)?;
+                let objective =
+                    ReachabilityObjective::until(&model, Some(before.index), after.index);
+                unbounded(model, &objective, non_determinism)
+            }
+            PathFormula::BoundedEventually { condition, bound } => {
+                let after = as_atomic_proposition(&condition)?;
+                let steps = bound_to_steps(&bound)?;
+                let objective = ReachabilityObjective::eventually(&model, after.index);
+                bounded(&model, &objective, steps, non_determinism)
+            }
+            PathFormula::BoundedUntil {
+                before,
+                after,
+                bound,
+            } => {
+                let before = as_atomic_proposition(&before)?;
+                let after = as_atomic_proposition(&after)?;
+                let steps = bound_to_steps(&bound)?;
+                let objective =
+                    ReachabilityObjective::until(&model, Some(before.index), after.index);
+                bounded(&model, &objective, steps, non_determinism)
+            }
+            PathFormula::Next { condition } => {
+                let condition = as_atomic_proposition(&condition)?;
+                match non_determinism {
+                    NonDeterminismKind::Maximise => next_maximise(&model, condition.index),
+                    NonDeterminismKind::Minimise => next_minimise(&model, condition.index),
+                }
+            }
+            path @ (PathFormula::Generally { .. }
+            | PathFormula::WeakUntil { .. }
+            | PathFormula::Release { .. }) => {
+                // The formula holds iff its complementary reachability objective does not, so the
+                // optimisation direction flips.
+                let safety_complement = path
+                    .as_safety_complement()
+                    .ok_or(super::CheckerError::NoSuitableAlgorithm)?;
+                let (target, avoid) = safety_complement_states(&model, &safety_complement);
+                let objective = ReachabilityObjective::new(target, avoid);
+                complement(unbounded(model, &objective, non_determinism.opposite()))
+            }
+            PathFormula::Ltl { formula } => {
+                let result = match non_determinism {
+                    NonDeterminismKind::Maximise => {
+                        ltl_probabilities_maximise(model, &formula, 0.000_001)
+                    }
+                    NonDeterminismKind::Minimise => {
+                        ltl_probabilities_minimise(model, &formula, 0.000_001)
+                    }
+                };
+                result.ok_or(super::CheckerError::NoSuitableAlgorithm)?
+            }
+        };
+        return Ok(result);
+    }
+    if let Query::RewardValue {
+        non_determinism: Some(non_determinism),
+        name,
+        reward,
+    } = query
+    {
+        return check_reward(&model, Some(non_determinism), name.as_deref(), &reward);
+    }
+    if let Query::LongRunAverageValue {
+        non_determinism: Some(non_determinism),
+        condition,
+    } = query
+    {
+        return check_steady_state(&model, Some(non_determinism), &condition);
     }
 
     Err(super::CheckerError::NoSuitableAlgorithm)
 }
+
+fn unbounded<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+    non_determinism: NonDeterminismKind,
+) -> Vec<f64> {
+    match non_determinism {
+        NonDeterminismKind::Maximise => {
+            optimistic_reachability_maximise(model, objective, 0.000_001)
+        }
+        NonDeterminismKind::Minimise => {
+            optimistic_reachability_minimise(model, objective, 0.000_001)
+        }
+    }
+}
+
+fn bounded<
+    M: ModelTypes<
+            Distribution = DistributionVector,
+            ActionCollection = ActionVector<DistributionVector>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: &ProbabilisticModel<M>,
+    objective: &ReachabilityObjective,
+    steps: usize,
+    non_determinism: NonDeterminismKind,
+) -> Vec<f64> {
+    match non_determinism {
+        NonDeterminismKind::Maximise => bounded_reachability_maximise(model, objective, steps),
+        NonDeterminismKind::Minimise => bounded_reachability_minimise(model, objective, steps),
+    }
+}
+
+/// The probabilities of the complementary objective
+fn complement(values: Vec<f64>) -> Vec<f64> {
+    values.into_iter().map(|p| 1.0 - p).collect()
+}
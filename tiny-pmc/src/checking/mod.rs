@@ -1,51 +1,258 @@
 mod markov_chains;
 pub use markov_chains::check_markov_chain;
 
+mod continuous_time_markov_chains;
+pub use continuous_time_markov_chains::check_ctmc;
+
 mod stochastic_games;
 pub use stochastic_games::check_stochastic_game;
 
 mod transition_systems;
-pub use transition_systems::check_transition_system;
+use transition_systems::supported_by_transition_systems;
+pub use transition_systems::{TransitionSystemResult, check_transition_system};
 
 mod markov_decision_processes;
 pub use markov_decision_processes::check_mdp;
 
+mod rewards;
+
 mod nonstochastic_games;
-pub use nonstochastic_games::check_nonstochastic_game;
+use nonstochastic_games::supported_by_nonstochastic_games;
+pub use nonstochastic_games::{NonstochasticGameResult, check_nonstochastic_game};
 
 use crate::CheckerError;
 use probabilistic_models::{
-    AtomicProposition, IterFunctions, IterProbabilisticModel, Mdp, ModelTypes, ProbabilisticModel,
-    VectorPredecessors,
+    AtomicProposition, AtomicPropositions, Ctmc, Dtmc, InitialStates, IterFunctions,
+    IterProbabilisticModel, Mdp, ModelTypes, ProbabilisticModel, TransitionSystem, TwoPlayer,
+    TwoPlayerNonstochasticGame, TwoPlayerStochasticGame, VectorPredecessors,
+};
+use probabilistic_properties::{
+    Bound, BoundOperator, FilterOperator, NonDeterminismKind, PathFormula, Query, SafetyComplement,
+    StateFormula,
 };
 
+/// The result of checking a query
+pub struct QueryResult {
+    /// The result of the query. Queries with a bound hold if they hold in every initial state,
+    /// which is represented by one and zero. For `=?` queries, this is the value in the first
+    /// initial state, and `initial_values` contains the values in all initial states. For filters,
+    /// this is the aggregated value.
+    pub value: f64,
+    /// The initial states and their values, in the order of the initial states of the model.
+    /// Empty for filters other than `filter(print, ...)`.
+    pub initial_values: Vec<(usize, f64)>,
+    /// The states and values reported by `filter(print, ...)`, ordered by state index
+    pub printed_states: Vec<(usize, f64)>,
+}
+
+impl QueryResult {
+    fn from_value(value: f64) -> Self {
+        Self {
+            value,
+            initial_values: Vec::new(),
+            printed_states: Vec::new(),
+        }
+    }
+
+    fn from_initial_values(
+        query: &Query<f64, f64, AtomicProposition>,
+        initial_values: Vec<(usize, f64)>,
+    ) -> Self {
+        let value = if matches!(query, Query::StateFormula(_)) {
+            if initial_values.iter().all(|(_, value)| *value != 0.0) {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            initial_values[0].1
+        };
+        Self {
+            value,
+            initial_values,
+            printed_states: Vec::new(),
+        }
+    }
+}
+
 pub fn check<M: ModelTypes>(
     model: ProbabilisticModel<M>,
-    query: probabilistic_properties::Query<i64, f64, AtomicProposition>,
-) -> Result<f64, CheckerError> {
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<QueryResult, CheckerError>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    let initial_states: Vec<usize> = model.initial_states.iter().copied().collect();
+    match query {
+        Query::Filter {
+            operator,
+            query,
+            states,
+        } => {
+            let filter_states = filter_states(&model, states, check_states)?;
+            let values = check_states(model, *query)?;
+            apply_filter(operator, &values, &filter_states, &initial_states)
+        }
+        query => {
+            let values = check_initial_states(model, &query)?;
+            let initial_values = initial_states.into_iter().zip(values).collect();
+            Ok(QueryResult::from_initial_values(&query, initial_values))
+        }
+    }
+}
+
+/// Computes the value of the query in every initial state. Qualitative properties of transition
+/// systems with a single initial state are checked by a search from that state; all other
+/// properties are checked for all states.
+fn check_initial_states<M: ModelTypes>(
+    model: ProbabilisticModel<M>,
+    query: &Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, CheckerError>
+where
+    ProbabilisticModel<M>: Clone,
+{
     let features = model.get_model_features();
 
-    // if features.representable_as_transition_system() {
-    //     let ts: TransitionSystem<
-    //         M::Predecessors,
-    //         M::Valuation,
-    //         M::AtomicPropositions,
-    //         M::InitialStates,
-    //     > = model.into_iter().map_owners(|_| ()).collect();
-    //     let ts: TransitionSystem<
-    //         VectorPredecessors,
-    //         M::Valuation,
-    //         M::AtomicPropositions,
-    //         M::InitialStates,
-    //     > = ts.rebuild_and_transform_predecessors();
-    //
-    //     let result = check_transition_system(ts, &query);
-    //
-    //     let model = match result {
-    //         Ok(result) => return Ok(result),
-    //         Err((CheckerError::NoSuitableAlgorithm)) => (),
-    //     };
-    // };
+    if !features.continuous_time
+        && features.representable_as_transition_system()
+        && model.initial_states.count() == 1
+        && supported_by_transition_systems(query)
+    {
+        let ts: TransitionSystem<
+            M::Predecessors,
+            M::Valuation,
+            M::AtomicPropositions,
+            M::InitialStates,
+        > = model.into_iter().map_owners(|_| ()).collect();
+        let ts: TransitionSystem<
+            VectorPredecessors,
+            M::Valuation,
+            M::AtomicPropositions,
+            M::InitialStates,
+        > = ts.rebuild_and_transform_predecessors();
+
+        return check_transition_system(ts, query).map(|result| vec![result.value]);
+    }
+
+    let initial_states: Vec<usize> = model.initial_states.iter().copied().collect();
+    let values = check_states(model, query.clone())?;
+    Ok(initial_states
+        .into_iter()
+        .map(|state| values[state])
+        .collect())
+}
+
+/// Computes the value of the query in every state, indexed by state
+pub fn check_states<M: ModelTypes>(
+    mut model: ProbabilisticModel<M>,
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, CheckerError>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    let features = model.get_model_features();
+    let nonstochastic = !features.continuous_time
+        && !features.probabilism
+        && supported_by_nonstochastic_games(&query);
+
+    match query {
+        Query::Filter { .. } => {
+            // A nested filter has the same value in every state
+            let state_count = model.states.len();
+            return check(model, query).map(|result| vec![result.value; state_count]);
+        }
+        Query::StateFormula(StateFormula::Expression(ap)) => {
+            return Ok(atomic_proposition_values(&model, ap));
+        }
+        Query::StateFormula(StateFormula::ProbabilityBound {
+            non_determinism,
+            bound,
+            path,
+        }) if !nonstochastic => {
+            // Without min/max, the bound must hold for all resolutions of the non-determinism.
+            let non_determinism = non_determinism.unwrap_or(match bound.operator {
+                BoundOperator::GreaterThan | BoundOperator::GreaterOrEqual => {
+                    NonDeterminismKind::Minimise
+                }
+                BoundOperator::LessThan | BoundOperator::LessOrEqual => {
+                    NonDeterminismKind::Maximise
+                }
+            });
+            let query = Query::ProbabilityValue {
+                non_determinism: Some(non_determinism),
+                path: *path,
+            };
+            return check_states(model, query).map(|values| satisfies_bound(&values, &bound));
+        }
+        Query::ProbabilityValue {
+            non_determinism,
+            path: path @ PathFormula::Ltl { .. },
+        } if features.representable_as_markov_decision_process() => {
+            // LTL formulas are checked on the product with a Rabin automaton, which is an MDP in
+            // any case. They do not depend on timing, so continuous-time models are checked on
+            // their embedded chain. Without non-determinism, minimising and maximising coincide.
+            let non_determinism = match non_determinism {
+                Some(non_determinism) => non_determinism,
+                None if features.representable_as_markov_chain() => NonDeterminismKind::Maximise,
+                None => return Err(CheckerError::NoSuitableAlgorithm),
+            };
+            model.add_self_loops_to_deadlocks();
+            let mdp: Mdp<M::Predecessors, M::Valuation, M::AtomicPropositions, M::InitialStates> =
+                model.into_iter().map_owners(|_| ()).collect();
+            let mdp: Mdp<
+                VectorPredecessors,
+                M::Valuation,
+                M::AtomicPropositions,
+                M::InitialStates,
+            > = mdp.rebuild_and_transform_predecessors();
+            let query = Query::ProbabilityValue {
+                non_determinism: Some(non_determinism),
+                path,
+            };
+            return check_mdp(mdp, query);
+        }
+        _ => (),
+    }
+
+    if features.continuous_time {
+        model.add_self_loops_to_deadlocks();
+        let ctmc: Ctmc<M::Predecessors, M::Valuation, M::AtomicPropositions, M::InitialStates> =
+            model.into_iter().map_owners(|_| ()).collect();
+        let ctmc: Ctmc<VectorPredecessors, M::Valuation, M::AtomicPropositions, M::InitialStates> =
+            ctmc.rebuild_and_transform_predecessors();
+
+        return check_ctmc(ctmc, query);
+    }
+    if nonstochastic {
+        // Qualitative properties without min/max must hold for all resolutions of the
+        // non-determinism, so all choices are made by the opponent.
+        let game: TwoPlayerNonstochasticGame<
+            M::Predecessors,
+            M::Valuation,
+            M::AtomicPropositions,
+            M::InitialStates,
+        > = model
+            .into_iter()
+            .map_owners(|_| TwoPlayer::PlayerTwo)
+            .collect();
+        let game: TwoPlayerNonstochasticGame<
+            VectorPredecessors,
+            M::Valuation,
+            M::AtomicPropositions,
+            M::InitialStates,
+        > = game.rebuild_and_transform_predecessors();
+
+        return check_nonstochastic_game(game, query).map(|result| result.values());
+    }
+    if features.representable_as_markov_chain() {
+        model.add_self_loops_to_deadlocks();
+        let dtmc: Dtmc<M::Predecessors, M::Valuation, M::AtomicPropositions, M::InitialStates> =
+            model.into_iter().map_owners(|_| ()).collect();
+        let dtmc: Dtmc<VectorPredecessors, M::Valuation, M::AtomicPropositions, M::InitialStates> =
+            dtmc.rebuild_and_transform_predecessors();
+
+        return check_markov_chain(dtmc, query);
+    }
     if features.representable_as_markov_decision_process() {
         let mdp: Mdp<M::Predecessors, M::Valuation, M::AtomicPropositions, M::InitialStates> =
             model.into_iter().map_owners(|_| ()).collect();
@@ -57,7 +264,244 @@ pub fn check<M: ModelTypes>(
         match result {
             Ok(result) => return Ok(result),
             Err(CheckerError::NoSuitableAlgorithm) => (),
+            Err(err) => return Err(err),
         };
     }
     Err(CheckerError::NoSuitableAlgorithm)
 }
+
+pub fn check_game<M: ModelTypes<Owners = TwoPlayer>>(
+    model: ProbabilisticModel<M>,
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<QueryResult, CheckerError>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    let initial_states: Vec<usize> = model.initial_states.iter().copied().collect();
+    match query {
+        Query::Filter {
+            operator,
+            query,
+            states,
+        } => {
+            let filter_states = filter_states(&model, states, check_game_states)?;
+            let values = check_game_states(model, *query)?;
+            apply_filter(operator, &values, &filter_states, &initial_states)
+        }
+        query => {
+            let values = check_game_states(model, query.clone())?;
+            let initial_values = initial_states
+                .into_iter()
+                .map(|state| (state, values[state]))
+                .collect();
+            Ok(QueryResult::from_initial_values(&query, initial_values))
+        }
+    }
+}
+
+/// Computes the value of the query in every state of the game, indexed by state
+pub fn check_game_states<M: ModelTypes<Owners = TwoPlayer>>(
+    model: ProbabilisticModel<M>,
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, CheckerError>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    let features = model.get_model_features();
+
+    match query {
+        Query::Filter { .. } => {
+            let state_count = model.states.len();
+            return check_game(model, query).map(|result| vec![result.value; state_count]);
+        }
+        Query::StateFormula(StateFormula::Expression(ap)) => {
+            return Ok(atomic_proposition_values(&model, ap));
+        }
+        _ => (),
+    }
+
+    if !features.probabilism && supported_by_nonstochastic_games(&query) {
+        let game: TwoPlayerNonstochasticGame<
+            M::Predecessors,
+            M::Valuation,
+            M::AtomicPropositions,
+            M::InitialStates,
+        > = model.into_iter().collect();
+        let game: TwoPlayerNonstochasticGame<
+            VectorPredecessors,
+            M::Valuation,
+            M::AtomicPropositions,
+            M::InitialStates,
+        > = game.rebuild_and_transform_predecessors();
+
+        return check_nonstochastic_game(game, query).map(|result| result.values());
+    }
+
+    let game: TwoPlayerStochasticGame<
+        M::Predecessors,
+        M::Valuation,
+        M::AtomicPropositions,
+        M::InitialStates,
+    > = model.into_iter().collect();
+    let game: TwoPlayerStochasticGame<
+        VectorPredecessors,
+        M::Valuation,
+        M::AtomicPropositions,
+        M::InitialStates,
+    > = game.rebuild_and_transform_predecessors();
+
+    check_stochastic_game(game, query)
+}
+
+/// The states over which a filter aggregates. Without a filter formula, these are all states.
+/// Filter formulas other than atomic propositions are checked on a copy of the model.
+fn filter_states<M: ModelTypes>(
+    model: &ProbabilisticModel<M>,
+    states: Option<StateFormula<f64, f64, AtomicProposition>>,
+    check_states: impl FnOnce(
+        ProbabilisticModel<M>,
+        Query<f64, f64, AtomicProposition>,
+    ) -> Result<Vec<f64>, CheckerError>,
+) -> Result<Vec<bool>, CheckerError>
+where
+    ProbabilisticModel<M>: Clone,
+{
+    match states {
+        Some(StateFormula::Expression(ap)) => Ok(model
+            .states
+            .iter()
+            .map(|state| state.atomic_propositions.get_value(ap.index))
+            .collect()),
+        Some(states) => Ok(check_states(model.clone(), Query::StateFormula(states))?
+            .into_iter()
+            .map(|value| value != 0.0)
+            .collect()),
+        None => Ok(vec![true; model.states.len()]),
+    }
+}
+
+/// Combines the values of the filter states. Boolean values are represented by one and zero.
+fn apply_filter(
+    operator: FilterOperator,
+    values: &[f64],
+    filter_states: &[bool],
+    initial_states: &[usize],
+) -> Result<QueryResult, CheckerError> {
+    let selected: Vec<(usize, f64)> = values
+        .iter()
+        .enumerate()
+        .filter(|(state, _)| filter_states[*state])
+        .map(|(state, &value)| (state, value))
+        .collect();
+    let selected_values = || selected.iter().map(|(_, value)| *value);
+    let from_bool = |holds: bool| if holds { 1.0 } else { 0.0 };
+
+    if selected.is_empty()
+        && matches!(
+            operator,
+            FilterOperator::Min
+                | FilterOperator::Max
+                | FilterOperator::Avg
+                | FilterOperator::First
+                | FilterOperator::State
+        )
+    {
+        return Err(CheckerError::EmptyFilter);
+    }
+
+    let value = match operator {
+        FilterOperator::Min => selected_values().fold(f64::INFINITY, f64::min),
+        FilterOperator::Max => selected_values().fold(f64::NEG_INFINITY, f64::max),
+        FilterOperator::Avg => selected_values().sum::<f64>() / selected.len() as f64,
+        FilterOperator::Sum => selected_values().sum(),
+        FilterOperator::Count => selected_values().filter(|value| *value != 0.0).count() as f64,
+        FilterOperator::ForAll => from_bool(selected_values().all(|value| value != 0.0)),
+        FilterOperator::Exists => from_bool(selected_values().any(|value| value != 0.0)),
+        FilterOperator::First => selected[0].1,
+        FilterOperator::Print => {
+            return Ok(QueryResult {
+                value: values[initial_states[0]],
+                initial_values: initial_states
+                    .iter()
+                    .map(|&state| (state, values[state]))
+                    .collect(),
+                printed_states: selected,
+            });
+        }
+        FilterOperator::State => {
+            if selected.len() > 1 {
+                return Err(CheckerError::AmbiguousFilterState(selected.len()));
+            }
+            selected[0].1
+        }
+    };
+    Ok(QueryResult::from_value(value))
+}
+
+fn atomic_proposition_values<M: ModelTypes>(
+    model: &ProbabilisticModel<M>,
+    ap: AtomicProposition,
+) -> Vec<f64> {
+    model
+        .states
+        .iter()
+        .map(|state| {
+            if state.atomic_propositions.get_value(ap.index) {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn satisfies_bound(values: &[f64], bound: &Bound<f64>) -> Vec<f64> {
+    values
+        .iter()
+        .map(|value| {
+            if bound.is_satisfied_by(value) {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn as_atomic_proposition(
+    formula: &StateFormula<f64, f64, AtomicProposition>,
+) -> Result<AtomicProposition, CheckerError> {
+    formula
+        .as_expression()
+        .copied()
+        .ok_or(CheckerError::NoSuitableAlgorithm)
+}
+
+/// Converts an upper step bound into the number of steps that may be taken. Lower bounds and
+/// non-integer bounds are not supported.
+fn bound_to_steps(bound: &Bound<f64>) -> Result<usize, CheckerError> {
+    if bound.value.fract() != 0.0 {
+        return Err(CheckerError::NoSuitableAlgorithm);
+    }
+    let steps = match bound.operator {
+        BoundOperator::LessOrEqual => bound.value as i64,
+        BoundOperator::LessThan => bound.value as i64 - 1,
+        BoundOperator::GreaterThan | BoundOperator::GreaterOrEqual => {
+            return Err(CheckerError::NoSuitableAlgorithm);
+        }
+    };
+    usize::try_from(steps).map_err(|_| CheckerError::NoSuitableAlgorithm)
+}
+
+/// The target states and the avoided states of the complementary reachability objective of a
+/// `G`, `W` or `R` formula
+fn safety_complement_states<M: ModelTypes>(
+    model: &ProbabilisticModel<M>,
+    complement: &SafetyComplement<AtomicProposition>,
+) -> (Vec<bool>, Vec<bool>) {
+    model
+        .states
+        .iter()
+        .map(|state| complement.classify(|ap| state.atomic_propositions.get_value(ap.index)))
+        .unzip()
+}
@@ -1,9 +1,42 @@
+use probabilistic_model_algorithms::deterministic_games::{
+    BuechiAlgorithmCollection, MemorylessStrategy, NextAlgorithmCollection,
+    NonstochasticGameAlgorithm, ReachabilityAlgorithmCollection, SafetyAlgorithmCollection,
+};
+use probabilistic_model_algorithms::regions::BoxedStateRegion;
 use probabilistic_models::{
-    ActionVector, AtomicProposition, ModelTypes, ProbabilisticModel, SingleStateDistribution,
-    TwoPlayer, VectorPredecessors,
+    ActionVector, AtomicProposition, InitialStates, ModelTypes, ProbabilisticModel,
+    SingleStateDistribution, TwoPlayer, VectorPredecessors,
 };
 use probabilistic_properties::Query;
 
+pub struct NonstochasticGameResult {
+    pub winner: TwoPlayer,
+    pub winning_region: BoxedStateRegion,
+    pub strategy: MemorylessStrategy,
+}
+
+impl NonstochasticGameResult {
+    pub fn value(&self) -> f64 {
+        match self.winner {
+            TwoPlayer::PlayerOne => 1.0,
+            TwoPlayer::PlayerTwo => 0.0,
+        }
+    }
+
+    /// One for the states in the winning region of player one and zero for all other states
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.winning_region.get_size())
+            .map(|state| {
+                if self.winning_region.contains(state) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
 pub fn check_nonstochastic_game<
     M: ModelTypes<
             Predecessors = VectorPredecessors,
@@ -13,8 +46,50 @@ pub fn check_nonstochastic_game<
         >,
 >(
     model: ProbabilisticModel<M>,
-    query: Query<i64, f64, AtomicProposition>,
-) -> Result<f64, super::CheckerError> {
-    let _ = (model, query);
-    Err(super::CheckerError::NoSuitableAlgorithm)
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<NonstochasticGameResult, super::CheckerError> {
+    if let Some(solver) = ReachabilityAlgorithmCollection::create_if_compatible(&query) {
+        Ok(solve(&model, solver))
+    } else if let Some(solver) = SafetyAlgorithmCollection::create_if_compatible(&query) {
+        Ok(solve(&model, solver))
+    } else if let Some(solver) = BuechiAlgorithmCollection::create_if_compatible(&query) {
+        Ok(solve(&model, solver))
+    } else if let Some(solver) = NextAlgorithmCollection::create_if_compatible(&query) {
+        Ok(solve(&model, solver))
+    } else {
+        Err(super::CheckerError::NoSuitableAlgorithm)
+    }
+}
+
+pub fn supported_by_nonstochastic_games(query: &Query<f64, f64, AtomicProposition>) -> bool {
+    ReachabilityAlgorithmCollection::create_if_compatible(query).is_some()
+        || SafetyAlgorithmCollection::create_if_compatible(query).is_some()
+        || BuechiAlgorithmCollection::create_if_compatible(query).is_some()
+        || NextAlgorithmCollection::create_if_compatible(query).is_some()
+}
+
+fn solve<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = TwoPlayer>,
+    A: NonstochasticGameAlgorithm<WinningRegionType: 'static>,
+>(
+    model: &ProbabilisticModel<M>,
+    mut solver: A,
+) -> NonstochasticGameResult {
+    let (winning_region, strategy) = solver.winning_region_and_strategy(model);
+    let winning_region: BoxedStateRegion = winning_region.into();
+    // Player one wins if they win from every initial state
+    let winner = if model
+        .initial_states
+        .iter()
+        .all(|&state| winning_region.contains(state))
+    {
+        TwoPlayer::PlayerOne
+    } else {
+        TwoPlayer::PlayerTwo
+    };
+    NonstochasticGameResult {
+        winner,
+        winning_region,
+        strategy,
+    }
 }
@@ -0,0 +1,110 @@
+use super::as_atomic_proposition;
+use crate::CheckerError;
+use probabilistic_model_algorithms::value_iteration::long_run_average::{
+    long_run_average_markov_chain, long_run_average_maximise, long_run_average_minimise,
+};
+use probabilistic_model_algorithms::value_iteration::rewards::{
+    cumulative_reward_maximise, cumulative_reward_minimise, expected_reward_maximise,
+    expected_reward_minimise, instantaneous_reward_maximise, instantaneous_reward_minimise,
+};
+use probabilistic_models::{
+    AtomicProposition, AtomicPropositions, ModelTypes, ProbabilisticModel, RewardStructure,
+    SinglePlayer, VectorPredecessors,
+};
+use probabilistic_properties::{NonDeterminismKind, RewardFormula, StateFormula};
+
+/// Computes the values of a reward query in all states. Markov chains pass `None` as
+/// non-determinism.
+pub(super) fn check_reward<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    non_determinism: Option<NonDeterminismKind>,
+    name: Option<&str>,
+    reward: &RewardFormula<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, CheckerError> {
+    let rewards = model
+        .get_reward_structure(name)
+        .ok_or_else(|| CheckerError::UnknownRewardStructure(name.map(str::to_string)))?;
+    // Without non-determinism, both optimisation directions coincide.
+    let optimisation = non_determinism.unwrap_or(NonDeterminismKind::Maximise);
+
+    let values = match reward {
+        RewardFormula::Finally { states } => {
+            let goal = as_atomic_proposition(states)?;
+            let target: Vec<bool> = model
+                .states
+                .iter()
+                .map(|state| state.atomic_propositions.get_value(goal.index))
+                .collect();
+            match optimisation {
+                NonDeterminismKind::Maximise => {
+                    expected_reward_maximise(model, rewards, &target, 0.000_001)
+                }
+                NonDeterminismKind::Minimise => {
+                    expected_reward_minimise(model, rewards, &target, 0.000_001)
+                }
+            }
+        }
+        RewardFormula::Cumulative { k } => {
+            let steps = time_to_steps(*k)?;
+            match optimisation {
+                NonDeterminismKind::Maximise => cumulative_reward_maximise(model, rewards, steps),
+                NonDeterminismKind::Minimise => cumulative_reward_minimise(model, rewards, steps),
+            }
+        }
+        RewardFormula::Instantaneous { k } => {
+            let steps = time_to_steps(*k)?;
+            match optimisation {
+                NonDeterminismKind::Maximise => {
+                    instantaneous_reward_maximise(model, rewards, steps)
+                }
+                NonDeterminismKind::Minimise => {
+                    instantaneous_reward_minimise(model, rewards, steps)
+                }
+            }
+        }
+        RewardFormula::LongRunAverage => long_run_average(model, non_determinism, rewards),
+    };
+    Ok(values)
+}
+
+/// Computes the long-run fraction of time spent in states satisfying `condition`, which is the
+/// long-run average of a reward of one in these states.
+pub(super) fn check_steady_state<
+    M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>,
+>(
+    model: &ProbabilisticModel<M>,
+    non_determinism: Option<NonDeterminismKind>,
+    condition: &StateFormula<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, CheckerError> {
+    let condition = as_atomic_proposition(condition)?;
+    let mut rewards = RewardStructure::new(None);
+    for (state_index, state) in model.states.iter().enumerate() {
+        if state.atomic_propositions.get_value(condition.index) {
+            rewards.set_state_reward(state_index, 1.0);
+        }
+    }
+    Ok(long_run_average(model, non_determinism, &rewards))
+}
+
+fn long_run_average<M: ModelTypes<Predecessors = VectorPredecessors, Owners = SinglePlayer>>(
+    model: &ProbabilisticModel<M>,
+    non_determinism: Option<NonDeterminismKind>,
+    rewards: &RewardStructure,
+) -> Vec<f64> {
+    match non_determinism {
+        None => long_run_average_markov_chain(model, rewards, 0.000_001),
+        Some(NonDeterminismKind::Maximise) => long_run_average_maximise(model, rewards, 0.000_001),
+        Some(NonDeterminismKind::Minimise) => long_run_average_minimise(model, rewards, 0.000_001),
+    }
+}
+
+/// In discrete-time models, the time bounds of `C<=k` and `I=k` must be natural numbers.
+fn time_to_steps(k: f64) -> Result<usize, CheckerError> {
+    if k >= 0.0 && k.fract() == 0.0 {
+        Ok(k as usize)
+    } else {
+        Err(CheckerError::NoSuitableAlgorithm)
+    }
+}
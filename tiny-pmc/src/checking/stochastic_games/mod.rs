@@ -1,3 +1,6 @@
+use probabilistic_model_algorithms::traits::StochasticGameAlgorithm;
+use probabilistic_model_algorithms::value_iteration::stochastic_game_rewards::StochasticGameRewardAlgorithm;
+use probabilistic_model_algorithms::value_iteration::stochastic_games::StochasticGameValueIterationAlgorithm;
 use probabilistic_models::{
     ActionVector, AtomicProposition, DistributionVector, ModelTypes, ProbabilisticModel, TwoPlayer,
     VectorPredecessors,
@@ -13,8 +16,29 @@ pub fn check_stochastic_game<
         >,
 >(
     model: ProbabilisticModel<M>,
-    query: Query<i64, f64, AtomicProposition>,
-) -> Result<f64, super::CheckerError> {
-    let _ = (model, query);
+    query: Query<f64, f64, AtomicProposition>,
+) -> Result<Vec<f64>, super::CheckerError> {
+    if let Some(mut solver) = StochasticGameValueIterationAlgorithm::create_if_compatible(&query) {
+        let Ok(probabilities) = solver.player_one_probabilities(&model);
+        return Ok(match solver.bound() {
+            Some(bound) => probabilities
+                .iter()
+                .map(|probability| {
+                    if bound.is_satisfied_by(probability) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect(),
+            None => probabilities,
+        });
+    }
+    if let Some(mut solver) = StochasticGameRewardAlgorithm::create_if_compatible(&query) {
+        return solver
+            .player_one_probabilities(&model)
+            .map_err(|error| super::CheckerError::UnknownRewardStructure(error.name));
+    }
+
     Err(super::CheckerError::NoSuitableAlgorithm)
 }
@@ -1,8 +1,22 @@
+use probabilistic_model_algorithms::attractor;
+use probabilistic_model_algorithms::deterministic_games::MemorylessStrategy;
+use probabilistic_model_algorithms::regions::{FlagStateRegion, StateRegion};
+use probabilistic_model_algorithms::transition_systems::{Path, ReachableResult, is_reachable};
 use probabilistic_models::{
-    ActionVector, AtomicProposition, ModelTypes, ProbabilisticModel, SinglePlayer,
-    SingleStateDistribution, VectorPredecessors,
+    ActionCollection, ActionVector, AtomicProposition, Distribution, InitialStates, IterFunctions,
+    IterProbabilisticModel, ModelTypes, ProbabilisticModel, SinglePlayer, SingleStateDistribution,
+    TwoPlayer, TwoPlayerNonstochasticGame, VectorPredecessors,
 };
-use probabilistic_properties::Query;
+use probabilistic_properties::{
+    Bound, BoundOperator, NonDeterminismKind, PathFormula, Query, StateFormula,
+};
+
+pub struct TransitionSystemResult {
+    pub value: f64,
+    /// A path from the initial state that demonstrates an existential property. For `F`, it ends
+    /// in a goal state, for `G`, it ends by revisiting a state of the path or in a deadlock.
+    pub witness: Option<Path>,
+}
 
 pub fn check_transition_system<
     M: ModelTypes<
@@ -13,8 +27,201 @@ pub fn check_transition_system<
         >,
 >(
     model: ProbabilisticModel<M>,
-    query: &Query<i64, f64, AtomicProposition>,
-) -> Result<f64, super::CheckerError> {
-    let _ = (model, query);
-    Err(super::CheckerError::NoSuitableAlgorithm)
+    query: &Query<f64, f64, AtomicProposition>,
+) -> Result<TransitionSystemResult, super::CheckerError> {
+    let objective = TransitionSystemObjective::from_query(query)
+        .ok_or(super::CheckerError::NoSuitableAlgorithm)?;
+
+    let result = match (objective.non_determinism, objective.path) {
+        (NonDeterminismKind::Maximise, QualitativePath::Eventually(goal)) => {
+            match is_reachable(&model, goal.index) {
+                ReachableResult::Reachable(path) => TransitionSystemResult {
+                    value: 1.0,
+                    witness: Some(path),
+                },
+                ReachableResult::Unreachable => TransitionSystemResult {
+                    value: 0.0,
+                    witness: None,
+                },
+            }
+        }
+        (NonDeterminismKind::Minimise, QualitativePath::Eventually(goal)) => {
+            // Only states from which all paths reach the goal are attracted, as all of them
+            // are owned by the avoiding player.
+            let initial_state = model.initial_states.get(0);
+            let goal_states = model.get_states_with_ap(goal);
+            let game = into_game(model);
+            let reached = attractor::attractor_contains_state(
+                &game,
+                goal_states.into_iter(),
+                initial_state,
+                TwoPlayer::PlayerTwo,
+            );
+            TransitionSystemResult {
+                value: if reached { 1.0 } else { 0.0 },
+                witness: None,
+            }
+        }
+        (NonDeterminismKind::Maximise, QualitativePath::Generally(safe)) => {
+            let initial_state = model.initial_states.get(0);
+            let bad_states = model.get_states_without_ap(safe);
+            let game = into_game(model);
+            let forced_to_bad_states = attractor::attractor::<_, _, FlagStateRegion>(
+                &game,
+                bad_states.into_iter(),
+                TwoPlayer::PlayerTwo,
+            );
+            if forced_to_bad_states.contains(initial_state) {
+                TransitionSystemResult {
+                    value: 0.0,
+                    witness: None,
+                }
+            } else {
+                let safe_region = forced_to_bad_states.inverted();
+                let mut strategy = MemorylessStrategy::create(game.states.len());
+                strategy.choose_actions_staying_in(
+                    &game,
+                    (0..game.states.len()).filter(|&s| safe_region.contains(s)),
+                    &safe_region,
+                );
+                TransitionSystemResult {
+                    value: 1.0,
+                    witness: Some(follow_strategy(&game, &strategy, initial_state)),
+                }
+            }
+        }
+        (NonDeterminismKind::Minimise, QualitativePath::Generally(safe)) => {
+            let initial_state = model.initial_states.get(0);
+            let bad_states = model.get_states_without_ap(safe);
+            let game = into_game(model);
+            let reaches_bad_state = attractor::attractor_contains_state(
+                &game,
+                bad_states.into_iter(),
+                initial_state,
+                TwoPlayer::PlayerOne,
+            );
+            TransitionSystemResult {
+                value: if reaches_bad_state { 0.0 } else { 1.0 },
+                witness: None,
+            }
+        }
+    };
+
+    match objective.bound {
+        None => Ok(result),
+        Some(bound) => Ok(TransitionSystemResult {
+            value: if bound.is_satisfied_by(&result.value) {
+                1.0
+            } else {
+                0.0
+            },
+            witness: result.witness,
+        }),
+    }
+}
+
+pub fn supported_by_transition_systems(query: &Query<f64, f64, AtomicProposition>) -> bool {
+    TransitionSystemObjective::from_query(query).is_some()
+}
+
+enum QualitativePath {
+    Eventually(AtomicProposition),
+    Generally(AtomicProposition),
+}
+
+struct TransitionSystemObjective<'a> {
+    non_determinism: NonDeterminismKind,
+    path: QualitativePath,
+    bound: Option<&'a Bound<f64>>,
+}
+
+impl<'a> TransitionSystemObjective<'a> {
+    fn from_query(query: &'a Query<f64, f64, AtomicProposition>) -> Option<Self> {
+        let (non_determinism, path, bound) = match query {
+            Query::ProbabilityValue {
+                non_determinism: Some(non_determinism),
+                path,
+            } => (*non_determinism, path, None),
+            Query::StateFormula(StateFormula::ProbabilityBound {
+                non_determinism,
+                bound,
+                path,
+            }) => {
+                // Without explicit optimisation direction, the bound must hold for all
+                // resolutions of non-determinism, so the worst case is relevant.
+                let non_determinism = non_determinism.unwrap_or(match bound.operator {
+                    BoundOperator::GreaterThan | BoundOperator::GreaterOrEqual => {
+                        NonDeterminismKind::Minimise
+                    }
+                    BoundOperator::LessThan | BoundOperator::LessOrEqual => {
+                        NonDeterminismKind::Maximise
+                    }
+                });
+                (non_determinism, path.as_ref(), Some(bound))
+            }
+            _ => return None,
+        };
+
+        let path = match path {
+            PathFormula::Eventually { condition } => match condition.as_ref() {
+                StateFormula::Expression(ap) => QualitativePath::Eventually(*ap),
+                _ => return None,
+            },
+            PathFormula::Generally { condition } => match condition.as_ref() {
+                StateFormula::Expression(ap) => QualitativePath::Generally(*ap),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some(Self {
+            non_determinism,
+            path,
+            bound,
+        })
+    }
+}
+
+fn into_game<
+    M: ModelTypes<
+            Predecessors = VectorPredecessors,
+            Distribution = SingleStateDistribution,
+            ActionCollection = ActionVector<SingleStateDistribution>,
+            Owners = SinglePlayer,
+        >,
+>(
+    model: ProbabilisticModel<M>,
+) -> TwoPlayerNonstochasticGame<
+    VectorPredecessors,
+    M::Valuation,
+    M::AtomicPropositions,
+    M::InitialStates,
+> {
+    model
+        .into_iter()
+        .map_owners(|_| TwoPlayer::PlayerOne)
+        .collect()
+}
+
+fn follow_strategy<M: ModelTypes>(
+    model: &ProbabilisticModel<M>,
+    strategy: &MemorylessStrategy,
+    initial_state: usize,
+) -> Path {
+    let mut visited = vec![false; model.states.len()];
+    let mut states = vec![initial_state];
+    let mut actions = Vec::new();
+    let mut current = initial_state;
+    visited[current] = true;
+    while let Some(action_index) = strategy.get_choice(current) {
+        let action = model.states[current].actions.get_action(action_index);
+        current = action.successors.get_successor(0).index;
+        states.push(current);
+        actions.push(action_index);
+        if visited[current] {
+            break;
+        }
+        visited[current] = true;
+    }
+    Path { states, actions }
 }
@@ -6,6 +6,9 @@ pub mod building;
 pub mod checking;
 pub mod parsing;
 
+#[cfg(test)]
+mod tests;
+
 pub type PrismModel = prism_model::Model<
     (),
     Identifier<SimpleSpan>,
@@ -21,11 +24,28 @@ pub type PrismQuery = probabilistic_properties::Query<
 
 pub enum CheckerError {
     NoSuitableAlgorithm,
+    UnknownRewardStructure(Option<String>),
+    /// A filter that needs at least one state, e.g. `min` or `first`, has no states
+    EmptyFilter,
+    /// The `state` filter has more than one state
+    AmbiguousFilterState(usize),
 }
 impl std::fmt::Debug for CheckerError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             CheckerError::NoSuitableAlgorithm => write!(f, "No suitable model-checking algorithm"),
+            CheckerError::UnknownRewardStructure(Some(name)) => {
+                write!(f, "The model has no reward structure named \"{}\"", name)
+            }
+            CheckerError::UnknownRewardStructure(None) => {
+                write!(f, "The model has no reward structure")
+            }
+            CheckerError::EmptyFilter => write!(f, "No state satisfies the filter"),
+            CheckerError::AmbiguousFilterState(count) => write!(
+                f,
+                "The filter must be satisfied by exactly one state, but {} states satisfy it",
+                count
+            ),
         }
     }
 }
@@ -4,7 +4,7 @@ use ariadne::{Label, Report, ReportKind, Source};
 use chumsky::error::RichPattern;
 use chumsky::span::SimpleSpan;
 use chumsky::util::MaybeRef;
-use prism_model::{InvalidName, ModuleExpansionError};
+use prism_model::{ArgumentCount, InvalidName, ModuleExpansionError, PlayerError, TypeError};
 use prism_parser::{CharacterToLineMap, PrismParserError, PrismParserValidationError, Span};
 use std::ops::Range;
 
@@ -80,6 +80,34 @@ pub fn parse_prism_and_print_errors<P: AsRef<str>>(
     }
 }
 
+pub type PrismNamedProperty = prism_model::NamedProperty<crate::PrismQuery, SimpleSpan>;
+
+pub fn parse_prism_with_properties_file_and_print_errors(
+    file_name: Option<&str>,
+    source: &str,
+    properties_file_name: Option<&str>,
+    properties_source: &str,
+) -> Option<(PrismModel, Vec<PrismNamedProperty>, CharacterToLineMap)> {
+    let parse_results = prism_parser::parse_prism_with_properties_file(source, properties_source);
+    let has_errors =
+        !parse_results.model.errors.is_empty() || !parse_results.properties.errors.is_empty();
+    for error in parse_results.model.errors {
+        print_error(&file_name, source, error);
+    }
+    for error in parse_results.properties.errors {
+        print_error(&properties_file_name, properties_source, error);
+    }
+    if has_errors {
+        return None;
+    }
+
+    Some((
+        parse_results.model.output?,
+        parse_results.properties.output?,
+        parse_results.model.character_to_lines?,
+    ))
+}
+
 fn print_error(file_name: &Option<&str>, source: &str, error: PrismParserError<Span, String>) {
     let file_name = match file_name {
         Some(name) => name,
@@ -197,14 +225,14 @@ fn build_validation(
                 Label::new((file_name, span.into_range()))
                     .with_message(format!("Model type {} is not supported", model_type)),
             );
-            builder.add_help("Supported model types are `dtmc`, `mdp` and `ctmc`.");
+            builder.add_help("Supported model types are `dtmc`, `mdp`, `ctmc` and `smg`.");
             builder
         }
         PrismParserValidationError::MissingModelType => {
             let mut builder = Report::build(ReportKind::Error, (file_name, 0..1));
             builder.set_message("Missing model type");
             builder.add_label(Label::new((file_name, 0..1)));
-            builder.add_help("Add a line with `dtmc`, `mdp` or `ctmc` to your model.");
+            builder.add_help("Add a line with `dtmc`, `mdp`, `ctmc` or `smg` to your model.");
             builder
         }
         PrismParserValidationError::DuplicateModelType {
@@ -403,6 +431,21 @@ fn build_validation(
             builder
         }
 
+        PrismParserValidationError::MissingInitialValue { variable } => {
+            let mut builder =
+                Report::build(ReportKind::Error, (file_name, variable.span.into_range()));
+            builder.set_message("Missing initial value");
+
+            builder.add_label(
+                Label::new((file_name, variable.span.into_range())).with_message(format!(
+                    "Variable {} has no default initial value",
+                    variable.name
+                )),
+            );
+            builder.add_help("Add an initial value with `init` or use an init constraint.");
+
+            builder
+        }
         PrismParserValidationError::UnknownVariable { identifier } => {
             let mut builder =
                 Report::build(ReportKind::Error, (file_name, identifier.span.into_range()));
@@ -417,5 +460,244 @@ fn build_validation(
 
             builder
         }
+
+        PrismParserValidationError::TypeError {
+            error:
+                TypeError::UnexpectedType {
+                    span,
+                    expected,
+                    found,
+                },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, span.into_range()));
+            builder.set_message("Unexpected type");
+
+            builder.add_label(
+                Label::new((file_name, span.into_range())).with_message(format!(
+                    "Expected {}, but this expression has type {}",
+                    expected.get_name(),
+                    found.get_name()
+                )),
+            );
+
+            builder
+        }
+        PrismParserValidationError::TypeError {
+            error: TypeError::IncompatibleOperands { span, lhs, rhs },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, span.into_range()));
+            builder.set_message("Incompatible operands");
+
+            builder.add_label(
+                Label::new((file_name, lhs.1.into_range()))
+                    .with_message(format!("This operand has type {}", lhs.0.get_name())),
+            );
+            builder.add_label(
+                Label::new((file_name, rhs.1.into_range()))
+                    .with_message(format!("This operand has type {}", rhs.0.get_name())),
+            );
+            builder.add_note("The operands must either both be numbers or both be booleans.");
+
+            builder
+        }
+        PrismParserValidationError::TypeError {
+            error: TypeError::UnknownFunction { name },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, name.span.into_range()));
+            builder.set_message("Unknown function");
+
+            builder.add_label(
+                Label::new((file_name, name.span.into_range()))
+                    .with_message(format!("Cannot find function {}", name.name)),
+            );
+            builder.add_help(
+                "Supported functions are `min`, `max`, `floor`, `ceil`, `round`, `pow`, `mod` and `log`.",
+            );
+
+            builder
+        }
+        PrismParserValidationError::TypeError {
+            error:
+                TypeError::WrongArgumentCount {
+                    name,
+                    span,
+                    expected,
+                    found,
+                },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, span.into_range()));
+            builder.set_message("Wrong number of arguments");
+
+            let expected = match expected {
+                ArgumentCount::Exactly(count) => format!("{}", count),
+                ArgumentCount::AtLeast(count) => format!("at least {}", count),
+            };
+            builder.add_label(
+                Label::new((file_name, span.into_range())).with_message(format!(
+                    "Function {} expects {} arguments, but {} were given",
+                    name.name, expected, found
+                )),
+            );
+
+            builder
+        }
+        PrismParserValidationError::TypeError {
+            error:
+                TypeError::AssignmentToConstant {
+                    target_span,
+                    definition,
+                },
+        } => {
+            let mut builder =
+                Report::build(ReportKind::Error, (file_name, target_span.into_range()));
+            builder.set_message("Assignment to constant");
+
+            builder.add_label(
+                Label::new((file_name, target_span.into_range()))
+                    .with_message("Constants cannot be updated"),
+            );
+            builder.add_label(
+                Label::new((file_name, definition.into_range()))
+                    .with_message("The constant is defined here"),
+            );
+
+            builder
+        }
+        PrismParserValidationError::TypeError {
+            error:
+                TypeError::IncompatibleValue {
+                    variable_name,
+                    variable_type,
+                    value_span,
+                    value_type,
+                },
+        } => {
+            let mut builder =
+                Report::build(ReportKind::Error, (file_name, value_span.into_range()));
+            builder.set_message("Incompatible value");
+
+            builder.add_label(
+                Label::new((file_name, variable_name.span.into_range())).with_message(format!(
+                    "{} has type {}",
+                    variable_name.name,
+                    variable_type.get_name()
+                )),
+            );
+            builder.add_label(
+                Label::new((file_name, value_span.into_range()))
+                    .with_message(format!("This value has type {}", value_type.get_name())),
+            );
+
+            builder
+        }
+        PrismParserValidationError::TypeError {
+            error:
+                TypeError::ValueOutOfRange {
+                    variable_name,
+                    range_span,
+                    value_span,
+                    value,
+                    min,
+                    max,
+                },
+        } => {
+            let mut builder =
+                Report::build(ReportKind::Error, (file_name, value_span.into_range()));
+            builder.set_message("Value out of range");
+
+            builder.add_label(
+                Label::new((file_name, value_span.into_range())).with_message(format!(
+                    "The value {} is outside the range of {}",
+                    value, variable_name.name
+                )),
+            );
+            builder.add_label(
+                Label::new((file_name, range_span.into_range()))
+                    .with_message(format!("The range is {}..{}", min, max)),
+            );
+
+            builder
+        }
+
+        PrismParserValidationError::PlayerError {
+            error: PlayerError::PlayerOutsideGame { player, model_type },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, player.into_range()));
+            builder.set_message("Player outside of game");
+
+            builder.add_label(
+                Label::new((file_name, player.into_range()))
+                    .with_message("Players can only be declared in games"),
+            );
+            builder.add_label(
+                Label::new((file_name, model_type.into_range()))
+                    .with_message("The model type is set here"),
+            );
+            builder.add_help("Use the model type `smg` for stochastic multi-player games.");
+
+            builder
+        }
+        PrismParserValidationError::PlayerError {
+            error: PlayerError::MissingPlayers { model_type },
+        } => {
+            let mut builder =
+                Report::build(ReportKind::Error, (file_name, model_type.into_range()));
+            builder.set_message("Missing players");
+
+            builder.add_label(
+                Label::new((file_name, model_type.into_range()))
+                    .with_message("Games must declare at least one player"),
+            );
+            builder.add_help("Declare players with `player name module1, [action1] endplayer`.");
+
+            builder
+        }
+        PrismParserValidationError::PlayerError {
+            error: PlayerError::UnknownModule { name },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, name.span.into_range()));
+            builder.set_message("Unknown module");
+
+            builder.add_label(
+                Label::new((file_name, name.span.into_range()))
+                    .with_message(format!("Cannot find module with name {}", name.name)),
+            );
+
+            builder
+        }
+        PrismParserValidationError::PlayerError {
+            error: PlayerError::UnknownAction { name },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, name.span.into_range()));
+            builder.set_message("Unknown action");
+
+            builder.add_label(
+                Label::new((file_name, name.span.into_range()))
+                    .with_message(format!("No command is labelled with action {}", name.name)),
+            );
+
+            builder
+        }
+        PrismParserValidationError::PlayerError {
+            error:
+                PlayerError::DuplicateControl {
+                    name,
+                    previous_occurrence,
+                },
+        } => {
+            let mut builder = Report::build(ReportKind::Error, (file_name, name.span.into_range()));
+            builder.set_message("Controlled by several players");
+
+            builder.add_label(
+                Label::new((file_name, previous_occurrence.into_range()))
+                    .with_message(format!("{} is first assigned to a player here", name.name)),
+            );
+            builder.add_label(
+                Label::new((file_name, name.span.into_range()))
+                    .with_message("Assigned to a player again here"),
+            );
+
+            builder
+        }
     }
 }
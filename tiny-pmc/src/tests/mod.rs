@@ -0,0 +1,1005 @@
+use crate::CheckerError;
+use crate::checking::{QueryResult, TransitionSystemResult};
+use prism_model::{ArgumentCount, ExpectedType, ExpressionType, PlayerError, TypeError};
+use prism_model_builder::{ModelBuildingError, ModelBuildingOutput};
+use prism_parser::{PrismParserError, PrismParserValidationError, Span};
+use probabilistic_model_algorithms::deterministic_games::{
+    NonstochasticGameAlgorithm, NonstochasticGameAndSolverExternalOwners,
+    ReachabilityAlgorithmCollection, SolvableNonstochasticGame,
+};
+use probabilistic_model_algorithms::regions::StateRegion;
+use probabilistic_model_algorithms::traits::{
+    SolvableStochasticGame, StochasticGameAlgorithm, StochasticGameAndSolver,
+};
+use probabilistic_model_algorithms::value_iteration::stochastic_games::StochasticGameValueIterationAlgorithm;
+use probabilistic_models::{
+    AtomicProposition, BitFlagsAtomicPropositions, InitialStateVector, InitialStates,
+    IterProbabilisticModel, Mdp, MdpType, NonTrackedPredecessors, TransitionSystem,
+    TransitionSystemType, TwoPlayer, TwoPlayerNonstochasticGame, TwoPlayerNonstochasticGameType,
+    TwoPlayerStochasticGame, TwoPlayerStochasticGameType, ValuationVector, VectorPredecessors,
+};
+use probabilistic_properties::Query;
+
+#[test]
+fn dtmc_eventually() {
+    let source = include_str!("files/random-walk.prism");
+    assert_result(check_source(source, "P=? [F \"goal\"]"), 1.0 / 3.0);
+}
+
+#[test]
+fn dtmc_until() {
+    let source = include_str!("files/random-walk.prism");
+    assert_result(check_source(source, "P=? [s<2 U \"goal\"]"), 1.0 / 3.0);
+    assert_result(check_source(source, "P=? [s=0 U s=1]"), 0.5);
+}
+
+#[test]
+fn dtmc_bounded() {
+    let source = include_str!("files/random-walk.prism");
+    assert_result(check_source(source, "P=? [F<=1 \"goal\"]"), 0.0);
+    assert_result(check_source(source, "P=? [F<=2 \"goal\"]"), 0.25);
+    assert_result(check_source(source, "P=? [F<4 \"goal\"]"), 0.25);
+    assert_result(check_source(source, "P=? [F<=4 \"goal\"]"), 0.3125);
+    assert_result(check_source(source, "P=? [s=0 U<=5 s=1]"), 0.5);
+}
+
+#[test]
+fn dtmc_next_weak_until_release() {
+    let source = include_str!("files/random-walk.prism");
+    assert_result(check_source(source, "P=? [X s=1]"), 0.5);
+    assert_result(check_source(source, "P=? [s=0 W s=1]"), 0.5);
+    assert_result(check_source(source, "P=? [s<2 W \"goal\"]"), 1.0 / 3.0);
+    assert_result(check_source(source, "P=? [\"goal\" R s!=2]"), 1.0 / 3.0);
+}
+
+#[test]
+fn ctmc_time_bounded() {
+    let source = include_str!("files/two-phase.prism");
+    assert_result(check_source(source, "P=? [F \"goal\"]"), 0.5);
+    assert_result(check_source(source, "P=? [F<=1 \"goal\"]"), 0.346784);
+    assert_result(check_source(source, "P=? [F<=T \"goal\"]"), 0.171311);
+    assert_result(check_source(source, "P=? [s<=1 U<=1 \"goal\"]"), 0.346784);
+    assert_result(check_source(source, "P=? [s=0 U<=1 \"goal\"]"), 0.0);
+}
+
+#[test]
+fn mdp_eventually() {
+    let source = include_str!("files/simple-mdp.prism");
+    assert_result(check_source(source, "Pmax=? [F \"goal\"]"), 0.75);
+    assert_result(check_source(source, "Pmin=? [F \"goal\"]"), 0.0);
+}
+
+#[test]
+fn mdp_until() {
+    let source = include_str!("files/simple-mdp.prism");
+    assert_result(check_source(source, "Pmax=? [s!=2 U \"goal\"]"), 0.5);
+    assert_result(check_source(source, "Pmin=? [s!=2 U \"goal\"]"), 0.0);
+}
+
+#[test]
+fn mdp_bounded() {
+    let source = include_str!("files/simple-mdp.prism");
+    assert_result(check_source(source, "Pmax=? [F<=1 \"goal\"]"), 0.0);
+    assert_result(check_source(source, "Pmax=? [F<=2 \"goal\"]"), 0.75);
+    assert_result(check_source(source, "Pmax=? [F<3 \"goal\"]"), 0.75);
+    assert_result(check_source(source, "Pmin=? [F<=3 \"goal\"]"), 0.0);
+    assert_result(check_source(source, "Pmax=? [s!=2 U<=2 \"goal\"]"), 0.5);
+}
+
+#[test]
+fn mdp_generally() {
+    let source = include_str!("files/simple-mdp.prism");
+    assert_result(check_source(source, "Pmax=? [G \"safe\"]"), 1.0);
+    assert_result(check_source(source, "Pmin=? [G \"safe\"]"), 0.25);
+}
+
+#[test]
+fn mdp_next_weak_until_release() {
+    let source = include_str!("files/simple-mdp.prism");
+    for (property, expected) in [
+        ("Pmax=? [X s=1]", 0.5),
+        ("Pmin=? [X s=1]", 0.0),
+        ("Pmax=? [s=0 W \"goal\"]", 1.0),
+        ("Pmin=? [s=0 W \"goal\"]", 0.0),
+        ("Pmin=? [s<=2 W \"goal\"]", 0.75),
+        ("Pmax=? [\"goal\" R s<=2]", 1.0),
+        ("Pmin=? [\"goal\" R s<=2]", 0.0),
+        ("Pmin=? [\"goal\" R s!=4]", 0.75),
+    ] {
+        assert_result(check_source(source, property), expected);
+    }
+}
+
+#[test]
+fn dtmc_rewards() {
+    let source = include_str!("files/coin-rewards.prism");
+    assert_result(check_source(source, "R=? [F \"done\"]"), 3.0);
+    assert_result(check_source(source, "R{\"tosses\"}=? [F \"done\"]"), 4.0);
+    assert_result(check_source(source, "R{\"steps\"}=? [C<=2]"), 2.0);
+    assert_result(check_source(source, "R{\"tosses\"}=? [C<=2]"), 3.0);
+    assert_result(check_source(source, "R{\"at_one\"}=? [I=1]"), 0.5);
+    assert_result(check_source(source, "R{\"at_one\"}=? [I=2]"), 0.25);
+    assert!(matches!(
+        check_source(source, "R{\"missing\"}=? [C<=2]"),
+        Err(CheckerError::UnknownRewardStructure(Some(_)))
+    ));
+}
+
+#[test]
+fn mdp_rewards() {
+    let source = include_str!("files/choice-rewards.prism");
+    assert_result(check_source(source, "Rmin=? [F \"done\"]"), 1.5);
+    assert_eq!(
+        check_source(source, "Rmax=? [F \"done\"]").unwrap(),
+        f64::INFINITY
+    );
+    assert_result(check_source(source, "Rmax=? [C<=2]"), 5.0);
+    assert_result(check_source(source, "Rmin=? [C<=2]"), 0.0);
+    assert_result(check_source(source, "Rmax=? [I=1]"), 0.0);
+}
+
+#[test]
+fn dtmc_long_run_average() {
+    let source = include_str!("files/steady-state.prism");
+    assert_result(check_source(source, "S=? [\"up\"]"), 0.25 + 0.5 * 3.0 / 7.0);
+    assert_result(check_source(source, "R=? [S]"), 0.5);
+}
+
+#[test]
+fn mdp_long_run_average() {
+    let source = include_str!("files/steady-state-mdp.prism");
+    assert_result(check_source(source, "Smax=? [\"up\"]"), 0.5);
+    assert_result(check_source(source, "Smin=? [\"up\"]"), 0.0);
+    assert_result(check_source(source, "Rmax=? [S]"), 2.0);
+    assert_result(check_source(source, "Rmin=? [S]"), 0.0);
+}
+
+#[test]
+fn mdp_long_run_average_single_component() {
+    // All states form one end component, in which the initial policy has two recurrent classes and
+    // the minimising policy cycles through all states with stationary distribution (2/5, 2/5, 1/5).
+    let source = include_str!("files/long-run-mec.prism");
+    assert_result(check_source(source, "Rmax=? [S]"), 3.0);
+    assert_result(check_source(source, "Rmin=? [S]"), 0.4 * 2.0 + 0.2 * 3.0);
+    assert_result(check_source(source, "Smax=? [\"high\"]"), 1.0);
+    assert_result(check_source(source, "Smin=? [\"high\"]"), 0.0);
+}
+
+#[test]
+fn stochastic_game_reachability() {
+    let source = include_str!("files/simple-game.prism");
+    assert_result(check_source(source, "Pmax=? [F \"goal\"]"), 0.75);
+    assert_result(
+        check_game_source(source, "Pmax=? [F \"goal\"]", "player_two"),
+        0.25,
+    );
+}
+
+#[test]
+fn stochastic_game_objectives() {
+    let source = include_str!("files/simple-game.prism");
+    for (property, expected) in [
+        ("Pmin=? [F \"goal\"]", 0.5),
+        ("Pmax=? [G !\"goal\"]", 0.5),
+        ("Pmin=? [G !\"goal\"]", 0.75),
+        ("P>=0.25 [F \"goal\"]", 1.0),
+        ("P>0.25 [F \"goal\"]", 0.0),
+        ("P<=0.5 [F \"goal\"]", 1.0),
+        ("P<0.5 [F \"goal\"]", 0.0),
+        ("Pmax=? [s!=2 U \"goal\"]", 0.0),
+        ("Pmax=? [s!=1 W \"goal\"]", 0.5),
+        ("Pmax=? [\"goal\" R s!=4]", 0.25),
+        ("Pmin=? [\"goal\" R s!=4]", 0.5),
+    ] {
+        assert_result(check_game_source(source, property, "player_two"), expected);
+    }
+}
+
+#[test]
+fn stochastic_game_rewards() {
+    let source = include_str!("files/reward-game.prism");
+    assert_result(
+        check_game_source(source, "R{\"cost\"}min=? [F \"goal\"]", "player_two"),
+        4.0,
+    );
+    assert_eq!(
+        check_game_source(source, "R{\"cost\"}max=? [F \"goal\"]", "player_two").unwrap(),
+        f64::INFINITY
+    );
+    assert_result(
+        check_game_source(source, "R{\"cost\"}max=? [F \"done\"]", "player_two"),
+        6.0,
+    );
+    assert_result(
+        check_game_source(source, "R{\"cost\"}min=? [F \"done\"]", "player_two"),
+        0.0,
+    );
+    assert!(matches!(
+        check_game_source(source, "R{\"missing\"}min=? [F \"goal\"]", "player_two"),
+        Err(CheckerError::UnknownRewardStructure(Some(_)))
+    ));
+}
+
+#[test]
+fn nonstochastic_game_objectives() {
+    let source = include_str!("files/nonstochastic-game.prism");
+    for property in [
+        "P>=1 [F \"goal\"]",
+        "P>=1 [G \"safe\"]",
+        "P>=1 [G P>=1 [F \"goal\"]]",
+        "P>=1 [s!=1 U \"goal\"]",
+        "P>=1 [s=0 W s=2]",
+        "P>=1 [s=2 R s!=1]",
+        "P>=1 [\"goal\" R \"safe\"]",
+    ] {
+        assert_result(check_game_source(source, property, "player_two"), 1.0);
+        assert_result(check_source(source, property), 0.0);
+    }
+}
+
+#[test]
+fn next_in_games() {
+    let source = include_str!("files/nonstochastic-game.prism");
+    assert_result(
+        check_game_source(source, "P>=1 [X \"goal\"]", "player_two"),
+        0.0,
+    );
+    assert_result(
+        check_game_source(source, "P>=1 [X s!=0]", "player_two"),
+        1.0,
+    );
+    // Player two avoids the goal in state 1, so only state 2 can force it in one step
+    assert_result(
+        check_game_source(source, "filter(count, P>=1 [X \"goal\"])", "player_two"),
+        1.0,
+    );
+    assert_result(
+        check_game_source(source, "filter(count, P>=1 [X \"goal\"])", "goal"),
+        2.0,
+    );
+
+    let source = include_str!("files/two-player-game.prism");
+    assert_result(
+        build_and_check_game(source, "Pmax=? [X \"goal\"]").unwrap(),
+        0.5,
+    );
+    assert_result(
+        build_and_check_game(source, "Pmin=? [X \"goal\"]").unwrap(),
+        0.0,
+    );
+    // Player two pursues the opposite objective
+    assert_result(
+        build_and_check_game(source, "filter(max, Pmax=? [X \"goal\"], s=1)").unwrap(),
+        0.7,
+    );
+    assert_result(
+        build_and_check_game(source, "filter(max, Pmin=? [X \"goal\"], s=1)").unwrap(),
+        0.9,
+    );
+}
+
+#[test]
+fn nonstochastic_game_strategy() {
+    let source = include_str!("files/nonstochastic-game.prism");
+    let (model, property, player_two_states) =
+        build_source(source, "P>=1 [F \"goal\"]", Some("player_two"));
+    let game: TwoPlayerNonstochasticGame<VectorPredecessors> =
+        crate::building::assign_owners(model, player_two_states.unwrap())
+            .into_iter()
+            .collect::<TwoPlayerNonstochasticGameType>()
+            .rebuild_and_transform_predecessors();
+    let result = crate::checking::check_nonstochastic_game(game, property).unwrap();
+
+    assert_eq!(result.winner, TwoPlayer::PlayerOne);
+    for (state, winning) in [(0, true), (1, false), (2, true), (3, true), (4, false)] {
+        assert_eq!(result.winning_region.contains(state), winning);
+    }
+    assert_eq!(result.strategy.get_choice(0), Some(1));
+    assert_eq!(result.strategy.get_choice(2), Some(0));
+}
+
+#[test]
+fn nonstochastic_game_incremental() {
+    let source = include_str!("files/nonstochastic-game.prism");
+    // In `s!=2 U "goal"`, state 2 is a sink that is never attracted
+    for property in ["P>=1 [F \"goal\"]", "P>=1 [s!=2 U \"goal\"]"] {
+        let (model, property, player_two_states) =
+            build_source(source, property, Some("player_two"));
+        let game: TwoPlayerNonstochasticGame<VectorPredecessors> =
+            crate::building::assign_owners(model, player_two_states.unwrap())
+                .into_iter()
+                .collect::<TwoPlayerNonstochasticGameType>()
+                .rebuild_and_transform_predecessors();
+        let solver = ReachabilityAlgorithmCollection::create_if_compatible(&property).unwrap();
+        let mut solvable =
+            NonstochasticGameAndSolverExternalOwners::new(game.clone(), solver.clone());
+
+        // Player one both gains and loses states between consecutive owner assignments
+        for owners in all_owner_assignments(game.states.len()) {
+            let mut expected_game = game.clone();
+            for (state, &owner) in owners.iter().enumerate() {
+                solvable.set_owner(state, owner);
+                expected_game.states[state].owner = owner;
+            }
+            let expected_region = solver.clone().winning_region(&expected_game);
+
+            assert_eq!(
+                solvable.get_winner_incremental(),
+                solver.clone().winning(&expected_game)
+            );
+            let region = solvable.get_winning_region_incremental();
+            for state in 0..game.states.len() {
+                assert_eq!(region.contains(state), expected_region.contains(state));
+            }
+        }
+    }
+}
+
+#[test]
+fn stochastic_game_incremental() {
+    let source = include_str!("files/cyclic-game.prism");
+    for property in ["Pmax=? [F \"goal\"]", "Pmin=? [F \"goal\"]"] {
+        let (model, property, player_two_states) =
+            build_source(source, property, Some("player_two"));
+        let game: TwoPlayerStochasticGame<VectorPredecessors> =
+            crate::building::assign_owners(model, player_two_states.unwrap())
+                .into_iter()
+                .collect::<TwoPlayerStochasticGameType>()
+                .rebuild_and_transform_predecessors();
+        let solver =
+            StochasticGameValueIterationAlgorithm::create_if_compatible(&property).unwrap();
+        let Ok(mut solvable) = StochasticGameAndSolver::new(game.clone(), solver.clone());
+
+        for owners in all_owner_assignments(game.states.len()) {
+            let mut expected_game = game.clone();
+            for (state, &owner) in owners.iter().enumerate() {
+                solvable.set_owner(state, owner);
+                expected_game.states[state].owner = owner;
+            }
+            let Ok(expected) = solver.clone().player_one_probability(&expected_game);
+
+            assert_result(
+                Ok(solvable.maximum_player_1_probability_incremental()),
+                expected,
+            );
+        }
+    }
+}
+
+/// All assignments of owners to `state_count` states, ordered like the coalitions in which state
+/// `i` belongs to player one iff bit `i` is set.
+fn all_owner_assignments(state_count: usize) -> Vec<Vec<TwoPlayer>> {
+    (0..1usize << state_count)
+        .map(|mask| {
+            (0..state_count)
+                .map(|state| match mask & (1 << state) != 0 {
+                    true => TwoPlayer::PlayerOne,
+                    false => TwoPlayer::PlayerTwo,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn player_blocks() {
+    let source = include_str!("files/two-player-game.prism");
+    assert_result(
+        build_and_check_game(source, "Pmax=? [F \"goal\"]").unwrap(),
+        0.7,
+    );
+    assert_result(
+        build_and_check_game(source, "Pmin=? [F \"goal\"]").unwrap(),
+        0.5,
+    );
+
+    // Loading the game as a single-player model ignores the players
+    assert_result(check_source(source, "Pmax=? [F \"goal\"]"), 0.9);
+
+    // Both players have an enabled command in the initial state
+    let concurrent = source.replace("[] s=1 -> 0.7", "[] s<=1 -> 0.7");
+    assert!(matches!(
+        build_and_check_game(&concurrent, "Pmax=? [F \"goal\"]"),
+        Err(ModelBuildingError::ConcurrentPlayers { .. })
+    ));
+
+    let uncontrolled = source.replace("    environment\n", "");
+    assert!(matches!(
+        build_and_check_game(&uncontrolled, "Pmax=? [F \"goal\"]"),
+        Err(ModelBuildingError::UncontrolledCommand { .. })
+    ));
+
+    let three_players = format!("{source}\nplayer carol\n    [unused]\nendplayer\n").replace(
+        "[risky] s=0 -> (s'=1);",
+        "[risky] s=0 -> (s'=1);\n    [unused] false -> true;",
+    );
+    assert!(matches!(
+        build_and_check_game(&three_players, "Pmax=? [F \"goal\"]"),
+        Err(ModelBuildingError::TooManyPlayers {
+            players: 3,
+            supported: 2
+        })
+    ));
+}
+
+#[test]
+fn player_validation() {
+    let source = include_str!("files/two-player-game.prism");
+    let errors = player_errors(&source.replace("chooser, [risky]", "chooser, [safe]"));
+    assert!(matches!(&errors[..], [PlayerError::UnknownAction { name }] if name.name == "safe"));
+
+    let errors = player_errors(&source.replace("    environment\n", "    chooser, environment\n"));
+    assert!(
+        matches!(&errors[..], [PlayerError::DuplicateControl { name, .. }] if name.name == "chooser")
+    );
+
+    let errors = player_errors(&source.replace("    environment\n", "    other\n"));
+    assert!(matches!(&errors[..], [PlayerError::UnknownModule { name }] if name.name == "other"));
+
+    let errors = player_errors(&source.replace("smg", "mdp"));
+    assert!(matches!(
+        &errors[..],
+        [
+            PlayerError::PlayerOutsideGame { .. },
+            PlayerError::PlayerOutsideGame { .. }
+        ]
+    ));
+}
+
+fn build_and_check_game(
+    source: &str,
+    property: &str,
+) -> Result<Result<f64, CheckerError>, ModelBuildingError> {
+    let (mut prism_model, properties, _) =
+        crate::parsing::parse_prism_and_print_errors(None, source, &[property])
+            .expect("Model and property must parse");
+    let mut atomic_propositions = Vec::new();
+    let properties = crate::building::prism_objectives_to_atomic_propositions(
+        &mut atomic_propositions,
+        properties,
+    );
+    let output: ModelBuildingOutput<TwoPlayerStochasticGameType> =
+        prism_model_builder::build_model(
+            &mut prism_model,
+            &atomic_propositions[..],
+            properties.into_iter(),
+            &crate::parsing::parse_const_assignments("").unwrap(),
+        )?;
+    Ok(
+        crate::checking::check_game(output.model, output.properties[0].clone())
+            .map(|result| result.value),
+    )
+}
+
+fn player_errors(source: &str) -> Vec<PlayerError<Span>> {
+    validation_errors(source, "P=? [F s=3]", |error| match error {
+        PrismParserValidationError::PlayerError { error } => Some(error),
+        _ => None,
+    })
+}
+
+/// Parses the model and the property and returns the validation errors of the kind that `extract`
+/// accepts. Panics on any other error.
+fn validation_errors<T>(
+    source: &str,
+    property: &str,
+    extract: impl Fn(PrismParserValidationError<Span>) -> Option<T>,
+) -> Vec<T> {
+    let Err(errors) = crate::parsing::parse_model_from_source(source, &[property]) else {
+        return Vec::new();
+    };
+    errors
+        .into_iter()
+        .map(|(_, error)| match error {
+            PrismParserError::Validation(error) => {
+                let description = format!("{:?}", error);
+                extract(error).unwrap_or_else(|| panic!("Unexpected error {}", description))
+            }
+            error => panic!("Unexpected error {:?}", error),
+        })
+        .collect()
+}
+
+#[test]
+fn transition_system_objectives() {
+    let source = include_str!("files/nonstochastic-game.prism");
+    assert_result(check_source(source, "Pmax=? [F \"goal\"]"), 1.0);
+    assert_result(check_source(source, "Pmin=? [F \"goal\"]"), 0.0);
+    assert_result(check_source(source, "Pmax=? [G \"safe\"]"), 1.0);
+    assert_result(check_source(source, "Pmin=? [G \"safe\"]"), 0.0);
+    assert_result(check_source(source, "Pmax>0 [F s=4]"), 1.0);
+    assert_result(check_source(source, "P<=0 [F s=4]"), 0.0);
+    assert_result(check_source(source, "P<1 [G \"safe\"]"), 0.0);
+}
+
+#[test]
+fn transition_system_witnesses() {
+    let source = include_str!("files/nonstochastic-game.prism");
+
+    let (model, property, _) = build_source(source, "Pmax=? [F \"goal\"]", None);
+    let witness = check_transition_system_model(model, &property)
+        .witness
+        .unwrap();
+    assert_eq!(witness.states.first(), Some(&0));
+    assert_eq!(witness.states.last(), Some(&3));
+    assert_eq!(witness.actions.len() + 1, witness.states.len());
+
+    let (model, property, _) = build_source(source, "Pmax=? [G \"safe\"]", None);
+    let witness = check_transition_system_model(model, &property)
+        .witness
+        .unwrap();
+    assert_eq!(witness.states, vec![0, 1, 3, 0]);
+    assert_eq!(witness.actions, vec![0, 0, 0]);
+}
+
+#[test]
+fn transition_system_witness_from_goal_state() {
+    let source = include_str!("files/nonstochastic-game.prism");
+    let (model, property, _) = build_source(source, "Pmax=? [F s=0]", None);
+    let witness = check_transition_system_model(model, &property)
+        .witness
+        .unwrap();
+    assert_eq!(witness.states, vec![0]);
+    assert!(witness.actions.is_empty());
+}
+
+#[test]
+fn init_constraint_initial_states() {
+    let source = include_str!("files/init-constraint.prism");
+    let (mut prism_model, _, _) =
+        crate::parsing::parse_prism_and_print_errors::<&str>(None, source, &[])
+            .expect("Model must parse");
+    let output: ModelBuildingOutput<
+        MdpType<
+            NonTrackedPredecessors,
+            ValuationVector,
+            BitFlagsAtomicPropositions,
+            InitialStateVector,
+        >,
+    > = prism_model_builder::build_model(
+        &mut prism_model,
+        &[],
+        std::iter::empty(),
+        &crate::parsing::parse_const_assignments("").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(output.model.initial_states.count(), 2);
+    // Both initial states and their successors are explored
+    assert_eq!(output.model.states.len(), 4);
+}
+
+#[test]
+fn multiple_initial_states() {
+    // The initial states are s=1 and s=2
+    let source = include_str!("files/init-constraint.prism");
+    let result = check_source_with_result(source, "P=? [F \"goal\"]").unwrap();
+    let initial_values: Vec<f64> = result.initial_values.iter().map(|(_, v)| *v).collect();
+    assert_eq!(initial_values.len(), 2);
+    assert_result(Ok(initial_values[0]), 2.0 / 3.0);
+    assert_result(Ok(initial_values[1]), 0.0);
+    assert_result(Ok(result.value), 2.0 / 3.0);
+    // Bounds must hold in every initial state
+    assert_result(check_source(source, "P>0.5 [F \"goal\"]"), 0.0);
+    assert_result(check_source(source, "P>=0 [F \"goal\"]"), 1.0);
+
+    let source = include_str!("files/nonstochastic-game.prism")
+        .replace("s: [0..4] init 0;", "s: [0..4];")
+        .replace("endmodule", "endmodule\n\ninit s=2 | s=4 endinit");
+    let result = check_source_with_result(&source, "Pmax=? [F \"goal\"]").unwrap();
+    assert_eq!(result.initial_values, vec![(0, 1.0), (1, 0.0)]);
+    assert_result(
+        check_game_source(&source, "P>=1 [F \"goal\"]", "player_two"),
+        0.0,
+    );
+    assert_result(
+        check_game_source(
+            &source.replace("s=2 | s=4", "s=2 | s=3"),
+            "P>=1 [F \"goal\"]",
+            "player_two",
+        ),
+        1.0,
+    );
+}
+
+#[test]
+fn init_constraint_single_initial_state() {
+    let source = include_str!("files/init-constraint.prism").replace("s>=1 & s<=2", "s=1");
+    assert_result(check_source(&source, "P=? [F \"goal\"]"), 2.0 / 3.0);
+}
+
+#[test]
+fn properties_file() {
+    let source = include_str!("files/random-walk.prism");
+    let properties_source = r#"
+        const int K;
+
+        // Named properties can be referenced by later properties
+        "goal_prob": P=? [F "goal"];
+        "bounded": P=? [F<=K "goal"];
+        "not_two": s!=2;
+        "again": "goal_prob";
+        P=? ["not_two" U "goal"]
+    "#;
+    let (mut prism_model, properties, _) =
+        crate::parsing::parse_prism_with_properties_file_and_print_errors(
+            None,
+            source,
+            None,
+            properties_source,
+        )
+        .expect("Model and properties file must parse");
+    let names: Vec<_> = properties
+        .iter()
+        .map(|p| p.name.as_ref().map(|name| name.name.clone()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            Some("goal_prob".to_string()),
+            Some("bounded".to_string()),
+            Some("not_two".to_string()),
+            Some("again".to_string()),
+            None
+        ]
+    );
+
+    let mut atomic_propositions = Vec::new();
+    let queries = crate::building::prism_objectives_to_atomic_propositions(
+        &mut atomic_propositions,
+        properties.into_iter().map(|p| p.query).collect(),
+    );
+    let output: ModelBuildingOutput<MdpType> = prism_model_builder::build_model(
+        &mut prism_model,
+        &atomic_propositions[..],
+        queries.into_iter(),
+        &crate::parsing::parse_const_assignments("K=2").unwrap(),
+    )
+    .unwrap();
+
+    let check = |index: usize| {
+        crate::checking::check(output.model.clone(), output.properties[index].clone())
+            .map(|result| result.value)
+    };
+    assert_result(check(0), 1.0 / 3.0);
+    assert_result(check(1), 0.25);
+    assert_result(check(3), 1.0 / 3.0);
+    assert_result(check(4), 1.0 / 3.0);
+}
+
+#[test]
+fn filters() {
+    let source = include_str!("files/random-walk.prism");
+    let check = |property: &str| check_source(source, property);
+    assert_result(check("filter(min, P=? [F \"goal\"])"), 0.0);
+    assert_result(check("filter(max, P=? [F \"goal\"])"), 1.0);
+    assert_result(check("filter(avg, P=? [F \"goal\"])"), 0.5);
+    assert_result(check("filter(sum, P=? [F \"goal\"], s<3)"), 1.0);
+    assert_result(check("filter(count, P>0.5 [F \"goal\"])"), 2.0);
+    assert_result(check("filter(forall, P>0 [F \"goal\"], s!=2)"), 1.0);
+    assert_result(check("filter(forall, P>0 [F \"goal\"])"), 0.0);
+    assert_result(check("filter(exists, \"goal\")"), 1.0);
+    assert_result(check("filter(first, P=? [F \"goal\"], s=1)"), 2.0 / 3.0);
+    assert_result(check("filter(state, P=? [F \"goal\"], s=1)"), 2.0 / 3.0);
+    assert_result(check("filter(max, filter(min, P=? [F \"goal\"]))"), 0.0);
+    assert_result(check("filter(forall, P>0 [F \"goal\"], \"init\")"), 1.0);
+    assert_result(
+        check("filter(first, P=? [F \"goal\"], \"init\")"),
+        1.0 / 3.0,
+    );
+    assert_result(
+        check("filter(sum, P=? [F \"goal\"], P>0.5 [F \"goal\"])"),
+        5.0 / 3.0,
+    );
+    assert!(matches!(
+        check("filter(state, P=? [F \"goal\"], s<2)"),
+        Err(CheckerError::AmbiguousFilterState(2))
+    ));
+    assert!(matches!(
+        check("filter(min, P=? [F \"goal\"], s>3)"),
+        Err(CheckerError::EmptyFilter)
+    ));
+
+    let result = check_source_with_result(source, "filter(print, P=? [F \"goal\"], s<=1)").unwrap();
+    assert_result(Ok(result.value), 1.0 / 3.0);
+    let printed: Vec<f64> = result.printed_states.iter().map(|(_, v)| *v).collect();
+    assert_eq!(printed.len(), 2);
+    assert_result(Ok(printed[0]), 1.0 / 3.0);
+    assert_result(Ok(printed[1]), 2.0 / 3.0);
+}
+
+#[test]
+fn filters_with_non_determinism() {
+    // State 0 can loop forever, so it forms a MEC whose value is mapped back to the state.
+    let source = include_str!("files/simple-mdp.prism");
+    assert_result(
+        check_source(source, "filter(avg, Pmax=? [F \"goal\"])"),
+        0.65,
+    );
+    assert_result(
+        check_source(source, "filter(sum, Pmin=? [F \"goal\"])"),
+        2.5,
+    );
+    assert_result(
+        check_source(source, "filter(max, Pmax=? [X \"goal\"], s<=1)"),
+        1.0,
+    );
+
+    let source = include_str!("files/nonstochastic-game.prism");
+    assert_result(
+        check_source(source, "filter(count, P>=1 [F \"goal\"])"),
+        2.0,
+    );
+    assert_result(
+        check_game_source(source, "filter(count, P>=1 [F \"goal\"])", "player_two"),
+        3.0,
+    );
+    // The filter formula is checked for the same game as the filtered property
+    assert_result(
+        check_game_source(
+            source,
+            "filter(count, P>=1 [F \"goal\"], P>=1 [X \"goal\"])",
+            "player_two",
+        ),
+        1.0,
+    );
+    assert_result(
+        check_game_source(
+            source,
+            "filter(forall, P>=1 [F \"goal\"], \"init\")",
+            "player_two",
+        ),
+        1.0,
+    );
+
+    let source = include_str!("files/two-player-game.prism");
+    assert_result(
+        build_and_check_game(source, "filter(sum, Pmax=? [F \"goal\"])").unwrap(),
+        2.4,
+    );
+}
+
+#[test]
+fn ltl_formulas() {
+    // The deadlocks in states 2 and 3 are turned into self-loops.
+    let source = include_str!("files/random-walk.prism");
+    let check = |property: &str| check_source(source, property);
+    assert_result(check("P=? [G F \"goal\"]"), 1.0 / 3.0);
+    assert_result(check("P=? [F G !\"goal\"]"), 2.0 / 3.0);
+    assert_result(check("P=? [X X \"goal\"]"), 0.25);
+    assert_result(check("P=? [G F \"goal\" | F G s=2]"), 1.0);
+    assert_result(check("P=? [F (s=1 & X s=0)]"), 0.25);
+    assert_result(check("P=? [G (s=1 => X s=0)]"), 2.0 / 3.0);
+    assert_result(check("P=? [F \"goal\" & G s!=2]"), 1.0 / 3.0);
+    assert_result(check("P=? [(s!=2 U s=1) U \"goal\"]"), 1.0 / 3.0);
+    assert_result(check("filter(count, P>0.5 [F G !\"goal\"])"), 2.0);
+
+    // Plain PCTL path formulas are still checked without an automaton.
+    assert_result(check("P=? [F \"goal\"]"), 1.0 / 3.0);
+}
+
+#[test]
+fn ltl_formulas_with_non_determinism() {
+    let source = include_str!("files/simple-mdp.prism");
+    let check = |property: &str| check_source(source, property);
+    assert_result(check("Pmax=? [G F \"goal\"]"), 0.75);
+    assert_result(check("Pmin=? [G F \"goal\"]"), 0.0);
+    assert_result(check("Pmax=? [F G \"safe\"]"), 1.0);
+    assert_result(check("Pmin=? [F G \"safe\"]"), 0.25);
+    assert_result(check("Pmax=? [F s=2 & F \"goal\"]"), 0.25);
+    assert_result(check("Pmin=? [G !\"goal\" | F s=1]"), 0.75);
+}
+
+#[test]
+fn type_checking() {
+    let source = include_str!("files/random-walk.prism");
+    assert!(type_errors(source, "P=? [F \"goal\"]").is_empty());
+
+    let errors = type_errors(&source.replace("(s=0)", "(s+1)"), "P=? [F \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::UnexpectedType {
+            expected: ExpectedType::Bool,
+            found: ExpressionType::Int,
+            ..
+        }]
+    ));
+
+    let errors = type_errors(&source.replace("(s'=1)", "(s'=true)"), "P=? [F \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::IncompatibleValue {
+            variable_type: ExpressionType::Int,
+            value_type: ExpressionType::Bool,
+            ..
+        }]
+    ));
+
+    let errors = type_errors(&source.replace("(s'=1)", "(s'=s/2)"), "P=? [F \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::IncompatibleValue {
+            value_type: ExpressionType::Float,
+            ..
+        }]
+    ));
+
+    let errors = type_errors(&source.replace("(s'=1)", "(s'=5)"), "P=? [F \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::ValueOutOfRange {
+            value: 5,
+            min: 0,
+            max: 3,
+            ..
+        }]
+    ));
+
+    let errors = type_errors(&source.replace("init 0", "init -1"), "P=? [F \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::ValueOutOfRange { value: -1, .. }]
+    ));
+
+    // Bounds that depend on constants are only checked when the model is built
+    let constant_source = format!(
+        "const int N = 3;\n{}",
+        source
+            .replace("[0..3]", "[0..N]")
+            .replace("(s'=1)", "(s'=5)")
+    );
+    assert!(type_errors(&constant_source, "P=? [F \"goal\"]").is_empty());
+
+    let errors = type_errors(&source.replace("s=3;", "s=true;"), "P=? [F s=3]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::IncompatibleOperands { .. }]
+    ));
+
+    let errors = type_errors(source, "P=? [F mod(s, 2, 1)=0]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::WrongArgumentCount {
+            expected: ArgumentCount::Exactly(2),
+            found: 3,
+            ..
+        }]
+    ));
+
+    let errors = type_errors(source, "P=? [F max(s)>1.5 | sqrt(s)>0]");
+    assert!(matches!(
+        errors[..],
+        [
+            TypeError::WrongArgumentCount { .. },
+            TypeError::UnknownFunction { .. }
+        ]
+    ));
+
+    let errors = type_errors(source, "P=? [F<=true \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::UnexpectedType {
+            expected: ExpectedType::Number,
+            ..
+        }]
+    ));
+
+    let constant_source = format!("const int N = 2;\n{}", source.replace("(s'=1)", "(N'=1)"));
+    let errors = type_errors(&constant_source, "P=? [F \"goal\"]");
+    assert!(matches!(
+        errors[..],
+        [TypeError::AssignmentToConstant { .. }]
+    ));
+
+    // The initial states are derived from the model, but errors point to the label in the property
+    let errors = type_errors(source, "P=? [F \"init\"+1>0]");
+    let [TypeError::UnexpectedType { span, .. }] = &errors[..] else {
+        panic!("Unexpected errors {:?}", errors);
+    };
+    assert_eq!(span.into_range(), 7..13);
+}
+
+#[test]
+fn missing_initial_values() {
+    let source = include_str!("files/random-walk.prism");
+    let errors = validation_errors(
+        &source.replace("s: [0..3] init 0;", "s: int;"),
+        "P=? [F \"goal\"]",
+        |error| match error {
+            PrismParserValidationError::MissingInitialValue { variable } => Some(variable.name),
+            _ => None,
+        },
+    );
+    assert_eq!(errors, vec!["s".to_string()]);
+
+    // An init constraint determines the initial values of all variables
+    let source = source
+        .replace("s: [0..3] init 0;", "s: int;")
+        .replace("endmodule", "endmodule\n\ninit s=1 endinit");
+    assert!(validation_errors(&source, "P=? [F \"goal\"]", |_| Some(())).is_empty());
+}
+
+fn type_errors(source: &str, property: &str) -> Vec<TypeError<Span>> {
+    validation_errors(source, property, |error| match error {
+        PrismParserValidationError::TypeError { error } => Some(error),
+        _ => None,
+    })
+}
+
+fn check_transition_system_model(
+    model: Mdp,
+    property: &Query<f64, f64, AtomicProposition>,
+) -> TransitionSystemResult {
+    let ts: TransitionSystem<VectorPredecessors> = model
+        .into_iter()
+        .collect::<TransitionSystemType>()
+        .rebuild_and_transform_predecessors();
+    crate::checking::check_transition_system(ts, property).unwrap()
+}
+
+fn check_source(source: &str, property: &str) -> Result<f64, CheckerError> {
+    check_source_with_result(source, property).map(|result| result.value)
+}
+
+fn check_source_with_result(source: &str, property: &str) -> Result<QueryResult, CheckerError> {
+    let (model, property, _) = build_source(source, property, None);
+    crate::checking::check(model, property)
+}
+
+fn check_game_source(
+    source: &str,
+    property: &str,
+    player_two_label: &str,
+) -> Result<f64, CheckerError> {
+    let (model, property, player_two_states) =
+        build_source(source, property, Some(player_two_label));
+    let game = crate::building::assign_owners(model, player_two_states.unwrap());
+    crate::checking::check_game(game, property).map(|result| result.value)
+}
+
+fn build_source(
+    source: &str,
+    property: &str,
+    player_two_label: Option<&str>,
+) -> (
+    Mdp,
+    Query<f64, f64, AtomicProposition>,
+    Option<AtomicProposition>,
+) {
+    let (mut prism_model, properties, _) =
+        crate::parsing::parse_prism_and_print_errors(None, source, &[property])
+            .expect("Model and property must parse");
+
+    let mut atomic_propositions = Vec::new();
+    let player_two_states = player_two_label.map(|label| {
+        crate::building::player_two_label_to_atomic_proposition(
+            &prism_model,
+            label,
+            &mut atomic_propositions,
+        )
+        .expect("Player label must exist")
+    });
+    let properties = crate::building::prism_objectives_to_atomic_propositions(
+        &mut atomic_propositions,
+        properties,
+    );
+    let output: ModelBuildingOutput<MdpType> = prism_model_builder::build_model(
+        &mut prism_model,
+        &atomic_propositions[..],
+        properties.into_iter(),
+        &crate::parsing::parse_const_assignments("").unwrap(),
+    )
+    .unwrap();
+
+    (
+        output.model,
+        output.properties[0].clone(),
+        player_two_states,
+    )
+}
+
+fn assert_result(actual: Result<f64, CheckerError>, expected: f64) {
+    let actual = actual.unwrap();
+    if (actual - expected).abs() >= 0.000_01 {
+        panic!("Incorrect result. Got {}, expected {}", actual, expected);
+    }
+}